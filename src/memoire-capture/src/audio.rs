@@ -1,6 +1,8 @@
 //! WASAPI audio capture for Windows
 //!
 //! Supports both input device (microphone) and loopback (system audio) capture.
+//! Opening an output device by ID captures it in loopback mode, so chunks
+//! are tagged with the direction of the device they actually came from.
 
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
@@ -49,6 +51,9 @@ pub struct AudioCaptureConfig {
     /// Specific device ID to capture from (None = default device)
     pub device_id: Option<String>,
     /// Whether to capture in loopback mode (system audio)
+    ///
+    /// Selects the default output device when no `device_id` is given. A
+    /// `device_id` naming an output device is always captured in loopback mode.
     pub is_loopback: bool,
     /// Chunk duration in seconds
     pub chunk_duration_secs: u32,
@@ -77,6 +82,8 @@ pub struct AudioCapture {
     device_name: String,
     sample_rate: u32,
     channels: u16,
+    /// Whether the opened device is an input (false for loopback of an output device)
+    is_input_device: bool,
 }
 
 impl AudioCapture {
@@ -177,6 +184,10 @@ impl AudioCapture {
         };
 
         let device_name = device.get_friendlyname().unwrap_or_else(|_| "Unknown".to_string());
+        let is_input_device = is_capture_device(&device);
+        if config.is_loopback && is_input_device {
+            warn!("{} is an input device, capturing it directly instead of in loopback mode", device_name);
+        }
 
         // Get audio format
         let audio_client = device.get_iaudioclient()?;
@@ -186,7 +197,7 @@ impl AudioCapture {
 
         info!(
             "audio capture initialized: {} ({} Hz, {} ch) [loopback={}]",
-            device_name, sample_rate, channels, config.is_loopback
+            device_name, sample_rate, channels, !is_input_device
         );
 
         Ok(Self {
@@ -195,9 +206,15 @@ impl AudioCapture {
             device_name,
             sample_rate,
             channels,
+            is_input_device,
         })
     }
 
+    /// Whether audio comes from an input device (mic) rather than an output device (loopback)
+    pub fn is_input_device(&self) -> bool {
+        self.is_input_device
+    }
+
     /// Get device name
     pub fn device_name(&self) -> &str {
         &self.device_name
@@ -287,6 +304,9 @@ impl AudioCapture {
             enumerator.get_default_device(&Direction::Capture)?
         };
 
+        // Capturing from an output device is loopback, whichever way it was selected
+        let is_loopback = !is_capture_device(&device);
+
         let mut audio_client = device.get_iaudioclient()?;
         let device_format = audio_client.get_mixformat()?;
         let blockalign = device_format.get_blockalign() as usize;
@@ -300,7 +320,7 @@ impl AudioCapture {
 
         // For loopback: use polling mode (event mode doesn't work with AUDCLNT_STREAMFLAGS_LOOPBACK)
         // For regular capture: use event-driven mode
-        let (stream_mode, use_polling) = if config.is_loopback {
+        let (stream_mode, use_polling) = if is_loopback {
             (
                 StreamMode::PollingShared {
                     autoconvert: true,
@@ -331,7 +351,7 @@ impl AudioCapture {
         };
 
         audio_client.start_stream()?;
-        info!("audio capture started (loopback={}, polling={})", config.is_loopback, use_polling);
+        info!("audio capture started (loopback={}, polling={})", is_loopback, use_polling);
 
         // Calculate samples per chunk
         let samples_per_chunk = (config.chunk_duration_secs as usize * source_sample_rate as usize) as usize;
@@ -339,6 +359,13 @@ impl AudioCapture {
         let mut chunk_start_time = Utc::now();
         let mut raw_buffer: VecDeque<u8> = VecDeque::new();
 
+        // One resampler for the whole session keeps chunk boundaries seamless
+        let mut resampler = if source_sample_rate != config.target_sample_rate {
+            Some(Resampler::new(source_sample_rate, config.target_sample_rate)?)
+        } else {
+            None
+        };
+
         while running.load(Ordering::Acquire) {
             // Wait for audio data
             if use_polling {
@@ -380,10 +407,9 @@ impl AudioCapture {
                 // Convert to target format (mono, target sample rate)
                 let processed_samples = process_audio(
                     &chunk_samples,
-                    source_sample_rate,
                     source_channels,
-                    config.target_sample_rate,
                     config.target_channels,
+                    resampler.as_mut(),
                 );
 
                 let captured = CapturedAudio {
@@ -393,7 +419,7 @@ impl AudioCapture {
                     timestamp: chunk_start_time,
                     duration_secs: config.chunk_duration_secs as f32,
                     device_name: device_name.clone(),
-                    is_input_device: !config.is_loopback,
+                    is_input_device: !is_loopback,
                 };
 
                 // Send chunk
@@ -415,6 +441,11 @@ impl AudioCapture {
     }
 }
 
+/// Whether a WASAPI device records audio (microphone, line in) rather than plays it
+fn is_capture_device(device: &wasapi::Device) -> bool {
+    matches!(device.get_direction(), Direction::Capture)
+}
+
 /// Convert raw bytes to f32 samples based on format
 fn bytes_to_f32(data: &[u8], bits_per_sample: u16, sample_type: &Option<SampleType>) -> Vec<f32> {
     let is_float = matches!(sample_type, Some(SampleType::Float));
@@ -461,13 +492,13 @@ fn bytes_to_f32(data: &[u8], bits_per_sample: u16, sample_type: &Option<SampleTy
     }
 }
 
-/// Process audio: convert to mono and resample if needed
+/// Process audio: convert to mono and resample if needed.
+/// `resampler` is the session's stream resampler (None when rates match).
 fn process_audio(
     samples: &[f32],
-    source_rate: u32,
     source_channels: u16,
-    target_rate: u32,
     target_channels: u16,
+    resampler: Option<&mut Resampler>,
 ) -> Vec<f32> {
     // First convert to mono if needed
     let mono_samples = if source_channels > 1 && target_channels == 1 {
@@ -477,10 +508,9 @@ fn process_audio(
     };
 
     // Then resample if needed
-    if source_rate != target_rate {
-        resample(&mono_samples, source_rate, target_rate)
-    } else {
-        mono_samples
+    match resampler {
+        Some(resampler) => resampler.process(&mono_samples),
+        None => mono_samples,
     }
 }
 
@@ -496,57 +526,140 @@ pub fn to_mono(samples: &[f32], channels: u16) -> Vec<f32> {
         .collect()
 }
 
-/// Resample audio from source rate to target rate using rubato
-pub fn resample(samples: &[f32], source_rate: u32, target_rate: u32) -> Vec<f32> {
-    if source_rate == target_rate || samples.is_empty() {
-        return samples.to_vec();
+/// Block size fed to the FFT resampler
+const RESAMPLER_CHUNK_SIZE: usize = 1024;
+
+/// Stateful mono resampler for a continuous stream.
+///
+/// Created once per capture session and fed successive buffers. Input that
+/// does not fill a whole block is kept for the next call, so the stream is
+/// resampled without zero padding or filter restarts at buffer boundaries.
+/// Output lags input by [`Resampler::output_delay`] samples.
+pub struct Resampler {
+    inner: rubato::FftFixedInOut<f32>,
+    source_rate: u32,
+    target_rate: u32,
+    /// Input not yet processed (less than one block)
+    pending: Vec<f32>,
+    input_total: u64,
+    output_total: u64,
+}
+
+impl Resampler {
+    /// Create a resampler from `source_rate` to `target_rate`
+    pub fn new(source_rate: u32, target_rate: u32) -> Result<Self> {
+        let inner = rubato::FftFixedInOut::<f32>::new(
+            source_rate as usize,
+            target_rate as usize,
+            RESAMPLER_CHUNK_SIZE,
+            1, // mono
+        )
+        .context("failed to create resampler")?;
+
+        Ok(Self {
+            inner,
+            source_rate,
+            target_rate,
+            pending: Vec::new(),
+            input_total: 0,
+            output_total: 0,
+        })
     }
 
-    use rubato::{FftFixedInOut, Resampler};
-
-    // Use chunk size that divides evenly
-    let chunk_size = 1024;
-    let resampler_result = FftFixedInOut::<f32>::new(
-        source_rate as usize,
-        target_rate as usize,
-        chunk_size,
-        1, // mono
-    );
-
-    match resampler_result {
-        Ok(mut resampler) => {
-            let mut output = Vec::new();
-            let input_frames = resampler.input_frames_next();
-
-            // Process in chunks
-            for chunk in samples.chunks(input_frames) {
-                if chunk.len() < input_frames {
-                    // Pad last chunk with zeros
-                    let mut padded = chunk.to_vec();
-                    padded.resize(input_frames, 0.0);
-                    let input = vec![padded];
-                    if let Ok(result) = resampler.process(&input, None) {
-                        if !result.is_empty() {
-                            output.extend(&result[0]);
-                        }
-                    }
-                } else {
-                    let input = vec![chunk.to_vec()];
-                    if let Ok(result) = resampler.process(&input, None) {
-                        if !result.is_empty() {
-                            output.extend(&result[0]);
-                        }
+    /// Number of output samples the stream is delayed by
+    pub fn output_delay(&self) -> usize {
+        use rubato::Resampler as _;
+        self.inner.output_delay()
+    }
+
+    /// Feed the next buffer and return all output for completed blocks
+    pub fn process(&mut self, samples: &[f32]) -> Vec<f32> {
+        use rubato::Resampler as _;
+
+        self.input_total += samples.len() as u64;
+        self.pending.extend_from_slice(samples);
+
+        let block = self.inner.input_frames_next();
+        let mut output = Vec::with_capacity(
+            self.pending.len() * self.target_rate as usize / self.source_rate as usize + block,
+        );
+
+        let mut consumed = 0;
+        while self.pending.len() - consumed >= block {
+            let input = [&self.pending[consumed..consumed + block]];
+            match self.inner.process(&input, None) {
+                Ok(result) => {
+                    if let Some(channel) = result.first() {
+                        output.extend_from_slice(channel);
                     }
                 }
+                Err(e) => warn!("resampling failed: {}", e),
             }
+            consumed += block;
+        }
+        self.pending.drain(..consumed);
 
-            output
+        self.output_total += output.len() as u64;
+        output
+    }
+
+    /// End the stream: process buffered input padded with silence and emit the
+    /// delayed tail, so total output matches total input at the new rate
+    /// (plus the initial delay).
+    pub fn flush(&mut self) -> Vec<f32> {
+        use rubato::Resampler as _;
+
+        let expected_total = self.output_delay() as u64
+            + (self.input_total * self.target_rate as u64).div_ceil(self.source_rate as u64);
+
+        let block = self.inner.input_frames_next();
+        let silence = vec![0.0; block];
+        let mut output = Vec::new();
+
+        // Padding with silence is not counted as stream input
+        let input_total = self.input_total;
+        while self.output_total < expected_total {
+            let needed = block - self.pending.len() % block;
+            let produced = self.process(&silence[..needed]);
+            if produced.is_empty() {
+                break;
+            }
+            output.extend(produced);
         }
+        self.input_total = input_total;
+
+        // Drop output produced purely from padding
+        let excess = (self.output_total.saturating_sub(expected_total)) as usize;
+        output.truncate(output.len().saturating_sub(excess));
+        self.output_total = expected_total.min(self.output_total);
+        self.pending.clear();
+
+        output
+    }
+}
+
+/// Resample a complete buffer from source rate to target rate.
+///
+/// One-shot wrapper around [`Resampler`]: the output is aligned with the input
+/// (resampler delay removed) and has `len * target / source` samples.
+pub fn resample(samples: &[f32], source_rate: u32, target_rate: u32) -> Vec<f32> {
+    if source_rate == target_rate || samples.is_empty() {
+        return samples.to_vec();
+    }
+
+    let mut resampler = match Resampler::new(source_rate, target_rate) {
+        Ok(resampler) => resampler,
         Err(e) => {
-            warn!("failed to create resampler: {}", e);
-            samples.to_vec()
+            warn!("{:#}", e);
+            return samples.to_vec();
         }
-    }
+    };
+
+    let delay = resampler.output_delay();
+    let mut output = resampler.process(samples);
+    output.extend(resampler.flush());
+    output.drain(..delay.min(output.len()));
+    output
 }
 
 /// Save audio samples to a WAV file
@@ -583,32 +696,13 @@ pub fn save_wav(audio: &CapturedAudio, path: &PathBuf) -> Result<()> {
 
 /// Load audio samples from a WAV file
 pub fn load_wav(path: &PathBuf) -> Result<CapturedAudio> {
-    use hound::WavReader;
-
-    let reader = WavReader::open(path)
-        .context("Failed to open WAV file")?;
-
-    let spec = reader.spec();
-    let samples: Vec<f32> = match spec.sample_format {
-        hound::SampleFormat::Int => {
-            reader.into_samples::<i16>()
-                .filter_map(|s| s.ok())
-                .map(|s| s as f32 / i16::MAX as f32)
-                .collect()
-        }
-        hound::SampleFormat::Float => {
-            reader.into_samples::<f32>()
-                .filter_map(|s| s.ok())
-                .collect()
-        }
-    };
-
-    let duration_secs = samples.len() as f32 / spec.sample_rate as f32 / spec.channels as f32;
+    let wav = crate::wav::read_wav(path)?;
+    let duration_secs = wav.duration_secs() as f32;
 
     Ok(CapturedAudio {
-        samples,
-        sample_rate: spec.sample_rate,
-        channels: spec.channels,
+        samples: wav.samples,
+        sample_rate: wav.sample_rate,
+        channels: wav.channels,
         timestamp: Utc::now(),
         duration_secs,
         device_name: path.file_name()
@@ -639,6 +733,42 @@ mod tests {
         assert_eq!(result.len(), mono.len());
     }
 
+    fn sine(len: usize, rate: u32) -> Vec<f32> {
+        (0..len)
+            .map(|i| (2.0 * std::f32::consts::PI * 440.0 * i as f32 / rate as f32).sin() * 0.5)
+            .collect()
+    }
+
+    #[test]
+    fn test_resample_length() {
+        let input = sine(48_000, 48_000);
+        let output = resample(&input, 48_000, 16_000);
+        assert_eq!(output.len(), 16_000);
+
+        let input = sine(44_100, 44_100);
+        let output = resample(&input, 44_100, 16_000);
+        assert_eq!(output.len(), 16_000);
+    }
+
+    #[test]
+    fn test_resampler_stream_matches_one_shot() {
+        let input = sine(30_000, 48_000);
+
+        let mut whole = Resampler::new(48_000, 16_000).unwrap();
+        let mut expected = whole.process(&input);
+        expected.extend(whole.flush());
+
+        // Uneven buffer sizes must not change the output
+        let mut streamed = Resampler::new(48_000, 16_000).unwrap();
+        let mut output = Vec::new();
+        for part in input.chunks(777) {
+            output.extend(streamed.process(part));
+        }
+        output.extend(streamed.flush());
+
+        assert_eq!(output, expected);
+    }
+
     #[test]
     fn test_bytes_to_f32_16bit() {
         // i16::MAX as bytes
@@ -0,0 +1,215 @@
+//! Active browser URL detection via UI Automation
+//!
+//! Reads the address bar of the foreground browser window using the UI
+//! Automation `Value` pattern. Only Chromium-based browsers and Firefox are
+//! recognized; any other foreground window yields `None`.
+
+use anyhow::Result;
+use tracing::{debug, trace};
+use windows::{
+    core::{PWSTR, VARIANT},
+    Win32::{
+        Foundation::{CloseHandle, HWND},
+        System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_MULTITHREADED},
+        System::Threading::{
+            OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+            PROCESS_QUERY_LIMITED_INFORMATION,
+        },
+        UI::Accessibility::*,
+        UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowTextW, GetWindowThreadProcessId},
+    },
+};
+
+/// Executable names of supported browsers (lowercase)
+const KNOWN_BROWSERS: &[&str] = &["chrome.exe", "msedge.exe", "firefox.exe", "brave.exe"];
+
+/// Window title markers for private/incognito windows, which are never recorded
+const PRIVATE_WINDOW_MARKERS: &[&str] = &["InPrivate", "Incognito", "Private Browsing"];
+
+/// Reads the URL of the focused browser tab.
+///
+/// Holds a UI Automation client and caches the address bar element of the
+/// last seen browser window, since locating it requires a tree walk.
+pub struct BrowserUrlReader {
+    automation: IUIAutomation,
+    /// Foreground window handle and its address bar element
+    cached: Option<(isize, IUIAutomationElement)>,
+}
+
+impl BrowserUrlReader {
+    /// Create a reader. Initializes COM on the calling thread if needed.
+    pub fn new() -> Result<Self> {
+        unsafe {
+            // S_FALSE / RPC_E_CHANGED_MODE both mean COM is already usable on this thread
+            let hr = CoInitializeEx(None, COINIT_MULTITHREADED);
+            if hr.is_err() {
+                debug!("CoInitializeEx returned {:?}, continuing", hr);
+            }
+        }
+
+        let automation: IUIAutomation =
+            unsafe { CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER)? };
+
+        Ok(Self {
+            automation,
+            cached: None,
+        })
+    }
+
+    /// Get the URL of the foreground browser window, if any.
+    ///
+    /// Returns `None` when the foreground window is not a known browser, is a
+    /// private window, or the address bar could not be read.
+    pub fn current_url(&mut self) -> Option<String> {
+        let hwnd = unsafe { GetForegroundWindow() };
+        if hwnd.0.is_null() {
+            return None;
+        }
+
+        let process_name = foreground_process_name(hwnd)?;
+        if !is_browser_process(&process_name) {
+            self.cached = None;
+            return None;
+        }
+
+        if is_private_window(&window_title(hwnd)) {
+            trace!("skipping private browser window");
+            return None;
+        }
+
+        let key = hwnd.0 as isize;
+        let element = match &self.cached {
+            Some((cached_hwnd, element)) if *cached_hwnd == key => element.clone(),
+            _ => {
+                let element = self.find_address_bar(hwnd)?;
+                self.cached = Some((key, element.clone()));
+                element
+            }
+        };
+
+        let value = unsafe {
+            element
+                .GetCurrentPatternAs::<IUIAutomationValuePattern>(UIA_ValuePatternId)
+                .and_then(|pattern| pattern.CurrentValue())
+        };
+
+        match value {
+            Ok(value) => normalize_url(&value.to_string()),
+            Err(e) => {
+                // Element may be stale (window closed or UI rebuilt)
+                debug!("failed to read address bar: {}", e);
+                self.cached = None;
+                None
+            }
+        }
+    }
+
+    /// Locate the address bar (first edit control) within a browser window
+    fn find_address_bar(&self, hwnd: HWND) -> Option<IUIAutomationElement> {
+        unsafe {
+            let root = self.automation.ElementFromHandle(hwnd).ok()?;
+            let condition = self
+                .automation
+                .CreatePropertyCondition(
+                    UIA_ControlTypePropertyId,
+                    &VARIANT::from(UIA_EditControlTypeId.0),
+                )
+                .ok()?;
+            root.FindFirst(TreeScope_Descendants, &condition).ok()
+        }
+    }
+}
+
+/// Get the executable file name of the process owning a window
+fn foreground_process_name(hwnd: HWND) -> Option<String> {
+    unsafe {
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut pid));
+        if pid == 0 {
+            return None;
+        }
+
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut buffer = [0u16; 260];
+        let mut len = buffer.len() as u32;
+        let result = QueryFullProcessImageNameW(
+            process,
+            PROCESS_NAME_WIN32,
+            PWSTR(buffer.as_mut_ptr()),
+            &mut len,
+        );
+        let _ = CloseHandle(process);
+        result.ok()?;
+
+        let path = String::from_utf16_lossy(&buffer[..len as usize]);
+        path.rsplit(['\\', '/']).next().map(|name| name.to_string())
+    }
+}
+
+/// Get the title text of a window
+fn window_title(hwnd: HWND) -> String {
+    let mut buffer = [0u16; 512];
+    let len = unsafe { GetWindowTextW(hwnd, &mut buffer) };
+    String::from_utf16_lossy(&buffer[..len.max(0) as usize])
+}
+
+/// Check whether an executable name belongs to a supported browser
+fn is_browser_process(name: &str) -> bool {
+    let name = name.to_lowercase();
+    KNOWN_BROWSERS.contains(&name.as_str())
+}
+
+/// Check whether a browser window title indicates a private window
+fn is_private_window(title: &str) -> bool {
+    PRIVATE_WINDOW_MARKERS.iter().any(|marker| title.contains(marker))
+}
+
+/// Turn an address bar value into a URL.
+///
+/// Chromium hides the `https://` scheme in the omnibox, so it is restored
+/// when missing. Values containing whitespace are search text being typed,
+/// not URLs.
+fn normalize_url(value: &str) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() || value.contains(char::is_whitespace) {
+        return None;
+    }
+
+    if value.contains("://") || value.starts_with("about:") {
+        Some(value.to_string())
+    } else {
+        Some(format!("https://{}", value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_browser_process() {
+        assert!(is_browser_process("chrome.exe"));
+        assert!(is_browser_process("MSEdge.exe"));
+        assert!(is_browser_process("firefox.exe"));
+        assert!(!is_browser_process("explorer.exe"));
+    }
+
+    #[test]
+    fn test_is_private_window() {
+        assert!(is_private_window("New tab - [InPrivate] - Microsoft Edge"));
+        assert!(is_private_window("Mozilla Firefox Private Browsing"));
+        assert!(!is_private_window("docs.rs - Google Chrome"));
+    }
+
+    #[test]
+    fn test_normalize_url() {
+        assert_eq!(normalize_url("docs.rs/tokio"), Some("https://docs.rs/tokio".to_string()));
+        assert_eq!(
+            normalize_url("http://localhost:8080/"),
+            Some("http://localhost:8080/".to_string())
+        );
+        assert_eq!(normalize_url("about:blank"), Some("about:blank".to_string()));
+        assert_eq!(normalize_url("rust async book"), None);
+        assert_eq!(normalize_url("  "), None);
+    }
+}
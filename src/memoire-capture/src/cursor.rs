@@ -0,0 +1,228 @@
+//! Mouse cursor compositing for captured frames
+//!
+//! DXGI Desktop Duplication delivers the hardware cursor separately from the
+//! desktop image. This module keeps the last known pointer shape and draws it
+//! onto the RGBA frame buffer.
+
+/// Pointer shape format as reported by `DXGI_OUTDUPL_POINTER_SHAPE_INFO.Type`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CursorShapeType {
+    /// 1bpp AND mask followed by 1bpp XOR mask (height is doubled)
+    Monochrome,
+    /// 32bpp BGRA with per-pixel alpha
+    Color,
+    /// 32bpp BGRA where the alpha byte selects replace (0) or XOR (0xFF)
+    MaskedColor,
+}
+
+impl CursorShapeType {
+    /// Map the raw DXGI shape type value
+    pub fn from_dxgi(value: u32) -> Option<Self> {
+        match value {
+            1 => Some(Self::Monochrome),
+            2 => Some(Self::Color),
+            4 => Some(Self::MaskedColor),
+            _ => None,
+        }
+    }
+}
+
+/// Cursor shape bitmap
+#[derive(Debug, Clone)]
+pub struct CursorShape {
+    pub shape_type: CursorShapeType,
+    /// Width in pixels
+    pub width: u32,
+    /// Height in pixels (for monochrome cursors this includes both masks)
+    pub height: u32,
+    /// Bytes per row in `buffer`
+    pub pitch: u32,
+    /// Raw shape data as returned by `GetFramePointerShape`
+    pub buffer: Vec<u8>,
+}
+
+impl CursorShape {
+    /// Composite this cursor onto an RGBA buffer with its top-left corner at (x, y).
+    /// Pixels falling outside the frame are clipped.
+    pub fn draw(&self, frame: &mut [u8], frame_width: u32, frame_height: u32, x: i32, y: i32) {
+        let visible_height = match self.shape_type {
+            CursorShapeType::Monochrome => self.height / 2,
+            _ => self.height,
+        };
+
+        for row in 0..visible_height as i32 {
+            let fy = y + row;
+            if fy < 0 || fy >= frame_height as i32 {
+                continue;
+            }
+
+            for col in 0..self.width as i32 {
+                let fx = x + col;
+                if fx < 0 || fx >= frame_width as i32 {
+                    continue;
+                }
+
+                let dst = (fy as usize * frame_width as usize + fx as usize) * 4;
+                if dst + 3 >= frame.len() {
+                    continue;
+                }
+
+                match self.shape_type {
+                    CursorShapeType::Monochrome => self.draw_monochrome_pixel(
+                        &mut frame[dst..dst + 4],
+                        row as u32,
+                        col as u32,
+                        visible_height,
+                    ),
+                    CursorShapeType::Color => {
+                        self.draw_color_pixel(&mut frame[dst..dst + 4], row as u32, col as u32)
+                    }
+                    CursorShapeType::MaskedColor => {
+                        self.draw_masked_pixel(&mut frame[dst..dst + 4], row as u32, col as u32)
+                    }
+                }
+            }
+        }
+    }
+
+    fn mask_bit(&self, row: u32, col: u32) -> Option<bool> {
+        let idx = (row * self.pitch + col / 8) as usize;
+        self.buffer
+            .get(idx)
+            .map(|byte| byte & (0x80 >> (col % 8)) != 0)
+    }
+
+    fn bgra_at(&self, row: u32, col: u32) -> Option<[u8; 4]> {
+        let idx = (row * self.pitch + col * 4) as usize;
+        self.buffer
+            .get(idx..idx + 4)
+            .map(|p| [p[0], p[1], p[2], p[3]])
+    }
+
+    fn draw_monochrome_pixel(&self, dst: &mut [u8], row: u32, col: u32, mask_height: u32) {
+        let (Some(and), Some(xor)) = (
+            self.mask_bit(row, col),
+            self.mask_bit(row + mask_height, col),
+        ) else {
+            return;
+        };
+
+        match (and, xor) {
+            // Opaque black or white
+            (false, false) => dst[..3].copy_from_slice(&[0, 0, 0]),
+            (false, true) => dst[..3].copy_from_slice(&[0xFF, 0xFF, 0xFF]),
+            // Transparent
+            (true, false) => {}
+            // Invert the screen pixel
+            (true, true) => dst[..3].iter_mut().for_each(|c| *c = !*c),
+        }
+    }
+
+    fn draw_color_pixel(&self, dst: &mut [u8], row: u32, col: u32) {
+        let Some([b, g, r, a]) = self.bgra_at(row, col) else {
+            return;
+        };
+
+        let alpha = a as u32;
+        for (d, s) in dst[..3].iter_mut().zip([r, g, b]) {
+            *d = ((s as u32 * alpha + *d as u32 * (255 - alpha)) / 255) as u8;
+        }
+    }
+
+    fn draw_masked_pixel(&self, dst: &mut [u8], row: u32, col: u32) {
+        let Some([b, g, r, a]) = self.bgra_at(row, col) else {
+            return;
+        };
+
+        if a == 0 {
+            dst[..3].copy_from_slice(&[r, g, b]);
+        } else {
+            dst[0] ^= r;
+            dst[1] ^= g;
+            dst[2] ^= b;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid_frame(width: u32, height: u32, value: u8) -> Vec<u8> {
+        vec![value; (width * height * 4) as usize]
+    }
+
+    #[test]
+    fn test_monochrome_cursor() {
+        // 8x1 cursor: AND row then XOR row
+        // pixels 0-1: black, 2-3: white, 4-5: transparent, 6-7: invert
+        let shape = CursorShape {
+            shape_type: CursorShapeType::Monochrome,
+            width: 8,
+            height: 2,
+            pitch: 1,
+            buffer: vec![0b0000_1111, 0b0011_0011],
+        };
+
+        let mut frame = solid_frame(8, 1, 0x40);
+        shape.draw(&mut frame, 8, 1, 0, 0);
+
+        assert_eq!(&frame[0..3], &[0, 0, 0]);
+        assert_eq!(&frame[8..11], &[0xFF, 0xFF, 0xFF]);
+        assert_eq!(&frame[16..19], &[0x40, 0x40, 0x40]);
+        assert_eq!(&frame[24..27], &[0xBF, 0xBF, 0xBF]);
+    }
+
+    #[test]
+    fn test_color_cursor_converts_bgra() {
+        let shape = CursorShape {
+            shape_type: CursorShapeType::Color,
+            width: 1,
+            height: 1,
+            pitch: 4,
+            buffer: vec![0x10, 0x20, 0x30, 0xFF],
+        };
+
+        let mut frame = solid_frame(2, 2, 0);
+        shape.draw(&mut frame, 2, 2, 1, 1);
+
+        assert_eq!(&frame[12..15], &[0x30, 0x20, 0x10]);
+        assert_eq!(&frame[0..3], &[0, 0, 0]);
+    }
+
+    #[test]
+    fn test_masked_color_cursor() {
+        let shape = CursorShape {
+            shape_type: CursorShapeType::MaskedColor,
+            width: 2,
+            height: 1,
+            pitch: 8,
+            buffer: vec![0x00, 0x00, 0xFF, 0x00, 0xFF, 0xFF, 0xFF, 0xFF],
+        };
+
+        let mut frame = solid_frame(2, 1, 0x0F);
+        shape.draw(&mut frame, 2, 1, 0, 0);
+
+        // Replace with red, then XOR with white
+        assert_eq!(&frame[0..3], &[0xFF, 0x00, 0x00]);
+        assert_eq!(&frame[4..7], &[0xF0, 0xF0, 0xF0]);
+    }
+
+    #[test]
+    fn test_cursor_clipped_at_edges() {
+        let shape = CursorShape {
+            shape_type: CursorShapeType::Color,
+            width: 2,
+            height: 2,
+            pitch: 8,
+            buffer: vec![0xFF; 16],
+        };
+
+        let mut frame = solid_frame(2, 2, 0);
+        shape.draw(&mut frame, 2, 2, -1, -1);
+
+        assert_eq!(&frame[0..3], &[0xFF, 0xFF, 0xFF]);
+        assert_eq!(&frame[4..7], &[0, 0, 0]);
+        assert_eq!(&frame[8..11], &[0, 0, 0]);
+    }
+}
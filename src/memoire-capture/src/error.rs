@@ -4,6 +4,7 @@ use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum CaptureError {
+    #[cfg(windows)]
     #[error("windows error: {0}")]
     Windows(#[from] windows::core::Error),
 
@@ -28,6 +29,9 @@ pub enum CaptureError {
     #[error("device removed or reset")]
     DeviceRemoved,
 
+    #[error("hotkey error: {0}")]
+    Hotkey(String),
+
     #[error("image error: {0}")]
     Image(#[from] image::ImageError),
 }
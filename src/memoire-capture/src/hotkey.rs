@@ -0,0 +1,243 @@
+//! Global hotkeys
+//!
+//! Hotkeys are written as `+`-separated modifiers and a key, e.g.
+//! `ctrl+shift+s` or `alt+f9`. Parsing is portable; [`HotkeyListener`]
+//! registers the combination with `RegisterHotKey` on a thread of its own,
+//! which receives `WM_HOTKEY` whichever window has focus.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::CaptureError;
+
+/// `RegisterHotKey` modifier flags
+const MOD_ALT: u32 = 0x0001;
+const MOD_CONTROL: u32 = 0x0002;
+const MOD_SHIFT: u32 = 0x0004;
+const MOD_WIN: u32 = 0x0008;
+
+/// A key combination to register as a global hotkey
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hotkey {
+    /// `MOD_*` flags
+    pub modifiers: u32,
+    /// Virtual-key code
+    pub key: u32,
+}
+
+impl FromStr for Hotkey {
+    type Err = CaptureError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| CaptureError::Hotkey(format!("invalid hotkey '{}': {}", s, reason));
+
+        let mut modifiers = 0;
+        let mut key = None;
+        for part in s.split('+').map(|p| p.trim().to_ascii_lowercase()) {
+            let modifier = match part.as_str() {
+                "ctrl" | "control" => Some(MOD_CONTROL),
+                "shift" => Some(MOD_SHIFT),
+                "alt" => Some(MOD_ALT),
+                "win" | "super" => Some(MOD_WIN),
+                _ => None,
+            };
+            match (modifier, key) {
+                (Some(flag), None) => modifiers |= flag,
+                (None, None) => {
+                    key = Some(virtual_key(&part).ok_or_else(|| invalid(&format!("unknown key '{}'", part)))?)
+                }
+                (_, Some(_)) => return Err(invalid("the key must come last")),
+            }
+        }
+
+        let key = key.ok_or_else(|| invalid("no key given"))?;
+        if modifiers == 0 {
+            return Err(invalid("at least one of ctrl, shift, alt or win is required"));
+        }
+        Ok(Self { modifiers, key })
+    }
+}
+
+impl fmt::Display for Hotkey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (flag, name) in [(MOD_CONTROL, "ctrl"), (MOD_SHIFT, "shift"), (MOD_ALT, "alt"), (MOD_WIN, "win")] {
+            if self.modifiers & flag != 0 {
+                write!(f, "{}+", name)?;
+            }
+        }
+        match key_name(self.key) {
+            Some(name) => write!(f, "{}", name),
+            None => write!(f, "0x{:02x}", self.key),
+        }
+    }
+}
+
+/// Named keys and their virtual-key codes
+const NAMED_KEYS: &[(&str, u32)] = &[
+    ("space", 0x20),
+    ("pageup", 0x21),
+    ("pagedown", 0x22),
+    ("end", 0x23),
+    ("home", 0x24),
+    ("printscreen", 0x2C),
+    ("insert", 0x2D),
+    ("delete", 0x2E),
+    ("pause", 0x13),
+];
+
+/// Virtual-key code of a lowercase key name: a letter, digit, F1-F24 or a named key
+fn virtual_key(name: &str) -> Option<u32> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        if c.is_ascii_alphanumeric() {
+            // Letters and digits use their uppercase ASCII code
+            return Some(c.to_ascii_uppercase() as u32);
+        }
+    }
+    if let Some(n) = name.strip_prefix('f').and_then(|n| n.parse::<u32>().ok()) {
+        if (1..=24).contains(&n) {
+            return Some(0x70 + n - 1);
+        }
+    }
+    NAMED_KEYS.iter().find(|(n, _)| *n == name).map(|(_, vk)| *vk)
+}
+
+fn key_name(vk: u32) -> Option<String> {
+    match vk {
+        0x30..=0x39 | 0x41..=0x5A => Some(((vk as u8) as char).to_ascii_lowercase().to_string()),
+        0x70..=0x87 => Some(format!("f{}", vk - 0x70 + 1)),
+        _ => NAMED_KEYS.iter().find(|(_, k)| *k == vk).map(|(n, _)| n.to_string()),
+    }
+}
+
+#[cfg(windows)]
+pub use listener::HotkeyListener;
+
+#[cfg(windows)]
+mod listener {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{mpsc, Arc};
+    use std::thread::{self, JoinHandle};
+    use tracing::{debug, warn};
+    use windows::Win32::{
+        Foundation::{HWND, LPARAM, WPARAM},
+        System::Threading::GetCurrentThreadId,
+        UI::Input::KeyboardAndMouse::{RegisterHotKey, UnregisterHotKey, HOT_KEY_MODIFIERS, MOD_NOREPEAT},
+        UI::WindowsAndMessaging::{GetMessageW, PeekMessageW, PostThreadMessageW, MSG, PM_NOREMOVE, WM_HOTKEY, WM_QUIT},
+    };
+
+    use super::Hotkey;
+    use crate::error::CaptureError;
+
+    /// Identifier of the hotkey on its thread (each listener has its own thread)
+    const HOTKEY_ID: i32 = 1;
+
+    /// A registered global hotkey, unregistered on drop
+    pub struct HotkeyListener {
+        thread_id: u32,
+        thread: Option<JoinHandle<()>>,
+    }
+
+    impl HotkeyListener {
+        /// Register `hotkey`, setting `pressed` each time it is hit
+        ///
+        /// Fails when the combination is already taken by another application.
+        pub fn register(hotkey: Hotkey, pressed: Arc<AtomicBool>) -> Result<Self, CaptureError> {
+            let (ready_tx, ready_rx) = mpsc::channel();
+
+            let thread = thread::Builder::new()
+                .name("hotkey".to_string())
+                .spawn(move || {
+                    // SAFETY: the message loop only touches this thread's queue and a local MSG
+                    unsafe {
+                        let mut msg = MSG::default();
+                        // Create the message queue before the thread can be asked to quit
+                        let _ = PeekMessageW(&mut msg, HWND::default(), 0, 0, PM_NOREMOVE);
+
+                        let modifiers = HOT_KEY_MODIFIERS(hotkey.modifiers) | MOD_NOREPEAT;
+                        if let Err(e) = RegisterHotKey(HWND::default(), HOTKEY_ID, modifiers, hotkey.key) {
+                            let _ = ready_tx.send(Err(e.to_string()));
+                            return;
+                        }
+                        let _ = ready_tx.send(Ok(GetCurrentThreadId()));
+
+                        while GetMessageW(&mut msg, HWND::default(), 0, 0).0 > 0 {
+                            if msg.message == WM_HOTKEY && msg.wParam.0 == HOTKEY_ID as usize {
+                                debug!("hotkey {} pressed", hotkey);
+                                pressed.store(true, Ordering::SeqCst);
+                            }
+                        }
+
+                        let _ = UnregisterHotKey(HWND::default(), HOTKEY_ID);
+                    }
+                })
+                .map_err(|e| CaptureError::Hotkey(format!("failed to start hotkey thread: {}", e)))?;
+
+            let thread_id = match ready_rx.recv() {
+                Ok(Ok(thread_id)) => thread_id,
+                Ok(Err(e)) => {
+                    let _ = thread.join();
+                    return Err(CaptureError::Hotkey(format!("{} could not be registered: {}", hotkey, e)));
+                }
+                Err(_) => {
+                    let _ = thread.join();
+                    return Err(CaptureError::Hotkey(format!("{} could not be registered", hotkey)));
+                }
+            };
+
+            Ok(Self {
+                thread_id,
+                thread: Some(thread),
+            })
+        }
+    }
+
+    impl Drop for HotkeyListener {
+        fn drop(&mut self) {
+            // SAFETY: posting to a thread id has no memory safety requirements
+            if let Err(e) = unsafe { PostThreadMessageW(self.thread_id, WM_QUIT, WPARAM(0), LPARAM(0)) } {
+                warn!("failed to stop hotkey thread: {}", e);
+                return;
+            }
+            if let Some(thread) = self.thread.take() {
+                let _ = thread.join();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hotkey() {
+        let hotkey: Hotkey = "ctrl+shift+s".parse().unwrap();
+        assert_eq!(hotkey, Hotkey { modifiers: MOD_CONTROL | MOD_SHIFT, key: 'S' as u32 });
+
+        let hotkey: Hotkey = " Alt + F9 ".parse().unwrap();
+        assert_eq!(hotkey, Hotkey { modifiers: MOD_ALT, key: 0x78 });
+
+        let hotkey: Hotkey = "win+printscreen".parse().unwrap();
+        assert_eq!(hotkey.key, 0x2C);
+        assert_eq!(hotkey.to_string(), "win+printscreen");
+    }
+
+    #[test]
+    fn test_parse_hotkey_errors() {
+        assert!("s".parse::<Hotkey>().is_err(), "a key needs a modifier");
+        assert!("ctrl+shift".parse::<Hotkey>().is_err(), "no key");
+        assert!("ctrl+s+shift".parse::<Hotkey>().is_err(), "key not last");
+        assert!("ctrl+f25".parse::<Hotkey>().is_err());
+        assert!("ctrl+clip".parse::<Hotkey>().is_err());
+    }
+
+    #[test]
+    fn test_display_round_trips() {
+        for text in ["ctrl+shift+s", "alt+f12", "ctrl+alt+0", "shift+win+pause"] {
+            let hotkey: Hotkey = text.parse().unwrap();
+            assert_eq!(hotkey.to_string(), text);
+            assert_eq!(hotkey.to_string().parse::<Hotkey>().unwrap(), hotkey);
+        }
+    }
+}
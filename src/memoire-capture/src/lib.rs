@@ -1,14 +1,37 @@
 //! memoire-capture - Screen and audio capture for Memoire
 //!
 //! Provides DXGI Desktop Duplication for screen capture
-//! and WASAPI for audio capture.
+//! and WASAPI for audio capture, plus the WAV decoder shared
+//! with speech-to-text. Capture is Windows-only; on other
+//! platforms only the portable modules are built.
 
+#[cfg(windows)]
 pub mod screen;
+pub mod cursor;
+pub mod phash;
+#[cfg(windows)]
 pub mod monitor;
 pub mod error;
+pub mod wav;
+pub mod hotkey;
+#[cfg(windows)]
 pub mod audio;
+#[cfg(windows)]
+pub mod browser;
+#[cfg(windows)]
+pub mod session;
 
+#[cfg(windows)]
 pub use screen::ScreenCapture;
+#[cfg(windows)]
 pub use monitor::{Monitor, MonitorInfo};
 pub use error::CaptureError;
-pub use audio::{AudioCapture, AudioCaptureConfig, AudioDeviceInfo, CapturedAudio, save_wav, load_wav};
+pub use hotkey::Hotkey;
+#[cfg(windows)]
+pub use hotkey::HotkeyListener;
+#[cfg(windows)]
+pub use browser::BrowserUrlReader;
+#[cfg(windows)]
+pub use session::{idle_duration, is_session_locked};
+#[cfg(windows)]
+pub use audio::{AudioCapture, AudioCaptureConfig, AudioDeviceInfo, CapturedAudio, Resampler, resample, save_wav, load_wav};
@@ -0,0 +1,176 @@
+//! DCT-based perceptual hashing (pHash) of RGBA frames
+//!
+//! The frame is reduced to a 32x32 grayscale image, transformed with a 2D
+//! DCT-II, and the 8x8 lowest-frequency coefficients are compared to their
+//! median. Low frequencies capture the layout of the screen rather than fine
+//! detail, and a uniform brightness change only moves the DC coefficient, so
+//! the hash is stable under dimming while still separating different content.
+
+use std::f64::consts::PI;
+use std::sync::OnceLock;
+
+/// Side of the grayscale image the DCT runs on
+const SAMPLE_SIZE: usize = 32;
+
+/// Side of the low-frequency coefficient block kept for the hash (64 bits)
+const HASH_SIZE: usize = 8;
+
+/// Compute a 64-bit pHash of an RGBA buffer
+///
+/// Bit `v * 8 + u` is set when DCT coefficient (u, v) is above the median of
+/// the 64 kept coefficients. Compare hashes with a Hamming distance.
+pub fn perceptual_hash(data: &[u8], width: u32, height: u32) -> u64 {
+    let (width, height) = (width as usize, height as usize);
+    if width == 0 || height == 0 || data.len() < width * height * 4 {
+        return 0;
+    }
+
+    let pixels = downscale_grayscale(data, width, height);
+    let coefficients = low_frequency_dct(&pixels);
+
+    let mut sorted = coefficients;
+    sorted.sort_by(f64::total_cmp);
+    let median = (sorted[31] + sorted[32]) / 2.0;
+
+    coefficients
+        .iter()
+        .enumerate()
+        .filter(|(_, &c)| c > median)
+        .fold(0u64, |hash, (i, _)| hash | (1u64 << i))
+}
+
+/// Area-average the frame down to SAMPLE_SIZE x SAMPLE_SIZE luma values
+///
+/// Frames smaller than the sample grid repeat their pixels.
+fn downscale_grayscale(data: &[u8], width: usize, height: usize) -> [f64; SAMPLE_SIZE * SAMPLE_SIZE] {
+    let mut pixels = [0.0; SAMPLE_SIZE * SAMPLE_SIZE];
+
+    for sy in 0..SAMPLE_SIZE {
+        let y0 = sy * height / SAMPLE_SIZE;
+        let y1 = ((sy + 1) * height / SAMPLE_SIZE).max(y0 + 1).min(height);
+
+        for sx in 0..SAMPLE_SIZE {
+            let x0 = sx * width / SAMPLE_SIZE;
+            let x1 = ((sx + 1) * width / SAMPLE_SIZE).max(x0 + 1).min(width);
+
+            let mut sum = 0u64;
+            for y in y0..y1 {
+                let row = &data[(y * width + x0) * 4..(y * width + x1) * 4];
+                for px in row.chunks_exact(4) {
+                    // ITU-R BT.601 luma, scaled by 1000
+                    sum += px[0] as u64 * 299 + px[1] as u64 * 587 + px[2] as u64 * 114;
+                }
+            }
+
+            let count = ((y1 - y0) * (x1 - x0)) as f64;
+            pixels[sy * SAMPLE_SIZE + sx] = sum as f64 / (count * 1000.0);
+        }
+    }
+
+    pixels
+}
+
+/// 2D DCT-II of the sample grid, keeping only the HASH_SIZE x HASH_SIZE
+/// lowest frequencies (row-major by vertical frequency)
+fn low_frequency_dct(pixels: &[f64; SAMPLE_SIZE * SAMPLE_SIZE]) -> [f64; HASH_SIZE * HASH_SIZE] {
+    let cos = cosine_table();
+
+    // Rows first: horizontal frequencies u for every sample row
+    let mut rows = [[0.0; HASH_SIZE]; SAMPLE_SIZE];
+    for (y, row) in rows.iter_mut().enumerate() {
+        for (u, out) in row.iter_mut().enumerate() {
+            *out = (0..SAMPLE_SIZE)
+                .map(|x| pixels[y * SAMPLE_SIZE + x] * cos[u][x])
+                .sum();
+        }
+    }
+
+    // Then columns: vertical frequencies v
+    let mut coefficients = [0.0; HASH_SIZE * HASH_SIZE];
+    for v in 0..HASH_SIZE {
+        for u in 0..HASH_SIZE {
+            coefficients[v * HASH_SIZE + u] = (0..SAMPLE_SIZE)
+                .map(|y| rows[y][u] * cos[v][y])
+                .sum();
+        }
+    }
+
+    coefficients
+}
+
+/// cos((2n + 1) * k * pi / 2N) for the kept frequencies k
+fn cosine_table() -> &'static [[f64; SAMPLE_SIZE]; HASH_SIZE] {
+    static TABLE: OnceLock<[[f64; SAMPLE_SIZE]; HASH_SIZE]> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let mut table = [[0.0; SAMPLE_SIZE]; HASH_SIZE];
+        for (k, row) in table.iter_mut().enumerate() {
+            for (n, value) in row.iter_mut().enumerate() {
+                *value = ((2 * n + 1) as f64 * k as f64 * PI / (2 * SAMPLE_SIZE) as f64).cos();
+            }
+        }
+        table
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(width: u32, height: u32, pixel: impl Fn(u32, u32) -> u8) -> Vec<u8> {
+        let mut data = Vec::with_capacity((width * height * 4) as usize);
+        for y in 0..height {
+            for x in 0..width {
+                let v = pixel(x, y);
+                data.extend_from_slice(&[v, v, v, 0xFF]);
+            }
+        }
+        data
+    }
+
+    /// A window-like layout: dark background with a bright panel and text rows
+    fn layout(x: u32, y: u32) -> u8 {
+        let panel = (40..140).contains(&x) && (20..100).contains(&y);
+        let text = panel && y % 12 < 4 && x % 9 < 6;
+        match (panel, text) {
+            (true, true) => 60,
+            (true, false) => 200,
+            _ => 30,
+        }
+    }
+
+    fn distance(a: u64, b: u64) -> u32 {
+        (a ^ b).count_ones()
+    }
+
+    #[test]
+    fn test_identical_frames_match() {
+        let a = perceptual_hash(&frame(192, 108, layout), 192, 108);
+        let b = perceptual_hash(&frame(192, 108, layout), 192, 108);
+        assert_eq!(a, b);
+        assert_ne!(a, 0);
+    }
+
+    #[test]
+    fn test_brightness_shift_is_ignored() {
+        let base = perceptual_hash(&frame(192, 108, layout), 192, 108);
+        let dimmed = perceptual_hash(&frame(192, 108, |x, y| layout(x, y) - 25), 192, 108);
+        assert!(distance(base, dimmed) <= 1, "distance {}", distance(base, dimmed));
+    }
+
+    #[test]
+    fn test_different_layouts_differ() {
+        let base = perceptual_hash(&frame(192, 108, layout), 192, 108);
+        // Same content with the panel moved to the other side of the screen
+        let moved = perceptual_hash(&frame(192, 108, |x, y| layout(191 - x, y)), 192, 108);
+        assert!(distance(base, moved) > 10, "distance {}", distance(base, moved));
+    }
+
+    #[test]
+    fn test_small_and_empty_frames() {
+        let tiny = frame(3, 2, |x, y| (x * 80 + y * 40) as u8);
+        assert_eq!(perceptual_hash(&tiny, 3, 2), perceptual_hash(&tiny, 3, 2));
+        assert_eq!(perceptual_hash(&[], 0, 0), 0);
+        // Truncated buffers are rejected rather than read out of bounds
+        assert_eq!(perceptual_hash(&tiny[..8], 3, 2), 0);
+    }
+}
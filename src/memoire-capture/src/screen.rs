@@ -15,6 +15,7 @@ use windows::{
     },
 };
 
+use crate::cursor::{CursorShape, CursorShapeType};
 use crate::error::CaptureError;
 use crate::monitor::Monitor;
 
@@ -40,66 +41,11 @@ impl CapturedFrame {
         Ok(())
     }
 
-    /// Compute a 64-bit perceptual hash (average hash) for deduplication.
-    /// Downsamples the image to 8x8, converts to grayscale, and compares
-    /// each pixel to the mean brightness to produce a 64-bit fingerprint.
+    /// Compute a 64-bit DCT perceptual hash (pHash) for deduplication.
+    /// See [`crate::phash`]; hashes stored before pHash replaced the average
+    /// hash are not comparable with new ones.
     pub fn compute_perceptual_hash(&self) -> u64 {
-        const HASH_SIZE: usize = 8;
-
-        // Calculate block sizes
-        let block_w = self.width as usize / HASH_SIZE;
-        let block_h = self.height as usize / HASH_SIZE;
-
-        if block_w == 0 || block_h == 0 {
-            // Image too small, return hash of raw data
-            return self.data.iter().fold(0u64, |acc, &b| acc.wrapping_add(b as u64));
-        }
-
-        // Compute average grayscale value for each 8x8 block
-        let mut block_values = [0u64; HASH_SIZE * HASH_SIZE];
-
-        for by in 0..HASH_SIZE {
-            for bx in 0..HASH_SIZE {
-                let mut sum = 0u64;
-                let mut count = 0u32;
-
-                // Sample pixels in this block
-                let start_y = by * block_h;
-                let start_x = bx * block_w;
-                let end_y = std::cmp::min(start_y + block_h, self.height as usize);
-                let end_x = std::cmp::min(start_x + block_w, self.width as usize);
-
-                for y in start_y..end_y {
-                    for x in start_x..end_x {
-                        let idx = (y * self.width as usize + x) * 4;
-                        if idx + 2 < self.data.len() {
-                            // Convert RGB to grayscale using luminance formula
-                            let r = self.data[idx] as u64;
-                            let g = self.data[idx + 1] as u64;
-                            let b = self.data[idx + 2] as u64;
-                            sum += (r * 299 + g * 587 + b * 114) / 1000;
-                            count += 1;
-                        }
-                    }
-                }
-
-                block_values[by * HASH_SIZE + bx] = if count > 0 { sum / count as u64 } else { 0 };
-            }
-        }
-
-        // Calculate mean of all blocks
-        let total: u64 = block_values.iter().sum();
-        let mean = total / (HASH_SIZE * HASH_SIZE) as u64;
-
-        // Build hash: 1 if above mean, 0 if below
-        let mut hash = 0u64;
-        for (i, &value) in block_values.iter().enumerate() {
-            if value >= mean {
-                hash |= 1u64 << i;
-            }
-        }
-
-        hash
+        crate::phash::perceptual_hash(&self.data, self.width, self.height)
     }
 
     /// Calculate the Hamming distance between two hashes (number of differing bits).
@@ -117,6 +63,12 @@ pub struct ScreenCapture {
     width: u32,
     height: u32,
     staging_texture: Option<ID3D11Texture2D>,
+    /// Whether to draw the mouse cursor onto captured frames
+    capture_cursor: bool,
+    /// Last pointer shape reported by the duplication API
+    cursor_shape: Option<CursorShape>,
+    /// Last pointer position (None when the cursor is hidden)
+    cursor_position: Option<(i32, i32)>,
 }
 
 impl ScreenCapture {
@@ -169,6 +121,9 @@ impl ScreenCapture {
             width,
             height,
             staging_texture: None,
+            capture_cursor: true,
+            cursor_shape: None,
+            cursor_position: None,
         })
     }
 
@@ -204,6 +159,10 @@ impl ScreenCapture {
             "no resource returned".to_string(),
         ))?;
 
+        if self.capture_cursor {
+            self.update_cursor(&frame_info);
+        }
+
         // Get the texture from the resource
         let desktop_texture: ID3D11Texture2D = desktop_resource.cast()?;
 
@@ -291,6 +250,12 @@ impl ScreenCapture {
             self.duplication.ReleaseFrame()?;
         }
 
+        if self.capture_cursor {
+            if let (Some(shape), Some((x, y))) = (&self.cursor_shape, self.cursor_position) {
+                shape.draw(&mut data, self.width, self.height, x, y);
+            }
+        }
+
         Ok(Some(CapturedFrame {
             data,
             width: self.width,
@@ -304,6 +269,71 @@ impl ScreenCapture {
         (self.width, self.height)
     }
 
+    /// Enable or disable drawing the mouse cursor onto captured frames
+    pub fn set_capture_cursor(&mut self, enabled: bool) {
+        self.capture_cursor = enabled;
+    }
+
+    /// Whether the mouse cursor is drawn onto captured frames
+    pub fn capture_cursor(&self) -> bool {
+        self.capture_cursor
+    }
+
+    /// Update cached cursor position and shape from the latest frame info.
+    /// Must be called before the frame is released.
+    fn update_cursor(&mut self, frame_info: &DXGI_OUTDUPL_FRAME_INFO) {
+        // A zero update time means the pointer did not change since the last frame
+        if frame_info.LastMouseUpdateTime == 0 {
+            return;
+        }
+
+        let pointer = frame_info.PointerPosition;
+        self.cursor_position = if pointer.Visible.as_bool() {
+            Some((pointer.Position.x, pointer.Position.y))
+        } else {
+            None
+        };
+
+        if frame_info.PointerShapeBufferSize == 0 {
+            return;
+        }
+
+        let mut buffer = vec![0u8; frame_info.PointerShapeBufferSize as usize];
+        let mut required = 0u32;
+        let mut shape_info = DXGI_OUTDUPL_POINTER_SHAPE_INFO::default();
+
+        let result = unsafe {
+            self.duplication.GetFramePointerShape(
+                buffer.len() as u32,
+                buffer.as_mut_ptr() as *mut _,
+                &mut required,
+                &mut shape_info,
+            )
+        };
+
+        if let Err(e) = result {
+            warn!("failed to get pointer shape: {}", e);
+            return;
+        }
+
+        match CursorShapeType::from_dxgi(shape_info.Type) {
+            Some(shape_type) => {
+                trace!(
+                    "pointer shape updated: {:?} {}x{}",
+                    shape_type, shape_info.Width, shape_info.Height
+                );
+                self.cursor_shape = Some(CursorShape {
+                    shape_type,
+                    width: shape_info.Width,
+                    height: shape_info.Height,
+                    pitch: shape_info.Pitch,
+                    buffer,
+                });
+            }
+            None => debug!("unsupported pointer shape type: {}", shape_info.Type),
+        }
+    }
+
     fn get_or_create_staging_texture(&mut self) -> Result<ID3D11Texture2D> {
         if let Some(ref texture) = self.staging_texture {
             return Ok(texture.clone());
@@ -0,0 +1,118 @@
+//! Workstation lock and idle detection
+//!
+//! While the session is locked, input goes to the secure Winlogon desktop.
+//! Desktop Duplication cannot capture it and returns black frames or access
+//! denied errors, so callers should pause capture until the user is back.
+//!
+//! While unlocked, the time since the last keyboard or mouse input tells
+//! whether the user is actively working or away from an unlocked screen.
+
+use std::time::Duration;
+use tracing::trace;
+use windows::Win32::{
+    Foundation::HANDLE,
+    System::StationsAndDesktops::{
+        CloseDesktop, GetUserObjectInformationW, OpenInputDesktop, DESKTOP_CONTROL_FLAGS,
+        DESKTOP_READOBJECTS, HDESK, UOI_NAME,
+    },
+    System::SystemInformation::GetTickCount,
+    UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO},
+};
+
+/// Name of the interactive user desktop
+const USER_DESKTOP: &str = "Default";
+
+/// Check whether the workstation is locked
+///
+/// Also reports `true` while another secure desktop (such as a UAC prompt)
+/// has input, since it cannot be captured either.
+pub fn is_session_locked() -> bool {
+    // SAFETY: the desktop handle is only used for the name query and closed before returning
+    unsafe {
+        // A normal user process cannot open the secure desktop, so failing to
+        // open the input desktop means it is not the user's
+        let desktop = match OpenInputDesktop(DESKTOP_CONTROL_FLAGS(0), false, DESKTOP_READOBJECTS) {
+            Ok(desktop) => desktop,
+            Err(e) => {
+                trace!("input desktop not accessible: {}", e);
+                return true;
+            }
+        };
+
+        let name = desktop_name(desktop);
+        let _ = CloseDesktop(desktop);
+
+        match name {
+            Some(name) => !is_user_desktop(&name),
+            None => false,
+        }
+    }
+}
+
+/// Time since the last keyboard or mouse input in this session
+///
+/// Returns zero if the input time cannot be read, so failures count as activity.
+pub fn idle_duration() -> Duration {
+    let mut info = LASTINPUTINFO {
+        cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+        dwTime: 0,
+    };
+
+    // SAFETY: info is a properly sized LASTINPUTINFO owned by this frame
+    let ok = unsafe { GetLastInputInfo(&mut info) }.as_bool();
+    if !ok {
+        trace!("GetLastInputInfo failed");
+        return Duration::ZERO;
+    }
+
+    // SAFETY: GetTickCount has no preconditions
+    let now = unsafe { GetTickCount() };
+    Duration::from_millis(ticks_since(now, info.dwTime) as u64)
+}
+
+/// Milliseconds between two tick counts, across the 49.7 day wraparound
+fn ticks_since(now: u32, then: u32) -> u32 {
+    now.wrapping_sub(then)
+}
+
+/// Read a desktop's name
+unsafe fn desktop_name(desktop: HDESK) -> Option<String> {
+    let mut buffer = [0u16; 64];
+    let mut needed = 0u32;
+
+    GetUserObjectInformationW(
+        HANDLE(desktop.0),
+        UOI_NAME,
+        Some(buffer.as_mut_ptr().cast()),
+        std::mem::size_of_val(&buffer) as u32,
+        Some(&mut needed),
+    )
+    .ok()?;
+
+    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    Some(String::from_utf16_lossy(&buffer[..len]))
+}
+
+/// Whether a desktop name is the interactive user desktop
+fn is_user_desktop(name: &str) -> bool {
+    name.eq_ignore_ascii_case(USER_DESKTOP)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_user_desktop() {
+        assert!(is_user_desktop("Default"));
+        assert!(is_user_desktop("default"));
+        assert!(!is_user_desktop("Winlogon"));
+        assert!(!is_user_desktop("Screen-saver"));
+    }
+
+    #[test]
+    fn test_ticks_since_wraps() {
+        assert_eq!(ticks_since(5_000, 2_000), 3_000);
+        assert_eq!(ticks_since(1_000, u32::MAX - 999), 2_000);
+    }
+}
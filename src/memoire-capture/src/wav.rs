@@ -0,0 +1,283 @@
+//! WAV decoding to normalized f32 samples
+//!
+//! Shared by `load_wav` and the speech-to-text loader so every WAV file is
+//! scaled the same way whatever the device recorded: 8-bit unsigned, 16, 24
+//! and 32-bit signed integer, and 32 or 64-bit IEEE float PCM, with plain or
+//! WAVE_FORMAT_EXTENSIBLE headers. Integer samples are divided by
+//! 2^(bits - 1), so full scale maps to [-1.0, 1.0).
+
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+
+const FORMAT_PCM: u16 = 1;
+const FORMAT_IEEE_FLOAT: u16 = 3;
+const FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+/// Decoded WAV audio
+#[derive(Debug, Clone)]
+pub struct DecodedWav {
+    /// Interleaved samples normalized to [-1.0, 1.0]
+    pub samples: Vec<f32>,
+    /// Sample rate in Hz
+    pub sample_rate: u32,
+    /// Number of interleaved channels
+    pub channels: u16,
+    /// Container size of one sample in the file
+    pub bits_per_sample: u16,
+}
+
+impl DecodedWav {
+    /// Duration in seconds
+    pub fn duration_secs(&self) -> f64 {
+        if self.sample_rate == 0 || self.channels == 0 {
+            return 0.0;
+        }
+        self.samples.len() as f64 / self.channels as f64 / self.sample_rate as f64
+    }
+
+    /// Average the channels into a single mono signal
+    pub fn to_mono(&self) -> Vec<f32> {
+        let channels = self.channels.max(1) as usize;
+        if channels == 1 {
+            return self.samples.clone();
+        }
+        self.samples
+            .chunks_exact(channels)
+            .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+            .collect()
+    }
+}
+
+/// Read and decode a WAV file
+pub fn read_wav(path: &Path) -> Result<DecodedWav> {
+    let bytes = std::fs::read(path).with_context(|| format!("failed to open WAV file {:?}", path))?;
+    decode_wav(&bytes).with_context(|| format!("failed to decode WAV file {:?}", path))
+}
+
+/// Decode an in-memory WAV file
+///
+/// A data chunk whose declared size runs past the end of the buffer (left by a
+/// writer that was interrupted) is read up to the last complete sample.
+pub fn decode_wav(bytes: &[u8]) -> Result<DecodedWav> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        bail!("not a RIFF/WAVE file");
+    }
+
+    let mut format = None;
+    let mut data = None;
+    let mut pos = 12;
+
+    while pos + 8 <= bytes.len() {
+        let id = &bytes[pos..pos + 4];
+        let size = u32_at(bytes, pos + 4) as usize;
+        let body_start = pos + 8;
+        let body_end = body_start.saturating_add(size).min(bytes.len());
+        let body = &bytes[body_start..body_end];
+
+        match id {
+            b"fmt " => format = Some(parse_format(body)?),
+            b"data" => data = Some(body),
+            _ => {}
+        }
+
+        // Chunks are padded to an even size
+        pos = body_start.saturating_add(size).saturating_add(size & 1);
+    }
+
+    let format = format.context("missing fmt chunk")?;
+    let data = data.context("missing data chunk")?;
+
+    if format.channels == 0 {
+        bail!("WAV file has no channels");
+    }
+
+    let samples = match (format.tag, format.bits_per_sample) {
+        (FORMAT_PCM, 8) => data.iter().map(|&b| (b as f32 - 128.0) / 128.0).collect(),
+        (FORMAT_PCM, 16) => data
+            .chunks_exact(2)
+            .map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32_768.0)
+            .collect(),
+        (FORMAT_PCM, 24) => data
+            .chunks_exact(3)
+            // Place the sample in the top bytes of an i32 to sign-extend it
+            .map(|b| (i32::from_le_bytes([0, b[0], b[1], b[2]]) >> 8) as f32 / 8_388_608.0)
+            .collect(),
+        (FORMAT_PCM, 32) => data
+            .chunks_exact(4)
+            .map(|b| (i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64 / 2_147_483_648.0) as f32)
+            .collect(),
+        (FORMAT_IEEE_FLOAT, 32) => data
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect(),
+        (FORMAT_IEEE_FLOAT, 64) => data
+            .chunks_exact(8)
+            .map(|b| f64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]) as f32)
+            .collect(),
+        (tag, bits) => bail!("unsupported WAV encoding (format {:#06x}, {} bits)", tag, bits),
+    };
+
+    Ok(DecodedWav {
+        samples,
+        sample_rate: format.sample_rate,
+        channels: format.channels,
+        bits_per_sample: format.bits_per_sample,
+    })
+}
+
+struct Format {
+    tag: u16,
+    channels: u16,
+    sample_rate: u32,
+    bits_per_sample: u16,
+}
+
+fn parse_format(body: &[u8]) -> Result<Format> {
+    if body.len() < 16 {
+        bail!("fmt chunk too short ({} bytes)", body.len());
+    }
+
+    let mut tag = u16_at(body, 0);
+    // WAVE_FORMAT_EXTENSIBLE stores the real format in the first two bytes of
+    // the sub-format GUID
+    if tag == FORMAT_EXTENSIBLE {
+        if body.len() < 26 {
+            bail!("extensible fmt chunk too short ({} bytes)", body.len());
+        }
+        tag = u16_at(body, 24);
+    }
+
+    Ok(Format {
+        tag,
+        channels: u16_at(body, 2),
+        sample_rate: u32_at(body, 4),
+        bits_per_sample: u16_at(body, 14),
+    })
+}
+
+fn u16_at(bytes: &[u8], pos: usize) -> u16 {
+    u16::from_le_bytes([bytes[pos], bytes[pos + 1]])
+}
+
+fn u32_at(bytes: &[u8], pos: usize) -> u32 {
+    u32::from_le_bytes([bytes[pos], bytes[pos + 1], bytes[pos + 2], bytes[pos + 3]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a WAV file around raw sample bytes
+    fn wav(tag: u16, channels: u16, bits: u16, data: &[u8], extensible: bool) -> Vec<u8> {
+        let sample_rate = 16_000u32;
+        let block_align = channels * bits / 8;
+
+        let mut fmt = Vec::new();
+        fmt.extend_from_slice(&(if extensible { FORMAT_EXTENSIBLE } else { tag }).to_le_bytes());
+        fmt.extend_from_slice(&channels.to_le_bytes());
+        fmt.extend_from_slice(&sample_rate.to_le_bytes());
+        fmt.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+        fmt.extend_from_slice(&block_align.to_le_bytes());
+        fmt.extend_from_slice(&bits.to_le_bytes());
+        if extensible {
+            fmt.extend_from_slice(&22u16.to_le_bytes());
+            fmt.extend_from_slice(&bits.to_le_bytes());
+            fmt.extend_from_slice(&0u32.to_le_bytes());
+            fmt.extend_from_slice(&tag.to_le_bytes());
+            fmt.extend_from_slice(&[0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71]);
+        }
+
+        let mut out = Vec::new();
+        out.extend_from_slice(b"RIFF");
+        out.extend_from_slice(&((4 + 8 + fmt.len() + 8 + data.len()) as u32).to_le_bytes());
+        out.extend_from_slice(b"WAVE");
+        out.extend_from_slice(b"fmt ");
+        out.extend_from_slice(&(fmt.len() as u32).to_le_bytes());
+        out.extend_from_slice(&fmt);
+        out.extend_from_slice(b"data");
+        out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        out.extend_from_slice(data);
+        out
+    }
+
+    fn assert_samples(actual: &[f32], expected: &[f32]) {
+        assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-4, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_8_bit_unsigned() {
+        let decoded = decode_wav(&wav(FORMAT_PCM, 1, 8, &[0, 128, 192, 255], false)).unwrap();
+        assert_samples(&decoded.samples, &[-1.0, 0.0, 0.5, 127.0 / 128.0]);
+    }
+
+    #[test]
+    fn test_16_bit() {
+        let data: Vec<u8> = [i16::MIN, 0, 16_384, i16::MAX].iter().flat_map(|s| s.to_le_bytes()).collect();
+        let decoded = decode_wav(&wav(FORMAT_PCM, 1, 16, &data, false)).unwrap();
+        assert_samples(&decoded.samples, &[-1.0, 0.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn test_24_bit() {
+        let data: Vec<u8> = [-8_388_608i32, -4_194_304, 0, 4_194_304, 8_388_607]
+            .iter()
+            .flat_map(|s| s.to_le_bytes()[..3].to_vec())
+            .collect();
+        let decoded = decode_wav(&wav(FORMAT_PCM, 1, 24, &data, false)).unwrap();
+        // 24-bit full scale must not be read as 16-bit (far out of range) or quiet
+        assert_samples(&decoded.samples, &[-1.0, -0.5, 0.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn test_32_bit_int() {
+        let data: Vec<u8> = [i32::MIN, -1_073_741_824, 0, i32::MAX].iter().flat_map(|s| s.to_le_bytes()).collect();
+        let decoded = decode_wav(&wav(FORMAT_PCM, 1, 32, &data, false)).unwrap();
+        assert_samples(&decoded.samples, &[-1.0, -0.5, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_32_bit_float() {
+        let data: Vec<u8> = [-1.0f32, -0.25, 0.0, 0.75].iter().flat_map(|s| s.to_le_bytes()).collect();
+        let decoded = decode_wav(&wav(FORMAT_IEEE_FLOAT, 1, 32, &data, false)).unwrap();
+        assert_samples(&decoded.samples, &[-1.0, -0.25, 0.0, 0.75]);
+    }
+
+    #[test]
+    fn test_64_bit_float() {
+        let data: Vec<u8> = [-0.5f64, 0.0, 0.125, 1.0].iter().flat_map(|s| s.to_le_bytes()).collect();
+        let decoded = decode_wav(&wav(FORMAT_IEEE_FLOAT, 1, 64, &data, false)).unwrap();
+        assert_samples(&decoded.samples, &[-0.5, 0.0, 0.125, 1.0]);
+    }
+
+    #[test]
+    fn test_extensible_header() {
+        // WASAPI shared mode writes 32-bit float in an extensible header
+        let data: Vec<u8> = [0.5f32, -0.5, 0.25, 0.75].iter().flat_map(|s| s.to_le_bytes()).collect();
+        let decoded = decode_wav(&wav(FORMAT_IEEE_FLOAT, 2, 32, &data, true)).unwrap();
+        assert_eq!(decoded.channels, 2);
+        assert_eq!(decoded.sample_rate, 16_000);
+        assert_samples(&decoded.to_mono(), &[0.0, 0.5]);
+        assert!((decoded.duration_secs() - 2.0 / 16_000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_truncated_data_chunk() {
+        let data: Vec<u8> = [1000i16, 2000, 3000].iter().flat_map(|s| s.to_le_bytes()).collect();
+        let mut bytes = wav(FORMAT_PCM, 1, 16, &data, false);
+        // Drop the last byte: the final sample is incomplete
+        bytes.pop();
+        let decoded = decode_wav(&bytes).unwrap();
+        assert_eq!(decoded.samples.len(), 2);
+    }
+
+    #[test]
+    fn test_unsupported_and_invalid() {
+        assert!(decode_wav(&wav(FORMAT_PCM, 1, 12, &[0; 4], false)).is_err());
+        assert!(decode_wav(&wav(0x0055, 1, 16, &[0; 4], false)).is_err());
+        assert!(decode_wav(b"RIFF\0\0\0\0AVI ").is_err());
+        assert!(decode_wav(&[]).is_err());
+    }
+}
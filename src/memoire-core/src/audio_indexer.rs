@@ -2,7 +2,7 @@
 
 use anyhow::Result;
 use chrono::{DateTime, Utc};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -10,14 +10,18 @@ use tokio::sync::{broadcast, RwLock};
 use tracing::{debug, error, info, warn};
 
 use memoire_db::Database;
-use memoire_stt::{SttConfig, SttEngine};
+use memoire_stt::{SttConfig, SttEngine, SttError, SttWorker, TranscriptionResult};
 
 use crate::recorder::ChunkFinalizedEvent;
 
-/// Audio indexer batch settings
-const AUDIO_BATCH_SIZE: i64 = 5;
+/// Default audio indexer batch settings
+pub const DEFAULT_AUDIO_BATCH_SIZE: i64 = 5;
 /// Default maximum chunks to process per second
 const DEFAULT_CHUNKS_PER_SEC: f64 = 2.0;
+/// Default number of engines used by [`transcribe_backlog`]
+pub const DEFAULT_BACKLOG_CONCURRENCY: usize = 2;
+/// Backlog chunks handed to each engine per batch (engines are reloaded between batches)
+const BACKLOG_CHUNKS_PER_WORKER: usize = 25;
 
 /// Statistics for audio transcription processing
 #[derive(Debug, Clone)]
@@ -32,9 +36,10 @@ pub struct AudioIndexerStats {
 /// Audio Indexer that transcribes audio chunks in background
 pub struct AudioIndexer {
     db: Database,
-    stt_engine: SttEngine,
+    stt: SttWorker,
     data_dir: PathBuf,
     chunks_per_sec: f64,
+    batch_size: i64,
     running: Arc<AtomicBool>,
     stats: Arc<RwLock<AudioIndexerStats>>,
     processed_count: Arc<AtomicU64>,
@@ -42,27 +47,29 @@ pub struct AudioIndexer {
 }
 
 impl AudioIndexer {
-    /// Create a new audio indexer
-    pub fn new(data_dir: PathBuf, use_gpu: bool) -> Result<Self> {
+    /// Create a new audio indexer (`batch_size` falls back to the module default)
+    ///
+    /// `stt_config` carries the GPU and threading options; its model directory
+    /// is replaced with the one under `data_dir`.
+    pub fn new(data_dir: PathBuf, stt_config: SttConfig, batch_size: Option<i64>) -> Result<Self> {
         info!("initializing audio indexer");
 
         let db_path = data_dir.join("memoire.db");
-        let db = Database::open(&db_path)?;
+        let db = crate::config::open_database(&db_path)?;
         info!("database opened at {:?}", db_path);
 
         // Create STT engine
         let stt_config = SttConfig {
-            model_dir: memoire_stt::default_model_dir(),
-            use_gpu,
-            language: None, // Auto-detect
-            num_threads: 4,
+            model_dir: crate::config::model_dir(&data_dir),
+            ..stt_config
         };
 
-        let stt_engine = SttEngine::new(stt_config)?;
+        // The engine lives on its own thread so transcription never blocks the runtime
+        let stt = SttEngine::spawn_worker(stt_config)?;
         info!(
-            "STT engine initialized (GPU: {}, model loaded: {})",
-            stt_engine.is_gpu_enabled(),
-            stt_engine.is_model_loaded()
+            "STT engine initialized (provider: {}, model loaded: {})",
+            stt.active_provider(),
+            stt.is_model_loaded()
         );
 
         let stats = AudioIndexerStats {
@@ -75,9 +82,10 @@ impl AudioIndexer {
 
         Ok(Self {
             db,
-            stt_engine,
+            stt,
             data_dir,
             chunks_per_sec: DEFAULT_CHUNKS_PER_SEC,
+            batch_size: batch_size.unwrap_or(DEFAULT_AUDIO_BATCH_SIZE).max(1),
             running: Arc::new(AtomicBool::new(true)), // Start as running
             stats: Arc::new(RwLock::new(stats)),
             processed_count: Arc::new(AtomicU64::new(0)),
@@ -228,7 +236,7 @@ impl AudioIndexer {
         // Query audio chunks without transcription
         let chunks = memoire_db::get_audio_chunks_without_transcription(
             self.db.connection(),
-            AUDIO_BATCH_SIZE,
+            self.batch_size,
         )?;
 
         if chunks.is_empty() {
@@ -251,78 +259,9 @@ impl AudioIndexer {
                 continue;
             }
 
-            // Transcribe the audio file (blocking operation - run in thread pool)
-            let audio_path_clone = audio_path.clone();
-            let transcribe_result = tokio::task::spawn_blocking(move || {
-                // Create a temporary STT engine for this thread
-                // Note: We can't share the engine across threads easily
-                let stt_config = SttConfig {
-                    model_dir: memoire_stt::default_model_dir(),
-                    use_gpu: false, // Use CPU for thread pool tasks
-                    language: None,
-                    num_threads: 1,
-                };
-                let engine = SttEngine::new(stt_config)?;
-                engine.transcribe_file(&audio_path_clone)
-            }).await;
-
-            match transcribe_result {
-                Ok(Ok(result)) => {
-                    // Insert transcription segments
-                    for segment in &result.segments {
-                        let new_transcription = memoire_db::NewAudioTranscription {
-                            audio_chunk_id: chunk.id,
-                            transcription: segment.text.clone(),
-                            timestamp: chunk.timestamp,
-                            speaker_id: None,
-                            start_time: Some(segment.start),
-                            end_time: Some(segment.end),
-                        };
-                        memoire_db::insert_audio_transcription(
-                            self.db.connection(),
-                            &new_transcription,
-                        )?;
-                    }
-
-                    // If no segments, insert the full text as a single transcription
-                    if result.segments.is_empty() && !result.text.is_empty() {
-                        let new_transcription = memoire_db::NewAudioTranscription {
-                            audio_chunk_id: chunk.id,
-                            transcription: result.text.clone(),
-                            timestamp: chunk.timestamp,
-                            speaker_id: None,
-                            start_time: None,
-                            end_time: None,
-                        };
-                        memoire_db::insert_audio_transcription(
-                            self.db.connection(),
-                            &new_transcription,
-                        )?;
-                    } else if result.segments.is_empty() {
-                        // Insert empty transcription to mark as processed
-                        self.insert_empty_transcription(chunk.id)?;
-                    }
-
-                    info!(
-                        "transcribed chunk {}: '{}' ({} chars, {} segments, {}ms)",
-                        chunk.id,
-                        if result.text.len() > 100 { &result.text[..100] } else { &result.text },
-                        result.text.len(),
-                        result.segments.len(),
-                        result.processing_time_ms
-                    );
-                }
-                Ok(Err(e)) => {
-                    warn!("failed to transcribe chunk {}: {}", chunk.id, e);
-                    // Insert empty transcription to mark as processed
-                    self.insert_empty_transcription(chunk.id)?;
-                }
-                Err(e) => {
-                    warn!("task join error transcribing chunk {}: {}", chunk.id, e);
-                    // Insert empty transcription to mark as processed
-                    self.insert_empty_transcription(chunk.id)?;
-                }
-            }
+            // Transcribe the audio file on the STT worker thread
+            let result = self.stt.transcribe_file(audio_path).await;
+            store_transcription(&self.db, chunk, result)?;
 
             processed_count += 1;
         }
@@ -334,16 +273,7 @@ impl AudioIndexer {
 
     /// Insert an empty transcription to mark a chunk as processed
     fn insert_empty_transcription(&self, chunk_id: i64) -> Result<()> {
-        let new_transcription = memoire_db::NewAudioTranscription {
-            audio_chunk_id: chunk_id,
-            transcription: String::new(),
-            timestamp: Utc::now(),
-            speaker_id: None,
-            start_time: None,
-            end_time: None,
-        };
-        memoire_db::insert_audio_transcription(self.db.connection(), &new_transcription)?;
-        Ok(())
+        insert_empty_transcription(&self.db, chunk_id)
     }
 
     /// Update statistics
@@ -360,3 +290,142 @@ impl AudioIndexer {
         Ok(())
     }
 }
+
+/// Transcribe every pending audio chunk with `concurrency` engines, then return
+///
+/// For catching up on a large backlog: unlike [`AudioIndexer::run`], chunks
+/// are transcribed in parallel. Stops between batches once `shutdown` is set.
+/// Returns the number of chunks processed.
+pub async fn transcribe_backlog(
+    data_dir: &Path,
+    stt_config: SttConfig,
+    concurrency: usize,
+    shutdown: Arc<AtomicBool>,
+) -> Result<usize> {
+    let db = crate::config::open_database(&data_dir.join("memoire.db"))?;
+    let stt_config = SttConfig {
+        model_dir: crate::config::model_dir(data_dir),
+        ..stt_config
+    };
+
+    // Failed chunks are marked as processed, so never start without a model
+    let downloader = memoire_stt::ModelDownloader::new(stt_config.model_dir.clone());
+    if !downloader.is_complete() {
+        return Err(anyhow::anyhow!(
+            "speech-to-text model not found in {:?}, run 'memoire download-models' first",
+            stt_config.model_dir
+        ));
+    }
+
+    let concurrency = concurrency.max(1);
+    let page_size = (concurrency * BACKLOG_CHUNKS_PER_WORKER) as i64;
+    let pending = memoire_db::get_audio_stats(db.connection())?.pending_chunks;
+    info!("transcribing backlog of {} audio chunks with {} engines", pending, concurrency);
+
+    let started = Instant::now();
+    let mut processed = 0;
+
+    while !shutdown.load(Ordering::Relaxed) {
+        let chunks = memoire_db::get_audio_chunks_without_transcription(db.connection(), page_size)?;
+        if chunks.is_empty() {
+            break;
+        }
+
+        let (present, missing): (Vec<_>, Vec<_>) = chunks
+            .into_iter()
+            .partition(|chunk| data_dir.join(&chunk.file_path).exists());
+
+        for chunk in &missing {
+            warn!("audio file not found: {:?}", data_dir.join(&chunk.file_path));
+            insert_empty_transcription(&db, chunk.id)?;
+        }
+
+        let files = present.iter().map(|chunk| data_dir.join(&chunk.file_path)).collect();
+        let results = memoire_stt::transcribe_batch(files, stt_config.clone(), concurrency).await;
+        for (chunk, (_, result)) in present.iter().zip(results) {
+            store_transcription(&db, chunk, result)?;
+        }
+
+        processed += present.len() + missing.len();
+        info!(
+            "backlog progress: {}/{} chunks ({:.2} chunks/sec)",
+            processed,
+            pending,
+            processed as f64 / started.elapsed().as_secs_f64()
+        );
+    }
+
+    Ok(processed)
+}
+
+/// Store the transcription of a chunk, or mark it processed when it failed or was silent
+fn store_transcription(
+    db: &Database,
+    chunk: &memoire_db::AudioChunk,
+    result: Result<TranscriptionResult, SttError>,
+) -> Result<()> {
+    let result = match result {
+        Ok(result) => result,
+        Err(e) => {
+            warn!("failed to transcribe chunk {}: {}", chunk.id, e);
+            // Insert empty transcription to mark as processed
+            return insert_empty_transcription(db, chunk.id);
+        }
+    };
+
+    // Insert transcription segments
+    for segment in &result.segments {
+        let new_transcription = memoire_db::NewAudioTranscription {
+            audio_chunk_id: chunk.id,
+            transcription: segment.text.clone(),
+            timestamp: chunk.timestamp,
+            speaker_id: None,
+            start_time: Some(segment.start),
+            end_time: Some(segment.end),
+            language: result.language.clone(),
+        };
+        memoire_db::insert_audio_transcription(db.connection(), &new_transcription)?;
+    }
+
+    // If no segments, insert the full text as a single transcription
+    if result.segments.is_empty() && !result.text.is_empty() {
+        let new_transcription = memoire_db::NewAudioTranscription {
+            audio_chunk_id: chunk.id,
+            transcription: result.text.clone(),
+            timestamp: chunk.timestamp,
+            speaker_id: None,
+            start_time: None,
+            end_time: None,
+            language: result.language.clone(),
+        };
+        memoire_db::insert_audio_transcription(db.connection(), &new_transcription)?;
+    } else if result.segments.is_empty() {
+        // Insert empty transcription to mark as processed
+        insert_empty_transcription(db, chunk.id)?;
+    }
+
+    info!(
+        "transcribed chunk {}: '{}' ({} chars, {} segments, {}ms)",
+        chunk.id,
+        result.text.chars().take(100).collect::<String>(),
+        result.text.len(),
+        result.segments.len(),
+        result.processing_time_ms
+    );
+    Ok(())
+}
+
+/// Insert an empty transcription to mark a chunk as processed
+fn insert_empty_transcription(db: &Database, chunk_id: i64) -> Result<()> {
+    let new_transcription = memoire_db::NewAudioTranscription {
+        audio_chunk_id: chunk_id,
+        transcription: String::new(),
+        timestamp: Utc::now(),
+        speaker_id: None,
+        start_time: None,
+        end_time: None,
+        language: None,
+    };
+    memoire_db::insert_audio_transcription(db.connection(), &new_transcription)?;
+    Ok(())
+}
@@ -0,0 +1,311 @@
+//! Throughput self-test (`memoire bench`)
+//!
+//! Measures screen capture, OCR and speech-to-text speed on this machine and
+//! compares them with the rates a recording session would need, so users can
+//! tell whether the indexers will keep up before recording all day.
+
+use anyhow::Result;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use memoire_capture::{Monitor, ScreenCapture};
+use memoire_ocr::{FrameData, Processor as OcrProcessor};
+use memoire_stt::{SttConfig, SttEngine};
+
+/// Distinct captured frames kept for the OCR benchmark (cycled through)
+const OCR_SAMPLE_FRAMES: usize = 5;
+
+/// Length of the synthetic clip transcribed when no WAV is given
+const SYNTHETIC_AUDIO_SECS: usize = 30;
+
+/// Timeout for a single capture attempt
+const CAPTURE_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Benchmark settings
+pub struct BenchOptions {
+    pub data_dir: PathBuf,
+    pub monitor: usize,
+    /// Frames to capture
+    pub frames: u32,
+    /// OCR passes to time
+    pub ocr_frames: usize,
+    pub ocr_language: Option<String>,
+    /// Recording framerate to check against
+    pub fps: f64,
+    /// Indexer OCR rate limit to check against
+    pub ocr_fps: u32,
+    /// Audio clip to transcribe (synthetic audio when None)
+    pub wav: Option<PathBuf>,
+    pub use_gpu: bool,
+    pub gpu_device: i32,
+    pub stt_threads: usize,
+}
+
+/// Run every benchmark and print a summary
+pub async fn run(options: BenchOptions) -> Result<()> {
+    let monitors = Monitor::enumerate_all()?;
+    let monitor_count = monitors.len().max(1);
+    // Without deduplication every captured frame needs OCR
+    let required_fps = options.fps * monitor_count as f64;
+
+    println!(
+        "target: {} FPS on {} monitor(s) = {:.2} frames/sec to capture and index\n",
+        options.fps, monitor_count, required_fps
+    );
+
+    let (capture_fps, samples) = match bench_capture(&options, monitors) {
+        Ok(result) => result,
+        Err(e) => {
+            println!("  capture: FAILED ({})\n", e);
+            (None, Vec::new())
+        }
+    };
+
+    let ocr_fps = match bench_ocr(&options, samples).await {
+        Ok(fps) => fps,
+        Err(e) => {
+            println!("  ocr: FAILED ({})\n", e);
+            None
+        }
+    };
+
+    let (cpu_rtf, gpu_rtf) = bench_stt(&options);
+
+    println!("summary:");
+    report(
+        "capture",
+        capture_fps.map(|fps| fps / required_fps),
+        capture_fps.map(|fps| format!("{:.1} frames/sec (need {:.2})", fps, required_fps)),
+    );
+    // The indexer never runs faster than its configured rate limit
+    let ocr_effective = ocr_fps.map(|fps| fps.min(options.ocr_fps as f64));
+    report(
+        "ocr",
+        ocr_effective.map(|fps| fps / required_fps),
+        ocr_effective.map(|fps| format!("{:.1} frames/sec (need {:.2})", fps, required_fps)),
+    );
+    report(
+        "stt (cpu)",
+        cpu_rtf,
+        cpu_rtf.map(|rtf| format!("{:.1}x realtime (need 1x)", rtf)),
+    );
+    if options.use_gpu {
+        report(
+            "stt (gpu)",
+            gpu_rtf,
+            gpu_rtf.map(|rtf| format!("{:.1}x realtime (need 1x)", rtf)),
+        );
+    }
+
+    Ok(())
+}
+
+/// Print one summary line; `headroom` >= 1.0 means the stage keeps up
+fn report(stage: &str, headroom: Option<f64>, detail: Option<String>) {
+    match (headroom, detail) {
+        (Some(headroom), Some(detail)) => {
+            let verdict = if headroom >= 1.0 { "keeps up" } else { "FALLS BEHIND" };
+            println!("  {:<10} {:<13} {}", stage, verdict, detail);
+        }
+        _ => println!("  {:<10} {:<13}", stage, "not measured"),
+    }
+}
+
+/// Time capture and hashing of `options.frames` frames
+///
+/// Returns the sustainable capture rate and a few frames for the OCR benchmark.
+/// DXGI only returns a frame when the screen changed, so attempts that time
+/// out on a static screen are excluded from the rate.
+fn bench_capture(options: &BenchOptions, monitors: Vec<memoire_capture::MonitorInfo>) -> Result<(Option<f64>, Vec<FrameData>)> {
+    let info = monitors.get(options.monitor).cloned().ok_or_else(|| {
+        anyhow::anyhow!("monitor {} not found ({} available)", options.monitor, monitors.len())
+    })?;
+
+    println!("capture: {} frames from [{}] {} ({}x{})", options.frames, options.monitor, info.name, info.width, info.height);
+    if memoire_capture::is_session_locked() {
+        println!("  warning: the session is locked, the lock screen cannot be captured");
+    }
+
+    let monitor = Monitor::from_info(info)?;
+    let mut capture = ScreenCapture::new(&monitor)
+        .map_err(|e| anyhow::anyhow!("failed to start desktop duplication: {}", e))?;
+
+    let mut samples = Vec::new();
+    let mut captured = 0u32;
+    let mut timeouts = 0u32;
+    let mut busy = Duration::ZERO;
+
+    // Static screens produce no frames; give up after as many empty attempts
+    while captured < options.frames && timeouts < options.frames {
+        let started = Instant::now();
+        match capture.capture_frame(CAPTURE_TIMEOUT)? {
+            Some(frame) => {
+                frame.compute_perceptual_hash();
+                busy += started.elapsed();
+                captured += 1;
+
+                if samples.len() < OCR_SAMPLE_FRAMES && captured % (options.frames / OCR_SAMPLE_FRAMES as u32).max(1) == 0 {
+                    samples.push(FrameData {
+                        width: frame.width,
+                        height: frame.height,
+                        data: frame.data,
+                    });
+                }
+            }
+            None => timeouts += 1,
+        }
+    }
+
+    if captured == 0 {
+        println!("  no frames captured (screen unchanged); move a window and try again\n");
+        return Ok((None, samples));
+    }
+
+    let fps = captured as f64 / busy.as_secs_f64();
+    println!(
+        "  {} frames, {:.1} ms per frame (capture + hash) = {:.1} frames/sec",
+        captured,
+        busy.as_secs_f64() * 1000.0 / captured as f64,
+        fps
+    );
+    if timeouts > 0 {
+        println!("  {} attempts found the screen unchanged (not counted)", timeouts);
+    }
+    println!();
+
+    Ok((Some(fps), samples))
+}
+
+/// Time OCR over the sampled frames
+async fn bench_ocr(options: &BenchOptions, samples: Vec<FrameData>) -> Result<Option<f64>> {
+    println!("ocr: {} frames", options.ocr_frames);
+    if samples.is_empty() || options.ocr_frames == 0 {
+        println!("  skipped (no frames to recognize)\n");
+        return Ok(None);
+    }
+
+    let processor = match &options.ocr_language {
+        Some(languages) => OcrProcessor::with_languages(&memoire_ocr::parse_language_list(languages))?,
+        None => OcrProcessor::new()?,
+    };
+
+    let started = Instant::now();
+    let mut lines = 0;
+    for i in 0..options.ocr_frames {
+        let sample = &samples[i % samples.len()];
+        let frame = FrameData {
+            width: sample.width,
+            height: sample.height,
+            data: sample.data.clone(),
+        };
+        lines += processor.process_frame(frame).await?.lines.len();
+    }
+    let elapsed = started.elapsed().as_secs_f64();
+
+    let fps = options.ocr_frames as f64 / elapsed;
+    println!(
+        "  {:.1} ms per frame = {:.1} frames/sec ({} lines recognized)\n",
+        elapsed * 1000.0 / options.ocr_frames as f64,
+        fps,
+        lines
+    );
+    Ok(Some(fps))
+}
+
+/// Transcribe the sample on CPU and (when enabled) GPU, returning realtime factors
+fn bench_stt(options: &BenchOptions) -> (Option<f64>, Option<f64>) {
+    let model_dir = crate::config::model_dir(&options.data_dir);
+    if memoire_stt::has_bundled_onnx_runtime(&model_dir) {
+        if let Err(e) = memoire_stt::configure_onnx_runtime(&model_dir) {
+            println!("stt: {}\n", e);
+        }
+    }
+
+    let source = match &options.wav {
+        Some(path) => path.display().to_string(),
+        None => format!("{} s synthetic clip (no speech; timing is representative)", SYNTHETIC_AUDIO_SECS),
+    };
+    println!("stt: {}", source);
+
+    let config = SttConfig {
+        model_dir,
+        num_threads: options.stt_threads.max(1),
+        gpu_device: options.gpu_device,
+        ..SttConfig::default()
+    };
+
+    let cpu = bench_stt_device(options, config.clone().cpu_only(), "cpu");
+    let gpu = if options.use_gpu {
+        bench_stt_device(options, config, "gpu")
+    } else {
+        None
+    };
+    println!();
+
+    (cpu, gpu)
+}
+
+fn bench_stt_device(options: &BenchOptions, config: SttConfig, label: &str) -> Option<f64> {
+    let use_gpu = config.wants_gpu();
+    let mut engine = match SttEngine::new(config) {
+        Ok(engine) => engine,
+        Err(e) => {
+            println!("  {}: failed to load model ({})", label, e);
+            return None;
+        }
+    };
+    if !engine.is_model_loaded() {
+        println!("  {}: model not found, run 'memoire download-models' first", label);
+        return None;
+    }
+    if use_gpu && !engine.active_provider().is_gpu() {
+        println!("  {}: no GPU execution provider available", label);
+        return None;
+    }
+
+    let started = Instant::now();
+    let (audio_secs, result) = match &options.wav {
+        Some(path) => match memoire_stt::wav_duration_secs(path) {
+            Ok(secs) => (secs, engine.transcribe_file(path)),
+            Err(e) => {
+                println!("  {}: failed to read {} ({})", label, path.display(), e);
+                return None;
+            }
+        },
+        None => {
+            let samples = synthetic_audio(SYNTHETIC_AUDIO_SECS);
+            (SYNTHETIC_AUDIO_SECS as f64, engine.transcribe_samples(&samples, memoire_stt::SAMPLE_RATE))
+        }
+    };
+    let elapsed = started.elapsed().as_secs_f64();
+
+    if let Err(e) = result {
+        println!("  {}: transcription failed ({})", label, e);
+        return None;
+    }
+
+    let rtf = audio_secs / elapsed;
+    println!(
+        "  {} ({}): {:.1} s of audio in {:.2} s = {:.1}x realtime",
+        label,
+        engine.active_provider(),
+        audio_secs,
+        elapsed,
+        rtf
+    );
+    Some(rtf)
+}
+
+/// Low-level tones with a varying envelope, so the model sees non-silent input
+fn synthetic_audio(secs: usize) -> Vec<f32> {
+    let rate = memoire_stt::SAMPLE_RATE as f32;
+    (0..secs * memoire_stt::SAMPLE_RATE as usize)
+        .map(|i| {
+            let t = i as f32 / rate;
+            let envelope = 0.5 + 0.5 * (t * 2.0 * std::f32::consts::PI * 0.7).sin();
+            let tone = (t * 2.0 * std::f32::consts::PI * 220.0).sin()
+                + 0.5 * (t * 2.0 * std::f32::consts::PI * 440.0).sin();
+            0.1 * envelope * tone
+        })
+        .collect()
+}
@@ -2,6 +2,7 @@
 //!
 //! Provides a custom tracing formatter that adds colored prefixes to distinguish
 //! output from different components (recorder, indexers, viewer, etc.).
+//! A JSON mode emits one object per event for log aggregators instead.
 
 use owo_colors::{OwoColorize, Style};
 use std::fmt;
@@ -13,6 +14,16 @@ use tracing_subscriber::fmt::{
 };
 use tracing_subscriber::registry::LookupSpan;
 
+/// Log output format, selected with `--log-format`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum LogFormat {
+    /// Human-readable single-line output
+    #[default]
+    Compact,
+    /// One JSON object per event (timestamp, level, target, fields)
+    Json,
+}
+
 /// Component identifier for prefixing logs
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Component {
@@ -93,22 +104,61 @@ where
     }
 }
 
+/// Initialize logging for the CLI commands
+///
+/// Compact mode matches the original human-readable output; JSON mode uses
+/// `tracing_subscriber`'s JSON formatter. Events at `level` and above are
+/// logged; `RUST_LOG` can enable more for specific targets (`web=debug`).
+pub fn init_logger(format: LogFormat, level: Level) -> anyhow::Result<()> {
+    let filter = tracing_subscriber::EnvFilter::from_default_env().add_directive(level.into());
+    let builder = tracing_subscriber::fmt().with_env_filter(filter);
+
+    match format {
+        LogFormat::Compact => builder.with_target(false).compact().try_init(),
+        LogFormat::Json => builder
+            .json()
+            .flatten_event(true)
+            .with_current_span(false)
+            .try_init(),
+    }
+    .map_err(|e| anyhow::anyhow!("failed to initialize logger: {}", e))
+}
+
 /// Initialize colored logging for a specific component
 ///
-/// This sets up a tracing subscriber with colored output.
+/// This sets up a tracing subscriber with colored output, or JSON output
+/// when `format` is [`LogFormat::Json`].
 /// Should be called once per component/process.
-pub fn init_component_logger(component: Component) -> anyhow::Result<()> {
+pub fn init_component_logger(component: Component, format: LogFormat) -> anyhow::Result<()> {
     use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-    let fmt_layer = tracing_subscriber::fmt::layer()
-        .event_format(ColoredFormatter { component })
-        .with_writer(io::stdout);
+    let filter = tracing_subscriber::EnvFilter::from_default_env()
+        .add_directive(tracing::Level::INFO.into());
+
+    match format {
+        LogFormat::Compact => {
+            let fmt_layer = tracing_subscriber::fmt::layer()
+                .event_format(ColoredFormatter { component })
+                .with_writer(io::stdout);
+
+            tracing_subscriber::registry()
+                .with(filter)
+                .with(fmt_layer)
+                .try_init()?;
+        }
+        LogFormat::Json => {
+            let fmt_layer = tracing_subscriber::fmt::layer()
+                .json()
+                .flatten_event(true)
+                .with_current_span(false)
+                .with_writer(io::stdout);
 
-    tracing_subscriber::registry()
-        .with(tracing_subscriber::EnvFilter::from_default_env()
-            .add_directive(tracing::Level::INFO.into()))
-        .with(fmt_layer)
-        .try_init()?;
+            tracing_subscriber::registry()
+                .with(filter)
+                .with(fmt_layer)
+                .try_init()?;
+        }
+    }
 
     Ok(())
 }
@@ -1,7 +1,129 @@
 //! Configuration management
 
+use memoire_db::{Database, JournalMode};
+use memoire_processing::EncoderPreset;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tracing::warn;
+
+/// Lowest accepted recording framerate (one frame every 100 seconds)
+pub const MIN_FPS: f64 = 0.01;
+
+/// Environment variable overriding the default data directory
+pub const DATA_DIR_ENV: &str = "MEMOIRE_DATA_DIR";
+
+/// Resolve the data directory: `--data-dir` > `MEMOIRE_DATA_DIR` > platform default
+pub fn resolve_data_dir(cli: Option<PathBuf>) -> PathBuf {
+    cli.or_else(|| {
+        std::env::var_os(DATA_DIR_ENV)
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+    })
+    .unwrap_or_else(|| {
+        dirs::data_local_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("Memoire")
+    })
+}
+
+/// Environment variable selecting the SQLite journal mode (wal, delete or truncate)
+pub const JOURNAL_MODE_ENV: &str = "MEMOIRE_JOURNAL_MODE";
+
+/// Journal mode chosen for this process with `--journal-mode` or the config file
+static JOURNAL_MODE: OnceLock<JournalMode> = OnceLock::new();
+
+/// Use `mode` for every database opened by this process
+///
+/// The first call wins, so `--journal-mode` takes precedence over the config file.
+pub fn set_journal_mode(mode: JournalMode) {
+    let _ = JOURNAL_MODE.set(mode);
+}
+
+/// Resolve the journal mode: `--journal-mode`/config > `MEMOIRE_JOURNAL_MODE` > WAL
+pub fn journal_mode() -> JournalMode {
+    if let Some(mode) = JOURNAL_MODE.get() {
+        return *mode;
+    }
+    match std::env::var(JOURNAL_MODE_ENV) {
+        Ok(value) if !value.is_empty() => value.parse().unwrap_or_else(|e| {
+            warn!("ignoring {}: {}", JOURNAL_MODE_ENV, e);
+            JournalMode::default()
+        }),
+        _ => JournalMode::default(),
+    }
+}
+
+/// Open the database at `path` in the process-wide journal mode
+///
+/// All processes sharing a data directory must use the same mode, so every
+/// command opens its database through here.
+pub fn open_database(path: &Path) -> memoire_db::Result<Database> {
+    Database::open_with_journal_mode(path, journal_mode())
+}
+
+/// Speech-to-text model directory inside a data directory
+pub fn model_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join("models")
+}
+
+/// Convert a size limit given in GB (1024^3 bytes) to bytes
+pub fn gb_to_bytes(gb: f64) -> u64 {
+    (gb.max(0.0) * 1024.0 * 1024.0 * 1024.0) as u64
+}
+
+/// Which monitors to record and the device names stored for them
+///
+/// Monitors are referred to by their index in `memoire monitors` or by their
+/// system name (e.g. `\\.\DISPLAY2`, case-insensitive).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MonitorSelection {
+    /// Monitors to record (empty = all)
+    #[serde(default)]
+    pub include: Vec<String>,
+
+    /// Monitors never recorded, even if included
+    #[serde(default)]
+    pub exclude: Vec<String>,
+
+    /// Friendly names stored as the chunk device name, keyed by index or name
+    #[serde(default)]
+    pub aliases: HashMap<String, String>,
+}
+
+impl MonitorSelection {
+    /// Whether the monitor at `index` named `name` should be recorded
+    pub fn is_selected(&self, index: usize, name: &str) -> bool {
+        let included = self.include.is_empty()
+            || self.include.iter().any(|s| matches_monitor(s, index, name));
+        included && !self.exclude.iter().any(|s| matches_monitor(s, index, name))
+    }
+
+    /// Device name to record for a monitor: its alias, or the system name
+    pub fn device_name(&self, index: usize, name: &str) -> String {
+        self.aliases
+            .iter()
+            .find(|(selector, _)| matches_monitor(selector, index, name))
+            .map(|(_, alias)| alias.clone())
+            .unwrap_or_else(|| name.to_string())
+    }
+}
+
+/// Match a monitor selector against a monitor's index or system name
+fn matches_monitor(selector: &str, index: usize, name: &str) -> bool {
+    let selector = selector.trim();
+    selector.parse::<usize>() == Ok(index) || selector.eq_ignore_ascii_case(name)
+}
+
+/// Recording the microphone and system audio together
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DualAudio {
+    /// One chunk series per device
+    Separate,
+    /// A single mono stream, each device scaled by its gain
+    Mixed { mic_gain: f32, system_gain: f32 },
+}
 
 /// Recorder configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -9,25 +131,168 @@ pub struct Config {
     /// Data directory for videos and database
     pub data_dir: PathBuf,
 
-    /// Recording framerate
-    pub fps: u32,
+    /// Recording framerate (fractional values like 0.5 capture less than once per second)
+    pub fps: f64,
 
     /// Use hardware encoding (NVENC)
     pub use_hw_encoding: bool,
 
     /// Video chunk duration in seconds
     pub chunk_duration_secs: u64,
+
+    /// Video quality (CRF/CQ, 0-51, lower = better quality and larger files)
+    pub quality: u32,
+
+    /// Encoder speed/compression preset
+    pub preset: EncoderPreset,
+
+    /// Draw the mouse cursor onto captured frames
+    pub capture_cursor: bool,
+
+    /// Record audio alongside video
+    pub record_audio: bool,
+
+    /// Audio device ID (None = default device)
+    pub audio_device: Option<String>,
+
+    /// Capture system audio (loopback) instead of microphone
+    pub audio_loopback: bool,
+
+    /// Close audio chunks on a pause near the chunk duration instead of mid-word
+    pub audio_split_on_silence: bool,
+
+    /// Also record system audio next to `audio_device` (None = one device only)
+    pub audio_dual: Option<DualAudio>,
+
+    /// Mux the recorded audio into each video chunk as AAC
+    ///
+    /// The configured device is muxed (the mix with `DualAudio::Mixed`);
+    /// WAV chunks are still written for transcription.
+    pub mux_audio: bool,
+
+    /// Frames buffered before a database write
+    pub frame_batch_size: usize,
+
+    /// Maximum seconds between database writes of buffered frames
+    pub frame_flush_interval_secs: u64,
+
+    /// Monitors to record and their aliases
+    pub monitors: MonitorSelection,
+
+    /// Seconds without input after which frames are stored as unfocused (idle)
+    pub idle_threshold_secs: u64,
+
+    /// Delete audio files older than this many days (None = keep forever)
+    ///
+    /// Transcriptions are kept. Applies to all audio in the data directory,
+    /// whether or not this session records audio.
+    pub audio_retention_days: Option<u32>,
+
+    /// Prune the oldest recordings once the database and media exceed this many bytes
+    /// (None = no limit)
+    pub max_storage_bytes: Option<u64>,
+
+    /// Stop recording once the data directory's volume has less than this many
+    /// bytes free (None = never)
+    pub min_free_bytes: Option<u64>,
+
+    /// Clip mode: keep only this many seconds of frames in memory and save
+    /// them when the clip hotkey is pressed (None = record continuously)
+    pub clip_buffer_secs: Option<u64>,
+
+    /// Global hotkey saving the clip buffer, e.g. "ctrl+shift+s"
+    pub clip_hotkey: String,
+
+    /// Debugging: also save recorded frames as PNG under this directory,
+    /// throttled and capped per monitor (None = off)
+    pub save_raw_frames: Option<PathBuf>,
+
+    /// SQLite journal mode (DELETE for network shares and OneDrive-synced folders)
+    pub journal_mode: JournalMode,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
-            data_dir: dirs::data_local_dir()
-                .unwrap_or_else(|| PathBuf::from("."))
-                .join("Memoire"),
-            fps: 1,
+            data_dir: resolve_data_dir(None),
+            fps: 1.0,
             use_hw_encoding: true,
             chunk_duration_secs: 300,
+            quality: memoire_processing::encoder::DEFAULT_QUALITY,
+            preset: EncoderPreset::default(),
+            capture_cursor: true,
+            record_audio: false,
+            audio_device: None,
+            audio_loopback: false,
+            audio_split_on_silence: false,
+            audio_dual: None,
+            mux_audio: false,
+            frame_batch_size: crate::recorder::DEFAULT_FRAME_BATCH_SIZE,
+            frame_flush_interval_secs: crate::recorder::DEFAULT_FRAME_FLUSH_INTERVAL_SECS,
+            monitors: MonitorSelection::default(),
+            idle_threshold_secs: crate::recorder::DEFAULT_IDLE_THRESHOLD_SECS,
+            audio_retention_days: None,
+            max_storage_bytes: None,
+            min_free_bytes: None,
+            clip_buffer_secs: None,
+            clip_hotkey: crate::recorder::DEFAULT_CLIP_HOTKEY.to_string(),
+            save_raw_frames: None,
+            journal_mode: journal_mode(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn selection(include: &[&str], exclude: &[&str]) -> MonitorSelection {
+        MonitorSelection {
+            include: include.iter().map(|s| s.to_string()).collect(),
+            exclude: exclude.iter().map(|s| s.to_string()).collect(),
+            aliases: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_monitor_selection_include_exclude() {
+        let all = selection(&[], &[]);
+        assert!(all.is_selected(0, r"\\.\DISPLAY1"));
+        assert!(all.is_selected(1, r"\\.\DISPLAY2"));
+
+        let second = selection(&["1"], &[]);
+        assert!(!second.is_selected(0, r"\\.\DISPLAY1"));
+        assert!(second.is_selected(1, r"\\.\DISPLAY2"));
+
+        // Exclusion wins over inclusion
+        let both_but_first = selection(&["0", "1"], &[r"\\.\display1"]);
+        assert!(!both_but_first.is_selected(0, r"\\.\DISPLAY1"));
+        assert!(both_but_first.is_selected(1, r"\\.\DISPLAY2"));
+
+        let none_left = selection(&[], &["0", "1"]);
+        assert!(!none_left.is_selected(0, r"\\.\DISPLAY1"));
+        assert!(!none_left.is_selected(1, r"\\.\DISPLAY2"));
+    }
+
+    #[test]
+    fn test_matches_monitor_by_index_or_name() {
+        assert!(matches_monitor("1", 1, r"\\.\DISPLAY2"));
+        assert!(matches_monitor(" 1 ", 1, r"\\.\DISPLAY2"));
+        assert!(!matches_monitor("2", 1, r"\\.\DISPLAY2"));
+
+        // Names match case-insensitively, and never by the digits they contain
+        assert!(matches_monitor(r"\\.\display2", 1, r"\\.\DISPLAY2"));
+        assert!(!matches_monitor(r"\\.\DISPLAY2", 2, r"\\.\DISPLAY3"));
+    }
+
+    #[test]
+    fn test_monitor_aliases() {
+        let mut monitors = selection(&[], &[]);
+        monitors.aliases.insert("0".to_string(), "laptop".to_string());
+        monitors.aliases.insert(r"\\.\DISPLAY2".to_string(), "external".to_string());
+
+        assert_eq!(monitors.device_name(0, r"\\.\DISPLAY1"), "laptop");
+        assert_eq!(monitors.device_name(1, r"\\.\display2"), "external");
+        assert_eq!(monitors.device_name(2, r"\\.\DISPLAY3"), r"\\.\DISPLAY3");
+    }
+}
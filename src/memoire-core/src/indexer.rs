@@ -3,23 +3,46 @@
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use futures::{stream, StreamExt};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::{broadcast, RwLock};
+use tokio::sync::{broadcast, mpsc, RwLock};
 use tracing::{debug, error, info, warn};
 
+use memoire_capture::screen::CapturedFrame;
 use memoire_db::Database;
-use memoire_ocr::{FrameData, Processor as OcrProcessor};
+use memoire_ocr::{FrameData, OcrBackend, Processor as OcrProcessor};
 
 use crate::recorder::ChunkFinalizedEvent;
 
-/// OCR batch settings
-const OCR_BATCH_SIZE: usize = 30;
+/// Default OCR batch settings
+pub const DEFAULT_OCR_BATCH_SIZE: usize = 30;
 const DEFAULT_OCR_FPS: u32 = 10;
-/// Maximum concurrent frame extractions (limited by FFmpeg processes)
-const MAX_CONCURRENT_EXTRACTIONS: usize = 4;
+/// Default maximum concurrent frame extractions (limited by FFmpeg processes)
+pub const DEFAULT_MAX_CONCURRENT_EXTRACTIONS: usize = 4;
+/// Default maximum edit distance for reusing the previous frame's OCR text
+pub const DEFAULT_TEXT_DEDUP_DISTANCE: usize = 8;
+/// Maximum hash distance between a seek-extracted frame and its capture-time hash
+/// (allows for video compression artifacts)
+const SEEK_HASH_TOLERANCE: u32 = 4;
+/// OCR events buffered for slow subscribers
+const OCR_EVENT_CAPACITY: usize = 256;
+/// How long to wait for more chunk events before indexing, so chunks that
+/// several monitors finalize together are processed as one batch
+pub const CHUNK_EVENT_DEBOUNCE: Duration = Duration::from_secs(2);
+
+/// Event emitted when a frame's OCR result is stored
+#[derive(Debug, Clone)]
+pub struct OcrEvent {
+    pub frame_id: i64,
+    /// Capture time of the frame
+    pub timestamp: DateTime<Utc>,
+    /// Recognized text (empty when nothing was recognized or OCR failed)
+    pub text: String,
+    pub app_name: Option<String>,
+}
 
 /// Statistics for OCR processing
 #[derive(Debug, Clone)]
@@ -32,31 +55,47 @@ pub struct IndexerStats {
 }
 
 /// OCR Indexer that processes frames in background
-pub struct Indexer {
+///
+/// Scheduling, deduplication and batching are independent of the OCR engine:
+/// `B` is the Windows [`OcrProcessor`] by default, and any other
+/// [`OcrBackend`] can be plugged in with [`Indexer::with_backend`].
+pub struct Indexer<B = OcrProcessor> {
     db: Database,
-    processor: OcrProcessor,
+    backend: B,
     data_dir: PathBuf,
     ocr_fps: u32,
+    batch_size: usize,
+    max_concurrent_extractions: usize,
+    /// Max edit distance for storing a reference to the previous frame's text (None = off)
+    text_dedup_distance: Option<usize>,
+    /// Reuse the OCR of an earlier frame with the same perceptual hash
+    ocr_hash_cache: bool,
     running: Arc<AtomicBool>,
     stats: Arc<RwLock<IndexerStats>>,
     processed_count: Arc<AtomicU64>,
     chunk_events_rx: Option<broadcast::Receiver<ChunkFinalizedEvent>>,
+    /// Chunks whose OCR was reset from the viewer
+    reindex_rx: Option<mpsc::UnboundedReceiver<i64>>,
+    /// Broadcast channel for stored OCR results
+    ocr_events_tx: broadcast::Sender<OcrEvent>,
 }
 
-impl Indexer {
-    /// Create a new indexer with optional language configuration
-    pub fn new(data_dir: PathBuf, ocr_fps: Option<u32>, ocr_language: Option<String>) -> Result<Self> {
-        info!("initializing OCR indexer");
-
-        let db_path = data_dir.join("memoire.db");
-        let db = Database::open(&db_path)?;
-        info!("database opened at {:?}", db_path);
-
+impl Indexer<OcrProcessor> {
+    /// Create a new indexer using Windows OCR with optional language configuration.
+    /// `batch_size` and `max_concurrent_extractions` fall back to the module defaults.
+    pub fn new(
+        data_dir: PathBuf,
+        ocr_fps: Option<u32>,
+        ocr_language: Option<String>,
+        batch_size: Option<usize>,
+        max_concurrent_extractions: Option<usize>,
+    ) -> Result<Self> {
         // Create processor with specified language or default to English
         let processor = match ocr_language {
             Some(ref lang) => {
+                // A comma-separated list picks the best language per frame
                 info!("initializing OCR processor with language: {}", lang);
-                OcrProcessor::with_language(lang)?
+                memoire_ocr::create_processor_with_language(lang)?
             }
             None => {
                 info!("initializing OCR processor with default language (en-US)");
@@ -65,6 +104,43 @@ impl Indexer {
         };
         info!("OCR processor initialized");
 
+        Self::with_backend(data_dir, processor, ocr_fps, batch_size, max_concurrent_extractions)
+    }
+
+    /// Combine OCR lines from every configured language instead of keeping the best
+    ///
+    /// Lines keep their own language in `text_json`; the stored frame language
+    /// lists every language that contributed.
+    pub fn set_merge_languages(&mut self, merge: bool) {
+        self.backend.set_merge_languages(merge);
+    }
+
+    /// Downscale frames to at most `max_dimension` pixels on their longest side before OCR
+    ///
+    /// Speeds up recognition on 4K monitors; bounding boxes are still stored in
+    /// original frame coordinates. `None` keeps full resolution.
+    pub fn set_ocr_downscale(&mut self, max_dimension: Option<u32>) {
+        self.backend.set_max_dimension(max_dimension);
+    }
+}
+
+impl<B: OcrBackend> Indexer<B> {
+    /// Create an indexer recognizing frames with `backend`
+    ///
+    /// `batch_size` and `max_concurrent_extractions` fall back to the module defaults.
+    pub fn with_backend(
+        data_dir: PathBuf,
+        backend: B,
+        ocr_fps: Option<u32>,
+        batch_size: Option<usize>,
+        max_concurrent_extractions: Option<usize>,
+    ) -> Result<Self> {
+        info!("initializing OCR indexer");
+
+        let db_path = data_dir.join("memoire.db");
+        let db = crate::config::open_database(&db_path)?;
+        info!("database opened at {:?}", db_path);
+
         let stats = IndexerStats {
             total_frames: 0,
             frames_with_ocr: 0,
@@ -73,18 +149,36 @@ impl Indexer {
             last_updated: Utc::now(),
         };
 
+        let (ocr_events_tx, _rx) = broadcast::channel(OCR_EVENT_CAPACITY);
+
         Ok(Self {
             db,
-            processor,
+            backend,
             data_dir,
             ocr_fps: ocr_fps.unwrap_or(DEFAULT_OCR_FPS),
+            batch_size: batch_size.unwrap_or(DEFAULT_OCR_BATCH_SIZE).max(1),
+            max_concurrent_extractions: max_concurrent_extractions
+                .unwrap_or(DEFAULT_MAX_CONCURRENT_EXTRACTIONS)
+                .max(1),
+            text_dedup_distance: None,
+            ocr_hash_cache: false,
             running: Arc::new(AtomicBool::new(true)), // Start as running
             stats: Arc::new(RwLock::new(stats)),
             processed_count: Arc::new(AtomicU64::new(0)),
             chunk_events_rx: None, // Will be set via set_chunk_events_receiver()
+            reindex_rx: None,
+            ocr_events_tx,
         })
     }
 
+    /// Subscribe to OCR results as each frame is stored
+    ///
+    /// Events are sent once the batch holding the frame is committed, so the
+    /// text is already searchable. Each subscriber gets its own receiver.
+    pub fn subscribe(&self) -> broadcast::Receiver<OcrEvent> {
+        self.ocr_events_tx.subscribe()
+    }
+
     /// Set the chunk finalization event receiver
     ///
     /// This enables event-driven processing for immediate indexing of finalized chunks.
@@ -93,6 +187,40 @@ impl Indexer {
         self.chunk_events_rx = Some(rx);
     }
 
+    /// Set the receiver for chunks queued for re-indexing
+    ///
+    /// Their frames are processed as soon as a chunk id arrives instead of
+    /// waiting for the next poll.
+    pub fn set_reindex_receiver(&mut self, rx: mpsc::UnboundedReceiver<i64>) {
+        self.reindex_rx = Some(rx);
+    }
+
+    /// Enable deduplication of consecutive OCR text within a chunk
+    ///
+    /// When a frame's text is within `max_edit_distance` edits of the previous
+    /// frame's, an empty row referencing that frame is stored instead of the text.
+    /// `None` disables deduplication.
+    pub fn set_text_dedup(&mut self, max_edit_distance: Option<usize>) {
+        if let Some(distance) = max_edit_distance {
+            info!("OCR text deduplication enabled (max edit distance {})", distance);
+        }
+        self.text_dedup_distance = max_edit_distance;
+    }
+
+    /// Reuse existing OCR for frames whose perceptual hash was already indexed
+    ///
+    /// Matching frames skip extraction and OCR entirely; a reference to the
+    /// frame holding the text is stored instead. The hash only captures the
+    /// layout, so visually identical screens with different text (another
+    /// email in the same client, a ticking clock) reuse the earlier, stale
+    /// text. Off by default for that reason.
+    pub fn set_ocr_hash_cache(&mut self, enabled: bool) {
+        if enabled {
+            info!("OCR hash cache enabled");
+        }
+        self.ocr_hash_cache = enabled;
+    }
+
     /// Get current statistics
     pub async fn get_stats(&self) -> IndexerStats {
         self.stats.read().await.clone()
@@ -115,6 +243,7 @@ impl Indexer {
         // Take ownership of the receiver if present
         let mut chunk_rx = self.chunk_events_rx.take();
         let use_events = chunk_rx.is_some();
+        let mut reindex_rx = self.reindex_rx.take();
 
         if use_events {
             info!("OCR indexer using event-driven mode with {} fallback polling",
@@ -125,28 +254,44 @@ impl Indexer {
         }
 
         while !shutdown.load(Ordering::SeqCst) && self.running.load(Ordering::Relaxed) {
+            // Chunks queued for re-indexing go before pending frames
+            while let Some(chunk_id) = reindex_rx.as_mut().and_then(|rx| rx.try_recv().ok()) {
+                batch_count += self.reindex_chunk(chunk_id).await as u64;
+            }
+
             // Event-driven mode: wait for chunk events or timeout
             if let Some(ref mut rx) = chunk_rx {
                 tokio::select! {
+                    // Branch 0: Chunk queued for re-indexing
+                    Some(chunk_id) = next_reindex(&mut reindex_rx) => {
+                        batch_count += self.reindex_chunk(chunk_id).await as u64;
+                    }
+
                     // Branch 1: Chunk finalized event received
                     event = rx.recv() => {
                         match event {
                             Ok(evt) => {
                                 debug!("received chunk finalized event for chunk {}", evt.chunk_id);
-                                // Process this specific chunk immediately
-                                match self.process_chunk_frames(evt.chunk_id).await {
+                                // Other monitors finalize at about the same time; take their chunks too
+                                let (chunk_ids, closed) =
+                                    coalesce_chunk_events(rx, evt.chunk_id, CHUNK_EVENT_DEBOUNCE).await;
+                                match self.process_chunks_frames(&chunk_ids).await {
                                     Ok(count) if count > 0 => {
                                         batch_count += count as u64;
-                                        info!("processed {} frames from newly finalized chunk {}",
-                                              count, evt.chunk_id);
+                                        info!("processed {} frames from newly finalized chunks {:?}",
+                                              count, chunk_ids);
                                     }
                                     Ok(_) => {
-                                        debug!("no frames to process in chunk {}", evt.chunk_id);
+                                        debug!("no frames to process in chunks {:?}", chunk_ids);
                                     }
                                     Err(e) => {
-                                        error!("error processing chunk {}: {}", evt.chunk_id, e);
+                                        error!("error processing chunks {:?}: {}", chunk_ids, e);
                                     }
                                 }
+                                if closed {
+                                    warn!("chunk event channel closed, switching to polling mode");
+                                    chunk_rx = None;
+                                }
                             }
                             Err(broadcast::error::RecvError::Lagged(skipped)) => {
                                 warn!("indexer lagged, skipped {} chunk events - processing all pending", skipped);
@@ -222,19 +367,37 @@ impl Indexer {
         self.running.store(false, Ordering::Relaxed);
     }
 
-    /// Process frames from a specific chunk (event-driven)
-    async fn process_chunk_frames(&self, chunk_id: i64) -> Result<usize> {
-        // Query frames without OCR for this specific chunk
-        let frames = memoire_db::get_frames_for_chunk_without_ocr(
-            self.db.connection(),
-            chunk_id,
-        )?;
+    /// Process a chunk queued for re-indexing, returning the frames processed
+    async fn reindex_chunk(&self, chunk_id: i64) -> usize {
+        match self.process_chunks_frames(&[chunk_id]).await {
+            Ok(count) => {
+                info!("re-indexed {} frames from chunk {}", count, chunk_id);
+                count
+            }
+            Err(e) => {
+                error!("error re-indexing chunk {}: {}", chunk_id, e);
+                0
+            }
+        }
+    }
+
+    /// Process frames from specific chunks (event-driven), sharing one
+    /// extraction pool across them
+    async fn process_chunks_frames(&self, chunk_ids: &[i64]) -> Result<usize> {
+        // Query frames without OCR for these chunks
+        let mut frames = Vec::new();
+        for &chunk_id in chunk_ids {
+            frames.extend(memoire_db::get_frames_for_chunk_without_ocr(
+                self.db.connection(),
+                chunk_id,
+            )?);
+        }
 
         if frames.is_empty() {
             return Ok(0);
         }
 
-        debug!("processing {} frames from chunk {} (event-driven)", frames.len(), chunk_id);
+        debug!("processing {} frames from chunks {:?} (event-driven)", frames.len(), chunk_ids);
 
         // Use the same concurrent processing logic as process_batch
         self.process_frame_list(&frames).await
@@ -245,7 +408,7 @@ impl Indexer {
         // Query frames without OCR (limit to batch size)
         let frames = memoire_db::get_frames_without_ocr(
             self.db.connection(),
-            OCR_BATCH_SIZE as i64,
+            self.batch_size as i64,
         )?;
 
         if frames.is_empty() {
@@ -259,13 +422,33 @@ impl Indexer {
 
     /// Process a list of frames (shared logic for batch and event-driven processing)
     async fn process_frame_list(&self, frames: &[memoire_db::Frame]) -> Result<usize> {
+        let mut ocr_results = Vec::with_capacity(frames.len());
+
+        // Step 0: Reuse OCR of identical frames seen before, skipping extraction
+        let mut cached = HashMap::new();
+        if self.ocr_hash_cache {
+            for frame in frames {
+                let Some(hash) = frame.frame_hash else {
+                    continue;
+                };
+                if let Some((source_frame_id, text)) = memoire_db::get_ocr_by_hash(self.db.connection(), hash)? {
+                    cached.insert(frame.id, source_frame_id);
+                    ocr_results.push((frame.id, memoire_ocr::OcrFrameResult { text, ..empty_ocr_result() }));
+                }
+            }
+            if !cached.is_empty() {
+                debug!("reusing OCR for {} of {} frames by hash", cached.len(), frames.len());
+            }
+        }
 
         // Step 1: Extract all frames concurrently using spawn_blocking
         // This is the expensive I/O-bound FFmpeg operation
-        let extraction_tasks: Vec<_> = frames.iter().map(|frame| {
+        let to_extract = frames.iter().filter(|frame| !cached.contains_key(&frame.id));
+        let extraction_tasks: Vec<_> = to_extract.map(|frame| {
             let frame_id = frame.id;
             let video_chunk_id = frame.video_chunk_id;
             let offset_index = frame.offset_index;
+            let frame_hash = frame.frame_hash;
             let data_dir = self.data_dir.clone();
             let db_conn = self.db.connection();
 
@@ -277,17 +460,25 @@ impl Indexer {
                         return (frame_id, Err(anyhow::anyhow!("video chunk {} not found", video_chunk_id)));
                     }
                     Err(e) => {
-                        return (frame_id, Err(e));
+                        return (frame_id, Err(e.into()));
                     }
                 };
 
                 let video_path = data_dir.join(&chunk.file_path);
                 let cached_width = chunk.width;
                 let cached_height = chunk.height;
+                let fps = chunk.fps;
 
                 // Run FFmpeg extraction in a blocking task
                 let extraction_result = tokio::task::spawn_blocking(move || {
-                    Self::extract_frame_from_video_static(&video_path, offset_index, cached_width, cached_height)
+                    extract_frame_from_video(
+                        &video_path,
+                        offset_index,
+                        cached_width,
+                        cached_height,
+                        fps,
+                        frame_hash,
+                    )
                 }).await;
 
                 match extraction_result {
@@ -300,17 +491,15 @@ impl Indexer {
 
         // Execute extractions concurrently with limited concurrency
         let extracted_frames: Vec<_> = stream::iter(extraction_tasks)
-            .buffer_unordered(MAX_CONCURRENT_EXTRACTIONS)
+            .buffer_unordered(self.max_concurrent_extractions)
             .collect()
             .await;
 
         // Step 2: Process OCR sequentially (Windows OCR may not be thread-safe)
-        let mut ocr_results = Vec::with_capacity(frames.len());
-
         for (frame_id, extraction_result) in extracted_frames {
             match extraction_result {
                 Ok(frame_data) => {
-                    match self.processor.process_frame(frame_data).await {
+                    match self.backend.process_frame(frame_data).await {
                         Ok(result) => {
                             ocr_results.push((frame_id, result));
                         }
@@ -328,7 +517,7 @@ impl Indexer {
         }
 
         // Batch insert OCR results
-        self.insert_ocr_batch(&ocr_results)?;
+        self.insert_ocr_batch(frames, &ocr_results, &cached)?;
 
         let count = ocr_results.len();
         self.processed_count.fetch_add(count as u64, Ordering::Relaxed);
@@ -336,107 +525,56 @@ impl Indexer {
         Ok(count)
     }
 
-    /// Extract a specific frame from video using FFmpeg command-line tool (static version)
-    /// If cached_width/cached_height are provided, skips the ffprobe call for better performance.
-    /// This static version allows calling from spawn_blocking without borrowing self.
-    fn extract_frame_from_video_static(
-        video_path: &PathBuf,
-        frame_index: i64,
-        cached_width: Option<u32>,
-        cached_height: Option<u32>,
-    ) -> Result<FrameData> {
-        use std::process::{Command, Stdio};
-        use std::io::Read;
-
-        // Use ffmpeg to extract a specific frame as raw RGBA data
-        // -i input.mp4 -vf "select=eq(n\,FRAME_INDEX)" -vframes 1 -f rawvideo -pix_fmt rgba -
-
-        let frame_filter = format!("select=eq(n\\,{})", frame_index);
-
-        let mut child = Command::new("ffmpeg")
-            .arg("-i")
-            .arg(video_path)
-            .arg("-vf")
-            .arg(&frame_filter)
-            .arg("-vframes")
-            .arg("1")
-            .arg("-f")
-            .arg("rawvideo")
-            .arg("-pix_fmt")
-            .arg("rgba")
-            .arg("-")
-            .stdout(Stdio::piped())
-            .stderr(Stdio::null())
-            .spawn()
-            .map_err(|e| anyhow::anyhow!("failed to spawn ffmpeg: {}", e))?;
-
-        // Read frame data from stdout
-        let mut frame_data = Vec::new();
-        child.stdout.as_mut()
-            .ok_or_else(|| anyhow::anyhow!("failed to capture stdout"))?
-            .read_to_end(&mut frame_data)?;
-
-        let status = child.wait()?;
-        if !status.success() {
-            return Err(anyhow::anyhow!("ffmpeg failed with exit code {:?}", status.code()));
+    /// Insert OCR results in a batch
+    ///
+    /// Frames in `cached` (frame id -> source frame id) are stored as references
+    /// to the frame whose OCR they reused.
+    fn insert_ocr_batch(
+        &self,
+        frames: &[memoire_db::Frame],
+        results: &[(i64, memoire_ocr::OcrFrameResult)],
+        cached: &HashMap<i64, i64>,
+    ) -> Result<()> {
+        if results.is_empty() {
+            return Ok(());
         }
 
-        // Use cached dimensions if available, otherwise fall back to ffprobe
-        let (width, height) = match (cached_width, cached_height) {
-            (Some(w), Some(h)) => (w, h),
-            _ => {
-                // Fall back to ffprobe for legacy chunks without cached dimensions
-                let probe_output = Command::new("ffprobe")
-                    .arg("-v")
-                    .arg("error")
-                    .arg("-select_streams")
-                    .arg("v:0")
-                    .arg("-show_entries")
-                    .arg("stream=width,height")
-                    .arg("-of")
-                    .arg("csv=p=0")
-                    .arg(video_path)
-                    .output()
-                    .map_err(|e| anyhow::anyhow!("failed to run ffprobe: {}", e))?;
-
-                let dimensions = String::from_utf8_lossy(&probe_output.stdout);
-                let parts: Vec<&str> = dimensions.trim().split(',').collect();
-                if parts.len() != 2 {
-                    return Err(anyhow::anyhow!("invalid ffprobe output: {}", dimensions));
-                }
+        debug!("inserting {} OCR results", results.len());
 
-                let w: u32 = parts[0].parse()?;
-                let h: u32 = parts[1].parse()?;
-                (w, h)
-            }
-        };
+        // One transaction per batch, so FTS5 writes one index segment instead of one per frame
+        let tx = self.db.connection().unchecked_transaction()?;
+        let conn = &*tx;
+        let by_id: HashMap<i64, &memoire_db::Frame> = frames.iter().map(|f| (f.id, f)).collect();
 
-        // Validate frame data size
-        let expected_size = (width * height * 4) as usize;
-        if frame_data.len() != expected_size {
-            return Err(anyhow::anyhow!(
-                "unexpected frame data size: got {}, expected {}",
-                frame_data.len(),
-                expected_size
-            ));
-        }
+        // Insert in capture order so each frame is compared with its predecessor
+        let mut ordered: Vec<_> = results.iter().collect();
+        ordered.sort_by_key(|(frame_id, _)| by_id.get(frame_id).map(|f| (f.video_chunk_id, f.offset_index)));
 
-        Ok(FrameData {
-            width,
-            height,
-            data: frame_data,
-        })
-    }
+        let mut deduplicated = 0;
 
-    /// Insert OCR results in a batch
-    fn insert_ocr_batch(&self, results: &[(i64, memoire_ocr::OcrFrameResult)]) -> Result<()> {
-        if results.is_empty() {
-            return Ok(());
-        }
+        for (frame_id, result) in ordered.iter().copied() {
+            if let Some(&source_frame_id) = cached.get(frame_id) {
+                memoire_db::insert_ocr_reference(conn, *frame_id, source_frame_id)?;
+                continue;
+            }
 
-        debug!("inserting {} OCR results", results.len());
+            if let (Some(max_distance), Some(frame)) = (self.text_dedup_distance, by_id.get(frame_id)) {
+                let (chunk_id, offset_index) = (frame.video_chunk_id, frame.offset_index);
+                if !result.text.is_empty() {
+                    if let Some((source_frame_id, previous_text)) =
+                        memoire_db::get_previous_ocr_text(conn, chunk_id, offset_index)?
+                    {
+                        if !previous_text.is_empty()
+                            && within_edit_distance(&previous_text, &result.text, max_distance)
+                        {
+                            memoire_db::insert_ocr_reference(conn, *frame_id, source_frame_id)?;
+                            deduplicated += 1;
+                            continue;
+                        }
+                    }
+                }
+            }
 
-        for (frame_id, result) in results {
             let text_json = serde_json::to_string(&result.lines)?;
 
             let new_ocr = memoire_db::NewOcrText {
@@ -444,9 +582,31 @@ impl Indexer {
                 text: result.text.clone(),
                 text_json: Some(text_json),
                 confidence: Some(result.confidence as f64),
+                language: result.language.clone(),
             };
 
-            memoire_db::insert_ocr_text(self.db.connection(), &new_ocr)?;
+            let ocr_id = memoire_db::insert_ocr_text(conn, &new_ocr)?;
+            memoire_db::insert_ocr_lines(conn, ocr_id, *frame_id, &normalized_lines(result))?;
+        }
+        tx.commit()?;
+
+        if deduplicated > 0 {
+            debug!("stored {} OCR results as references to the previous frame", deduplicated);
+        }
+
+        // Nothing to build when nobody listens (`memoire index` runs without subscribers)
+        if self.ocr_events_tx.receiver_count() > 0 {
+            for (frame_id, result) in ordered {
+                let Some(frame) = by_id.get(frame_id) else {
+                    continue;
+                };
+                let _ = self.ocr_events_tx.send(OcrEvent {
+                    frame_id: *frame_id,
+                    timestamp: frame.timestamp,
+                    text: result.text.clone(),
+                    app_name: frame.app_name.clone(),
+                });
+            }
         }
 
         Ok(())
@@ -468,11 +628,322 @@ impl Indexer {
     }
 }
 
+/// Extract a specific frame from video using FFmpeg command-line tool
+/// If cached_width/cached_height are provided, skips the ffprobe call for better performance.
+/// When the chunk's fps is known, seeks directly to the frame's timestamp instead of
+/// decoding from the start, and verifies the result against the stored frame hash.
+/// A free function so it can run in spawn_blocking without borrowing the indexer.
+fn extract_frame_from_video(
+    video_path: &PathBuf,
+    frame_index: i64,
+    cached_width: Option<u32>,
+    cached_height: Option<u32>,
+    fps: Option<f64>,
+    expected_hash: Option<i64>,
+) -> Result<FrameData> {
+    use memoire_processing::ffmpeg::ffprobe_command;
+
+    // Use cached dimensions if available, otherwise fall back to ffprobe
+    let (width, height) = match (cached_width, cached_height) {
+        (Some(w), Some(h)) => (w, h),
+        _ => {
+            // Fall back to ffprobe for legacy chunks without cached dimensions
+            let probe_output = ffprobe_command()
+                .arg("-v")
+                .arg("error")
+                .arg("-select_streams")
+                .arg("v:0")
+                .arg("-show_entries")
+                .arg("stream=width,height")
+                .arg("-of")
+                .arg("csv=p=0")
+                .arg(video_path)
+                .output()
+                .map_err(|e| anyhow::anyhow!("failed to run ffprobe: {}", e))?;
+
+            let dimensions = String::from_utf8_lossy(&probe_output.stdout);
+            let parts: Vec<&str> = dimensions.trim().split(',').collect();
+            if parts.len() != 2 {
+                return Err(anyhow::anyhow!("invalid ffprobe output: {}", dimensions));
+            }
+
+            let w: u32 = parts[0].parse()?;
+            let h: u32 = parts[1].parse()?;
+            (w, h)
+        }
+    };
+    let expected_size = (width * height * 4) as usize;
+
+    // Fast path: input seeking to the frame's timestamp (chunks are constant frame rate)
+    if let Some(fps) = fps.filter(|f| *f > 0.0) {
+        // Seek half a frame early so float rounding never skips past the target
+        let seek_secs = ((frame_index as f64 - 0.5) / fps).max(0.0);
+        let seek = format!("{:.3}", seek_secs);
+
+        match run_ffmpeg_extract(&["-ss", &seek], video_path, &[]) {
+            Ok(data) if data.len() == expected_size => {
+                if matches_frame_hash(&data, width, height, expected_hash) {
+                    return Ok(FrameData { width, height, data });
+                }
+                debug!(
+                    "seek extraction returned wrong frame for index {} in {:?}, falling back",
+                    frame_index, video_path
+                );
+            }
+            Ok(data) => debug!(
+                "seek extraction returned {} bytes (expected {}), falling back",
+                data.len(),
+                expected_size
+            ),
+            Err(e) => debug!("seek extraction failed: {}, falling back", e),
+        }
+    }
+
+    // Slow path: decode from the start and select the frame by index
+    // -i input.mp4 -vf "select=eq(n\,FRAME_INDEX)" -vframes 1 -f rawvideo -pix_fmt rgba -
+    let frame_filter = format!("select=eq(n\\,{})", frame_index);
+    let frame_data = run_ffmpeg_extract(&[], video_path, &["-vf", &frame_filter])?;
+
+    // Validate frame data size
+    if frame_data.len() != expected_size {
+        return Err(anyhow::anyhow!(
+            "unexpected frame data size: got {}, expected {}",
+            frame_data.len(),
+            expected_size
+        ));
+    }
+
+    Ok(FrameData {
+        width,
+        height,
+        data: frame_data,
+    })
+}
+
+/// Run ffmpeg to decode a single frame as raw RGBA.
+/// `input_args` go before `-i` (e.g. input seeking), `filter_args` after it.
+fn run_ffmpeg_extract(input_args: &[&str], video_path: &PathBuf, filter_args: &[&str]) -> Result<Vec<u8>> {
+    use memoire_processing::ffmpeg::ffmpeg_command;
+    use std::io::Read;
+    use std::process::Stdio;
+
+    let mut child = ffmpeg_command()
+        .args(input_args)
+        .arg("-i")
+        .arg(video_path)
+        .args(filter_args)
+        .arg("-frames:v")
+        .arg("1")
+        .arg("-f")
+        .arg("rawvideo")
+        .arg("-pix_fmt")
+        .arg("rgba")
+        .arg("-")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("failed to spawn ffmpeg: {}", e))?;
+
+    // Read frame data from stdout
+    let mut frame_data = Vec::new();
+    child.stdout.as_mut()
+        .ok_or_else(|| anyhow::anyhow!("failed to capture stdout"))?
+        .read_to_end(&mut frame_data)?;
+
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(anyhow::anyhow!("ffmpeg failed with exit code {:?}", status.code()));
+    }
+
+    Ok(frame_data)
+}
+
+/// Check an extracted frame against the perceptual hash stored at capture time.
+/// Frames without a stored hash are accepted as-is.
+fn matches_frame_hash(data: &[u8], width: u32, height: u32, expected_hash: Option<i64>) -> bool {
+    let Some(expected) = expected_hash else {
+        return true;
+    };
+
+    let frame = CapturedFrame {
+        data: data.to_vec(),
+        width,
+        height,
+        timestamp: Utc::now(),
+    };
+    let distance = CapturedFrame::hash_distance(frame.compute_perceptual_hash(), expected as u64);
+    distance <= SEEK_HASH_TOLERANCE
+}
+
+/// Collect chunks finalized within `window` of `first`, without duplicates
+///
+/// Also returns whether the channel closed meanwhile. Lagged events are
+/// left to the polling fallback.
+async fn coalesce_chunk_events(
+    rx: &mut broadcast::Receiver<ChunkFinalizedEvent>,
+    first: i64,
+    window: Duration,
+) -> (Vec<i64>, bool) {
+    let mut chunk_ids = vec![first];
+    let deadline = tokio::time::Instant::now() + window;
+
+    loop {
+        match tokio::time::timeout_at(deadline, rx.recv()).await {
+            Ok(Ok(evt)) => {
+                if !chunk_ids.contains(&evt.chunk_id) {
+                    chunk_ids.push(evt.chunk_id);
+                }
+            }
+            Ok(Err(broadcast::error::RecvError::Lagged(skipped))) => {
+                warn!("indexer lagged, skipped {} chunk events - polling will catch up", skipped);
+            }
+            Ok(Err(broadcast::error::RecvError::Closed)) => return (chunk_ids, true),
+            Err(_) => return (chunk_ids, false),
+        }
+    }
+}
+
+/// Next chunk queued for re-indexing; never resolves without a receiver
+async fn next_reindex(rx: &mut Option<mpsc::UnboundedReceiver<i64>>) -> Option<i64> {
+    match rx {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
 /// Create an empty OCR result for frames that fail extraction or OCR
 fn empty_ocr_result() -> memoire_ocr::OcrFrameResult {
     memoire_ocr::OcrFrameResult {
         text: String::new(),
         lines: Vec::new(),
         confidence: 0.0,
+        width: 0,
+        height: 0,
+        language: None,
+    }
+}
+
+/// Check whether two strings are within `max` single-character edits
+/// (Levenshtein distance). Only a band of width `2 * max + 1` is computed,
+/// so comparing long, nearly identical texts stays cheap.
+fn within_edit_distance(a: &str, b: &str, max: usize) -> bool {
+    if a == b {
+        return true;
+    }
+
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if max == 0 || a.len().abs_diff(b.len()) > max {
+        return false;
+    }
+
+    // Distances above `max` are clamped to `limit`
+    let limit = max + 1;
+    let mut prev: Vec<usize> = (0..=b.len()).map(|j| j.min(limit)).collect();
+    let mut curr = vec![limit; b.len() + 1];
+
+    for i in 1..=a.len() {
+        let lo = i.saturating_sub(max).max(1);
+        let hi = (i + max).min(b.len());
+
+        curr[lo - 1] = if lo == 1 { i.min(limit) } else { limit };
+        let mut row_min = curr[lo - 1];
+
+        for j in lo..=hi {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let value = (prev[j] + 1)
+                .min(curr[j - 1] + 1)
+                .min(prev[j - 1] + cost)
+                .min(limit);
+            curr[j] = value;
+            row_min = row_min.min(value);
+        }
+
+        if hi < b.len() {
+            curr[hi + 1] = limit;
+        }
+        if row_min > max {
+            return false;
+        }
+
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()] <= max
+}
+
+/// Convert OCR lines to bounding boxes normalized to the frame dimensions
+fn normalized_lines(result: &memoire_ocr::OcrFrameResult) -> Vec<memoire_db::OcrLineBox> {
+    if result.width == 0 || result.height == 0 {
+        return Vec::new();
+    }
+
+    let (fw, fh) = (result.width as f64, result.height as f64);
+
+    result
+        .lines
+        .iter()
+        .filter_map(|line| {
+            let (x, y, w, h) = line.bounding_box()?;
+            Some(memoire_db::OcrLineBox {
+                text: line.text.clone(),
+                confidence: Some(line.confidence() as f64),
+                bbox: memoire_db::BoundingBox {
+                    x: x as f64 / fw,
+                    y: y as f64 / fh,
+                    w: w as f64 / fw,
+                    h: h as f64 / fh,
+                },
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_within_edit_distance_zero() {
+        assert!(within_edit_distance("invoice", "invoice", 0));
+        assert!(within_edit_distance("", "", 0));
+        assert!(!within_edit_distance("invoice", "invoices", 0));
+        assert!(!within_edit_distance("invoice", "invoica", 0));
+    }
+
+    #[test]
+    fn test_within_edit_distance_at_threshold() {
+        // kitten -> sitting: two substitutions and an insertion
+        assert!(within_edit_distance("kitten", "sitting", 3));
+        assert!(!within_edit_distance("kitten", "sitting", 2));
+
+        // A swap is two edits
+        assert!(within_edit_distance("ab", "ba", 2));
+        assert!(!within_edit_distance("ab", "ba", 1));
+
+        // Edits at both ends of a long text stay inside the band
+        let text = "Quarterly report draft ".repeat(20);
+        let edited = format!("X{}Y", &text[1..text.len() - 1]);
+        assert!(within_edit_distance(&text, &edited, 2));
+        assert!(!within_edit_distance(&text, &edited, 1));
+    }
+
+    #[test]
+    fn test_within_edit_distance_different_lengths() {
+        assert!(within_edit_distance("", "abc", 3));
+        assert!(!within_edit_distance("", "abc", 2));
+        assert!(!within_edit_distance("abc", "abcdefghij", 3));
+        assert!(within_edit_distance("abcdefghij", "abc", 7));
+        assert!(!within_edit_distance("x", &"y".repeat(1000), 10));
+    }
+
+    #[test]
+    fn test_within_edit_distance_counts_characters() {
+        // One character each, though several bytes
+        assert!(within_edit_distance("café", "cafe", 1));
+        assert!(within_edit_distance("naïve", "naive", 1));
+        assert!(within_edit_distance("日本語", "日本", 1));
+        assert!(within_edit_distance("ok 👍", "ok 👎", 1));
+        assert!(!within_edit_distance("日本語", "中国語", 1));
     }
 }
@@ -1,12 +1,23 @@
 //! Memoire Core Library
 //!
 //! Core functionality for Memoire including recording, indexing, and orchestration.
+//! Everything but logging needs the Windows-only `capture` feature.
 
+#[cfg(feature = "capture")]
 pub mod config;
+#[cfg(feature = "capture")]
 pub mod recorder;
+#[cfg(feature = "capture")]
 pub mod indexer;
+#[cfg(feature = "capture")]
 pub mod audio_indexer;
+#[cfg(feature = "capture")]
 pub mod tray;
+#[cfg(feature = "capture")]
+pub mod notifications;
+#[cfg(feature = "capture")]
 pub mod test_config;
+#[cfg(feature = "capture")]
 pub mod orchestrator;
 pub mod colored_logger;
+pub mod shutdown;
@@ -3,26 +3,33 @@
 //! Phase 1: Screen capture with video encoding and SQLite storage.
 
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use chrono::{DateTime, Utc};
+use clap::{Args, Parser, Subcommand};
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::atomic::Ordering;
 use tracing::{error, info, warn, Level};
-use tracing_subscriber::FmtSubscriber;
+
+use memoire_processing::EncoderPreset;
 
 mod recorder;
 mod config;
 mod tray;
+mod notifications;
 mod indexer;
 mod audio_indexer;
 mod test_config;
 mod orchestrator;
 mod colored_logger;
+mod bench;
+mod replay;
+mod shutdown;
+mod timelapse;
 
 use recorder::Recorder;
-use config::Config;
+use config::{Config, DualAudio};
 use tray::TrayApp;
 use indexer::Indexer;
+use colored_logger::LogFormat;
 
 #[derive(Parser)]
 #[command(name = "memoire")]
@@ -39,6 +46,223 @@ struct Cli {
     /// Enable verbose logging
     #[arg(short, long, global = true)]
     verbose: bool,
+
+    /// Path to the ffmpeg binary (or its directory), overrides PATH lookup
+    #[arg(long, global = true)]
+    ffmpeg_path: Option<PathBuf>,
+
+    /// Log output format (json emits one object per event)
+    #[arg(long, global = true, value_enum, default_value_t = LogFormat::Compact)]
+    log_format: LogFormat,
+
+    /// SQLite journal mode: wal, delete or truncate (use delete for network
+    /// shares and OneDrive-synced folders; also MEMOIRE_JOURNAL_MODE)
+    #[arg(long, global = true)]
+    journal_mode: Option<memoire_db::JournalMode>,
+}
+
+/// Recording options shared by `record` and `tray`
+#[derive(Args)]
+struct RecordArgs {
+    /// Recording framerate (FPS), fractional for timelapse (0.5 = one frame every 2 seconds);
+    /// default 1, `tray` defaults to the last value it was given
+    #[arg(short, long, value_parser = parse_fps)]
+    fps: Option<f64>,
+
+    /// Disable hardware encoding (use software x264); `tray` remembers it
+    /// until started with --no-hw=false
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+    no_hw: Option<bool>,
+
+    /// Video quality as CRF/CQ (0-51): 18 near-lossless archival, 23 default, 30 small files
+    #[arg(long, default_value = "23", value_parser = clap::value_parser!(u32).range(0..=51))]
+    quality: u32,
+
+    /// Encoder preset (ultrafast..veryslow or p1..p7): slower presets shrink files at higher CPU/GPU cost
+    #[arg(long, default_value = "fast")]
+    preset: EncoderPreset,
+
+    /// Do not draw the mouse cursor in recordings
+    #[arg(long)]
+    no_cursor: bool,
+
+    /// Video chunk duration in seconds
+    #[arg(long, default_value = "300")]
+    chunk_duration: u64,
+
+    /// Frames buffered before a database write
+    #[arg(long, default_value_t = recorder::DEFAULT_FRAME_BATCH_SIZE)]
+    frame_batch_size: usize,
+
+    /// Maximum seconds between database writes of buffered frames
+    #[arg(long, default_value_t = recorder::DEFAULT_FRAME_FLUSH_INTERVAL_SECS)]
+    flush_interval: u64,
+
+    /// Only record this monitor, by index or name (repeatable; default all)
+    #[arg(long = "monitor", value_name = "MONITOR")]
+    monitors: Vec<String>,
+
+    /// Never record this monitor, by index or name (repeatable)
+    #[arg(long = "exclude-monitor", value_name = "MONITOR")]
+    exclude_monitors: Vec<String>,
+
+    /// Store a monitor under a friendly name, e.g. "1=Main" (repeatable)
+    #[arg(long = "monitor-alias", value_name = "MONITOR=NAME", value_parser = parse_monitor_alias)]
+    monitor_aliases: Vec<(String, String)>,
+
+    /// Seconds without keyboard/mouse input after which frames are marked idle
+    #[arg(long, default_value_t = recorder::DEFAULT_IDLE_THRESHOLD_SECS)]
+    idle_threshold: u64,
+
+    /// Delete audio files older than this many days, keeping their transcriptions
+    /// (independent of video; default keeps audio forever)
+    #[arg(long, value_name = "DAYS", value_parser = clap::value_parser!(u32).range(1..))]
+    audio_retention_days: Option<u32>,
+
+    /// Delete the oldest video and audio once the database and recordings exceed
+    /// this size in GB (default: no limit)
+    #[arg(long, value_name = "GB")]
+    max_storage_gb: Option<f64>,
+
+    /// Finalize chunks and stop recording when less than this many GB are free
+    /// on the data directory's drive (default: no check)
+    #[arg(long, value_name = "GB")]
+    min_free_gb: Option<f64>,
+
+    /// Clip mode: keep only the last SECS of frames in memory and save them
+    /// when the clip hotkey is pressed, instead of recording continuously
+    #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+    clip_buffer: Option<u64>,
+
+    /// Global hotkey saving the clip buffer (e.g. "ctrl+shift+s", "alt+f9")
+    #[arg(long, default_value = recorder::DEFAULT_CLIP_HOTKEY, value_parser = parse_hotkey)]
+    clip_hotkey: String,
+
+    /// Debugging: also save recorded frames as PNG to DIR (one per monitor every
+    /// 10 seconds, at most 500 per monitor), to see the exact pixels OCR will get
+    #[arg(long, value_name = "DIR")]
+    save_raw_frames: Option<PathBuf>,
+}
+
+/// Validate a clip hotkey such as `ctrl+shift+s`
+fn parse_hotkey(s: &str) -> Result<String, String> {
+    s.parse::<memoire_capture::Hotkey>().map_err(|e| e.to_string())?;
+    Ok(s.to_string())
+}
+
+/// Parse a `MONITOR=NAME` alias
+fn parse_monitor_alias(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((monitor, name)) if !monitor.trim().is_empty() && !name.trim().is_empty() => {
+            Ok((monitor.trim().to_string(), name.trim().to_string()))
+        }
+        _ => Err(format!("invalid monitor alias '{}' (expected MONITOR=NAME)", s)),
+    }
+}
+
+/// Parse a timelapse speed factor (at least 1x)
+fn parse_speed(s: &str) -> Result<f64, String> {
+    let speed: f64 = s.parse().map_err(|_| format!("invalid speed: {}", s))?;
+    if !speed.is_finite() || speed < 1.0 {
+        return Err("speed must be at least 1".to_string());
+    }
+    Ok(speed)
+}
+
+/// Parse a normalization target RMS level (0 < level <= 1)
+fn parse_normalize_target(s: &str) -> Result<f32, String> {
+    let level: f32 = s.parse().map_err(|_| format!("invalid level: {}", s))?;
+    if !(level > 0.0 && level <= 1.0) {
+        return Err("level must be above 0 and at most 1".to_string());
+    }
+    Ok(level)
+}
+
+/// Parse a recording framerate, rejecting zero, negative and non-finite values
+fn parse_fps(s: &str) -> Result<f64, String> {
+    let fps: f64 = s.parse().map_err(|_| format!("invalid framerate: {}", s))?;
+    if !fps.is_finite() || fps < config::MIN_FPS {
+        return Err(format!("framerate must be at least {}", config::MIN_FPS));
+    }
+    Ok(fps)
+}
+
+/// Parse a mixing gain between 0 (muted) and 4
+fn parse_gain(s: &str) -> Result<f32, String> {
+    let gain: f32 = s.parse().map_err(|_| format!("invalid gain: {}", s))?;
+    if !(0.0..=4.0).contains(&gain) {
+        return Err("gain must be between 0 and 4".to_string());
+    }
+    Ok(gain)
+}
+
+/// Parse a `--from`/`--to` time: RFC 3339, or local "YYYY-MM-DD HH:MM[:SS]"
+fn parse_time(s: &str) -> Result<DateTime<Utc>, String> {
+    use chrono::TimeZone;
+
+    if let Ok(time) = DateTime::parse_from_rfc3339(s) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M"] {
+        if let Ok(naive) = chrono::NaiveDateTime::parse_from_str(s, format) {
+            return chrono::Local
+                .from_local_datetime(&naive)
+                .earliest()
+                .map(|time| time.with_timezone(&Utc))
+                .ok_or_else(|| format!("{} does not exist in the local time zone", s));
+        }
+    }
+    Err(format!("invalid time: {} (expected RFC 3339 or \"YYYY-MM-DD HH:MM[:SS]\")", s))
+}
+
+impl RecordArgs {
+    /// Build a recorder configuration for the given data directory
+    fn to_config(&self, data_dir: PathBuf) -> Config {
+        let defaults = Config::default();
+        Config {
+            data_dir,
+            fps: self.fps.unwrap_or(defaults.fps),
+            use_hw_encoding: self.use_hw_encoding().unwrap_or(defaults.use_hw_encoding),
+            chunk_duration_secs: self.chunk_duration,
+            quality: self.quality,
+            preset: self.preset,
+            capture_cursor: !self.no_cursor,
+            frame_batch_size: self.frame_batch_size.max(1),
+            frame_flush_interval_secs: self.flush_interval,
+            monitors: config::MonitorSelection {
+                include: self.monitors.clone(),
+                exclude: self.exclude_monitors.clone(),
+                aliases: self.monitor_aliases.iter().cloned().collect(),
+            },
+            idle_threshold_secs: self.idle_threshold,
+            audio_retention_days: self.audio_retention_days,
+            max_storage_bytes: self.max_storage_gb.map(config::gb_to_bytes),
+            min_free_bytes: self.min_free_gb.map(config::gb_to_bytes),
+            clip_buffer_secs: self.clip_buffer,
+            clip_hotkey: self.clip_hotkey.clone(),
+            save_raw_frames: self.save_raw_frames.clone(),
+            ..defaults
+        }
+    }
+
+    /// Hardware encoding setting, if given with `--no-hw`
+    fn use_hw_encoding(&self) -> Option<bool> {
+        self.no_hw.map(|no_hw| !no_hw)
+    }
+}
+
+/// Audio recording options for `record`
+struct AudioOptions {
+    /// Capture device ID (default device when unset)
+    device: Option<String>,
+    /// Capture system audio instead of the microphone
+    loopback: bool,
+    /// End chunks at a pause instead of a fixed duration
+    split_on_silence: bool,
+    /// Record the microphone and system audio together
+    dual: Option<DualAudio>,
+    /// Also mux the audio into each video chunk
+    mux: bool,
 }
 
 #[derive(Subcommand)]
@@ -49,13 +273,46 @@ enum Commands {
         #[arg(short, long)]
         data_dir: Option<PathBuf>,
 
-        /// Recording framerate (FPS)
-        #[arg(short, long, default_value = "1")]
-        fps: u32,
+        #[command(flatten)]
+        options: RecordArgs,
 
-        /// Disable hardware encoding (use software x264)
+        /// Also record audio into the same database
         #[arg(long)]
-        no_hw: bool,
+        audio: bool,
+
+        /// Audio device ID (from audio-devices command), requires --audio;
+        /// an output device is recorded in loopback mode
+        #[arg(long, requires = "audio")]
+        audio_device: Option<String>,
+
+        /// Capture system audio instead of microphone, requires --audio
+        #[arg(long, requires = "audio")]
+        loopback: bool,
+
+        /// Close audio chunks on a pause near the chunk duration, requires --audio
+        #[arg(long, requires = "audio")]
+        split_on_silence: bool,
+
+        /// Record system audio as well as the microphone, as separate chunks
+        #[arg(long, requires = "audio", conflicts_with = "loopback")]
+        audio_both: bool,
+
+        /// Record the microphone and system audio mixed into one stream
+        #[arg(long, requires = "audio", conflicts_with_all = ["loopback", "audio_both"])]
+        audio_mix: bool,
+
+        /// Microphone gain in the mix, requires --audio-mix
+        #[arg(long, default_value = "1.0", requires = "audio_mix", value_parser = parse_gain)]
+        mic_gain: f32,
+
+        /// System audio gain in the mix, requires --audio-mix
+        #[arg(long, default_value = "1.0", requires = "audio_mix", value_parser = parse_gain)]
+        system_gain: f32,
+
+        /// Mux the audio into the video chunks (AAC), requires --audio;
+        /// WAV chunks are still kept for transcription
+        #[arg(long, requires = "audio")]
+        mux_audio: bool,
     },
 
     /// Run in system tray mode
@@ -64,13 +321,8 @@ enum Commands {
         #[arg(short, long)]
         data_dir: Option<PathBuf>,
 
-        /// Recording framerate (FPS)
-        #[arg(short, long, default_value = "1")]
-        fps: u32,
-
-        /// Disable hardware encoding (use software x264)
-        #[arg(long)]
-        no_hw: bool,
+        #[command(flatten)]
+        options: RecordArgs,
     },
 
     /// Show system status
@@ -79,18 +331,114 @@ enum Commands {
     /// List monitors
     Monitors,
 
+    /// Capture a few frames from one monitor to PNG files for debugging
+    ///
+    /// Needs no database or encoder; prints each frame's perceptual hash and its
+    /// distance from the previous frame to tell static screens from capture failures.
+    CaptureTest {
+        /// Monitor index as listed by `memoire monitors`
+        #[arg(short, long, default_value = "0")]
+        monitor: usize,
+
+        /// Number of frames to capture
+        #[arg(short, long, default_value = "5")]
+        count: u32,
+
+        /// Output directory for the PNG files
+        #[arg(short, long, default_value = "./debug")]
+        out: PathBuf,
+
+        /// Milliseconds between captures
+        #[arg(long, default_value = "1000")]
+        interval_ms: u64,
+    },
+
     /// Check dependencies (FFmpeg, etc.)
     Check,
 
+    /// Measure capture, OCR and transcription throughput on this machine
+    ///
+    /// Reports whether the indexers can keep up with recording at the given rates.
+    Bench {
+        /// Data directory (for the speech-to-text models)
+        #[arg(short, long)]
+        data_dir: Option<PathBuf>,
+
+        /// Monitor index to capture from
+        #[arg(short, long, default_value = "0")]
+        monitor: usize,
+
+        /// Frames to capture
+        #[arg(long, default_value = "100")]
+        frames: u32,
+
+        /// Frames to run OCR on
+        #[arg(long, default_value = "20")]
+        ocr_frames: usize,
+
+        /// OCR language(s), as for `index`
+        #[arg(long)]
+        ocr_language: Option<String>,
+
+        /// Recording framerate to check against
+        #[arg(short, long, default_value = "1", value_parser = parse_fps)]
+        fps: f64,
+
+        /// OCR indexer rate limit to check against
+        #[arg(long, default_value = "10")]
+        ocr_fps: u32,
+
+        /// WAV file to transcribe (default: a synthetic 30 s clip)
+        #[arg(long)]
+        wav: Option<PathBuf>,
+
+        /// Skip the GPU transcription run
+        #[arg(long)]
+        no_gpu: bool,
+
+        /// GPU device index for transcription
+        #[arg(long, default_value = "0")]
+        gpu_device: i32,
+
+        /// Threads used for CPU inference
+        #[arg(long, default_value_t = memoire_stt::DEFAULT_NUM_THREADS)]
+        stt_threads: usize,
+    },
+
     /// Start validation viewer web interface
     Viewer {
         /// Data directory for videos and database
         #[arg(short, long)]
         data_dir: Option<PathBuf>,
 
+        /// Address to bind (127.0.0.1 keeps the viewer local; use a LAN address to expose it)
+        #[arg(long, default_value_t = memoire_web::DEFAULT_HOST)]
+        host: std::net::IpAddr,
+
         /// Web server port
         #[arg(short, long, default_value = "8080")]
         port: u16,
+
+        /// Memory budget in MiB for cached frame images (0 disables the cache)
+        #[arg(long, default_value = "64")]
+        frame_cache_mb: usize,
+
+        /// Seconds a request may take before it fails with 408
+        #[arg(long, default_value = "30")]
+        request_timeout_secs: u64,
+
+        /// Largest accepted request body in KiB
+        #[arg(long, default_value = "1024")]
+        max_body_kb: usize,
+
+        /// Largest page a listing or search returns (caps the `limit` parameter)
+        #[arg(long, default_value = "100")]
+        max_page_size: i64,
+
+        /// Open the database read-only, without migrating it (for inspecting backups;
+        /// re-indexing is disabled)
+        #[arg(long)]
+        read_only: bool,
     },
 
     /// Run OCR indexer on captured frames
@@ -103,9 +451,42 @@ enum Commands {
         #[arg(long, default_value = "10")]
         ocr_fps: u32,
 
-        /// OCR language (BCP47 tag, e.g., "en-US", "fr-FR", "de-DE", "ja-JP")
+        /// OCR language (BCP47 tag, e.g., "en-US", "fr-FR", "de-DE", "ja-JP"); a
+        /// comma-separated list such as "en-US,ja-JP" keeps the best result per frame
         #[arg(long)]
         ocr_language: Option<String>,
+
+        /// With several OCR languages, combine lines from all of them instead of
+        /// keeping the best one (for screens mixing scripts)
+        #[arg(long)]
+        merge_languages: bool,
+
+        /// Downscale frames to this many pixels on their longest side before OCR
+        /// (e.g. 1920 for 4K monitors); text positions keep full-resolution coordinates
+        #[arg(long, value_name = "PX", value_parser = clap::value_parser!(u32).range(1..))]
+        ocr_downscale: Option<u32>,
+
+        /// Frames fetched per OCR batch
+        #[arg(long, default_value_t = indexer::DEFAULT_OCR_BATCH_SIZE)]
+        batch_size: usize,
+
+        /// Concurrent FFmpeg frame extractions (raise on fast disks/many cores, lower on laptops)
+        #[arg(long, default_value_t = indexer::DEFAULT_MAX_CONCURRENT_EXTRACTIONS)]
+        max_extractions: usize,
+
+        /// Store a reference instead of repeating OCR text identical to the previous frame
+        #[arg(long)]
+        dedup_text: bool,
+
+        /// Max character edits still treated as identical text
+        #[arg(long, default_value_t = indexer::DEFAULT_TEXT_DEDUP_DISTANCE, requires = "dedup_text")]
+        dedup_distance: usize,
+
+        /// Reuse existing OCR for frames whose perceptual hash was already indexed
+        /// (skips extraction and OCR for screens seen before). Screens with the
+        /// same layout but different text get the earlier, stale text
+        #[arg(long)]
+        ocr_hash_cache: bool,
     },
 
     /// Search OCR text
@@ -120,6 +501,82 @@ enum Commands {
         /// Maximum number of results
         #[arg(short, long, default_value = "10")]
         limit: i64,
+
+        /// Skip frames captured while the user was idle
+        #[arg(long)]
+        focused_only: bool,
+    },
+
+    /// Export a time window as one MP4, joining the chunks it spans
+    Replay {
+        /// Window start (RFC 3339, or local time "YYYY-MM-DD HH:MM[:SS]")
+        #[arg(long, value_parser = parse_time)]
+        from: DateTime<Utc>,
+
+        /// Window end (RFC 3339, or local time "YYYY-MM-DD HH:MM[:SS]")
+        #[arg(long, value_parser = parse_time)]
+        to: DateTime<Utc>,
+
+        /// Output video file
+        #[arg(short, long, default_value = "replay.mp4")]
+        out: PathBuf,
+
+        /// Monitor (device name) to export when several were recorded
+        #[arg(short, long)]
+        monitor: Option<String>,
+
+        /// Only list the chunks in the window, don't export
+        #[arg(long)]
+        list: bool,
+
+        /// Data directory for videos and database
+        #[arg(short, long)]
+        data_dir: Option<PathBuf>,
+    },
+
+    /// Export a day of one monitor as a sped-up MP4 or GIF
+    Timelapse {
+        /// Day to export, YYYY-MM-DD in local time (default: today)
+        #[arg(long)]
+        date: Option<chrono::NaiveDate>,
+
+        /// Monitor (device name) to export when several were recorded
+        #[arg(short, long)]
+        monitor: Option<String>,
+
+        /// Output file; a .gif extension writes an animated GIF
+        #[arg(short, long, default_value = "timelapse.mp4")]
+        out: PathBuf,
+
+        /// Playback speed relative to recording time
+        #[arg(long, default_value_t = timelapse::DEFAULT_SPEED, value_parser = parse_speed)]
+        speed: f64,
+
+        /// Frame rate of the output
+        #[arg(long, default_value = "30", value_parser = parse_fps)]
+        fps: f64,
+
+        /// Only list the chunks of the day, don't export
+        #[arg(long)]
+        list: bool,
+
+        /// Data directory for videos and database
+        #[arg(short, long)]
+        data_dir: Option<PathBuf>,
+    },
+
+    /// Run OCR on a single image file and print the result (no database needed)
+    Ocr {
+        /// Image to recognize (PNG, JPEG, BMP, ...)
+        image: PathBuf,
+
+        /// OCR language (BCP47 tag, e.g., "en-US"); a comma-separated list keeps the best result
+        #[arg(long)]
+        language: Option<String>,
+
+        /// Print the full result (lines, words, bounding boxes, confidences) as JSON
+        #[arg(long)]
+        json: bool,
     },
 
     /// Reset OCR data (clear empty records for re-indexing)
@@ -133,6 +590,36 @@ enum Commands {
         all: bool,
     },
 
+    /// Show the database schema version (optionally migrate up or down)
+    DbVersion {
+        /// Data directory for videos and database
+        #[arg(short, long)]
+        data_dir: Option<PathBuf>,
+
+        /// Migrate the schema to this version (rolls back if lower)
+        #[arg(long)]
+        migrate_to: Option<i64>,
+    },
+
+    /// Check the database for corruption (SQLite and full-text index integrity)
+    DbCheck {
+        /// Data directory for videos and database
+        #[arg(short, long)]
+        data_dir: Option<PathBuf>,
+    },
+
+    /// Find chunk rows whose video/audio file is missing, and media files with no row
+    Reconcile {
+        /// Data directory for videos and database
+        #[arg(short, long)]
+        data_dir: Option<PathBuf>,
+
+        /// Remove the rows of missing files (video chunks with their frames and
+        /// OCR; audio chunks are marked pruned and keep their transcriptions)
+        #[arg(long)]
+        delete: bool,
+    },
+
     /// List available audio devices
     AudioDevices,
 
@@ -142,7 +629,7 @@ enum Commands {
         #[arg(short, long)]
         data_dir: Option<PathBuf>,
 
-        /// Audio device ID (from audio-devices command)
+        /// Audio device ID (from audio-devices command); an output device is recorded in loopback mode
         #[arg(long)]
         device: Option<String>,
 
@@ -153,6 +640,10 @@ enum Commands {
         /// Enable loopback mode (capture system audio instead of microphone)
         #[arg(long)]
         loopback: bool,
+
+        /// Close chunks on a pause between 0.8x and 1.5x the chunk duration
+        #[arg(long)]
+        split_on_silence: bool,
     },
 
     /// Run audio transcription indexer
@@ -164,6 +655,91 @@ enum Commands {
         /// Disable GPU acceleration
         #[arg(long)]
         no_gpu: bool,
+
+        /// GPU device index for transcription (see nvidia-smi or dxdiag)
+        #[arg(long, default_value = "0")]
+        gpu_device: i32,
+
+        /// Execution providers to try in order, comma separated (tensorrt, cuda, directml, cpu)
+        #[arg(long, value_delimiter = ',')]
+        stt_providers: Vec<memoire_stt::ExecutionProvider>,
+
+        /// Threads used for CPU inference
+        #[arg(long, default_value_t = memoire_stt::DEFAULT_NUM_THREADS)]
+        stt_threads: usize,
+
+        /// Raise quiet recordings to a common level before transcription
+        #[arg(long)]
+        normalize_audio: bool,
+
+        /// RMS level --normalize-audio aims for (0.1 is about -20 dBFS)
+        #[arg(long, default_value_t = memoire_stt::DEFAULT_NORMALIZE_TARGET_RMS, requires = "normalize_audio", value_parser = parse_normalize_target)]
+        normalize_target: f32,
+
+        /// Resampler for audio not recorded at 16kHz: fast (FFT) or high (sinc);
+        /// 8kHz telephony audio always uses sinc
+        #[arg(long, default_value_t = memoire_stt::ResampleQuality::default())]
+        resample_quality: memoire_stt::ResampleQuality,
+
+        /// Audio chunks fetched per transcription batch
+        #[arg(long, default_value_t = audio_indexer::DEFAULT_AUDIO_BATCH_SIZE)]
+        batch_size: i64,
+
+        /// Transcribe every pending chunk in parallel, then exit
+        #[arg(long)]
+        backlog: bool,
+
+        /// Engines running in parallel with --backlog (each loads its own model)
+        #[arg(long, default_value_t = audio_indexer::DEFAULT_BACKLOG_CONCURRENCY)]
+        concurrency: usize,
+
+        /// Clear all transcriptions first and transcribe every chunk again
+        /// (e.g. with a new model); pruned audio keeps its text
+        #[arg(long)]
+        retranscribe: bool,
+
+        /// Clear only transcriptions of chunks that produced no text, then transcribe them again
+        #[arg(long, conflicts_with = "retranscribe")]
+        retranscribe_empty: bool,
+    },
+
+    /// Transcribe a single WAV file and print the result (no database needed)
+    Transcribe {
+        /// WAV file to transcribe
+        file: PathBuf,
+
+        /// Data directory (for the speech-to-text models)
+        #[arg(short, long)]
+        data_dir: Option<PathBuf>,
+
+        /// Disable GPU acceleration
+        #[arg(long)]
+        no_gpu: bool,
+
+        /// GPU device index for transcription (see nvidia-smi or dxdiag)
+        #[arg(long, default_value = "0")]
+        gpu_device: i32,
+
+        /// Execution providers to try in order, comma separated (tensorrt, cuda, directml, cpu)
+        #[arg(long, value_delimiter = ',')]
+        stt_providers: Vec<memoire_stt::ExecutionProvider>,
+
+        /// Print the full result (text, segments with timestamps) as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Raise quiet recordings to a common level before transcription
+        #[arg(long)]
+        normalize_audio: bool,
+
+        /// RMS level --normalize-audio aims for (0.1 is about -20 dBFS)
+        #[arg(long, default_value_t = memoire_stt::DEFAULT_NORMALIZE_TARGET_RMS, requires = "normalize_audio", value_parser = parse_normalize_target)]
+        normalize_target: f32,
+
+        /// Resampler for audio not recorded at 16kHz: fast (FFT) or high (sinc);
+        /// 8kHz telephony audio always uses sinc
+        #[arg(long, default_value_t = memoire_stt::ResampleQuality::default())]
+        resample_quality: memoire_stt::ResampleQuality,
     },
 
     /// Download Parakeet TDT speech-to-text models
@@ -199,19 +775,61 @@ fn main() -> Result<()> {
     // Initialize logging (skip for test-all which uses colored logger)
     if !matches!(cli.command, Commands::TestAll { .. }) {
         let level = if cli.verbose { Level::DEBUG } else { Level::INFO };
-        FmtSubscriber::builder()
-            .with_max_level(level)
-            .with_target(false)
-            .compact()
-            .init();
+        colored_logger::init_logger(cli.log_format, level)?;
+    }
+
+    if let Some(mode) = cli.journal_mode {
+        config::set_journal_mode(mode);
+    }
+
+    // Resolve FFmpeg once so every subprocess uses the same binary
+    let ffmpeg = memoire_processing::FfmpegLocator::init(cli.ffmpeg_path.as_deref());
+    let needs_ffmpeg = matches!(
+        cli.command,
+        Commands::Record { .. }
+            | Commands::Tray { .. }
+            | Commands::Index { .. }
+            | Commands::TestAll { .. }
+            | Commands::Replay { list: false, .. }
+            | Commands::Timelapse { list: false, .. }
+    );
+    if needs_ffmpeg {
+        if let Err(e) = ffmpeg {
+            error!("{}", e);
+            return Err(e);
+        }
     }
 
     match cli.command {
-        Commands::Record { data_dir, fps, no_hw } => {
-            cmd_record(data_dir, fps, !no_hw)?;
+        Commands::Record {
+            data_dir,
+            options,
+            audio,
+            audio_device,
+            loopback,
+            split_on_silence,
+            audio_both,
+            audio_mix,
+            mic_gain,
+            system_gain,
+            mux_audio,
+        } => {
+            let dual = if audio_mix {
+                Some(DualAudio::Mixed { mic_gain, system_gain })
+            } else {
+                audio_both.then_some(DualAudio::Separate)
+            };
+            let audio = audio.then_some(AudioOptions {
+                device: audio_device,
+                loopback,
+                split_on_silence,
+                dual,
+                mux: mux_audio,
+            });
+            cmd_record(data_dir, options, audio)?;
         }
-        Commands::Tray { data_dir, fps, no_hw } => {
-            cmd_tray(data_dir, fps, !no_hw)?;
+        Commands::Tray { data_dir, options } => {
+            cmd_tray(data_dir, options)?;
         }
         Commands::Status => {
             cmd_status()?;
@@ -219,94 +837,194 @@ fn main() -> Result<()> {
         Commands::Monitors => {
             cmd_monitors()?;
         }
+        Commands::CaptureTest { monitor, count, out, interval_ms } => {
+            cmd_capture_test(monitor, count, out, interval_ms)?;
+        }
         Commands::Check => {
             cmd_check()?;
         }
-        Commands::Viewer { data_dir, port } => {
-            cmd_viewer(data_dir, port)?;
+        Commands::Bench { data_dir, monitor, frames, ocr_frames, ocr_language, fps, ocr_fps, wav, no_gpu, gpu_device, stt_threads } => {
+            cmd_bench(bench::BenchOptions {
+                data_dir: config::resolve_data_dir(data_dir),
+                monitor,
+                frames,
+                ocr_frames,
+                ocr_language,
+                fps,
+                ocr_fps,
+                wav,
+                use_gpu: !no_gpu,
+                gpu_device,
+                stt_threads,
+            })?;
+        }
+        Commands::Viewer { data_dir, host, port, frame_cache_mb, request_timeout_secs, max_body_kb, max_page_size, read_only } => {
+            let limits = memoire_web::RequestLimits {
+                timeout: std::time::Duration::from_secs(request_timeout_secs.max(1)),
+                max_body_bytes: max_body_kb.saturating_mul(1024),
+                max_page_size: max_page_size.max(1),
+            };
+            cmd_viewer(data_dir, host, port, frame_cache_mb, limits, read_only)?;
+        }
+        Commands::Index { data_dir, ocr_fps, ocr_language, merge_languages, ocr_downscale, batch_size, max_extractions, dedup_text, dedup_distance, ocr_hash_cache } => {
+            let text_dedup = dedup_text.then_some(dedup_distance);
+            cmd_index(data_dir, ocr_fps, ocr_language, merge_languages, ocr_downscale, batch_size, max_extractions, text_dedup, ocr_hash_cache)?;
+        }
+        Commands::Search { query, data_dir, limit, focused_only } => {
+            cmd_search(query, data_dir, limit, focused_only)?;
+        }
+        Commands::Replay { from, to, out, monitor, list, data_dir } => {
+            cmd_replay(data_dir, from, to, out, monitor, list)?;
         }
-        Commands::Index { data_dir, ocr_fps, ocr_language } => {
-            cmd_index(data_dir, ocr_fps, ocr_language)?;
+        Commands::Timelapse { date, monitor, out, speed, fps, list, data_dir } => {
+            let date = date.unwrap_or_else(|| chrono::Local::now().date_naive());
+            cmd_timelapse(data_dir, date, monitor, out, speed, fps, list)?;
         }
-        Commands::Search { query, data_dir, limit } => {
-            cmd_search(query, data_dir, limit)?;
+        Commands::Ocr { image, language, json } => {
+            cmd_ocr(image, language, json)?;
         }
         Commands::ResetOcr { data_dir, all } => {
             cmd_reset_ocr(data_dir, all)?;
         }
+        Commands::DbCheck { data_dir } => {
+            cmd_db_check(data_dir)?;
+        }
+        Commands::Reconcile { data_dir, delete } => {
+            cmd_reconcile(data_dir, delete)?;
+        }
+        Commands::DbVersion { data_dir, migrate_to } => {
+            cmd_db_version(data_dir, migrate_to)?;
+        }
         Commands::AudioDevices => {
             cmd_audio_devices()?;
         }
-        Commands::RecordAudio { data_dir, device, chunk_secs, loopback } => {
-            cmd_record_audio(data_dir, device, chunk_secs, loopback)?;
+        Commands::RecordAudio { data_dir, device, chunk_secs, loopback, split_on_silence } => {
+            cmd_record_audio(data_dir, device, chunk_secs, loopback, split_on_silence)?;
         }
-        Commands::AudioIndex { data_dir, no_gpu } => {
-            cmd_audio_index(data_dir, !no_gpu)?;
+        Commands::AudioIndex {
+            data_dir,
+            no_gpu,
+            gpu_device,
+            stt_providers,
+            stt_threads,
+            normalize_audio,
+            normalize_target,
+            resample_quality,
+            batch_size,
+            backlog,
+            concurrency,
+            retranscribe,
+            retranscribe_empty,
+        } => {
+            let stt_config = memoire_stt::SttConfig {
+                num_threads: stt_threads.max(1),
+                normalize_audio,
+                normalize_target_rms: normalize_target,
+                resample_quality,
+                ..stt_config(no_gpu, gpu_device, stt_providers)
+            };
+            let backlog_concurrency = backlog.then_some(concurrency);
+            cmd_audio_index(data_dir, stt_config, batch_size, backlog_concurrency, retranscribe, retranscribe_empty)?;
+        }
+        Commands::Transcribe { file, data_dir, no_gpu, gpu_device, stt_providers, json, normalize_audio, normalize_target, resample_quality } => {
+            let stt_config = memoire_stt::SttConfig {
+                normalize_audio,
+                normalize_target_rms: normalize_target,
+                resample_quality,
+                ..stt_config(no_gpu, gpu_device, stt_providers)
+            };
+            cmd_transcribe(file, data_dir, stt_config, json)?;
         }
         Commands::DownloadModels { data_dir, force } => {
             cmd_download_models(data_dir, force)?;
         }
         Commands::TestAll { config, profile, data_dir } => {
-            cmd_test_all(config, profile, data_dir)?;
+            cmd_test_all(config, profile, data_dir, cli.log_format)?;
         }
     }
 
     Ok(())
 }
 
-fn cmd_record(data_dir: Option<PathBuf>, fps: u32, use_hw: bool) -> Result<()> {
+fn cmd_record(
+    data_dir: Option<PathBuf>,
+    options: RecordArgs,
+    audio: Option<AudioOptions>,
+) -> Result<()> {
     // Resolve data directory
-    let data_dir = data_dir.unwrap_or_else(|| {
-        dirs::data_local_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("Memoire")
-    });
+    let data_dir = config::resolve_data_dir(data_dir);
+    let video = options.to_config(data_dir);
 
     info!("starting memoire recorder");
-    info!("data directory: {:?}", data_dir);
-    info!("fps: {}, hardware encoding: {}", fps, use_hw);
-
-    // Check FFmpeg
-    if !memoire_processing::encoder::check_ffmpeg() {
-        error!("ffmpeg not found in PATH - please install FFmpeg");
-        return Err(anyhow::anyhow!("FFmpeg not found"));
+    info!("data directory: {:?}", video.data_dir);
+    info!("fps: {}, hardware encoding: {}", video.fps, video.use_hw_encoding);
+    info!("quality: {}, preset: {}", options.quality, options.preset.x264_name());
+
+    let record_audio = audio.is_some();
+    let audio = audio.unwrap_or(AudioOptions {
+        device: None,
+        loopback: false,
+        split_on_silence: false,
+        dual: None,
+        mux: false,
+    });
+    if record_audio {
+        info!("audio: enabled (loopback: {}, split on silence: {})", audio.loopback, audio.split_on_silence);
+    }
+    if audio.mux {
+        info!("audio: muxing into video chunks");
+    }
+    match audio.dual {
+        Some(DualAudio::Separate) => info!("audio: recording microphone and system audio separately"),
+        Some(DualAudio::Mixed { mic_gain, system_gain }) => {
+            info!("audio: mixing microphone ({}) and system audio ({})", mic_gain, system_gain)
+        }
+        None => {}
+    }
+    if let Some(days) = options.audio_retention_days {
+        info!("audio retention: {} days", days);
+    }
+    if let Some(gb) = options.max_storage_gb {
+        info!("storage limit: {} GB", gb);
+    }
+    if let Some(gb) = options.min_free_gb {
+        info!("stopping when less than {} GB are free", gb);
+    }
+    if let Some(secs) = options.clip_buffer {
+        info!("clip mode: buffering {} seconds, saved with {}", secs, options.clip_hotkey);
+    }
+    if let Some(dir) = &options.save_raw_frames {
+        warn!("debug: saving raw frames to {:?}", dir);
     }
 
-    if use_hw && !memoire_processing::encoder::check_nvenc() {
+    if video.use_hw_encoding && !memoire_processing::encoder::check_nvenc() {
         warn!("NVENC not available, will fall back to software encoding");
     }
 
     // Setup signal handler
-    let running = Arc::new(AtomicBool::new(true));
-    let r = running.clone();
-
-    ctrlc::set_handler(move || {
-        info!("received shutdown signal");
-        r.store(false, Ordering::SeqCst);
-    })?;
+    let shutdown = shutdown::install_shutdown_handler();
 
     // Create and start recorder
     let config = Config {
-        data_dir,
-        fps,
-        use_hw_encoding: use_hw,
-        chunk_duration_secs: 300, // 5 minutes
+        record_audio,
+        audio_device: audio.device,
+        audio_loopback: audio.loopback,
+        audio_split_on_silence: audio.split_on_silence,
+        audio_dual: audio.dual,
+        mux_audio: audio.mux,
+        ..video
     };
 
     let mut recorder = Recorder::new(config)?;
-    recorder.run(running)?;
+    recorder.run(shutdown)?;
 
     info!("recorder stopped");
     Ok(())
 }
 
-fn cmd_tray(data_dir: Option<PathBuf>, fps: u32, use_hw: bool) -> Result<()> {
+fn cmd_tray(data_dir: Option<PathBuf>, options: RecordArgs) -> Result<()> {
     // Resolve data directory
-    let data_dir = data_dir.unwrap_or_else(|| {
-        dirs::data_local_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("Memoire")
-    });
+    let data_dir = config::resolve_data_dir(data_dir);
 
     // Create directories
     std::fs::create_dir_all(&data_dir)?;
@@ -315,29 +1033,16 @@ fn cmd_tray(data_dir: Option<PathBuf>, fps: u32, use_hw: bool) -> Result<()> {
     info!("starting memoire tray");
     info!("data directory: {:?}", data_dir);
 
-    // Check FFmpeg
-    if !memoire_processing::encoder::check_ffmpeg() {
-        error!("ffmpeg not found in PATH - please install FFmpeg");
-        return Err(anyhow::anyhow!("FFmpeg not found"));
-    }
+    let config = options.to_config(data_dir);
 
-    let config = Config {
-        data_dir,
-        fps,
-        use_hw_encoding: use_hw,
-        chunk_duration_secs: 300,
-    };
-
-    let app = TrayApp::new(config);
+    let app = TrayApp::new(config, options.fps, options.use_hw_encoding());
     app.run()?;
 
     Ok(())
 }
 
 fn cmd_status() -> Result<()> {
-    let data_dir = dirs::data_local_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("Memoire");
+    let data_dir = config::resolve_data_dir(None);
 
     let db_path = data_dir.join("memoire.db");
 
@@ -347,7 +1052,7 @@ fn cmd_status() -> Result<()> {
         return Ok(());
     }
 
-    let db = memoire_db::Database::open(&db_path)?;
+    let db = config::open_database(&db_path)?;
     let frame_count = memoire_db::get_frame_count(db.connection())?;
     let ocr_count = memoire_db::get_ocr_count(db.connection())?;
 
@@ -390,15 +1095,82 @@ fn cmd_monitors() -> Result<()> {
     Ok(())
 }
 
-fn cmd_check() -> Result<()> {
-    println!("checking dependencies...\n");
+fn cmd_capture_test(monitor_index: usize, count: u32, out: PathBuf, interval_ms: u64) -> Result<()> {
+    use memoire_capture::screen::CapturedFrame;
+    use std::time::Duration;
+
+    let monitors = memoire_capture::Monitor::enumerate_all()?;
+    let info = monitors.get(monitor_index).cloned().ok_or_else(|| {
+        anyhow::anyhow!(
+            "monitor {} not found ({} available, see 'memoire monitors')",
+            monitor_index,
+            monitors.len()
+        )
+    })?;
 
-    // FFmpeg
-    let ffmpeg_ok = memoire_processing::encoder::check_ffmpeg();
     println!(
-        "  ffmpeg: {}",
-        if ffmpeg_ok { "OK" } else { "NOT FOUND" }
+        "capturing {} frame(s) from [{}] {} ({}x{}) into {}\n",
+        count, monitor_index, info.name, info.width, info.height, out.display()
     );
+    if memoire_capture::is_session_locked() {
+        println!("warning: the session is locked, the lock screen cannot be captured\n");
+    }
+
+    let monitor = memoire_capture::Monitor::from_info(info)?;
+    let mut capture = memoire_capture::ScreenCapture::new(&monitor)
+        .map_err(|e| anyhow::anyhow!("failed to start desktop duplication: {}", e))?;
+    std::fs::create_dir_all(&out)?;
+
+    let mut previous_hash: Option<u64> = None;
+    let mut saved = 0;
+    for i in 0..count {
+        if i > 0 {
+            std::thread::sleep(Duration::from_millis(interval_ms));
+        }
+
+        // DXGI only hands out a frame when the screen changed since the last one
+        match capture.capture_frame(Duration::from_millis(1000)) {
+            Ok(Some(frame)) => {
+                let path = out.join(format!("frame_{:03}.png", i));
+                frame.save_png(&path.to_string_lossy())?;
+                saved += 1;
+
+                let hash = frame.compute_perceptual_hash();
+                match previous_hash {
+                    Some(previous) => {
+                        let distance = CapturedFrame::hash_distance(hash, previous);
+                        println!(
+                            "  [{}] {} hash {:016x} distance {}{}",
+                            i,
+                            path.display(),
+                            hash,
+                            distance,
+                            if distance <= recorder::DEFAULT_DEDUP_THRESHOLD { " (duplicate, recorder would skip)" } else { "" }
+                        );
+                    }
+                    None => println!("  [{}] {} hash {:016x}", i, path.display(), hash),
+                }
+                previous_hash = Some(hash);
+            }
+            Ok(None) => println!("  [{}] no new frame (screen unchanged or DXGI timeout)", i),
+            Err(e) => println!("  [{}] capture failed: {}", i, e),
+        }
+    }
+
+    println!("\nsaved {} of {} frame(s)", saved, count);
+    Ok(())
+}
+
+fn cmd_check() -> Result<()> {
+    println!("checking dependencies...\n");
+
+    // FFmpeg (resolved at startup)
+    let ffmpeg = memoire_processing::FfmpegLocator::global();
+    let ffmpeg_ok = ffmpeg.is_some();
+    match ffmpeg {
+        Some(locator) => println!("  ffmpeg: OK ({})", locator.ffmpeg().display()),
+        None => println!("  ffmpeg: NOT FOUND"),
+    }
 
     // NVENC
     if ffmpeg_ok {
@@ -427,13 +1199,21 @@ fn cmd_check() -> Result<()> {
 }
 
 #[tokio::main]
-async fn cmd_viewer(data_dir: Option<PathBuf>, port: u16) -> Result<()> {
+async fn cmd_bench(options: bench::BenchOptions) -> Result<()> {
+    bench::run(options).await
+}
+
+#[tokio::main]
+async fn cmd_viewer(
+    data_dir: Option<PathBuf>,
+    host: std::net::IpAddr,
+    port: u16,
+    frame_cache_mb: usize,
+    limits: memoire_web::RequestLimits,
+    read_only: bool,
+) -> Result<()> {
     // Resolve data directory
-    let data_dir = data_dir.unwrap_or_else(|| {
-        dirs::data_local_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("Memoire")
-    });
+    let data_dir = config::resolve_data_dir(data_dir);
 
     let db_path = data_dir.join("memoire.db");
 
@@ -446,26 +1226,41 @@ async fn cmd_viewer(data_dir: Option<PathBuf>, port: u16) -> Result<()> {
     info!("starting memoire validation viewer");
     info!("data directory: {:?}", data_dir);
     info!("database: {:?}", db_path);
-    info!("web interface: http://localhost:{}", port);
+    info!("web interface: http://{}", std::net::SocketAddr::new(host, port));
 
-    // Open database connection
-    let db = memoire_db::Database::open(&db_path)?;
+    // Open database connection (a read-only one leaves the file untouched)
+    let db = if read_only {
+        info!("read-only: the database will not be migrated or modified");
+        memoire_db::Database::open_read_only(&db_path)?
+    } else {
+        config::open_database(&db_path)?
+    };
     let connection = db.into_connection();
 
     // Start web server
-    memoire_web::serve(connection, data_dir, port).await?;
+    let state = memoire_web::AppState::new(connection, data_dir)
+        .with_frame_cache_budget(frame_cache_mb.saturating_mul(1024 * 1024))
+        .with_limits(limits)
+        .with_read_only(read_only);
+    memoire_web::serve_with_state(state, host, port).await?;
 
     Ok(())
 }
 
 #[tokio::main]
-async fn cmd_index(data_dir: Option<PathBuf>, ocr_fps: u32, ocr_language: Option<String>) -> Result<()> {
+async fn cmd_index(
+    data_dir: Option<PathBuf>,
+    ocr_fps: u32,
+    ocr_language: Option<String>,
+    merge_languages: bool,
+    ocr_downscale: Option<u32>,
+    batch_size: usize,
+    max_extractions: usize,
+    text_dedup: Option<usize>,
+    ocr_hash_cache: bool,
+) -> Result<()> {
     // Resolve data directory
-    let data_dir = data_dir.unwrap_or_else(|| {
-        dirs::data_local_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("Memoire")
-    });
+    let data_dir = config::resolve_data_dir(data_dir);
 
     let db_path = data_dir.join("memoire.db");
 
@@ -485,16 +1280,20 @@ async fn cmd_index(data_dir: Option<PathBuf>, ocr_fps: u32, ocr_language: Option
     }
 
     // Create indexer
-    let mut indexer = Indexer::new(data_dir, Some(ocr_fps), ocr_language)?;
+    let mut indexer = Indexer::new(
+        data_dir,
+        Some(ocr_fps),
+        ocr_language,
+        Some(batch_size),
+        Some(max_extractions),
+    )?;
+    indexer.set_text_dedup(text_dedup);
+    indexer.set_merge_languages(merge_languages);
+    indexer.set_ocr_downscale(ocr_downscale);
+    indexer.set_ocr_hash_cache(ocr_hash_cache);
 
     // Set up signal handler for graceful shutdown
-    let shutdown = Arc::new(AtomicBool::new(false));
-    let shutdown_handler = shutdown.clone();
-
-    ctrlc::set_handler(move || {
-        warn!("received shutdown signal, stopping indexer...");
-        shutdown_handler.store(true, Ordering::Relaxed);
-    })?;
+    let shutdown = shutdown::install_shutdown_handler();
 
     // Run indexer until Ctrl+C
     indexer.run(shutdown).await?;
@@ -503,13 +1302,9 @@ async fn cmd_index(data_dir: Option<PathBuf>, ocr_fps: u32, ocr_language: Option
     Ok(())
 }
 
-fn cmd_search(query: String, data_dir: Option<PathBuf>, limit: i64) -> Result<()> {
+fn cmd_search(query: String, data_dir: Option<PathBuf>, limit: i64, focused_only: bool) -> Result<()> {
     // Resolve data directory
-    let data_dir = data_dir.unwrap_or_else(|| {
-        dirs::data_local_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("Memoire")
-    });
+    let data_dir = config::resolve_data_dir(data_dir);
 
     let db_path = data_dir.join("memoire.db");
 
@@ -522,10 +1317,10 @@ fn cmd_search(query: String, data_dir: Option<PathBuf>, limit: i64) -> Result<()
     info!("searching for: '{}'", query);
 
     // Open database
-    let db = memoire_db::Database::open(&db_path)?;
+    let db = config::open_database(&db_path)?;
 
     // Perform search
-    let results = memoire_db::search_ocr(db.connection(), &query, limit, 0)?;
+    let results = memoire_db::search_ocr(db.connection(), &query, limit, 0, focused_only)?;
 
     if results.is_empty() {
         println!("no results found for query: '{}'", query);
@@ -561,16 +1356,171 @@ fn cmd_search(query: String, data_dir: Option<PathBuf>, limit: i64) -> Result<()
     Ok(())
 }
 
+fn cmd_replay(
+    data_dir: Option<PathBuf>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    out: PathBuf,
+    monitor: Option<String>,
+    list: bool,
+) -> Result<()> {
+    if from >= to {
+        return Err(anyhow::anyhow!("--from must be before --to"));
+    }
+
+    let data_dir = config::resolve_data_dir(data_dir);
+    let db_path = data_dir.join("memoire.db");
+    if !db_path.exists() {
+        error!("database not found at {:?}", db_path);
+        error!("please run 'memoire record' first to initialize the database");
+        return Err(anyhow::anyhow!("database not found"));
+    }
+
+    let db = config::open_database(&db_path)?;
+    let segments = replay::plan(&db, from, to, monitor.as_deref())?;
+    if segments.is_empty() {
+        println!("no frames recorded between {} and {}", from, to);
+        return Ok(());
+    }
+
+    println!("{} chunk(s) in the window:", segments.len());
+    for segment in &segments {
+        println!(
+            "  chunk {} [{}] {} -> {} ({} frames, {} {:.1}s-{:.1}s)",
+            segment.chunk.id,
+            segment.chunk.device_name,
+            segment.first_frame.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S"),
+            segment.last_frame.with_timezone(&chrono::Local).format("%H:%M:%S"),
+            segment.frames,
+            segment.chunk.file_path,
+            segment.inpoint,
+            segment.outpoint
+        );
+    }
+
+    if list {
+        return Ok(());
+    }
+
+    let written = replay::export(&segments, &data_dir, &out)?;
+    println!("\nwrote {} ({} segment(s))", out.display(), written);
+    Ok(())
+}
+
+fn cmd_timelapse(
+    data_dir: Option<PathBuf>,
+    date: chrono::NaiveDate,
+    monitor: Option<String>,
+    out: PathBuf,
+    speed: f64,
+    fps: f64,
+    list: bool,
+) -> Result<()> {
+    let data_dir = config::resolve_data_dir(data_dir);
+    let db_path = data_dir.join("memoire.db");
+    if !db_path.exists() {
+        error!("database not found at {:?}", db_path);
+        error!("please run 'memoire record' first to initialize the database");
+        return Err(anyhow::anyhow!("database not found"));
+    }
+
+    let db = config::open_database(&db_path)?;
+    let chunks = timelapse::plan(&db, date, monitor.as_deref())?;
+    if chunks.is_empty() {
+        println!("no finished video chunks recorded on {}", date);
+        return Ok(());
+    }
+
+    let recorded: f64 = chunks.iter().filter_map(|c| c.duration_secs).sum();
+    println!(
+        "{} chunk(s) on {}, {:.0} min recorded, {:.0} s at {}x:",
+        chunks.len(),
+        date,
+        recorded / 60.0,
+        recorded / speed,
+        speed
+    );
+    for chunk in &chunks {
+        println!(
+            "  chunk {} [{}] {} ({:.0}s, {})",
+            chunk.id,
+            chunk.device_name,
+            chunk.created_at.with_timezone(&chrono::Local).format("%H:%M:%S"),
+            chunk.duration_secs.unwrap_or(0.0),
+            chunk.file_path
+        );
+    }
+
+    if list {
+        return Ok(());
+    }
+
+    let written = timelapse::export(&chunks, &data_dir, &out, speed, fps)?;
+    println!("\nwrote {} ({} chunk(s))", out.display(), written);
+    Ok(())
+}
+
+#[tokio::main]
+async fn cmd_ocr(image_path: PathBuf, language: Option<String>, json: bool) -> Result<()> {
+    let image = image::open(&image_path)
+        .map_err(|e| anyhow::anyhow!("failed to load {}: {}", image_path.display(), e))?
+        .to_rgba8();
+    let (width, height) = image.dimensions();
+
+    let processor = match &language {
+        Some(languages) => memoire_ocr::Processor::with_languages(&memoire_ocr::parse_language_list(languages))?,
+        None => memoire_ocr::Processor::new()?,
+    };
+
+    let result = processor
+        .process_frame(memoire_ocr::FrameData {
+            width,
+            height,
+            data: image.into_raw(),
+        })
+        .await?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+    }
+
+    println!(
+        "{}x{} image, language: {}, confidence: {:.2}%\n",
+        width,
+        height,
+        result.language.as_deref().unwrap_or("unknown"),
+        result.confidence * 100.0
+    );
+
+    if result.lines.is_empty() {
+        println!("no text recognized");
+        return Ok(());
+    }
+
+    for (i, line) in result.lines.iter().enumerate() {
+        let bbox = line
+            .bounding_box()
+            .map(|(x, y, w, h)| format!("({:.0}, {:.0}) {:.0}x{:.0}", x, y, w, h))
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "{:>3}. [{:5.1}%] {:<24} {}",
+            i + 1,
+            line.confidence() * 100.0,
+            bbox,
+            line.text
+        );
+    }
+
+    Ok(())
+}
+
 fn cmd_reset_ocr(data_dir: Option<PathBuf>, clear_all: bool) -> Result<()> {
     // Resolve data directory
-    let data_dir = data_dir.unwrap_or_else(|| {
-        dirs::data_local_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("Memoire")
-    });
+    let data_dir = config::resolve_data_dir(data_dir);
 
     let db_path = data_dir.join("memoire.db");
-    let db = memoire_db::Database::open(&db_path)?;
+    let db = config::open_database(&db_path)?;
 
     if clear_all {
         println!("clearing ALL OCR records...");
@@ -585,6 +1535,106 @@ fn cmd_reset_ocr(data_dir: Option<PathBuf>, clear_all: bool) -> Result<()> {
     Ok(())
 }
 
+fn cmd_db_version(data_dir: Option<PathBuf>, migrate_to: Option<i64>) -> Result<()> {
+    // Resolve data directory
+    let data_dir = config::resolve_data_dir(data_dir);
+
+    let db_path = data_dir.join("memoire.db");
+    if !db_path.exists() {
+        println!("database: not found ({:?})", db_path);
+        return Ok(());
+    }
+
+    // Open without migrating so the on-disk version is reported as-is
+    let db = memoire_db::Database::open_unmigrated(&db_path)?;
+    let version = db.schema_version()?;
+
+    println!("database: {:?}", db_path);
+    println!("schema version: v{}", version);
+    println!("supported by this build: v{}", memoire_db::migrations::SCHEMA_VERSION);
+
+    if let Some(target) = migrate_to {
+        if target == version {
+            println!("already at v{}", target);
+        } else {
+            println!("migrating v{} -> v{}...", version, target);
+            db.migrate_to(target)?;
+            println!("✓ schema now at v{}", db.schema_version()?);
+        }
+    }
+
+    Ok(())
+}
+
+fn cmd_db_check(data_dir: Option<PathBuf>) -> Result<()> {
+    let data_dir = config::resolve_data_dir(data_dir);
+
+    let db_path = data_dir.join("memoire.db");
+    if !db_path.exists() {
+        println!("database: not found ({:?})", db_path);
+        return Ok(());
+    }
+
+    // Checking must not change the file, so skip migrations
+    let db = memoire_db::Database::open_unmigrated(&db_path)?;
+    println!("checking {:?}...", db_path);
+    let issues = memoire_db::integrity_check(db.connection())?;
+
+    if issues.is_empty() {
+        println!("✓ no problems found");
+        return Ok(());
+    }
+
+    for issue in &issues {
+        println!("  ✗ {}", issue);
+    }
+    Err(anyhow::anyhow!("{} problem(s) found in {:?}", issues.len(), db_path))
+}
+
+fn cmd_reconcile(data_dir: Option<PathBuf>, delete: bool) -> Result<()> {
+    let data_dir = config::resolve_data_dir(data_dir);
+
+    let db_path = data_dir.join("memoire.db");
+    if !db_path.exists() {
+        println!("database: not found ({:?})", db_path);
+        return Ok(());
+    }
+
+    let db = config::open_database(&db_path)?;
+    println!("checking chunks in {:?}...", data_dir);
+    let orphans = memoire_db::find_orphaned_chunks(db.connection(), &data_dir)?;
+
+    if orphans.is_empty() {
+        println!("✓ every chunk has its file and every file has a chunk");
+        return Ok(());
+    }
+
+    for chunk in &orphans.video_chunks {
+        println!("  ✗ video chunk {}: missing {}", chunk.id, chunk.file_path);
+    }
+    for chunk in &orphans.audio_chunks {
+        println!("  ✗ audio chunk {}: missing {}", chunk.id, chunk.file_path);
+    }
+    for path in &orphans.untracked_files {
+        println!("  ? no chunk for {}", path.display());
+    }
+
+    let missing = orphans.video_chunks.len() + orphans.audio_chunks.len();
+    if missing > 0 {
+        if delete {
+            memoire_db::remove_orphaned_chunks(db.connection(), &orphans)?;
+            println!("✓ removed {} video and marked {} audio chunk(s) pruned", orphans.video_chunks.len(), orphans.audio_chunks.len());
+        } else {
+            println!("{} chunk(s) without a file; run with --delete to remove them", missing);
+        }
+    }
+    if !orphans.untracked_files.is_empty() {
+        println!("{} file(s) not in the database were left in place", orphans.untracked_files.len());
+    }
+
+    Ok(())
+}
+
 fn cmd_audio_devices() -> Result<()> {
     println!("enumerating audio devices...\n");
 
@@ -612,13 +1662,9 @@ fn cmd_audio_devices() -> Result<()> {
 }
 
 #[tokio::main]
-async fn cmd_record_audio(data_dir: Option<PathBuf>, device_id: Option<String>, chunk_secs: u64, loopback: bool) -> Result<()> {
+async fn cmd_record_audio(data_dir: Option<PathBuf>, device_id: Option<String>, chunk_secs: u64, loopback: bool, split_on_silence: bool) -> Result<()> {
     // Resolve data directory
-    let data_dir = data_dir.unwrap_or_else(|| {
-        dirs::data_local_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("Memoire")
-    });
+    let data_dir = config::resolve_data_dir(data_dir);
 
     // Create audio output directory
     let audio_dir = data_dir.join("audio");
@@ -626,16 +1672,10 @@ async fn cmd_record_audio(data_dir: Option<PathBuf>, device_id: Option<String>,
 
     info!("starting audio capture (loopback={})", loopback);
     info!("data directory: {:?}", data_dir);
-    info!("chunk duration: {} seconds", chunk_secs);
+    info!("chunk duration: {} seconds (split on silence: {})", chunk_secs, split_on_silence);
 
     // Set up signal handler for graceful shutdown
-    let running = Arc::new(AtomicBool::new(true));
-    let r = running.clone();
-
-    ctrlc::set_handler(move || {
-        warn!("received shutdown signal, stopping audio capture...");
-        r.store(false, Ordering::Relaxed);
-    })?;
+    let shutdown = shutdown::install_shutdown_handler();
 
     // Configure audio capture
     let config = memoire_capture::AudioCaptureConfig {
@@ -643,18 +1683,20 @@ async fn cmd_record_audio(data_dir: Option<PathBuf>, device_id: Option<String>,
         is_loopback: loopback,
         target_sample_rate: 16000, // 16kHz for STT
         target_channels: 1,        // mono for STT
-        chunk_duration_secs: chunk_secs as u32,
+        // short buffers let the encoder choose split points close to a pause
+        chunk_duration_secs: if split_on_silence { 1 } else { chunk_secs as u32 },
     };
 
     // Start audio capture
     let mut capture = memoire_capture::AudioCapture::new(config)?;
+    let is_input_device = capture.is_input_device();
     let mut rx = capture.start()?;
 
     info!("audio capture started, press Ctrl+C to stop");
 
     // Open database for storing audio chunks
     let db_path = data_dir.join("memoire.db");
-    let db = memoire_db::Database::open(&db_path)?;
+    let db = config::open_database(&db_path)?;
 
     // Create audio encoder
     let encoder_config = memoire_processing::AudioEncoderConfig {
@@ -662,13 +1704,15 @@ async fn cmd_record_audio(data_dir: Option<PathBuf>, device_id: Option<String>,
         chunk_duration_secs: chunk_secs as u32,
         sample_rate: 16000,
         channels: 1,
+        split_on_silence,
+        ..Default::default()
     };
     let device_name_for_encoder = device_id.as_deref().unwrap_or("default");
     let mut encoder = memoire_processing::AudioEncoder::new(encoder_config, device_name_for_encoder)?;
 
     // Receive and process audio chunks
     let mut chunk_count = 0;
-    while running.load(Ordering::Relaxed) {
+    while !shutdown.load(Ordering::Relaxed) {
         match tokio::time::timeout(
             std::time::Duration::from_millis(500),
             rx.recv()
@@ -679,14 +1723,15 @@ async fn cmd_record_audio(data_dir: Option<PathBuf>, device_id: Option<String>,
                     chunk_count, audio.samples.len(), audio.duration_secs);
 
                 // Save to file using encoder
-                if let Some(file_path) = encoder.add_samples(&audio.samples, audio.timestamp)? {
-                    info!("saved audio chunk: {:?}", file_path);
+                for chunk in encoder.add_samples(&audio.samples, audio.timestamp)? {
+                    info!("saved audio chunk: {:?}", chunk.path);
 
                     // Insert into database
                     let new_chunk = memoire_db::NewAudioChunk {
-                        file_path: file_path.to_string_lossy().to_string(),
+                        file_path: chunk.path.to_string_lossy().to_string(),
                         device_name: Some(audio.device_name.clone()),
-                        is_input_device: Some(true),
+                        is_input_device: Some(audio.is_input_device),
+                        timestamp: chunk.start_time,
                     };
                     memoire_db::insert_audio_chunk(db.connection(), &new_chunk)?;
                 }
@@ -703,13 +1748,14 @@ async fn cmd_record_audio(data_dir: Option<PathBuf>, device_id: Option<String>,
     }
 
     // Finalize any remaining audio
-    if let Some(file_path) = encoder.finalize_chunk()? {
-        info!("saved final audio chunk: {:?}", file_path);
+    if let Some(chunk) = encoder.finalize_chunk()? {
+        info!("saved final audio chunk: {:?}", chunk.path);
 
         let new_chunk = memoire_db::NewAudioChunk {
-            file_path: file_path.to_string_lossy().to_string(),
-            device_name: None,
-            is_input_device: Some(true),
+            file_path: chunk.path.to_string_lossy().to_string(),
+            device_name: Some(capture.device_name().to_string()),
+            is_input_device: Some(is_input_device),
+            timestamp: chunk.start_time,
         };
         memoire_db::insert_audio_chunk(db.connection(), &new_chunk)?;
     }
@@ -720,14 +1766,33 @@ async fn cmd_record_audio(data_dir: Option<PathBuf>, device_id: Option<String>,
     Ok(())
 }
 
+/// Speech-to-text settings from the `--no-gpu`, `--gpu-device` and `--stt-providers` flags
+fn stt_config(no_gpu: bool, gpu_device: i32, providers: Vec<memoire_stt::ExecutionProvider>) -> memoire_stt::SttConfig {
+    let config = memoire_stt::SttConfig {
+        gpu_device,
+        ..memoire_stt::SttConfig::default()
+    };
+
+    if no_gpu {
+        config.cpu_only()
+    } else if providers.is_empty() {
+        config
+    } else {
+        memoire_stt::SttConfig { providers, ..config }
+    }
+}
+
 #[tokio::main]
-async fn cmd_audio_index(data_dir: Option<PathBuf>, use_gpu: bool) -> Result<()> {
+async fn cmd_audio_index(
+    data_dir: Option<PathBuf>,
+    stt_config: memoire_stt::SttConfig,
+    batch_size: i64,
+    backlog_concurrency: Option<usize>,
+    retranscribe: bool,
+    retranscribe_empty: bool,
+) -> Result<()> {
     // Resolve data directory
-    let data_dir = data_dir.unwrap_or_else(|| {
-        dirs::data_local_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("Memoire")
-    });
+    let data_dir = config::resolve_data_dir(data_dir);
 
     let db_path = data_dir.join("memoire.db");
 
@@ -739,11 +1804,27 @@ async fn cmd_audio_index(data_dir: Option<PathBuf>, use_gpu: bool) -> Result<()>
 
     info!("starting audio transcription indexer");
     info!("data directory: {:?}", data_dir);
-    info!("GPU enabled: {}", use_gpu);
+    if stt_config.wants_gpu() {
+        info!("GPU enabled: true (device {})", stt_config.gpu_device);
+    } else {
+        info!("GPU enabled: false");
+    }
+    info!("STT threads: {}", stt_config.num_threads);
+
+    if retranscribe || retranscribe_empty {
+        let db = config::open_database(&db_path)?;
+        if retranscribe {
+            let deleted = memoire_db::reset_all_transcriptions(db.connection())?;
+            info!("cleared {} transcription rows, all audio will be transcribed again", deleted);
+        } else {
+            let deleted = memoire_db::reset_empty_transcriptions(db.connection())?;
+            info!("cleared {} empty transcription rows", deleted);
+        }
+    }
 
     // Configure ONNX Runtime to use bundled DLL (required for ort 2.0.0-rc.10)
     // This must be done BEFORE creating the STT engine
-    let model_dir = data_dir.join("models");
+    let model_dir = config::model_dir(&data_dir);
     if memoire_stt::has_bundled_onnx_runtime(&model_dir) {
         memoire_stt::configure_onnx_runtime(&model_dir)?;
     } else {
@@ -751,17 +1832,17 @@ async fn cmd_audio_index(data_dir: Option<PathBuf>, use_gpu: bool) -> Result<()>
         warn!("if you get version errors, run 'memoire download-models' first");
     }
 
-    // Create indexer
-    let mut indexer = audio_indexer::AudioIndexer::new(data_dir, use_gpu)?;
-
     // Set up signal handler for graceful shutdown
-    let shutdown = Arc::new(AtomicBool::new(false));
-    let shutdown_handler = shutdown.clone();
+    let shutdown = shutdown::install_shutdown_handler();
 
-    ctrlc::set_handler(move || {
-        warn!("received shutdown signal, stopping audio indexer...");
-        shutdown_handler.store(true, Ordering::Relaxed);
-    })?;
+    if let Some(concurrency) = backlog_concurrency {
+        let processed = audio_indexer::transcribe_backlog(&data_dir, stt_config, concurrency, shutdown).await?;
+        info!("backlog finished, {} chunks processed", processed);
+        return Ok(());
+    }
+
+    // Create indexer
+    let mut indexer = audio_indexer::AudioIndexer::new(data_dir, stt_config, Some(batch_size))?;
 
     // Run indexer until Ctrl+C
     indexer.run(shutdown).await?;
@@ -770,12 +1851,55 @@ async fn cmd_audio_index(data_dir: Option<PathBuf>, use_gpu: bool) -> Result<()>
     Ok(())
 }
 
+fn cmd_transcribe(file: PathBuf, data_dir: Option<PathBuf>, stt_config: memoire_stt::SttConfig, json: bool) -> Result<()> {
+    if !file.exists() {
+        return Err(anyhow::anyhow!("file not found: {}", file.display()));
+    }
+
+    let model_dir = config::model_dir(&config::resolve_data_dir(data_dir));
+    info!("transcribing {:?} with models from {:?}", file, model_dir);
+
+    let result = memoire_stt::transcribe_wav(&model_dir, &file, stt_config)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+    }
+
+    println!("{}\n", result.text.trim());
+    for segment in &result.segments {
+        println!(
+            "  [{} -> {}] {}",
+            format_offset(segment.start),
+            format_offset(segment.end),
+            segment.text.trim()
+        );
+    }
+
+    let processing_secs = result.processing_time_ms as f64 / 1000.0;
+    match memoire_stt::wav_duration_secs(&file) {
+        Ok(audio_secs) if processing_secs > 0.0 => println!(
+            "\n{:.1} s of audio in {:.2} s ({:.1}x realtime)",
+            audio_secs,
+            processing_secs,
+            audio_secs / processing_secs
+        ),
+        _ => println!("\nprocessed in {:.2} s", processing_secs),
+    }
+
+    Ok(())
+}
+
+/// Format seconds into a clip as MM:SS.ss
+fn format_offset(secs: f64) -> String {
+    let secs = secs.max(0.0);
+    format!("{:02}:{:05.2}", (secs / 60.0) as u64, secs % 60.0)
+}
+
 #[tokio::main]
 async fn cmd_download_models(data_dir: Option<PathBuf>, force: bool) -> Result<()> {
     // Resolve model directory
-    let model_dir = data_dir
-        .map(|d| d.join("models"))
-        .unwrap_or_else(memoire_stt::default_model_dir);
+    let model_dir = config::model_dir(&config::resolve_data_dir(data_dir));
 
     info!("model directory: {:?}", model_dir);
 
@@ -817,13 +1941,14 @@ async fn cmd_test_all(
     config_path: PathBuf,
     profile: Option<String>,
     data_dir_override: Option<PathBuf>,
+    log_format: LogFormat,
 ) -> Result<()> {
     use colored_logger::Component;
     use orchestrator::Orchestrator;
     use test_config::TestConfig;
 
     // Initialize colored logger for orchestrator
-    colored_logger::init_component_logger(Component::Orchestrator)?;
+    colored_logger::init_component_logger(Component::Orchestrator, log_format)?;
 
     // Load configuration
     let mut config = if config_path.exists() {
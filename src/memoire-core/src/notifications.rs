@@ -0,0 +1,59 @@
+//! Desktop notifications for events worth surfacing outside the logs
+//!
+//! `tray-icon` has no balloon API, so notifications are shown as Windows
+//! toasts (the balloons of current Windows versions) through `notify-rust`.
+//! Failing to show one is logged and otherwise ignored.
+
+use notify_rust::Notification;
+use std::thread;
+use tracing::{debug, warn};
+
+/// Event shown to the user
+#[derive(Debug, Clone, PartialEq)]
+pub enum Notice {
+    RecordingStarted,
+    RecordingStopped,
+    /// The recorder thread stopped with an error
+    RecorderFailed(String),
+    /// Recording stopped because free disk space fell below the minimum
+    DiskLow,
+    /// Speech-to-text models finished downloading
+    ModelsDownloaded,
+}
+
+impl Notice {
+    fn title(&self) -> &'static str {
+        match self {
+            Notice::RecordingStarted => "Recording started",
+            Notice::RecordingStopped => "Recording stopped",
+            Notice::RecorderFailed(_) => "Recording failed",
+            Notice::DiskLow => "Disk almost full",
+            Notice::ModelsDownloaded => "Models downloaded",
+        }
+    }
+
+    fn body(&self) -> String {
+        match self {
+            Notice::RecordingStarted => "Memoire is capturing your screen.".to_string(),
+            Notice::RecordingStopped => "Memoire is no longer capturing.".to_string(),
+            Notice::RecorderFailed(error) => format!("The recorder stopped: {}", error),
+            Notice::DiskLow => "Recording stopped to keep free space on the data drive.".to_string(),
+            Notice::ModelsDownloaded => "Speech-to-text is ready to transcribe audio.".to_string(),
+        }
+    }
+}
+
+/// Show `notice` without blocking the caller
+pub fn notify(notice: Notice) {
+    debug!("notification: {:?}", notice);
+    thread::spawn(move || {
+        let result = Notification::new()
+            .appname("Memoire")
+            .summary(&format!("Memoire - {}", notice.title()))
+            .body(&notice.body())
+            .show();
+        if let Err(e) = result {
+            warn!("failed to show notification: {}", e);
+        }
+    });
+}
@@ -4,19 +4,38 @@
 //! process with unified logging and graceful shutdown.
 
 use anyhow::{Context, Result};
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::thread;
 use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
 use tokio::task::JoinHandle;
 use tracing::{error, info, warn};
 
-use crate::config::Config;
+use crate::config::{Config, MIN_FPS};
 use crate::test_config::TestConfig;
-use crate::recorder::Recorder;
-use crate::indexer::Indexer;
+use crate::recorder::{Recorder, RecorderChannels};
+use crate::indexer::{Indexer, OcrEvent};
 use crate::audio_indexer::AudioIndexer;
-use memoire_db::Database;
+use crate::notifications::{notify, Notice};
+
+/// Restarts of a failed recorder before the orchestrator gives up on it
+const MAX_RECORDER_RESTARTS: u32 = 5;
+
+/// Delay before the first recorder restart, doubled after each further failure
+const RECORDER_RESTART_BACKOFF: Duration = Duration::from_secs(2);
+
+/// Upper bound of the restart delay
+const MAX_RECORDER_RESTART_BACKOFF: Duration = Duration::from_secs(60);
+
+/// A recorder that ran this long before failing gets a fresh restart budget
+const RECORDER_STABLE_AFTER: Duration = Duration::from_secs(600);
+
+/// Component names tracked in the orchestrator's health list
+const RECORDER: &str = "recorder";
+const VIEWER: &str = "viewer";
+const OCR_INDEXER: &str = "ocr-indexer";
+const AUDIO_INDEXER: &str = "audio-indexer";
 
 /// Component health status
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -28,6 +47,10 @@ pub enum ComponentStatus {
 }
 
 /// Health monitor for a single component
+///
+/// Clones share the same status, so a component's task can update the entry
+/// kept by the orchestrator.
+#[derive(Clone)]
 pub struct ComponentHealth {
     pub name: &'static str,
     pub status: Arc<std::sync::Mutex<ComponentStatus>>,
@@ -47,6 +70,10 @@ impl ComponentHealth {
         *self.status.lock().unwrap() = status;
         *self.last_heartbeat.lock().unwrap() = Instant::now();
     }
+
+    fn status(&self) -> ComponentStatus {
+        *self.status.lock().unwrap()
+    }
 }
 
 /// Main orchestrator for running all components
@@ -58,29 +85,42 @@ pub struct Orchestrator {
 
 impl Orchestrator {
     pub fn new(config: TestConfig) -> Self {
+        let mut components = vec![
+            ComponentHealth::new(RECORDER),
+            ComponentHealth::new(VIEWER),
+            ComponentHealth::new(OCR_INDEXER),
+        ];
+        if config.audio.enabled {
+            components.push(ComponentHealth::new(AUDIO_INDEXER));
+        }
+
         Self {
             config,
-            shutdown: Arc::new(AtomicBool::new(false)),
-            components: vec![],
+            shutdown: crate::shutdown::install_shutdown_handler(),
+            components,
         }
     }
 
+    /// Health entry of a component (shared with the component's task)
+    fn health(&self, name: &'static str) -> ComponentHealth {
+        self.components
+            .iter()
+            .find(|c| c.name == name)
+            .cloned()
+            .unwrap_or_else(|| ComponentHealth::new(name))
+    }
+
     /// Run all components until shutdown signal
     pub async fn run(self) -> Result<()> {
         info!("🚀 Starting Memoire test orchestrator");
 
-        // Setup Ctrl+C handler
-        let shutdown_clone = self.shutdown.clone();
-        ctrlc::set_handler(move || {
-            info!("🛑 Shutdown signal received");
-            shutdown_clone.store(true, Ordering::SeqCst);
-        })
-        .context("Failed to set Ctrl+C handler")?;
-
         // Resolve data directory
         let data_dir = self.config.resolve_data_dir();
         info!("📁 Data directory: {}", data_dir.display());
         std::fs::create_dir_all(&data_dir)?;
+        if let Some(mode) = self.config.general.journal_mode {
+            crate::config::set_journal_mode(mode);
+        }
 
         // Step 1: Check/download models if needed
         if self.config.audio.enabled && self.config.general.auto_download_models {
@@ -90,21 +130,49 @@ impl Orchestrator {
         // Create LocalSet for non-Send futures (Indexer, AudioIndexer use rusqlite)
         let local = tokio::task::LocalSet::new();
 
-        // Step 2: Start viewer first (needs DB to exist)
-        let viewer_handle = self.spawn_viewer(&data_dir).await?;
-
-        // Step 3: Create recorder and subscribe to chunk events BEFORE spawning thread
+        // Step 2: Create recorder and subscribe to chunk events BEFORE spawning thread
         let (recorder, ocr_events_rx, audio_events_rx) = self.create_recorder_with_subscriptions(&data_dir)?;
 
-        // Step 3b: Spawn recorder thread
-        let recorder_handle = self.spawn_recorder_thread(recorder)?;
+        // Step 3: Start viewer (shares the recorder's duplicate frame counter for /metrics
+        // and streams its chunk events on /api/events)
+        let events = self.forward_chunk_events(&recorder);
+        let ocr_hub = events.clone();
+        let (reindex_tx, reindex_rx) = tokio::sync::mpsc::unbounded_channel();
+        let viewer_handle = self
+            .spawn_viewer(&data_dir, recorder.skipped_frames_counter(), events, reindex_tx)
+            .await?;
+
+        // Step 3b: Spawn recorder thread (restarted on failure)
+        let recorder_handle = self.spawn_recorder_thread(recorder, &data_dir)?;
 
         // Step 4 & 5: Start indexers in LocalSet (not Send due to rusqlite)
         let data_dir_clone = data_dir.clone();
         let ocr_fps = self.config.index.ocr_fps;
         let ocr_language = self.config.index.ocr_language.clone();
+        let ocr_merge_languages = self.config.index.merge_languages;
+        let ocr_downscale = self.config.index.ocr_downscale;
+        let ocr_batch_size = self.config.index.batch_size;
+        let max_extractions = self.config.index.max_concurrent_extractions;
+        let text_dedup = self.config.index.dedup_text.then_some(self.config.index.dedup_distance);
+        let ocr_hash_cache = self.config.index.ocr_hash_cache;
+        let audio_batch_size = self.config.audio.batch_size;
+        let stt_config = memoire_stt::SttConfig {
+            gpu_device: self.config.audio.gpu_device.unwrap_or(0),
+            num_threads: self.config.audio.stt_threads.max(1),
+            normalize_audio: self.config.audio.normalize_audio,
+            normalize_target_rms: self.config.audio.normalize_target_rms,
+            resample_quality: self.config.audio.resample_quality,
+            ..memoire_stt::SttConfig::default()
+        };
+        let stt_config = match (self.config.audio.gpu_device, &self.config.audio.stt_providers) {
+            (None, _) => stt_config.cpu_only(),
+            (Some(_), Some(providers)) => memoire_stt::SttConfig { providers: providers.clone(), ..stt_config },
+            (Some(_), None) => stt_config,
+        };
         let audio_enabled = self.config.audio.enabled;
         let shutdown_indexers = self.shutdown.clone();
+        let ocr_health = self.health(OCR_INDEXER);
+        let audio_health = self.health(AUDIO_INDEXER);
 
         let indexers_handle = local.spawn_local(async move {
             // Start OCR indexer
@@ -112,17 +180,39 @@ impl Orchestrator {
             let shutdown_ocr = shutdown_indexers.clone();
             let idx_task = tokio::task::spawn_local(async move {
                 info!("Starting OCR indexer at {} fps", ocr_fps);
-                match Indexer::new(data_dir_idx, Some(ocr_fps), ocr_language) {
+                let status = match Indexer::new(
+                    data_dir_idx,
+                    Some(ocr_fps),
+                    ocr_language,
+                    Some(ocr_batch_size),
+                    Some(max_extractions),
+                ) {
                     Ok(mut indexer) => {
+                        indexer.set_text_dedup(text_dedup);
+                        indexer.set_merge_languages(ocr_merge_languages);
+                        indexer.set_ocr_downscale(ocr_downscale);
+                        indexer.set_ocr_hash_cache(ocr_hash_cache);
+
                         // Enable event-driven chunk processing
                         indexer.set_chunk_events_receiver(ocr_events_rx);
-
-                        if let Err(e) = indexer.run(shutdown_ocr).await {
-                            error!("Indexer error: {}", e);
+                        indexer.set_reindex_receiver(reindex_rx);
+                        forward_ocr_events(indexer.subscribe(), ocr_hub);
+
+                        ocr_health.update_status(ComponentStatus::Running);
+                        match indexer.run(shutdown_ocr).await {
+                            Ok(()) => ComponentStatus::Stopped,
+                            Err(e) => {
+                                error!("Indexer error: {}", e);
+                                ComponentStatus::Failed
+                            }
                         }
                     }
-                    Err(e) => error!("Failed to create indexer: {}", e),
-                }
+                    Err(e) => {
+                        error!("Failed to create indexer: {}", e);
+                        ComponentStatus::Failed
+                    }
+                };
+                ocr_health.update_status(status);
                 info!("Indexer stopped");
             });
 
@@ -134,23 +224,33 @@ impl Orchestrator {
                     info!("Starting audio indexer");
 
                     // Configure ONNX Runtime to use bundled DLL (pattern from main.rs:744-752)
-                    let model_dir = data_dir_audio.join("models");
+                    let model_dir = crate::config::model_dir(&data_dir_audio);
                     if let Err(e) = memoire_stt::configure_onnx_runtime(&model_dir) {
                         error!("Failed to configure ONNX Runtime: {}", e);
+                        audio_health.update_status(ComponentStatus::Failed);
                         return;
                     }
 
-                    match AudioIndexer::new(data_dir_audio, false) {
+                    let status = match AudioIndexer::new(data_dir_audio, stt_config, Some(audio_batch_size)) {
                         Ok(mut indexer) => {
                             // Enable event-driven chunk processing
                             indexer.set_chunk_events_receiver(audio_events_rx);
 
-                            if let Err(e) = indexer.run(shutdown_audio).await {
-                                error!("Audio indexer error: {}", e);
+                            audio_health.update_status(ComponentStatus::Running);
+                            match indexer.run(shutdown_audio).await {
+                                Ok(()) => ComponentStatus::Stopped,
+                                Err(e) => {
+                                    error!("Audio indexer error: {}", e);
+                                    ComponentStatus::Failed
+                                }
                             }
                         }
-                        Err(e) => error!("Failed to create audio indexer: {}", e),
-                    }
+                        Err(e) => {
+                            error!("Failed to create audio indexer: {}", e);
+                            ComponentStatus::Failed
+                        }
+                    };
+                    audio_health.update_status(status);
                     info!("Audio indexer stopped");
                 });
                 let _ = tokio::join!(idx_task, audio_task);
@@ -172,7 +272,7 @@ impl Orchestrator {
 
     /// Ensure models are downloaded
     async fn ensure_models(&self, data_dir: &std::path::Path) -> Result<()> {
-        let model_dir = data_dir.join("models");
+        let model_dir = crate::config::model_dir(data_dir);
         let downloader = memoire_stt::ModelDownloader::new(model_dir.clone());
 
         if downloader.is_fully_complete() {
@@ -197,22 +297,37 @@ impl Orchestrator {
         }
 
         info!("✓ All models downloaded");
+        notify(Notice::ModelsDownloaded);
         Ok(())
     }
 
+    /// Recorder settings from the test configuration
+    fn recorder_config(&self, data_dir: &std::path::Path) -> Config {
+        Config {
+            data_dir: data_dir.to_path_buf(),
+            fps: self.config.record.fps.max(MIN_FPS), // Clamp to avoid division by zero
+            use_hw_encoding: self.config.record.use_hw_encoding,
+            chunk_duration_secs: self.config.record.chunk_duration_secs,
+            quality: self.config.record.quality,
+            preset: self.config.record.preset,
+            capture_cursor: self.config.record.capture_cursor,
+            frame_batch_size: self.config.record.frame_batch_size,
+            frame_flush_interval_secs: self.config.record.frame_flush_interval_secs,
+            idle_threshold_secs: self.config.record.idle_threshold_secs,
+            monitors: self.config.record.monitors.clone(),
+            audio_retention_days: self.config.audio.retention_days,
+            max_storage_bytes: self.config.record.max_storage_gb.map(crate::config::gb_to_bytes),
+            min_free_bytes: self.config.record.min_free_gb.map(crate::config::gb_to_bytes),
+            ..Config::default()
+        }
+    }
+
     /// Create recorder and subscribe to chunk finalization events
     fn create_recorder_with_subscriptions(
         &self,
         data_dir: &std::path::Path,
     ) -> Result<(Recorder, tokio::sync::broadcast::Receiver<crate::recorder::ChunkFinalizedEvent>, tokio::sync::broadcast::Receiver<crate::recorder::ChunkFinalizedEvent>)> {
-        let config = Config {
-            data_dir: data_dir.to_path_buf(),
-            fps: self.config.record.fps.max(1.0) as u32,
-            use_hw_encoding: self.config.record.use_hw_encoding,
-            chunk_duration_secs: self.config.record.chunk_duration_secs,
-        };
-
-        let recorder = Recorder::new(config)?;
+        let recorder = Recorder::new(self.recorder_config(data_dir))?;
 
         // Subscribe to chunk finalization events (one for OCR, one for audio)
         let ocr_events_rx = recorder.subscribe_to_chunk_events();
@@ -221,53 +336,85 @@ impl Orchestrator {
         Ok((recorder, ocr_events_rx, audio_events_rx))
     }
 
-    /// Spawn recorder in blocking thread
-    fn spawn_recorder_thread(&self, mut recorder: Recorder) -> Result<thread::JoinHandle<()>> {
+    /// Spawn the recorder supervisor thread
+    ///
+    /// The recorder is restarted with backoff when it returns an error or
+    /// panics (e.g. a DXGI edge case), up to MAX_RECORDER_RESTARTS times in a
+    /// row. Replacements reuse the first recorder's channels, so indexers and
+    /// the viewer keep receiving chunk events.
+    fn spawn_recorder_thread(&self, recorder: Recorder, data_dir: &std::path::Path) -> Result<thread::JoinHandle<()>> {
         let shutdown = self.shutdown.clone();
+        let config = self.recorder_config(data_dir);
+        let health = self.health(RECORDER);
 
         Ok(thread::spawn(move || {
             info!("Starting recorder");
-
-            if let Err(e) = recorder.run(shutdown) {
-                error!("Recorder error: {}", e);
-            }
-
+            supervise_recorder(recorder, config, shutdown, &health);
             info!("Recorder stopped");
         }))
     }
 
-    /// Spawn recorder in blocking thread (pattern from tray.rs:163-170)
-    fn spawn_recorder(&self, data_dir: &std::path::Path) -> Result<thread::JoinHandle<()>> {
-        let config = Config {
-            data_dir: data_dir.to_path_buf(),
-            fps: self.config.record.fps.max(1.0) as u32, // Clamp to minimum 1 FPS to avoid division by zero
-            use_hw_encoding: self.config.record.use_hw_encoding,
-            chunk_duration_secs: self.config.record.chunk_duration_secs,
-        };
-
-        let shutdown = self.shutdown.clone();
-
-        Ok(thread::spawn(move || {
-            info!("Starting recorder at {} fps", config.fps);
+    /// Forward the recorder's video and audio chunk events to the viewer's event hub
+    fn forward_chunk_events(&self, recorder: &Recorder) -> memoire_web::EventHub {
+        let hub = memoire_web::EventHub::new();
+
+        let mut video_rx = recorder.subscribe_to_chunk_events();
+        let video_hub = hub.clone();
+        tokio::spawn(async move {
+            loop {
+                match video_rx.recv().await {
+                    Ok(event) => {
+                        video_hub.publish(memoire_web::ChunkEvent::VideoChunk {
+                            chunk_id: event.chunk_id,
+                            monitor: event.monitor_name,
+                            file_path: event.video_path.to_string_lossy().to_string(),
+                        });
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Viewer event forwarder lagged, {} video chunk events dropped", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
 
-            match Recorder::new(config) {
-                Ok(mut recorder) => {
-                    if let Err(e) = recorder.run(shutdown) {
-                        error!("Recorder error: {}", e);
+        let mut audio_rx = recorder.subscribe_to_audio_chunk_events();
+        let audio_hub = hub.clone();
+        tokio::spawn(async move {
+            loop {
+                match audio_rx.recv().await {
+                    Ok(event) => {
+                        audio_hub.publish(memoire_web::ChunkEvent::AudioChunk {
+                            chunk_id: event.chunk_id,
+                            device: event.device_name,
+                            file_path: event.audio_path.to_string_lossy().to_string(),
+                        });
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Viewer event forwarder lagged, {} audio chunk events dropped", skipped);
                     }
+                    Err(broadcast::error::RecvError::Closed) => break,
                 }
-                Err(e) => error!("Failed to create recorder: {}", e),
             }
+        });
 
-            info!("Recorder stopped");
-        }))
+        hub
     }
 
     /// Spawn viewer as async task
-    async fn spawn_viewer(&self, data_dir: &std::path::Path) -> Result<JoinHandle<()>> {
+    async fn spawn_viewer(
+        &self,
+        data_dir: &std::path::Path,
+        skipped_frames: Arc<AtomicU64>,
+        events: memoire_web::EventHub,
+        reindex_tx: tokio::sync::mpsc::UnboundedSender<i64>,
+    ) -> Result<JoinHandle<()>> {
         let db_path = data_dir.join("memoire.db");
         let data_dir = data_dir.to_path_buf();
+        let host = self.config.viewer.host;
         let port = self.config.viewer.port;
+        let frame_cache_bytes = self.config.viewer.frame_cache_mb.saturating_mul(1024 * 1024);
+        let health = self.health(VIEWER);
 
         Ok(tokio::spawn(async move {
             // Wait for DB to exist (created by first recorder chunk)
@@ -280,14 +427,26 @@ impl Orchestrator {
 
             info!("Starting viewer on port {}", port);
 
-            match Database::open(&db_path) {
+            match crate::config::open_database(&db_path) {
                 Ok(db) => {
-                    let connection = db.into_connection();
-                    if let Err(e) = memoire_web::serve(connection, data_dir, port).await {
-                        error!("Viewer error: {}", e);
+                    health.update_status(ComponentStatus::Running);
+                    let state = memoire_web::AppState::new(db.into_connection(), data_dir)
+                        .with_skipped_frames(skipped_frames)
+                        .with_events(events)
+                        .with_reindex_sender(reindex_tx)
+                        .with_frame_cache_budget(frame_cache_bytes);
+                    match memoire_web::serve_with_state(state, host, port).await {
+                        Ok(()) => health.update_status(ComponentStatus::Stopped),
+                        Err(e) => {
+                            error!("Viewer error: {}", e);
+                            health.update_status(ComponentStatus::Failed);
+                        }
                     }
                 }
-                Err(e) => error!("Failed to open database: {}", e),
+                Err(e) => {
+                    error!("Failed to open database: {}", e);
+                    health.update_status(ComponentStatus::Failed);
+                }
             }
 
             info!("Viewer stopped");
@@ -338,7 +497,121 @@ impl Orchestrator {
         info!("Waiting for async components...");
         tokio::time::timeout(timeout.saturating_sub(start.elapsed()), viewer).await.ok();
 
+        for component in &self.components {
+            match component.status() {
+                ComponentStatus::Failed => warn!("{}: failed", component.name),
+                status => info!("{}: {:?}", component.name, status),
+            }
+        }
+
         info!("✅ All components stopped");
         Ok(())
     }
 }
+
+/// Forward the OCR indexer's results to viewer clients that asked for them
+fn forward_ocr_events(mut rx: broadcast::Receiver<OcrEvent>, hub: memoire_web::EventHub) {
+    tokio::spawn(async move {
+        loop {
+            match rx.recv().await {
+                Ok(event) => {
+                    hub.publish_ocr(memoire_web::ChunkEvent::OcrText {
+                        frame_id: event.frame_id,
+                        timestamp: event.timestamp,
+                        app_name: event.app_name,
+                        text: event.text,
+                    });
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("Viewer event forwarder lagged, {} OCR events dropped", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+/// Run the recorder until shutdown, restarting it with backoff when it fails
+fn supervise_recorder(
+    recorder: Recorder,
+    config: Config,
+    shutdown: Arc<AtomicBool>,
+    health: &ComponentHealth,
+) {
+    let channels: RecorderChannels = recorder.channels();
+    let mut next = Some(recorder);
+    let mut restarts = 0u32;
+
+    loop {
+        let started = Instant::now();
+        let recorder = match next.take() {
+            Some(recorder) => Ok(recorder),
+            None => Recorder::with_channels(config.clone(), channels.clone()),
+        };
+        let failure = match recorder {
+            Ok(recorder) => {
+                health.update_status(ComponentStatus::Running);
+                run_recorder(recorder, shutdown.clone())
+            }
+            Err(e) => Some(format!("failed to create recorder: {}", e)),
+        };
+
+        let Some(reason) = failure else {
+            health.update_status(ComponentStatus::Stopped);
+            return;
+        };
+
+        health.update_status(ComponentStatus::Failed);
+        error!("Recorder error: {}", reason);
+        if shutdown.load(Ordering::SeqCst) {
+            return;
+        }
+
+        if started.elapsed() >= RECORDER_STABLE_AFTER {
+            restarts = 0;
+        }
+        if restarts >= MAX_RECORDER_RESTARTS {
+            error!("Recorder failed {} times in a row, giving up", restarts + 1);
+            return;
+        }
+        restarts += 1;
+
+        let backoff = RECORDER_RESTART_BACKOFF
+            .saturating_mul(1 << (restarts - 1))
+            .min(MAX_RECORDER_RESTART_BACKOFF);
+        warn!(
+            "Restarting recorder in {}s (attempt {}/{})",
+            backoff.as_secs(),
+            restarts,
+            MAX_RECORDER_RESTARTS
+        );
+
+        // Sleep in short slices so shutdown stays responsive
+        let deadline = Instant::now() + backoff;
+        while Instant::now() < deadline {
+            if shutdown.load(Ordering::SeqCst) {
+                return;
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+    }
+}
+
+/// Run one recorder on its own thread, so a panic ends up in the join
+///
+/// Returns why the recorder failed, or None after a clean shutdown.
+fn run_recorder(mut recorder: Recorder, shutdown: Arc<AtomicBool>) -> Option<String> {
+    let handle = thread::spawn(move || recorder.run(shutdown));
+    match handle.join() {
+        Ok(Ok(())) => None,
+        Ok(Err(e)) => Some(e.to_string()),
+        Err(panic) => {
+            let message = panic
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            Some(format!("recorder thread panicked: {}", message))
+        }
+    }
+}
@@ -1,28 +1,66 @@
 //! Main recording orchestration with multi-monitor support
 
 use anyhow::Result;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use std::collections::VecDeque;
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::broadcast;
 use tracing::{debug, error, info, warn};
 
-use memoire_capture::{Monitor, MonitorInfo, ScreenCapture, screen::CapturedFrame};
-use memoire_db::{Database, NewFrame, NewVideoChunk};
-use memoire_processing::{VideoEncoder, encoder::EncoderConfig};
+use memoire_capture::{
+    AudioCapture, AudioCaptureConfig, BrowserUrlReader, CapturedAudio, Hotkey, HotkeyListener, Monitor,
+    MonitorInfo, ScreenCapture, idle_duration, is_session_locked, screen::CapturedFrame,
+};
+use memoire_db::{Database, NewAudioChunk, NewFrame, NewVideoChunk, VideoChunkMetadata};
+use memoire_processing::{
+    AudioChunkInfo, AudioEncoder, AudioEncoderConfig, AudioMixer, AudioTrack, EncodedChunkInfo, VideoEncoder, encoder::EncoderConfig,
+};
 
-use crate::config::Config;
+use crate::config::{Config, DualAudio, MIN_FPS};
 
-/// Frame batch settings for database writes
-const FRAME_BATCH_SIZE: usize = 30;
-const FRAME_FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+/// Default frame batch settings for database writes
+pub const DEFAULT_FRAME_BATCH_SIZE: usize = 30;
+pub const DEFAULT_FRAME_FLUSH_INTERVAL_SECS: u64 = 5;
 
 /// Frame deduplication settings
 /// Hamming distance threshold: frames with distance <= this are considered duplicates
 /// 0 = exact match only, 5 = ~92% similar, 10 = ~85% similar
-const DEFAULT_DEDUP_THRESHOLD: u32 = 5;
+pub const DEFAULT_DEDUP_THRESHOLD: u32 = 5;
+
+/// Seconds without keyboard or mouse input after which frames are stored as unfocused (idle)
+pub const DEFAULT_IDLE_THRESHOLD_SECS: u64 = 120;
+
+/// Hotkey saving the clip buffer when none is configured
+pub const DEFAULT_CLIP_HOTKEY: &str = "ctrl+shift+s";
+
+/// Longest single sleep between captures, so shutdown is noticed at low framerates
+const MAX_IDLE_SLEEP: Duration = Duration::from_secs(1);
+
+/// Audio settings when recording audio alongside video (16kHz mono for STT)
+const AUDIO_CHUNK_SECS: u32 = 30;
+const AUDIO_SAMPLE_RATE: u32 = 16000;
+
+/// Audio kept for muxing beyond a chunk's duration (and the clip buffer),
+/// covering chunks that are finalized late
+const MUX_AUDIO_MARGIN_SECS: u64 = 60;
+
+/// How often audio retention is applied while recording
+const AUDIO_PRUNE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// How often the storage limit is checked while recording
+const STORAGE_CHECK_INTERVAL: Duration = Duration::from_secs(300);
+
+/// How often free disk space is checked while recording
+const DISK_SPACE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Minimum time between raw frame dumps of one monitor
+pub const RAW_FRAME_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Raw frames dumped per monitor before dumping stops for the session
+pub const MAX_RAW_FRAMES: usize = 500;
 
 /// Event emitted when a video chunk is finalized and ready for indexing
 #[derive(Debug, Clone)]
@@ -32,41 +70,118 @@ pub struct ChunkFinalizedEvent {
     pub monitor_name: String,
 }
 
+/// Event emitted when an audio chunk is saved to the database
+#[derive(Debug, Clone)]
+pub struct AudioChunkEvent {
+    pub chunk_id: i64,
+    pub audio_path: PathBuf,
+    pub device_name: Option<String>,
+}
+
+/// A captured frame held in the clip buffer until it is saved or evicted
+struct BufferedFrame {
+    frame: CapturedFrame,
+    hash: u64,
+    browser_url: Option<String>,
+    focused: bool,
+}
+
+/// Debug dump of recorded frames as PNG, named after their database rows
+struct RawFrameDump {
+    dir: PathBuf,
+    saved: usize,
+    last_saved: Option<Instant>,
+}
+
+impl RawFrameDump {
+    fn new(dir: PathBuf) -> Self {
+        Self { dir, saved: 0, last_saved: None }
+    }
+
+    /// Save `frame` as `chunk<id>_frame<offset>.png` unless throttled or capped
+    fn save(&mut self, frame: &CapturedFrame, chunk_id: i64, offset_index: i64) {
+        if self.saved >= MAX_RAW_FRAMES
+            || self.last_saved.is_some_and(|last| last.elapsed() < RAW_FRAME_INTERVAL)
+        {
+            return;
+        }
+        self.last_saved = Some(Instant::now());
+
+        let path = self.dir.join(format!("chunk{}_frame{}.png", chunk_id, offset_index));
+        let saved = std::fs::create_dir_all(&self.dir)
+            .map_err(anyhow::Error::from)
+            .and_then(|_| frame.save_png(&path.to_string_lossy()));
+        match saved {
+            Ok(()) => {
+                self.saved += 1;
+                debug!("saved raw frame {:?}", path);
+                if self.saved == MAX_RAW_FRAMES {
+                    info!("saved {} raw frames to {:?}, not saving more", MAX_RAW_FRAMES, self.dir);
+                }
+            }
+            Err(e) => warn!("failed to save raw frame {:?}: {}", path, e),
+        }
+    }
+}
+
 /// Per-monitor recording state
 struct MonitorRecorder {
     info: MonitorInfo,
+    /// Name stored with chunks: the configured alias or the system name
+    device_name: String,
     capture: ScreenCapture,
     encoder: VideoEncoder,
+    /// Encoding frame rate (stored per chunk for seek-based extraction)
+    fps: f64,
     current_chunk_id: Option<i64>,
+    /// Frames captured into the current chunk (excluding skipped duplicates)
     frame_index: i64,
     chunk_index: u64,
     consecutive_errors: u32,
     pending_frames: Vec<NewFrame>,
     last_db_flush: Instant,
+    /// Frames buffered before a database write
+    frame_batch_size: usize,
+    /// Maximum time between database writes
+    frame_flush_interval: Duration,
     /// Last frame's perceptual hash for deduplication
     last_frame_hash: Option<u64>,
     /// Counter for skipped duplicate frames
     skipped_frames: u64,
+    /// Skipped duplicate frames attributed to the current chunk
+    chunk_skipped_frames: u64,
+    /// Skipped duplicate frames across all monitors (shared with metrics)
+    total_skipped_frames: Arc<AtomicU64>,
     /// Broadcast channel for chunk finalization events
     chunk_finalized_tx: broadcast::Sender<ChunkFinalizedEvent>,
+    /// Frames kept in clip mode (None = continuous recording)
+    clip_capacity: Option<usize>,
+    /// The most recent frames in clip mode, oldest first
+    clip_frames: VecDeque<BufferedFrame>,
+    /// PNG dump of recorded frames for debugging (None = off)
+    raw_frames: Option<RawFrameDump>,
 }
 
 impl MonitorRecorder {
     fn new(
         monitor: Monitor,
+        device_name: String,
         videos_dir: &std::path::Path,
         config: &Config,
         chunk_finalized_tx: broadcast::Sender<ChunkFinalizedEvent>,
+        total_skipped_frames: Arc<AtomicU64>,
+        audio_track: Option<AudioTrack>,
     ) -> Result<Self> {
         info!(
             "initializing capture for monitor: {} ({}x{})",
             monitor.info.name, monitor.info.width, monitor.info.height
         );
 
-        let capture = ScreenCapture::new(&monitor)?;
+        let mut capture = ScreenCapture::new(&monitor)?;
+        capture.set_capture_cursor(config.capture_cursor);
 
         // Create monitor-specific subdirectory
-        let monitor_name = sanitize_monitor_name(&monitor.info.name);
+        let monitor_name = sanitize_monitor_name(&device_name);
         let monitor_dir = videos_dir.join(&monitor_name);
         std::fs::create_dir_all(&monitor_dir)?;
 
@@ -75,33 +190,73 @@ impl MonitorRecorder {
             chunk_duration_secs: config.chunk_duration_secs,
             fps: config.fps,
             use_hw_encoding: config.use_hw_encoding,
-            quality: 23,
+            quality: config.quality,
+            preset: config.preset,
             use_piped_encoding: true, // Use efficient piped encoding by default
+            keyframe_interval: None,  // One keyframe per second for exact seeks
+            audio: audio_track,
         };
         let encoder = VideoEncoder::new(encoder_config)?;
 
+        let clip_capacity = config
+            .clip_buffer_secs
+            .map(|secs| ((secs as f64 * config.fps).ceil() as usize).max(1));
+        if let Some(capacity) = clip_capacity {
+            // Frames are buffered uncompressed (4 bytes per pixel)
+            let megabytes = capacity as f64 * monitor.info.width as f64 * monitor.info.height as f64 * 4.0 / (1024.0 * 1024.0);
+            info!(
+                "clip buffer for {}: {} frames (up to {:.0} MB)",
+                monitor.info.name, capacity, megabytes
+            );
+        }
+
         Ok(Self {
             info: monitor.info,
+            device_name,
             capture,
             encoder,
+            fps: config.fps,
             current_chunk_id: None,
             frame_index: 0,
             chunk_index: 0,
             consecutive_errors: 0,
-            pending_frames: Vec::with_capacity(FRAME_BATCH_SIZE),
+            pending_frames: Vec::with_capacity(config.frame_batch_size),
             last_db_flush: Instant::now(),
+            frame_batch_size: config.frame_batch_size.max(1),
+            frame_flush_interval: Duration::from_secs(config.frame_flush_interval_secs),
             last_frame_hash: None,
             skipped_frames: 0,
+            chunk_skipped_frames: 0,
+            total_skipped_frames,
             chunk_finalized_tx,
+            clip_capacity,
+            clip_frames: VecDeque::new(),
+            raw_frames: config
+                .save_raw_frames
+                .as_ref()
+                .map(|dir| RawFrameDump::new(dir.join(&monitor_name))),
         })
     }
 
-    fn capture_frame(&mut self, db: &Database) -> Result<bool> {
+    fn capture_frame(&mut self, db: &Database, browser: &mut Option<BrowserUrlReader>, focused: bool) -> Result<bool> {
         let frame = match self.capture.capture_frame(Duration::from_millis(100))? {
             Some(f) => f,
             None => return Ok(false),
         };
 
+        // A malformed buffer would corrupt the video stream; drop it before
+        // its metadata is stored
+        if !memoire_processing::is_valid_frame(&frame.data, frame.width, frame.height) {
+            warn!(
+                "dropping malformed frame from {}: {} bytes for {}x{}",
+                self.info.name,
+                frame.data.len(),
+                frame.width,
+                frame.height
+            );
+            return Ok(false);
+        }
+
         // Calculate perceptual hash for deduplication
         let frame_hash = frame.compute_perceptual_hash();
 
@@ -111,6 +266,8 @@ impl MonitorRecorder {
             if distance <= DEFAULT_DEDUP_THRESHOLD {
                 // Frame is too similar to previous, skip it
                 self.skipped_frames += 1;
+                self.chunk_skipped_frames += 1;
+                self.total_skipped_frames.fetch_add(1, Ordering::Relaxed);
                 debug!(
                     "skipping duplicate frame (distance={}, threshold={}), total skipped: {}",
                     distance, DEFAULT_DEDUP_THRESHOLD, self.skipped_frames
@@ -122,9 +279,49 @@ impl MonitorRecorder {
         // Update last frame hash
         self.last_frame_hash = Some(frame_hash);
 
+        let browser_url = browser.as_mut().and_then(|b| b.current_url());
+
+        // In clip mode frames stay in memory until the hotkey saves them
+        if let Some(capacity) = self.clip_capacity {
+            if self.clip_frames.len() >= capacity {
+                self.clip_frames.pop_front();
+            }
+            self.clip_frames.push_back(BufferedFrame {
+                frame,
+                hash: frame_hash,
+                browser_url,
+                focused,
+            });
+            self.consecutive_errors = 0;
+            return Ok(true);
+        }
+
+        self.record_frame(db, frame, frame_hash, browser_url, focused)?;
+        Ok(true)
+    }
+
+    /// Encode a frame into the current chunk and buffer its metadata
+    fn record_frame(
+        &mut self,
+        db: &Database,
+        frame: CapturedFrame,
+        frame_hash: u64,
+        browser_url: Option<String>,
+        focused: bool,
+    ) -> Result<()> {
+        // A resolution change (display settings, fullscreen games) needs a new
+        // chunk: close it here so this frame's metadata lands in the new one
+        if self.current_chunk_id.is_some() && self.encoder.frame_size_changed(frame.width, frame.height) {
+            info!(
+                "resolution of {} changed to {}x{}, starting a new chunk",
+                self.info.name, frame.width, frame.height
+            );
+            self.finalize_chunk(db)?;
+        }
+
         // Ensure we have a current chunk
         if self.current_chunk_id.is_none() {
-            self.start_new_chunk(db)?;
+            self.start_new_chunk(db, frame.width, frame.height)?;
         }
 
         let chunk_id = match self.current_chunk_id {
@@ -132,39 +329,60 @@ impl MonitorRecorder {
             None => {
                 // This should not happen after start_new_chunk, but handle gracefully
                 error!("chunk_id unexpectedly None after initialization - attempting recovery");
-                self.start_new_chunk(db)?;
+                self.start_new_chunk(db, frame.width, frame.height)?;
                 self.current_chunk_id
                     .ok_or_else(|| anyhow::anyhow!("failed to initialize chunk_id after retry"))?
             }
         };
 
-        // Buffer frame metadata for batch insert (store hash as i64 for SQLite)
+        // Buffer frame metadata for batch insert (store hash as i64 for SQLite).
+        // The offset is the frame's position in the video, which skips ahead
+        // of the captured count when duplicates were dropped.
         let new_frame = NewFrame {
             video_chunk_id: chunk_id,
-            offset_index: self.frame_index,
+            offset_index: self.encoder.frame_position(frame.timestamp) as i64,
+            capture_index: Some(self.frame_index),
             timestamp: frame.timestamp,
             app_name: None,
             window_name: None,
-            browser_url: None,
-            focused: true,
+            browser_url,
+            focused,
             frame_hash: Some(frame_hash as i64),
         };
+        if let Some(raw_frames) = self.raw_frames.as_mut() {
+            raw_frames.save(&frame, chunk_id, new_frame.offset_index);
+        }
         self.pending_frames.push(new_frame);
 
-        // Add frame to encoder
-        self.encoder.add_frame(&frame.data, frame.width, frame.height, frame.timestamp)?;
+        // Add frame to encoder (closes the chunk once its duration is reached)
+        let finished = self.encoder.add_frame(&frame.data, frame.width, frame.height, frame.timestamp)?;
 
         self.frame_index += 1;
         self.consecutive_errors = 0;
 
-        // Flush to database if batch is full or timeout reached
-        if self.pending_frames.len() >= FRAME_BATCH_SIZE
-            || self.last_db_flush.elapsed() >= FRAME_FLUSH_INTERVAL
+        if finished.is_some() {
+            self.complete_chunk(db, finished)?;
+        } else if self.pending_frames.len() >= self.frame_batch_size
+            || self.last_db_flush.elapsed() >= self.frame_flush_interval
         {
+            // Flush to database if batch is full or timeout reached
             self.flush_frames(db)?;
         }
 
-        Ok(true)
+        Ok(())
+    }
+
+    /// Encode the clip buffer into a chunk of its own, returning the frames saved
+    fn save_clip(&mut self, db: &Database) -> Result<usize> {
+        let frames = std::mem::take(&mut self.clip_frames);
+        let count = frames.len();
+        // Duplicates skipped while buffering mostly predate the clip
+        self.chunk_skipped_frames = 0;
+        for buffered in frames {
+            self.record_frame(db, buffered.frame, buffered.hash, buffered.browser_url, buffered.focused)?;
+        }
+        self.finalize_chunk(db)?;
+        Ok(count)
     }
 
     /// Flush pending frames to database in a single transaction
@@ -183,23 +401,27 @@ impl MonitorRecorder {
         self.pending_frames.clear();
         self.last_db_flush = Instant::now();
 
+        self.save_recording_stats(db)?;
+
         Ok(())
     }
 
-    fn start_new_chunk(&mut self, db: &Database) -> Result<()> {
+    /// Insert the database row for a new chunk of `width`x`height` frames
+    fn start_new_chunk(&mut self, db: &Database, width: u32, height: u32) -> Result<()> {
         let timestamp = Utc::now();
         let date_str = timestamp.format("%Y-%m-%d").to_string();
         let time_str = timestamp.format("%H-%M-%S").to_string();
-        let monitor_name = sanitize_monitor_name(&self.info.name);
+        let monitor_name = sanitize_monitor_name(&self.device_name);
 
         // Note: chunk_index matches encoder's internal index for this monitor
         let file_path = format!("videos/{}/{}/chunk_{}_{}.mp4", monitor_name, date_str, time_str, self.chunk_index);
 
         let new_chunk = NewVideoChunk {
             file_path,
-            device_name: self.info.name.clone(),
-            width: Some(self.info.width),
-            height: Some(self.info.height),
+            device_name: self.device_name.clone(),
+            width: Some(width),
+            height: Some(height),
+            fps: Some(self.fps),
         };
 
         let chunk_id = memoire_db::insert_video_chunk(db.connection(), &new_chunk)?;
@@ -210,19 +432,61 @@ impl MonitorRecorder {
         Ok(())
     }
 
+    /// Persist captured/skipped counters for the current chunk
+    fn save_recording_stats(&self, db: &Database) -> Result<()> {
+        if let Some(chunk_id) = self.current_chunk_id {
+            memoire_db::upsert_recording_stats(
+                db.connection(),
+                chunk_id,
+                self.frame_index,
+                self.chunk_skipped_frames as i64,
+            )?;
+        }
+        Ok(())
+    }
+
     fn finalize_chunk(&mut self, db: &Database) -> Result<()> {
+        let finished = self.encoder.finalize_chunk()?;
+        self.complete_chunk(db, finished)
+    }
+
+    /// Record a chunk the encoder has closed and notify indexers
+    fn complete_chunk(&mut self, db: &Database, finished: Option<EncodedChunkInfo>) -> Result<()> {
         // Flush any pending frames before finalizing the chunk
         self.flush_frames(db)?;
-
-        if let Some(path) = self.encoder.finalize_chunk()? {
-            info!("finalized chunk for {}: {:?}", self.info.name, path);
+        self.save_recording_stats(db)?;
+        self.chunk_skipped_frames = 0;
+
+        if let Some(encoded) = finished {
+            info!(
+                "finalized chunk for {}: {:?} ({}x{} {}, {:.1}s, {} bytes)",
+                self.info.name,
+                encoded.path,
+                encoded.width,
+                encoded.height,
+                encoded.codec,
+                encoded.duration_secs,
+                encoded.size_bytes
+            );
 
             // Emit chunk finalized event for indexers
             if let Some(chunk_id) = self.current_chunk_id {
+                // Store what was actually encoded so the indexer can skip ffprobe
+                let metadata = VideoChunkMetadata {
+                    width: encoded.width,
+                    height: encoded.height,
+                    codec: encoded.codec.clone(),
+                    duration_secs: encoded.duration_secs,
+                    size_bytes: encoded.size_bytes as i64,
+                };
+                if let Err(e) = memoire_db::update_chunk_metadata(db.connection(), chunk_id, &metadata) {
+                    warn!("failed to store metadata for chunk {}: {}", chunk_id, e);
+                }
+
                 let event = ChunkFinalizedEvent {
                     chunk_id,
-                    video_path: path.clone(),
-                    monitor_name: self.info.name.clone(),
+                    video_path: encoded.path,
+                    monitor_name: self.device_name.clone(),
                 };
 
                 // Send event (ignore error if no receivers - indexers might not be running)
@@ -236,22 +500,325 @@ impl MonitorRecorder {
     }
 }
 
+/// One audio capture device and, unless it is mixed, its own encoder
+struct AudioSource {
+    capture: AudioCapture,
+    rx: Option<tokio::sync::mpsc::Receiver<CapturedAudio>>,
+    encoder: Option<AudioEncoder>,
+    is_input_device: bool,
+}
+
+/// Optional audio pipeline recording into the same database as video
+///
+/// Records the configured device, plus system audio when dual recording is
+/// enabled: as separate chunks per device, or mixed into one stream.
+struct AudioPipeline {
+    sources: Vec<AudioSource>,
+    /// Mixer and encoder of the combined stream (mixed dual recording)
+    mixed: Option<(AudioMixer, AudioEncoder)>,
+    /// Start of the mixed audio not yet handed to the encoder
+    mixed_timestamp: Option<DateTime<Utc>>,
+    /// Track muxed into video chunks: the first source, or the mix
+    mux_track: Option<AudioTrack>,
+    chunk_count: u64,
+    /// Broadcast channel for saved audio chunks
+    chunk_saved_tx: broadcast::Sender<AudioChunkEvent>,
+}
+
+impl AudioPipeline {
+    fn new(
+        config: &Config,
+        chunk_saved_tx: broadcast::Sender<AudioChunkEvent>,
+        mux_track: Option<AudioTrack>,
+    ) -> Result<Self> {
+        let audio_dir = config.data_dir.join("audio");
+        std::fs::create_dir_all(&audio_dir)?;
+
+        let mixing = matches!(config.audio_dual, Some(DualAudio::Mixed { .. }));
+
+        // Feed the encoder in short buffers when it picks its own split points,
+        // the mixer so neither source waits long for the other, and the muxed
+        // track so it is current when a video chunk is finalized
+        let short_buffers = config.audio_split_on_silence || mixing || mux_track.is_some();
+        let capture_chunk_secs = if short_buffers { 1 } else { AUDIO_CHUNK_SECS };
+        let capture_config = |device_id: Option<String>, is_loopback: bool| AudioCaptureConfig {
+            device_id,
+            is_loopback,
+            chunk_duration_secs: capture_chunk_secs,
+            target_sample_rate: AUDIO_SAMPLE_RATE,
+            target_channels: 1,
+        };
+
+        let mut captures = vec![AudioCapture::new(capture_config(
+            config.audio_device.clone(),
+            config.audio_loopback && config.audio_dual.is_none(),
+        ))?];
+        if config.audio_dual.is_some() {
+            // System audio next to the microphone
+            captures.push(AudioCapture::new(capture_config(None, true))?);
+        }
+
+        let mut sources = Vec::new();
+        for capture in captures {
+            let encoder = if mixing {
+                None
+            } else {
+                Some(Self::encoder(config, &audio_dir, capture.device_name())?)
+            };
+            info!(
+                "audio recording enabled (device: {}, loopback: {})",
+                capture.device_name(),
+                !capture.is_input_device()
+            );
+            sources.push(AudioSource {
+                is_input_device: capture.is_input_device(),
+                capture,
+                rx: None,
+                encoder,
+            });
+        }
+
+        let mixed = match config.audio_dual {
+            Some(DualAudio::Mixed { mic_gain, system_gain }) => {
+                let name = Self::mixed_device_name(&sources);
+                info!("mixing audio as \"{}\" (gains {} and {})", name, mic_gain, system_gain);
+                Some((
+                    AudioMixer::new([mic_gain, system_gain], AUDIO_SAMPLE_RATE),
+                    Self::encoder(config, &audio_dir, &name)?,
+                ))
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            sources,
+            mixed,
+            mixed_timestamp: None,
+            mux_track,
+            chunk_count: 0,
+            chunk_saved_tx,
+        })
+    }
+
+    fn encoder(config: &Config, audio_dir: &std::path::Path, device_name: &str) -> Result<AudioEncoder> {
+        let encoder_config = AudioEncoderConfig {
+            output_dir: audio_dir.to_path_buf(),
+            chunk_duration_secs: AUDIO_CHUNK_SECS,
+            sample_rate: AUDIO_SAMPLE_RATE,
+            channels: 1,
+            split_on_silence: config.audio_split_on_silence,
+            ..Default::default()
+        };
+        AudioEncoder::new(encoder_config, device_name)
+    }
+
+    /// Device name stored for mixed chunks, e.g. "Microphone + Speakers"
+    fn mixed_device_name(sources: &[AudioSource]) -> String {
+        sources
+            .iter()
+            .map(|s| s.capture.device_name())
+            .collect::<Vec<_>>()
+            .join(" + ")
+    }
+
+    /// Start the capture threads
+    fn start(&mut self) -> Result<()> {
+        for source in &mut self.sources {
+            source.rx = Some(source.capture.start()?);
+        }
+        Ok(())
+    }
+
+    /// Encode audio received since the last call and record finished chunks
+    fn drain(&mut self, db: &Database) -> Result<()> {
+        let mut saved = Vec::new();
+
+        for (index, source) in self.sources.iter_mut().enumerate() {
+            let Some(rx) = source.rx.as_mut() else {
+                continue;
+            };
+
+            while let Ok(audio) = rx.try_recv() {
+                if let (0, Some(track), None) = (index, &self.mux_track, &self.mixed) {
+                    track.push(&audio.samples, audio.timestamp);
+                }
+                match (source.encoder.as_mut(), self.mixed.as_mut()) {
+                    (Some(encoder), _) => {
+                        for chunk in encoder.add_samples(&audio.samples, audio.timestamp)? {
+                            saved.push((chunk, Some(audio.device_name.clone()), Some(source.is_input_device)));
+                        }
+                    }
+                    (None, Some((mixer, _))) => {
+                        mixer.push(index, &audio.samples);
+                        self.mixed_timestamp.get_or_insert(audio.timestamp);
+                    }
+                    (None, None) => {}
+                }
+            }
+        }
+
+        if let Some((mixer, encoder)) = self.mixed.as_mut() {
+            let samples = mixer.mix();
+            if !samples.is_empty() {
+                let timestamp = self.mixed_timestamp.take().unwrap_or_else(Utc::now);
+                if let Some(track) = &self.mux_track {
+                    track.push(&samples, timestamp);
+                }
+                let device_name = encoder.device_name().to_string();
+                for chunk in encoder.add_samples(&samples, timestamp)? {
+                    // A mix of a microphone and an output device is neither
+                    saved.push((chunk, Some(device_name.clone()), None));
+                }
+            }
+        }
+
+        for (chunk, device_name, is_input_device) in saved {
+            self.insert_chunk(db, chunk, device_name, is_input_device)?;
+        }
+        Ok(())
+    }
+
+    /// Stop capture and flush the partially filled chunks
+    fn finish(&mut self, db: &Database) -> Result<()> {
+        for source in &self.sources {
+            source.capture.stop();
+        }
+        self.flush(db)?;
+
+        info!("audio recording stopped, {} chunks recorded", self.chunk_count);
+        Ok(())
+    }
+
+    /// Drop audio received since the last call (while recording is paused)
+    fn discard(&mut self) {
+        for rx in self.sources.iter_mut().filter_map(|source| source.rx.as_mut()) {
+            while rx.try_recv().is_ok() {}
+        }
+    }
+
+    /// Record pending audio and close the partially filled chunks
+    ///
+    /// Capture keeps running; the next samples start new chunks.
+    fn flush(&mut self, db: &Database) -> Result<()> {
+        self.drain(db)?;
+
+        let mut saved = Vec::new();
+        for source in &mut self.sources {
+            if let Some(encoder) = source.encoder.as_mut() {
+                if let Some(chunk) = encoder.finalize_chunk()? {
+                    saved.push((chunk, Some(source.capture.device_name().to_string()), Some(source.is_input_device)));
+                }
+            }
+        }
+        if let Some((mixer, encoder)) = self.mixed.as_mut() {
+            let timestamp = self.mixed_timestamp.take().unwrap_or_else(Utc::now);
+            let samples = mixer.flush();
+            if let Some(track) = &self.mux_track {
+                track.push(&samples, timestamp);
+            }
+            let device_name = encoder.device_name().to_string();
+            let completed = encoder.add_samples(&samples, timestamp)?;
+            for chunk in completed.into_iter().chain(encoder.finalize_chunk()?) {
+                saved.push((chunk, Some(device_name.clone()), None));
+            }
+        }
+        for (chunk, device_name, is_input_device) in saved {
+            self.insert_chunk(db, chunk, device_name, is_input_device)?;
+        }
+        Ok(())
+    }
+
+    fn insert_chunk(
+        &mut self,
+        db: &Database,
+        chunk: AudioChunkInfo,
+        device_name: Option<String>,
+        is_input_device: Option<bool>,
+    ) -> Result<()> {
+        debug!("saved audio chunk: {:?}", chunk.path);
+
+        let new_chunk = NewAudioChunk {
+            file_path: chunk.path.to_string_lossy().to_string(),
+            device_name,
+            is_input_device,
+            timestamp: chunk.start_time,
+        };
+        let chunk_id = memoire_db::insert_audio_chunk(db.connection(), &new_chunk)?;
+        self.chunk_count += 1;
+
+        let _ = self.chunk_saved_tx.send(AudioChunkEvent {
+            chunk_id,
+            audio_path: chunk.path,
+            device_name: new_chunk.device_name,
+        });
+        Ok(())
+    }
+}
+
 /// Main recorder that orchestrates capture across all monitors
 pub struct Recorder {
     config: Config,
     db: Database,
     monitors: Vec<MonitorRecorder>,
     chunk_finalized_tx: broadcast::Sender<ChunkFinalizedEvent>,
+    audio_chunk_tx: broadcast::Sender<AudioChunkEvent>,
+    /// Duplicate frames skipped across all monitors
+    skipped_frames: Arc<AtomicU64>,
+    /// Audio recording alongside video (when enabled)
+    audio: Option<AudioPipeline>,
+    /// Set to save the clip buffers (clip mode only)
+    clip_requested: Arc<AtomicBool>,
+    /// Set when recording stopped because the disk is nearly full
+    disk_low: Arc<AtomicBool>,
+    /// Capture is suspended while set (chunks stay open)
+    paused: Arc<AtomicBool>,
+}
+
+/// Event channels and counters shared by successive recorders
+///
+/// A recorder restarted after a failure is created with the channels of the
+/// one it replaces, so indexers and the viewer keep receiving its events.
+#[derive(Clone)]
+pub struct RecorderChannels {
+    chunk_finalized_tx: broadcast::Sender<ChunkFinalizedEvent>,
+    audio_chunk_tx: broadcast::Sender<AudioChunkEvent>,
+    skipped_frames: Arc<AtomicU64>,
+}
+
+impl RecorderChannels {
+    pub fn new() -> Self {
+        // Capacity of 100 allows buffering events if indexers are slow to subscribe
+        let (chunk_finalized_tx, _rx) = broadcast::channel(100);
+        let (audio_chunk_tx, _rx) = broadcast::channel(100);
+        Self {
+            chunk_finalized_tx,
+            audio_chunk_tx,
+            skipped_frames: Arc::new(AtomicU64::new(0)),
+        }
+    }
+}
+
+impl Default for RecorderChannels {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Recorder {
     /// Create a new recorder for all available monitors
     pub fn new(config: Config) -> Result<Self> {
+        Self::with_channels(config, RecorderChannels::new())
+    }
+
+    /// Create a recorder publishing to existing channels (see [`Recorder::channels`])
+    pub fn with_channels(config: Config, channels: RecorderChannels) -> Result<Self> {
         info!("initializing multi-monitor recorder");
 
-        // Create broadcast channel for chunk finalization events
-        // Capacity of 100 allows buffering events if indexers are slow to subscribe
-        let (chunk_finalized_tx, _rx) = broadcast::channel(100);
+        let RecorderChannels {
+            chunk_finalized_tx,
+            audio_chunk_tx,
+            skipped_frames,
+        } = channels;
 
         // Create directories
         std::fs::create_dir_all(&config.data_dir)?;
@@ -260,18 +827,42 @@ impl Recorder {
 
         // Open database
         let db_path = config.data_dir.join("memoire.db");
-        let db = Database::open(&db_path)?;
+        let db = Database::open_with_journal_mode(&db_path, config.journal_mode)?;
         info!("database opened at {:?}", db_path);
 
         // Get all monitors
         let monitor_infos = Monitor::enumerate_all()?;
         info!("found {} monitor(s)", monitor_infos.len());
 
+        // Shared by the audio pipeline and every monitor's encoder
+        let audio_track = (config.record_audio && config.mux_audio).then(|| {
+            let window = config.chunk_duration_secs + config.clip_buffer_secs.unwrap_or(0) + MUX_AUDIO_MARGIN_SECS;
+            AudioTrack::new(AUDIO_SAMPLE_RATE, window)
+        });
+
         let mut monitors = Vec::new();
-        for info in monitor_infos {
+        for (index, info) in monitor_infos.into_iter().enumerate() {
+            if !config.monitors.is_selected(index, &info.name) {
+                info!("skipping monitor {} ({}) as configured", index, info.name);
+                continue;
+            }
+
+            let device_name = config.monitors.device_name(index, &info.name);
+            if device_name != info.name {
+                info!("recording monitor {} ({}) as \"{}\"", index, info.name, device_name);
+            }
+
             match Monitor::from_info(info.clone()) {
                 Ok(monitor) => {
-                    match MonitorRecorder::new(monitor, &videos_dir, &config, chunk_finalized_tx.clone()) {
+                    match MonitorRecorder::new(
+                        monitor,
+                        device_name,
+                        &videos_dir,
+                        &config,
+                        chunk_finalized_tx.clone(),
+                        skipped_frames.clone(),
+                        audio_track.clone(),
+                    ) {
                         Ok(recorder) => {
                             monitors.push(recorder);
                         }
@@ -287,16 +878,30 @@ impl Recorder {
         }
 
         if monitors.is_empty() {
-            return Err(anyhow::anyhow!("no monitors available for capture"));
+            return Err(anyhow::anyhow!(
+                "no monitors available for capture (check --monitor/--exclude-monitor)"
+            ));
         }
 
         info!("initialized {} monitor recorder(s)", monitors.len());
 
+        let audio = if config.record_audio {
+            Some(AudioPipeline::new(&config, audio_chunk_tx.clone(), audio_track)?)
+        } else {
+            None
+        };
+
         Ok(Self {
             config,
             db,
             monitors,
             chunk_finalized_tx,
+            audio_chunk_tx,
+            skipped_frames,
+            audio,
+            clip_requested: Arc::new(AtomicBool::new(false)),
+            disk_low: Arc::new(AtomicBool::new(false)),
+            paused: Arc::new(AtomicBool::new(false)),
         })
     }
 
@@ -308,6 +913,47 @@ impl Recorder {
         self.chunk_finalized_tx.subscribe()
     }
 
+    /// Subscribe to audio chunks saved while recording audio alongside video
+    pub fn subscribe_to_audio_chunk_events(&self) -> broadcast::Receiver<AudioChunkEvent> {
+        self.audio_chunk_tx.subscribe()
+    }
+
+    /// Shared counter of duplicate frames skipped across all monitors
+    pub fn skipped_frames_counter(&self) -> Arc<AtomicU64> {
+        self.skipped_frames.clone()
+    }
+
+    /// Flag saving the clip buffers when set, like the clip hotkey
+    pub fn clip_trigger(&self) -> Arc<AtomicBool> {
+        self.clip_requested.clone()
+    }
+
+    /// Flag set when [`Recorder::run`] returned because free disk space fell
+    /// below `min_free_bytes`
+    pub fn disk_low_flag(&self) -> Arc<AtomicBool> {
+        self.disk_low.clone()
+    }
+
+    /// Flag pausing capture while set
+    ///
+    /// Unlike stopping, pausing keeps each monitor's chunk and FFmpeg pipe
+    /// open. The first frame after resuming is placed by wall-clock time, so
+    /// the pause shows as the last frame held on screen; a chunk whose
+    /// duration ran out during the pause is closed on resume instead.
+    /// Audio received while paused is dropped.
+    pub fn pause_flag(&self) -> Arc<AtomicBool> {
+        self.paused.clone()
+    }
+
+    /// Channels to hand to a replacement recorder
+    pub fn channels(&self) -> RecorderChannels {
+        RecorderChannels {
+            chunk_finalized_tx: self.chunk_finalized_tx.clone(),
+            audio_chunk_tx: self.audio_chunk_tx.clone(),
+            skipped_frames: self.skipped_frames.clone(),
+        }
+    }
+
     /// Run the recording loop for all monitors
     pub fn run(&mut self, shutdown: Arc<AtomicBool>) -> Result<()> {
         info!(
@@ -316,28 +962,166 @@ impl Recorder {
             self.monitors.len()
         );
 
-        let frame_interval = Duration::from_secs_f64(1.0 / self.config.fps as f64);
+        let frame_interval = Duration::from_secs_f64(1.0 / self.config.fps.max(MIN_FPS));
         let mut last_capture = Instant::now();
         let mut total_frames = 0u64;
         let mut capture_attempts = 0u64;
         let max_consecutive_errors = 10;
 
+        // UI Automation client must live on the recording thread
+        let mut browser = match BrowserUrlReader::new() {
+            Ok(reader) => Some(reader),
+            Err(e) => {
+                warn!("browser URL detection unavailable: {}", e);
+                None
+            }
+        };
+
+        // Unregistered when the recorder stops
+        let _clip_hotkey = match self.config.clip_buffer_secs {
+            Some(secs) => {
+                let hotkey: Hotkey = self.config.clip_hotkey.parse()?;
+                let listener = HotkeyListener::register(hotkey, self.clip_requested.clone())?;
+                info!("clip mode: press {} to save the last {} seconds", hotkey, secs);
+                Some(listener)
+            }
+            None => None,
+        };
+
+        if let Some(audio) = self.audio.as_mut() {
+            audio.start()?;
+        }
+
+        let mut paused_for_lock = false;
+        let mut paused_by_user = false;
+        let idle_threshold = Duration::from_secs(self.config.idle_threshold_secs);
+        let mut user_idle = false;
+        let mut last_audio_prune: Option<Instant> = None;
+        let mut last_storage_check: Option<Instant> = None;
+        let mut last_disk_space_check: Option<Instant> = None;
+
         while !shutdown.load(Ordering::SeqCst) {
             // Wait for next frame time
             let elapsed = last_capture.elapsed();
             if elapsed < frame_interval {
-                std::thread::sleep(frame_interval - elapsed);
+                // Sleep in short slices so shutdown stays responsive at sub-1 FPS
+                std::thread::sleep((frame_interval - elapsed).min(MAX_IDLE_SLEEP));
+                continue;
             }
             last_capture = Instant::now();
+
+            if let Some(days) = self.config.audio_retention_days {
+                if last_audio_prune.is_none_or(|t| t.elapsed() >= AUDIO_PRUNE_INTERVAL) {
+                    last_audio_prune = Some(Instant::now());
+                    self.prune_audio(days);
+                }
+            }
+
+            if let Some(max_bytes) = self.config.max_storage_bytes {
+                if last_storage_check.is_none_or(|t| t.elapsed() >= STORAGE_CHECK_INTERVAL) {
+                    last_storage_check = Some(Instant::now());
+                    self.enforce_storage_limit(max_bytes);
+                }
+            }
+
+            if let Some(min_free) = self.config.min_free_bytes {
+                if last_disk_space_check.is_none_or(|t| t.elapsed() >= DISK_SPACE_CHECK_INTERVAL) {
+                    last_disk_space_check = Some(Instant::now());
+                    if !self.has_free_space(min_free) {
+                        self.disk_low.store(true, Ordering::SeqCst);
+                        break;
+                    }
+                }
+            }
+
+            // The lock screen can't be captured; close chunks instead of
+            // recording black frames, and resume with fresh ones on unlock
+            let locked = is_session_locked();
+            if locked != paused_for_lock {
+                paused_for_lock = locked;
+                if locked {
+                    info!("session locked, pausing screen capture");
+                    for monitor in &mut self.monitors {
+                        if let Err(e) = monitor.finalize_chunk(&self.db) {
+                            warn!("error finalizing chunk for {}: {}", monitor.info.name, e);
+                        }
+                    }
+                } else {
+                    info!("session unlocked, resuming screen capture");
+                    // Duplication outputs are usually lost across the secure desktop
+                    for monitor in &mut self.monitors {
+                        if let Err(e) = Self::reinitialize_monitor(monitor, &self.db) {
+                            error!("failed to reinitialize {}: {}", monitor.info.name, e);
+                        }
+                    }
+                }
+            }
+
+            let paused = self.paused.load(Ordering::SeqCst);
+            if paused != paused_by_user {
+                paused_by_user = paused;
+                if paused {
+                    info!("recording paused");
+                    for monitor in &mut self.monitors {
+                        if let Err(e) = monitor.flush_frames(&self.db) {
+                            warn!("error flushing frames for {}: {}", monitor.info.name, e);
+                        }
+                    }
+                    // Audio chunks are closed so later ones start at the right time
+                    if let Some(audio) = self.audio.as_mut() {
+                        if let Err(e) = audio.flush(&self.db) {
+                            error!("audio recording error: {}", e);
+                        }
+                    }
+                } else {
+                    info!("recording resumed");
+                    let now = Utc::now();
+                    for monitor in &mut self.monitors {
+                        if monitor.encoder.chunk_expired(now) {
+                            if let Err(e) = monitor.finalize_chunk(&self.db) {
+                                warn!("error finalizing chunk for {}: {}", monitor.info.name, e);
+                            }
+                        }
+                    }
+                }
+            }
+
+            if paused {
+                if let Some(audio) = self.audio.as_mut() {
+                    audio.discard();
+                }
+                continue;
+            }
+
+            if locked {
+                if let Some(audio) = self.audio.as_mut() {
+                    if let Err(e) = audio.drain(&self.db) {
+                        error!("audio recording error: {}", e);
+                    }
+                }
+                continue;
+            }
+
             capture_attempts += 1;
 
+            // Frames captured while nobody is at the keyboard are kept but marked unfocused
+            let idle = idle_duration() >= idle_threshold;
+            if idle != user_idle {
+                user_idle = idle;
+                if idle {
+                    info!("no input for {}s, marking frames as idle", idle_threshold.as_secs());
+                } else {
+                    info!("input resumed, marking frames as focused");
+                }
+            }
+
             // Capture from all monitors
             let mut any_captured = false;
             let mut monitors_to_reinit = Vec::new();
 
             let mut no_frame_count = 0;
             for (i, monitor) in self.monitors.iter_mut().enumerate() {
-                match monitor.capture_frame(&self.db) {
+                match monitor.capture_frame(&self.db, &mut browser, !user_idle) {
                     Ok(true) => {
                         any_captured = true;
                     }
@@ -364,6 +1148,16 @@ impl Recorder {
                 );
             }
 
+            if let Some(audio) = self.audio.as_mut() {
+                if let Err(e) = audio.drain(&self.db) {
+                    error!("audio recording error: {}", e);
+                }
+            }
+
+            if self.clip_requested.swap(false, Ordering::SeqCst) {
+                self.save_clips();
+            }
+
             // Reinitialize monitors that had too many errors
             for i in monitors_to_reinit {
                 let monitor = &mut self.monitors[i];
@@ -387,9 +1181,18 @@ impl Recorder {
 
         // Finalize all chunks
         info!("finalizing recording...");
+        if let Some(audio) = self.audio.as_mut() {
+            if let Err(e) = audio.finish(&self.db) {
+                warn!("error finalizing audio chunk: {}", e);
+            }
+        }
+
         let mut total_skipped = 0u64;
         for monitor in &mut self.monitors {
             total_skipped += monitor.skipped_frames;
+            if !monitor.clip_frames.is_empty() {
+                debug!("discarding {} unsaved clip frames for {}", monitor.clip_frames.len(), monitor.info.name);
+            }
             if let Err(e) = monitor.finalize_chunk(&self.db) {
                 warn!("error finalizing chunk for {}: {}", monitor.info.name, e);
             }
@@ -407,13 +1210,99 @@ impl Recorder {
         Ok(())
     }
 
+    /// Save every monitor's clip buffer as a chunk
+    fn save_clips(&mut self) {
+        for monitor in &mut self.monitors {
+            match monitor.save_clip(&self.db) {
+                Ok(0) => info!("clip requested, but no frames are buffered for {}", monitor.info.name),
+                Ok(frames) => info!("saved clip of {} frames for {}", frames, monitor.info.name),
+                Err(e) => error!("failed to save clip for {}: {}", monitor.info.name, e),
+            }
+        }
+    }
+
+    /// Delete audio files past the retention period, keeping their transcriptions
+    fn prune_audio(&self, retention_days: u32) {
+        let cutoff = Utc::now() - chrono::Duration::days(retention_days as i64);
+        match memoire_db::delete_audio_files_older_than(self.db.connection(), &self.config.data_dir, cutoff) {
+            Ok(pruned) if pruned.chunks > 0 => info!(
+                "pruned {} audio file(s) older than {} days ({:.1} MB freed)",
+                pruned.chunks,
+                retention_days,
+                pruned.bytes_freed as f64 / (1024.0 * 1024.0)
+            ),
+            Ok(_) => debug!("no audio files older than {} days", retention_days),
+            Err(e) => warn!("audio retention failed: {}", e),
+        }
+    }
+
+    /// Delete the oldest recordings while over the storage limit, sparing the chunks being written
+    fn enforce_storage_limit(&self, max_bytes: u64) {
+        let recording: Vec<i64> = self.monitors.iter().filter_map(|m| m.current_chunk_id).collect();
+        let pruned = match memoire_db::enforce_size_limit(self.db.connection(), &self.config.data_dir, max_bytes, &recording) {
+            Ok(pruned) => pruned,
+            Err(e) => {
+                warn!("storage limit failed: {}", e);
+                return;
+            }
+        };
+
+        let used_gb = pruned.bytes_used as f64 / (1024.0 * 1024.0 * 1024.0);
+        if pruned.video_chunks + pruned.audio_chunks > 0 {
+            info!(
+                "storage limit: pruned {} video and {} audio chunk(s) ({:.1} MB freed, {:.1} GB used)",
+                pruned.video_chunks,
+                pruned.audio_chunks,
+                pruned.bytes_freed as f64 / (1024.0 * 1024.0),
+                used_gb
+            );
+        } else {
+            debug!("storage: {:.1} GB used", used_gb);
+        }
+        if pruned.bytes_used > max_bytes {
+            warn!(
+                "storage still over the limit ({:.1} GB used, {} chunk(s) could not be deleted)",
+                used_gb, pruned.skipped
+            );
+        }
+    }
+
+    /// Whether the data directory's volume still has `min_free` bytes available
+    ///
+    /// A failed check is logged and treated as enough space, so a transient
+    /// error doesn't stop recording.
+    fn has_free_space(&self, min_free: u64) -> bool {
+        let available = match fs2::available_space(&self.config.data_dir) {
+            Ok(available) => available,
+            Err(e) => {
+                warn!("free disk space check failed: {}", e);
+                return true;
+            }
+        };
+
+        let gb = |bytes: u64| bytes as f64 / (1024.0 * 1024.0 * 1024.0);
+        if available < min_free {
+            error!(
+                "only {:.2} GB free on the volume of {:?} (minimum {:.2} GB), stopping recording",
+                gb(available),
+                self.config.data_dir,
+                gb(min_free)
+            );
+            return false;
+        }
+        debug!("disk space: {:.1} GB free", gb(available));
+        true
+    }
+
     fn reinitialize_monitor(monitor: &mut MonitorRecorder, db: &Database) -> Result<()> {
         // Finalize current chunk (flushes pending frames)
         let _ = monitor.finalize_chunk(db);
 
         // Re-create monitor and capture
         let new_monitor = Monitor::from_info(monitor.info.clone())?;
+        let capture_cursor = monitor.capture.capture_cursor();
         monitor.capture = ScreenCapture::new(&new_monitor)?;
+        monitor.capture.set_capture_cursor(capture_cursor);
         monitor.consecutive_errors = 0;
 
         info!("reinitialized capture for {}", monitor.info.name);
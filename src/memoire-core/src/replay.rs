@@ -0,0 +1,203 @@
+//! Time window export (`memoire replay`)
+//!
+//! Cuts the frames recorded between two times out of the video chunks that
+//! hold them and joins the pieces into a single MP4, so a moment spanning a
+//! chunk boundary plays in one go. FFmpeg's concat demuxer trims each chunk
+//! with inpoint/outpoint directives; the result is re-encoded, which keeps the
+//! cuts frame-accurate and tolerates chunks with different resolutions.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tracing::{debug, info, warn};
+
+use memoire_db::{Database, VideoChunk};
+use memoire_processing::ffmpeg::ffmpeg_command;
+
+/// Frames fetched per query while collecting the window
+const FRAME_PAGE_SIZE: i64 = 5000;
+
+/// x264 CRF of the exported video
+const REPLAY_CRF: u32 = 23;
+
+/// The part of one chunk inside the requested window
+pub struct Segment {
+    pub chunk: VideoChunk,
+    /// Recording times of the first and last frame in the window
+    pub first_frame: DateTime<Utc>,
+    pub last_frame: DateTime<Utc>,
+    pub frames: usize,
+    /// Trim points in seconds from the start of the chunk file
+    pub inpoint: f64,
+    pub outpoint: f64,
+}
+
+/// Frames of one chunk seen while paging through the window
+struct ChunkFrames {
+    min_offset: i64,
+    max_offset: i64,
+    first: DateTime<Utc>,
+    last: DateTime<Utc>,
+    count: usize,
+}
+
+/// Find the chunk segments covering `from..=to`, oldest first
+///
+/// Frames come from [`memoire_db::get_frames_in_range`]. When the window holds
+/// frames from several monitors, `monitor` (a device name, case-insensitive)
+/// must pick one.
+pub fn plan(db: &Database, from: DateTime<Utc>, to: DateTime<Utc>, monitor: Option<&str>) -> Result<Vec<Segment>> {
+    let conn = db.connection();
+
+    let mut by_chunk: BTreeMap<i64, ChunkFrames> = BTreeMap::new();
+    let mut offset = 0;
+    loop {
+        let page = memoire_db::get_frames_in_range(conn, from, to, FRAME_PAGE_SIZE, offset, false)?;
+        for frame in &page {
+            by_chunk
+                .entry(frame.video_chunk_id)
+                .and_modify(|c| {
+                    c.min_offset = c.min_offset.min(frame.offset_index);
+                    c.max_offset = c.max_offset.max(frame.offset_index);
+                    c.first = c.first.min(frame.timestamp);
+                    c.last = c.last.max(frame.timestamp);
+                    c.count += 1;
+                })
+                .or_insert(ChunkFrames {
+                    min_offset: frame.offset_index,
+                    max_offset: frame.offset_index,
+                    first: frame.timestamp,
+                    last: frame.timestamp,
+                    count: 1,
+                });
+        }
+        if (page.len() as i64) < FRAME_PAGE_SIZE {
+            break;
+        }
+        offset += FRAME_PAGE_SIZE;
+    }
+
+    let mut segments = Vec::new();
+    for (chunk_id, frames) in by_chunk {
+        let Some(chunk) = memoire_db::get_video_chunk(conn, chunk_id)? else {
+            warn!("chunk {} not found, skipping", chunk_id);
+            continue;
+        };
+
+        // Chunks are constant frame rate, so offsets map directly to times
+        let fps = chunk.fps.filter(|f| *f > 0.0).unwrap_or(1.0);
+        segments.push(Segment {
+            inpoint: frames.min_offset as f64 / fps,
+            outpoint: (frames.max_offset + 1) as f64 / fps,
+            chunk,
+            first_frame: frames.first,
+            last_frame: frames.last,
+            frames: frames.count,
+        });
+    }
+
+    match monitor {
+        Some(monitor) => segments.retain(|s| s.chunk.device_name.eq_ignore_ascii_case(monitor)),
+        None => {
+            let mut devices: Vec<&str> = segments.iter().map(|s| s.chunk.device_name.as_str()).collect();
+            devices.sort_unstable();
+            devices.dedup();
+            if devices.len() > 1 {
+                return Err(anyhow::anyhow!(
+                    "the window holds frames from {} monitors ({}), pick one with --monitor",
+                    devices.len(),
+                    devices.join(", ")
+                ));
+            }
+        }
+    }
+
+    segments.sort_by_key(|s| s.first_frame);
+    Ok(segments)
+}
+
+/// Trim and join `segments` into one MP4 at `out`
+///
+/// Chunks still being recorded or whose file is missing are skipped.
+/// Returns the number of segments written.
+pub fn export(segments: &[Segment], data_dir: &Path, out: &Path) -> Result<usize> {
+    let mut list = String::new();
+    let mut used = Vec::new();
+
+    for segment in segments {
+        // The demuxer resolves relative paths against the list file's directory
+        let path = absolute(&data_dir.join(&segment.chunk.file_path))?;
+        if segment.chunk.duration_secs.is_none() {
+            warn!("chunk {} is still being recorded, skipping", segment.chunk.id);
+            continue;
+        }
+        if !path.exists() {
+            warn!("video file not found: {}, skipping", path.display());
+            continue;
+        }
+
+        list.push_str(&concat_entry(&path, segment.inpoint, segment.outpoint));
+        used.push(segment);
+    }
+
+    let Some(first) = used.first() else {
+        return Err(anyhow::anyhow!("no finished video chunks in this window"));
+    };
+
+    let list_path = std::env::temp_dir().join(format!("memoire-replay-{}.txt", std::process::id()));
+    std::fs::write(&list_path, &list).context("failed to write the FFmpeg concat list")?;
+    debug!("concat list:\n{}", list);
+
+    let mut command = ffmpeg_command();
+    command
+        .args(["-y", "-hide_banner", "-loglevel", "error"])
+        .args(["-f", "concat", "-safe", "0", "-i"])
+        .arg(&list_path);
+
+    // Letterbox every chunk into the first one's size so resolution changes join cleanly
+    if let (Some(width), Some(height)) = (first.chunk.width, first.chunk.height) {
+        let (width, height) = (width & !1, height & !1);
+        command.arg("-vf").arg(format!(
+            "scale={w}:{h}:force_original_aspect_ratio=decrease,pad={w}:{h}:(ow-iw)/2:(oh-ih)/2,setsar=1",
+            w = width,
+            h = height
+        ));
+    }
+
+    info!("exporting {} segment(s) to {}", used.len(), out.display());
+    let output = command
+        .args(["-c:v", "libx264", "-preset", "veryfast", "-crf"])
+        .arg(REPLAY_CRF.to_string())
+        .args(["-pix_fmt", "yuv420p", "-movflags", "+faststart", "-an"])
+        .arg(out)
+        .stdin(Stdio::null())
+        .output();
+    let _ = std::fs::remove_file(&list_path);
+
+    let output = output.context("failed to run ffmpeg")?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "ffmpeg failed to export the replay: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(used.len())
+}
+
+pub(crate) fn absolute(path: &Path) -> Result<PathBuf> {
+    if path.is_absolute() {
+        return Ok(path.to_path_buf());
+    }
+    Ok(std::env::current_dir()?.join(path))
+}
+
+/// One concat demuxer entry: the file and its trim points
+pub(crate) fn concat_entry(path: &Path, inpoint: f64, outpoint: f64) -> String {
+    // Quoted paths are taken literally except for the quote itself; forward
+    // slashes work on Windows too
+    let path = path.to_string_lossy().replace('\\', "/").replace('\'', r"'\''");
+    format!("file '{}'\ninpoint {:.3}\noutpoint {:.3}\n", path, inpoint, outpoint)
+}
@@ -0,0 +1,63 @@
+//! Process-wide Ctrl+C handling
+//!
+//! `ctrlc` accepts one handler per process, so components don't install their
+//! own. Each asks [`install_shutdown_handler`] for a flag instead; the single
+//! handler sets every flag handed out, so several components can run in one
+//! process and a component can be started again after it stopped.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Once, PoisonError, Weak};
+use tracing::{info, warn};
+
+/// Flags handed out so far (dropped ones are pruned on the next call)
+static FLAGS: Mutex<Vec<Weak<AtomicBool>>> = Mutex::new(Vec::new());
+
+static INSTALL: Once = Once::new();
+
+/// A new flag that is set on Ctrl+C or a termination signal
+///
+/// The handler is installed on the first call. If something else in the
+/// process already installed one, the flag is still returned but only
+/// [`request_shutdown`] sets it.
+pub fn install_shutdown_handler() -> Arc<AtomicBool> {
+    INSTALL.call_once(|| {
+        if let Err(e) = ctrlc::try_set_handler(|| {
+            info!("received shutdown signal");
+            request_shutdown();
+        }) {
+            warn!("Ctrl+C handler not installed: {}", e);
+        }
+    });
+
+    let flag = Arc::new(AtomicBool::new(false));
+    let mut flags = FLAGS.lock().unwrap_or_else(PoisonError::into_inner);
+    flags.retain(|f| f.strong_count() > 0);
+    flags.push(Arc::downgrade(&flag));
+    flag
+}
+
+/// Set every flag returned by [`install_shutdown_handler`], as Ctrl+C does
+pub fn request_shutdown() {
+    let flags = FLAGS.lock().unwrap_or_else(PoisonError::into_inner);
+    for flag in flags.iter().filter_map(Weak::upgrade) {
+        flag.store(true, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_shutdown_sets_every_flag() {
+        let first = install_shutdown_handler();
+        let second = install_shutdown_handler();
+        assert!(!first.load(Ordering::SeqCst));
+        assert!(!second.load(Ordering::SeqCst));
+
+        request_shutdown();
+
+        assert!(first.load(Ordering::SeqCst));
+        assert!(second.load(Ordering::SeqCst));
+    }
+}
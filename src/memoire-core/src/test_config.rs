@@ -4,9 +4,13 @@
 //! all Memoire components simultaneously during testing.
 
 use anyhow::{Context, Result};
+use memoire_processing::EncoderPreset;
 use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
 use std::path::{Path, PathBuf};
 
+use crate::config::MonitorSelection;
+
 /// Main test configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TestConfig {
@@ -39,6 +43,9 @@ pub struct GeneralConfig {
     /// Automatically download models if missing
     #[serde(default = "default_true")]
     pub auto_download_models: bool,
+
+    /// SQLite journal mode (wal, delete or truncate; None = MEMOIRE_JOURNAL_MODE or wal)
+    pub journal_mode: Option<memoire_db::JournalMode>,
 }
 
 /// Recording configuration
@@ -55,6 +62,40 @@ pub struct RecordConfig {
     /// Video chunk duration in seconds (default 300 = 5 minutes)
     #[serde(default = "default_chunk_duration")]
     pub chunk_duration_secs: u64,
+
+    /// Video quality as CRF/CQ (0-51, lower = better)
+    #[serde(default = "default_quality")]
+    pub quality: u32,
+
+    /// Encoder preset (e.g., "fast", "medium", "p4")
+    #[serde(default)]
+    pub preset: EncoderPreset,
+
+    /// Draw the mouse cursor onto captured frames
+    #[serde(default = "default_true")]
+    pub capture_cursor: bool,
+
+    /// Frames buffered before a database write (default 30)
+    #[serde(default = "default_frame_batch_size")]
+    pub frame_batch_size: usize,
+
+    /// Maximum seconds between database writes of buffered frames (default 5)
+    #[serde(default = "default_frame_flush_interval")]
+    pub frame_flush_interval_secs: u64,
+
+    /// Seconds without input after which frames are marked idle (default 120)
+    #[serde(default = "default_idle_threshold")]
+    pub idle_threshold_secs: u64,
+
+    /// Prune the oldest recordings above this many GB of database and media (None = no limit)
+    pub max_storage_gb: Option<f64>,
+
+    /// Stop recording when less than this many GB are free on the data drive (None = never)
+    pub min_free_gb: Option<f64>,
+
+    /// Monitors to record and their aliases
+    #[serde(default)]
+    pub monitors: MonitorSelection,
 }
 
 /// OCR indexing configuration
@@ -64,8 +105,35 @@ pub struct IndexConfig {
     #[serde(default = "default_ocr_fps")]
     pub ocr_fps: u32,
 
-    /// OCR language code (e.g., "en-US")
+    /// OCR language code (e.g., "en-US"), or a comma-separated list such as "en-US,ja-JP"
     pub ocr_language: Option<String>,
+
+    /// Combine lines from every configured OCR language instead of keeping the best
+    #[serde(default)]
+    pub merge_languages: bool,
+
+    /// Downscale frames to this many pixels on their longest side before OCR
+    pub ocr_downscale: Option<u32>,
+
+    /// Frames fetched per OCR batch (default 30)
+    #[serde(default = "default_ocr_batch_size")]
+    pub batch_size: usize,
+
+    /// Concurrent FFmpeg frame extractions (default 4)
+    #[serde(default = "default_max_concurrent_extractions")]
+    pub max_concurrent_extractions: usize,
+
+    /// Reuse the previous frame's OCR text when nearly identical
+    #[serde(default)]
+    pub dedup_text: bool,
+
+    /// Max character edits still treated as identical text (default 8)
+    #[serde(default = "default_text_dedup_distance")]
+    pub dedup_distance: usize,
+
+    /// Reuse existing OCR for frames whose perceptual hash was already indexed
+    #[serde(default)]
+    pub ocr_hash_cache: bool,
 }
 
 /// Audio capture and transcription configuration
@@ -77,14 +145,52 @@ pub struct AudioConfig {
 
     /// Specific audio device name (None = default device)
     pub device: Option<String>,
+
+    /// Audio chunks fetched per transcription batch (default 5)
+    #[serde(default = "default_audio_batch_size")]
+    pub batch_size: i64,
+
+    /// Threads used for CPU inference (default 4)
+    #[serde(default = "default_stt_threads")]
+    pub stt_threads: usize,
+
+    /// GPU device for transcription (None = CPU only)
+    pub gpu_device: Option<i32>,
+
+    /// Execution providers to try in order when a GPU device is set
+    /// (tensorrt, cuda, directml, cpu; None = all in that order)
+    pub stt_providers: Option<Vec<memoire_stt::ExecutionProvider>>,
+
+    /// Raise quiet recordings to a common level before transcription
+    #[serde(default)]
+    pub normalize_audio: bool,
+
+    /// RMS level normalization aims for (default 0.1, about -20 dBFS)
+    #[serde(default = "default_normalize_target_rms")]
+    pub normalize_target_rms: f32,
+
+    /// Resampler for audio not recorded at 16kHz (fast or high; 8kHz sources always use sinc)
+    #[serde(default)]
+    pub resample_quality: memoire_stt::ResampleQuality,
+
+    /// Delete audio files older than this many days, keeping transcriptions (None = keep forever)
+    pub retention_days: Option<u32>,
 }
 
 /// Web viewer configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ViewerConfig {
+    /// Address to bind (default 127.0.0.1, local only)
+    #[serde(default = "default_viewer_host")]
+    pub host: IpAddr,
+
     /// Port for web viewer HTTP server
     #[serde(default = "default_viewer_port")]
     pub port: u16,
+
+    /// Memory budget in MiB for cached frame images (default 64)
+    #[serde(default = "default_frame_cache_mb")]
+    pub frame_cache_mb: usize,
 }
 
 /// Profile for overriding settings
@@ -99,8 +205,20 @@ pub struct ProfileConfig {
 // Default value functions
 fn default_test_fps() -> f64 { 0.25 }
 fn default_ocr_fps() -> u32 { 10 }
+fn default_viewer_host() -> IpAddr { memoire_web::DEFAULT_HOST }
 fn default_viewer_port() -> u16 { 8080 }
+fn default_frame_cache_mb() -> usize { memoire_web::DEFAULT_FRAME_CACHE_BYTES / (1024 * 1024) }
 fn default_chunk_duration() -> u64 { 300 }
+fn default_quality() -> u32 { memoire_processing::encoder::DEFAULT_QUALITY }
+fn default_frame_batch_size() -> usize { crate::recorder::DEFAULT_FRAME_BATCH_SIZE }
+fn default_frame_flush_interval() -> u64 { crate::recorder::DEFAULT_FRAME_FLUSH_INTERVAL_SECS }
+fn default_idle_threshold() -> u64 { crate::recorder::DEFAULT_IDLE_THRESHOLD_SECS }
+fn default_ocr_batch_size() -> usize { crate::indexer::DEFAULT_OCR_BATCH_SIZE }
+fn default_max_concurrent_extractions() -> usize { crate::indexer::DEFAULT_MAX_CONCURRENT_EXTRACTIONS }
+fn default_text_dedup_distance() -> usize { crate::indexer::DEFAULT_TEXT_DEDUP_DISTANCE }
+fn default_audio_batch_size() -> i64 { crate::audio_indexer::DEFAULT_AUDIO_BATCH_SIZE }
+fn default_stt_threads() -> usize { memoire_stt::DEFAULT_NUM_THREADS }
+fn default_normalize_target_rms() -> f32 { memoire_stt::DEFAULT_NORMALIZE_TARGET_RMS }
 fn default_true() -> bool { true }
 
 impl Default for GeneralConfig {
@@ -108,6 +226,7 @@ impl Default for GeneralConfig {
         Self {
             data_dir: None,
             auto_download_models: true,
+            journal_mode: None,
         }
     }
 }
@@ -118,6 +237,15 @@ impl Default for RecordConfig {
             fps: 0.25,
             use_hw_encoding: true,
             chunk_duration_secs: 300,
+            quality: default_quality(),
+            preset: EncoderPreset::default(),
+            capture_cursor: true,
+            frame_batch_size: default_frame_batch_size(),
+            frame_flush_interval_secs: default_frame_flush_interval(),
+            idle_threshold_secs: default_idle_threshold(),
+            max_storage_gb: None,
+            min_free_gb: None,
+            monitors: MonitorSelection::default(),
         }
     }
 }
@@ -127,6 +255,13 @@ impl Default for IndexConfig {
         Self {
             ocr_fps: 10,
             ocr_language: None,
+            merge_languages: false,
+            ocr_downscale: None,
+            batch_size: default_ocr_batch_size(),
+            max_concurrent_extractions: default_max_concurrent_extractions(),
+            dedup_text: false,
+            dedup_distance: default_text_dedup_distance(),
+            ocr_hash_cache: false,
         }
     }
 }
@@ -136,13 +271,25 @@ impl Default for AudioConfig {
         Self {
             enabled: true,
             device: None,
+            batch_size: default_audio_batch_size(),
+            stt_threads: default_stt_threads(),
+            gpu_device: None,
+            stt_providers: None,
+            normalize_audio: false,
+            normalize_target_rms: default_normalize_target_rms(),
+            resample_quality: memoire_stt::ResampleQuality::default(),
+            retention_days: None,
         }
     }
 }
 
 impl Default for ViewerConfig {
     fn default() -> Self {
-        Self { port: 8080 }
+        Self {
+            host: default_viewer_host(),
+            port: 8080,
+            frame_cache_mb: default_frame_cache_mb(),
+        }
     }
 }
 
@@ -195,16 +342,13 @@ impl TestConfig {
 
     /// Resolve data directory with fallback to default
     ///
-    /// Returns the configured data directory or defaults to:
-    /// %LOCALAPPDATA%\Memoire\test-data on Windows
+    /// Returns the configured data directory or defaults to `test-data` inside
+    /// the regular data directory (`MEMOIRE_DATA_DIR` or %LOCALAPPDATA%\Memoire)
     pub fn resolve_data_dir(&self) -> PathBuf {
         if let Some(ref dir) = self.general.data_dir {
             PathBuf::from(dir)
         } else {
-            dirs::data_local_dir()
-                .unwrap_or_else(|| PathBuf::from("."))
-                .join("Memoire")
-                .join("test-data")
+            crate::config::resolve_data_dir(None).join("test-data")
         }
     }
 }
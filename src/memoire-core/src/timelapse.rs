@@ -0,0 +1,183 @@
+//! Day timelapse export (`memoire timelapse`)
+//!
+//! Joins every finished chunk one monitor recorded on a day with FFmpeg's
+//! concat demuxer and plays them back sped up (`setpts`), resampled to the
+//! requested frame rate. Time between chunks, when nothing was recorded, is
+//! skipped. The output format follows the file extension: MP4, or a GIF with a
+//! generated palette.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use std::path::Path;
+use std::process::Stdio;
+use tracing::{debug, info, warn};
+
+use memoire_db::{Database, VideoChunk};
+use memoire_processing::ffmpeg::ffmpeg_command;
+
+use crate::replay::{absolute, concat_entry};
+
+/// Chunks fetched per query while listing the day
+const CHUNK_PAGE_SIZE: i64 = 500;
+
+/// Default playback speed (an 8 hour day lasts about 1.5 minutes)
+pub const DEFAULT_SPEED: f64 = 300.0;
+
+/// Widest GIF produced; larger frames are scaled down to keep the file small
+const GIF_MAX_WIDTH: u32 = 960;
+
+/// x264 CRF of an MP4 timelapse
+const TIMELAPSE_CRF: u32 = 23;
+
+/// Finished chunks `monitor` recorded on the local day `date`, oldest first
+///
+/// Chunks come from [`memoire_db::get_chunks_paginated`]. When several
+/// monitors recorded that day, `monitor` (a device name, case-insensitive)
+/// must pick one. Chunks still being recorded are skipped.
+pub fn plan(db: &Database, date: NaiveDate, monitor: Option<&str>) -> Result<Vec<VideoChunk>> {
+    let conn = db.connection();
+    let (start, end) = local_day(date);
+
+    let mut listed = Vec::new();
+    let mut offset = 0;
+    loop {
+        let page = memoire_db::get_chunks_paginated(conn, CHUNK_PAGE_SIZE, offset, None, Some(start), Some(end))?;
+        let done = (page.len() as i64) < CHUNK_PAGE_SIZE;
+        listed.extend(page);
+        if done {
+            break;
+        }
+        offset += CHUNK_PAGE_SIZE;
+    }
+
+    match monitor {
+        Some(monitor) => listed.retain(|c| c.device_name.eq_ignore_ascii_case(monitor)),
+        None => {
+            let mut devices: Vec<&str> = listed.iter().map(|c| c.device_name.as_str()).collect();
+            devices.sort_unstable();
+            devices.dedup();
+            if devices.len() > 1 {
+                return Err(anyhow::anyhow!(
+                    "{} monitors were recorded on {} ({}), pick one with --monitor",
+                    devices.len(),
+                    date,
+                    devices.join(", ")
+                ));
+            }
+        }
+    }
+
+    let mut chunks = Vec::new();
+    for listed in listed.iter().filter(|c| c.frame_count > 0) {
+        let Some(chunk) = memoire_db::get_video_chunk(conn, listed.id)? else {
+            continue;
+        };
+        if chunk.duration_secs.is_none() {
+            warn!("chunk {} is still being recorded, skipping", chunk.id);
+            continue;
+        }
+        chunks.push(chunk);
+    }
+
+    chunks.sort_by_key(|c| c.created_at);
+    Ok(chunks)
+}
+
+/// Join `chunks` into a timelapse at `out`, `speed` times faster than recorded
+///
+/// Chunks whose file is missing are skipped. Returns the number of chunks used.
+pub fn export(chunks: &[VideoChunk], data_dir: &Path, out: &Path, speed: f64, fps: f64) -> Result<usize> {
+    let mut list = String::new();
+    let mut used = Vec::new();
+
+    for chunk in chunks {
+        let path = absolute(&data_dir.join(&chunk.file_path))?;
+        if !path.exists() {
+            warn!("video file not found: {}, skipping", path.display());
+            continue;
+        }
+        list.push_str(&concat_entry(&path, 0.0, chunk.duration_secs.unwrap_or(0.0)));
+        used.push(chunk);
+    }
+
+    let Some(first) = used.first() else {
+        return Err(anyhow::anyhow!("no finished video chunks on this day"));
+    };
+
+    let list_path = std::env::temp_dir().join(format!("memoire-timelapse-{}.txt", std::process::id()));
+    std::fs::write(&list_path, &list).context("failed to write the FFmpeg concat list")?;
+    debug!("concat list:\n{}", list);
+
+    let gif = out
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("gif"));
+    let filter = filter_graph(first.width.zip(first.height), speed, fps, gif);
+
+    let mut command = ffmpeg_command();
+    command
+        .args(["-y", "-hide_banner", "-loglevel", "error"])
+        .args(["-f", "concat", "-safe", "0", "-i"])
+        .arg(&list_path)
+        .arg("-filter_complex")
+        .arg(&filter)
+        .arg("-an");
+    if !gif {
+        command
+            .args(["-c:v", "libx264", "-preset", "veryfast", "-crf"])
+            .arg(TIMELAPSE_CRF.to_string())
+            .args(["-pix_fmt", "yuv420p", "-movflags", "+faststart"]);
+    }
+
+    info!("exporting {} chunk(s) at {}x to {}", used.len(), speed, out.display());
+    let output = command.arg(out).stdin(Stdio::null()).output();
+    let _ = std::fs::remove_file(&list_path);
+
+    let output = output.context("failed to run ffmpeg")?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "ffmpeg failed to export the timelapse: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(used.len())
+}
+
+/// FFmpeg filter graph speeding the video up and fitting every chunk into
+/// the first one's size
+fn filter_graph(size: Option<(u32, u32)>, speed: f64, fps: f64, gif: bool) -> String {
+    let mut filter = format!("setpts=PTS/{},fps={}", speed, fps);
+
+    // Letterbox into the first chunk's size so resolution changes join cleanly
+    if let Some((width, height)) = size {
+        let (mut width, mut height) = (width & !1, height & !1);
+        if gif && width > GIF_MAX_WIDTH {
+            height = (height as u64 * GIF_MAX_WIDTH as u64 / width as u64) as u32 & !1;
+            width = GIF_MAX_WIDTH;
+        }
+        filter.push_str(&format!(
+            ",scale={w}:{h}:force_original_aspect_ratio=decrease,pad={w}:{h}:(ow-iw)/2:(oh-ih)/2,setsar=1",
+            w = width,
+            h = height
+        ));
+    }
+
+    if gif {
+        // A palette made from the video itself looks far better than the default one
+        filter.push_str(",split[a][b];[a]palettegen[p];[b][p]paletteuse");
+    }
+    filter
+}
+
+/// Start and end of a local calendar day in UTC
+fn local_day(date: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+    let at = |time: NaiveTime| {
+        Local
+            .from_local_datetime(&date.and_time(time))
+            .earliest()
+            .map(|dt| dt.with_timezone(&Utc))
+            .unwrap_or_else(|| Utc.from_utc_datetime(&date.and_time(time)))
+    };
+    let last_second = NaiveTime::from_hms_opt(23, 59, 59).unwrap_or(NaiveTime::MIN);
+    (at(NaiveTime::MIN), at(last_second))
+}
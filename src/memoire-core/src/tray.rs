@@ -1,6 +1,8 @@
 //! System tray interface for Memoire
 
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread;
@@ -13,15 +15,74 @@ use tray_icon::{
 use tracing::{debug, error, info, warn};
 
 use crate::config::Config;
+use crate::notifications::{notify, Notice};
 use crate::recorder::Recorder;
 
 /// Menu item IDs
 const ID_START_STOP: &str = "start_stop";
+const ID_PAUSE: &str = "pause";
 const ID_VIDEO_TOGGLE: &str = "video_toggle";
 const ID_AUDIO_TOGGLE: &str = "audio_toggle";
+const ID_SAVE_CLIP: &str = "save_clip";
 const ID_STATUS: &str = "status";
 const ID_EXIT: &str = "exit";
 
+/// File in the data directory holding the tray's remembered settings
+const PREFERENCES_FILE: &str = "tray-preferences.json";
+
+/// Tray settings remembered across restarts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrayPreferences {
+    pub video_enabled: bool,
+    pub audio_enabled: bool,
+    /// Last-used recording framerate
+    pub fps: f64,
+    /// Last-used hardware encoding setting
+    pub use_hw_encoding: bool,
+}
+
+impl Default for TrayPreferences {
+    fn default() -> Self {
+        let config = Config::default();
+        Self {
+            video_enabled: true,
+            audio_enabled: false,
+            fps: config.fps,
+            use_hw_encoding: config.use_hw_encoding,
+        }
+    }
+}
+
+impl TrayPreferences {
+    /// Location of the preferences file in a data directory
+    pub fn path(data_dir: &Path) -> PathBuf {
+        data_dir.join(PREFERENCES_FILE)
+    }
+
+    /// Load preferences, falling back to defaults when missing or unreadable
+    pub fn load(data_dir: &Path) -> Self {
+        let path = Self::path(data_dir);
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("ignoring invalid tray preferences {:?}: {}", path, e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Save preferences (written to a temporary file first so a crash can't truncate them)
+    pub fn save(&self, data_dir: &Path) -> Result<()> {
+        let path = Self::path(data_dir);
+        let tmp_path = path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, serde_json::to_string_pretty(self)?)?;
+        std::fs::rename(&tmp_path, &path)?;
+        debug!("saved tray preferences to {:?}", path);
+        Ok(())
+    }
+}
+
 /// Recording state shared between tray and recorder
 pub struct RecordingState {
     pub is_recording: AtomicBool,
@@ -29,16 +90,29 @@ pub struct RecordingState {
     pub video_enabled: AtomicBool,
     pub audio_enabled: AtomicBool,
     pub should_exit: AtomicBool,
+    /// Capture suspended without closing the current chunks
+    pub paused: AtomicBool,
+    /// Save Clip was clicked (clip mode only)
+    pub clip_requested: AtomicBool,
 }
 
 impl Default for RecordingState {
     fn default() -> Self {
+        Self::from_preferences(&TrayPreferences::default())
+    }
+}
+
+impl RecordingState {
+    /// Initial state with the remembered capture toggles
+    pub fn from_preferences(prefs: &TrayPreferences) -> Self {
         Self {
             is_recording: AtomicBool::new(false),
             recorder_running: AtomicBool::new(false),
-            video_enabled: AtomicBool::new(true),
-            audio_enabled: AtomicBool::new(false),
+            video_enabled: AtomicBool::new(prefs.video_enabled),
+            audio_enabled: AtomicBool::new(prefs.audio_enabled),
             should_exit: AtomicBool::new(false),
+            paused: AtomicBool::new(false),
+            clip_requested: AtomicBool::new(false),
         }
     }
 }
@@ -50,10 +124,34 @@ pub struct TrayApp {
 }
 
 impl TrayApp {
-    pub fn new(config: Config) -> Self {
+    /// Create the tray, restoring settings saved in the data directory.
+    ///
+    /// `fps` and `use_hw_encoding` are the values given on the command line:
+    /// when set they replace the remembered ones, otherwise the remembered
+    /// ones apply.
+    pub fn new(mut config: Config, fps: Option<f64>, use_hw_encoding: Option<bool>) -> Self {
+        let mut prefs = TrayPreferences::load(&config.data_dir);
+
+        if let Some(fps) = fps {
+            prefs.fps = fps;
+        }
+        if let Some(use_hw_encoding) = use_hw_encoding {
+            prefs.use_hw_encoding = use_hw_encoding;
+        }
+        config.fps = prefs.fps;
+        config.use_hw_encoding = prefs.use_hw_encoding;
+
+        info!(
+            "tray settings: fps {}, hardware encoding: {}, video: {}, audio: {}",
+            config.fps, config.use_hw_encoding, prefs.video_enabled, prefs.audio_enabled
+        );
+        if let Err(e) = prefs.save(&config.data_dir) {
+            warn!("failed to save tray preferences: {}", e);
+        }
+
         Self {
             config,
-            state: Arc::new(RecordingState::default()),
+            state: Arc::new(RecordingState::from_preferences(&prefs)),
         }
     }
 
@@ -111,14 +209,26 @@ impl TrayApp {
         let start_stop = MenuItem::with_id(ID_START_STOP, "Start Recording", true, None);
         menu.append(&start_stop)?;
 
+        // Keeps the recorder running with its chunks open
+        let pause = CheckMenuItem::with_id(ID_PAUSE, "Pause Recording", true, false, None);
+        menu.append(&pause)?;
+
+        // Same as the clip hotkey
+        if self.config.clip_buffer_secs.is_some() {
+            let label = format!("Save Clip ({})", self.config.clip_hotkey);
+            let save_clip = MenuItem::with_id(ID_SAVE_CLIP, label, true, None);
+            menu.append(&save_clip)?;
+        }
+
         menu.append(&PredefinedMenuItem::separator())?;
 
-        // Video toggle (checked by default)
-        let video_toggle = CheckMenuItem::with_id(ID_VIDEO_TOGGLE, "Video Capture", true, true, None);
+        // Capture toggles start from the remembered preferences
+        let video_enabled = self.state.video_enabled.load(Ordering::SeqCst);
+        let video_toggle = CheckMenuItem::with_id(ID_VIDEO_TOGGLE, "Video Capture", true, video_enabled, None);
         menu.append(&video_toggle)?;
 
-        // Audio toggle (unchecked - not yet implemented)
-        let audio_toggle = CheckMenuItem::with_id(ID_AUDIO_TOGGLE, "Audio Capture (Phase 3)", true, false, None);
+        let audio_enabled = self.state.audio_enabled.load(Ordering::SeqCst);
+        let audio_toggle = CheckMenuItem::with_id(ID_AUDIO_TOGGLE, "Audio Capture", true, audio_enabled, None);
         menu.append(&audio_toggle)?;
 
         menu.append(&PredefinedMenuItem::separator())?;
@@ -161,24 +271,45 @@ fn handle_menu_event(event: &MenuEvent, state: &Arc<RecordingState>, config: &Co
                 let state_clone = state.clone();
                 let config_clone = config.clone();
                 thread::spawn(move || {
-                    if let Err(e) = run_recorder(&state_clone, config_clone) {
-                        error!("recorder error: {}", e);
+                    match run_recorder(&state_clone, config_clone) {
+                        Ok(true) => notify(Notice::DiskLow),
+                        Ok(false) => notify(Notice::RecordingStopped),
+                        Err(e) => {
+                            error!("recorder error: {}", e);
+                            notify(Notice::RecorderFailed(e.to_string()));
+                        }
                     }
                     info!("recorder thread finished");
                     state_clone.is_recording.store(false, Ordering::SeqCst);
                     state_clone.recorder_running.store(false, Ordering::SeqCst);
                 });
+                notify(Notice::RecordingStarted);
+            }
+        }
+        ID_PAUSE => {
+            let paused = !state.paused.load(Ordering::SeqCst);
+            state.paused.store(paused, Ordering::SeqCst);
+            info!("recording {} via tray", if paused { "paused" } else { "resumed" });
+        }
+        ID_SAVE_CLIP => {
+            if state.recorder_running.load(Ordering::SeqCst) {
+                info!("saving clip via tray");
+                state.clip_requested.store(true, Ordering::SeqCst);
+            } else {
+                info!("nothing to save, recording is stopped");
             }
         }
         ID_VIDEO_TOGGLE => {
             let current = state.video_enabled.load(Ordering::SeqCst);
             state.video_enabled.store(!current, Ordering::SeqCst);
             info!("video capture: {}", if !current { "enabled" } else { "disabled" });
+            save_preferences(state, config);
         }
         ID_AUDIO_TOGGLE => {
             let current = state.audio_enabled.load(Ordering::SeqCst);
             state.audio_enabled.store(!current, Ordering::SeqCst);
-            info!("audio capture: {}", if !current { "enabled" } else { "disabled" });
+            info!("audio capture: {} (applies from the next recording)", if !current { "enabled" } else { "disabled" });
+            save_preferences(state, config);
         }
         ID_EXIT => {
             info!("exit requested");
@@ -208,30 +339,55 @@ fn handle_menu_event(event: &MenuEvent, state: &Arc<RecordingState>, config: &Co
     }
 }
 
-fn run_recorder(state: &Arc<RecordingState>, config: Config) -> Result<()> {
-    // Create running flag that mirrors the state
-    let running = Arc::new(AtomicBool::new(true));
-    let running_clone = running.clone();
+/// Remember the current toggles and recording settings
+fn save_preferences(state: &RecordingState, config: &Config) {
+    let prefs = TrayPreferences {
+        video_enabled: state.video_enabled.load(Ordering::SeqCst),
+        audio_enabled: state.audio_enabled.load(Ordering::SeqCst),
+        fps: config.fps,
+        use_hw_encoding: config.use_hw_encoding,
+    };
+    if let Err(e) = prefs.save(&config.data_dir) {
+        warn!("failed to save tray preferences: {}", e);
+    }
+}
+
+/// Record until stopped from the menu; returns whether recording stopped
+/// early because the disk was nearly full
+fn run_recorder(state: &Arc<RecordingState>, mut config: Config) -> Result<bool> {
+    config.record_audio = state.audio_enabled.load(Ordering::SeqCst);
+
+    let mut recorder = Recorder::new(config)?;
+    let clip_trigger = recorder.clip_trigger();
+    let disk_low = recorder.disk_low_flag();
+    let paused = recorder.pause_flag();
+
+    // Shutdown flag set when recording is stopped from the menu
+    let shutdown = Arc::new(AtomicBool::new(false));
+    let shutdown_clone = shutdown.clone();
     let state_clone = state.clone();
 
     // Monitor state changes
     thread::spawn(move || {
-        while running_clone.load(Ordering::SeqCst) {
+        while !shutdown_clone.load(Ordering::SeqCst) {
             if !state_clone.is_recording.load(Ordering::SeqCst) {
-                running_clone.store(false, Ordering::SeqCst);
+                shutdown_clone.store(true, Ordering::SeqCst);
                 break;
             }
+            if state_clone.clip_requested.swap(false, Ordering::SeqCst) {
+                clip_trigger.store(true, Ordering::SeqCst);
+            }
+            paused.store(state_clone.paused.load(Ordering::SeqCst), Ordering::SeqCst);
             thread::sleep(Duration::from_millis(100));
         }
     });
 
-    let mut recorder = Recorder::new(config)?;
-    recorder.run(running)?;
+    recorder.run(shutdown)?;
 
     // Recorder.run() returns after finalizing all chunks
     info!("recorder stopped and finalized");
 
-    Ok(())
+    Ok(disk_low.load(Ordering::SeqCst))
 }
 
 /// Create a simple colored icon
@@ -0,0 +1,304 @@
+//! High-level read-only search API for embedding Memoire
+//!
+//! Wraps a read-only connection so plugins and desktop frontends can query the
+//! database without handling raw connections or FTS5 query syntax.
+
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+use std::path::Path;
+
+use crate::error::Result;
+use crate::queries::{self, MergedSearch, FUZZY_CANDIDATE_LIMIT};
+use crate::schema::*;
+
+/// Filters applied to [`SearchClient::search`]
+#[derive(Debug, Clone)]
+pub struct SearchFilters {
+    /// How query terms are matched
+    pub mode: SearchMode,
+    /// Include OCR text from screen frames
+    pub include_ocr: bool,
+    /// Include audio transcriptions
+    pub include_audio: bool,
+    /// Only results at or after this time
+    pub start: Option<DateTime<Utc>>,
+    /// Only results at or before this time
+    pub end: Option<DateTime<Utc>>,
+    /// Only OCR results from this application (case-insensitive)
+    pub app_name: Option<String>,
+    /// Skip OCR results from frames captured while the user was idle
+    pub focused_only: bool,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+impl Default for SearchFilters {
+    fn default() -> Self {
+        Self {
+            mode: SearchMode::default(),
+            include_ocr: true,
+            include_audio: true,
+            start: None,
+            end: None,
+            app_name: None,
+            focused_only: false,
+            limit: 50,
+            offset: 0,
+        }
+    }
+}
+
+/// Read-only search client over a Memoire database
+pub struct SearchClient {
+    conn: Connection,
+}
+
+impl SearchClient {
+    /// Open an existing database read-only
+    ///
+    /// Fails if the database was created by an older Memoire that has not
+    /// migrated it yet, since a read-only connection cannot apply migrations.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let db = crate::Database::open_read_only(path)?;
+        Ok(Self { conn: db.into_connection() })
+    }
+
+    /// Wrap an already open (and migrated) connection
+    pub fn from_connection(conn: Connection) -> Self {
+        Self { conn }
+    }
+
+    /// Search OCR text and transcriptions, newest first
+    ///
+    /// `query` is plain user text; it is sanitized for FTS5 according to
+    /// `filters.mode`.
+    pub fn search(&self, query: &str, filters: &SearchFilters) -> Result<Vec<SearchResult>> {
+        let fts_query = queries::sanitize_fts5_query_with_mode(query, filters.mode)?;
+
+        // Transcriptions have no application, so an app filter excludes them
+        let search = MergedSearch {
+            include_ocr: filters.include_ocr,
+            include_audio: filters.include_audio && filters.app_name.is_none(),
+            substring: filters.mode == SearchMode::Substring,
+            start: filters.start,
+            end: filters.end,
+            app_name: filters.app_name.as_deref(),
+            focused_only: filters.focused_only,
+        };
+
+        if filters.mode != SearchMode::Fuzzy {
+            return queries::search_merged(&self.conn, &fts_query, &search, filters.limit, filters.offset);
+        }
+
+        // Fuzzy matching happens here, so page through the newest candidates
+        // that pass it
+        Ok(queries::search_merged(&self.conn, &fts_query, &search, FUZZY_CANDIDATE_LIMIT, 0)?
+            .into_iter()
+            .filter(|result| match result {
+                SearchResult::Ocr { ocr, .. } => queries::fuzzy_matches(&ocr.text, query),
+                SearchResult::Audio { transcription, .. } => {
+                    queries::fuzzy_matches(&transcription.transcription, query)
+                }
+            })
+            .skip(filters.offset.max(0) as usize)
+            .take(filters.limit.max(0) as usize)
+            .collect())
+    }
+
+    /// Most recently captured frames with their OCR text, newest first
+    pub fn recent_frames(&self, limit: i64) -> Result<Vec<FrameWithOcr>> {
+        queries::get_recent_frames_with_ocr(&self.conn, limit)
+    }
+
+    /// Totals and indexing progress
+    pub fn stats(&self) -> Result<DatabaseStats> {
+        Ok(DatabaseStats {
+            total_chunks: queries::get_total_chunk_count(&self.conn, None, None, None)?,
+            total_frames: queries::get_frame_count(&self.conn)?,
+            ocr: queries::get_ocr_stats(&self.conn)?,
+            audio: queries::get_audio_stats(&self.conn)?,
+        })
+    }
+
+    /// Underlying connection, for queries not covered by the client
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Database;
+    use chrono::TimeZone;
+
+    fn at(minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, 1, 10, minute, 0).unwrap()
+    }
+
+    fn seeded_client() -> SearchClient {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+
+        let chunk_id = queries::insert_video_chunk(conn, &NewVideoChunk {
+            file_path: "videos/chunk.mp4".to_string(),
+            device_name: "monitor-0".to_string(),
+            width: Some(1920),
+            height: Some(1080),
+            fps: Some(1.0),
+        }).unwrap();
+
+        for (i, (app, text)) in [("Code", "cargo build failed"), ("Chrome", "cargo docs"), ("Code", "hello")]
+            .into_iter()
+            .enumerate()
+        {
+            let frame_id = queries::insert_frame(conn, &NewFrame {
+                video_chunk_id: chunk_id,
+                offset_index: i as i64,
+                capture_index: None,
+                timestamp: at(i as u32),
+                app_name: Some(app.to_string()),
+                window_name: None,
+                browser_url: None,
+                focused: true,
+                frame_hash: None,
+            }).unwrap();
+            queries::insert_ocr_text(conn, &NewOcrText {
+                frame_id,
+                text: text.to_string(),
+                text_json: None,
+                confidence: Some(0.9),
+                language: None,
+            }).unwrap();
+        }
+
+        let audio_id = queries::insert_audio_chunk(conn, &NewAudioChunk {
+            file_path: "audio/a.wav".to_string(),
+            device_name: Some("Microphone".to_string()),
+            is_input_device: Some(true),
+            timestamp: at(5),
+        }).unwrap();
+        queries::insert_audio_transcription(conn, &NewAudioTranscription {
+            audio_chunk_id: audio_id,
+            transcription: "the cargo shipment".to_string(),
+            timestamp: at(5),
+            speaker_id: None,
+            start_time: None,
+            end_time: None,
+            language: None,
+        }).unwrap();
+
+        SearchClient::from_connection(db.into_connection())
+    }
+
+    #[test]
+    fn test_search_with_filters() {
+        let client = seeded_client();
+
+        let all = client.search("cargo", &SearchFilters::default()).unwrap();
+        assert_eq!(all.len(), 3);
+        assert!(matches!(all[0], SearchResult::Audio { .. }));
+
+        let code_only = SearchFilters {
+            app_name: Some("code".to_string()),
+            ..SearchFilters::default()
+        };
+        let results = client.search("cargo", &code_only).unwrap();
+        assert_eq!(results.len(), 1);
+
+        let ocr_in_range = SearchFilters {
+            include_audio: false,
+            start: Some(at(1)),
+            end: Some(at(2)),
+            ..SearchFilters::default()
+        };
+        let results = client.search("cargo", &ocr_in_range).unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(matches!(&results[0], SearchResult::Ocr { ocr, .. } if ocr.text == "cargo docs"));
+
+        let fuzzy = SearchFilters {
+            mode: SearchMode::Fuzzy,
+            ..SearchFilters::default()
+        };
+        assert_eq!(client.search("cargp", &fuzzy).unwrap().len(), 3);
+
+        let substring = SearchFilters {
+            mode: SearchMode::Substring,
+            ..SearchFilters::default()
+        };
+        let results = client.search("arg", &substring).unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| matches!(r, SearchResult::Ocr { .. })));
+
+        // Special characters are sanitized rather than rejected by FTS5
+        assert!(client.search("cargo\" OR (", &SearchFilters::default()).is_ok());
+    }
+
+    #[test]
+    fn test_filters_apply_before_paging() {
+        let client = seeded_client();
+        let conn = client.connection();
+
+        // Bury the seeded matches under more newer candidates than a
+        // post-filtered search would look at
+        let chunk_id = queries::get_frame(conn, 1).unwrap().unwrap().video_chunk_id;
+        for i in 0..FUZZY_CANDIDATE_LIMIT {
+            let frame_id = queries::insert_frame(conn, &NewFrame {
+                video_chunk_id: chunk_id,
+                offset_index: 10 + i,
+                capture_index: None,
+                timestamp: at(30),
+                app_name: Some("Terminal".to_string()),
+                window_name: None,
+                browser_url: None,
+                focused: true,
+                frame_hash: None,
+            }).unwrap();
+            queries::insert_ocr_text(conn, &NewOcrText {
+                frame_id,
+                text: "cargo test".to_string(),
+                text_json: None,
+                confidence: Some(0.9),
+                language: None,
+            }).unwrap();
+        }
+
+        let early = SearchFilters {
+            end: Some(at(10)),
+            ..SearchFilters::default()
+        };
+        assert_eq!(client.search("cargo", &early).unwrap().len(), 3);
+
+        let code_only = SearchFilters {
+            app_name: Some("Code".to_string()),
+            ..SearchFilters::default()
+        };
+        let results = client.search("cargo", &code_only).unwrap();
+        assert!(matches!(&results[..], [SearchResult::Ocr { ocr, .. }] if ocr.text == "cargo build failed"));
+
+        let second_page = SearchFilters {
+            limit: 2,
+            offset: 1,
+            ..early
+        };
+        let results = client.search("cargo", &second_page).unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(matches!(&results[0], SearchResult::Ocr { frame, .. } if frame.timestamp == at(1)));
+    }
+
+    #[test]
+    fn test_recent_frames_and_stats() {
+        let client = seeded_client();
+
+        let recent = client.recent_frames(2).unwrap();
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].timestamp, at(2));
+        assert_eq!(recent[0].ocr_text.as_ref().unwrap().text, "hello");
+
+        let stats = client.stats().unwrap();
+        assert_eq!(stats.total_chunks, 1);
+        assert_eq!(stats.total_frames, 3);
+        assert_eq!(stats.ocr.frames_with_ocr, 3);
+        assert_eq!(stats.audio.total_chunks, 1);
+    }
+}
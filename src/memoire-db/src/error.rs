@@ -2,10 +2,20 @@
 
 use thiserror::Error;
 
+/// Result type of the database layer
+pub type Result<T, E = DatabaseError> = std::result::Result<T, E>;
+
 #[derive(Error, Debug)]
 pub enum DatabaseError {
     #[error("sqlite error: {0}")]
-    Sqlite(#[from] rusqlite::Error),
+    Sqlite(rusqlite::Error),
+
+    /// FTS5 rejected a MATCH expression
+    #[error("invalid search syntax: {0}")]
+    Fts5Syntax(String),
+
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
 
     #[error("migration error: {0}")]
     Migration(String),
@@ -15,4 +25,53 @@ pub enum DatabaseError {
 
     #[error("invalid data: {0}")]
     InvalidData(String),
+
+    /// A query parameter was rejected before reaching SQLite
+    #[error("invalid query: {0}")]
+    InvalidQuery(String),
+}
+
+impl DatabaseError {
+    /// Whether another connection held a lock for longer than the busy timeout
+    pub fn is_busy(&self) -> bool {
+        matches!(
+            self,
+            DatabaseError::Sqlite(rusqlite::Error::SqliteFailure(e, _))
+                if matches!(e.code, rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked)
+        )
+    }
+}
+
+impl From<rusqlite::Error> for DatabaseError {
+    fn from(err: rusqlite::Error) -> Self {
+        // FTS5 reports malformed queries as generic errors; only the message tells them apart
+        if let rusqlite::Error::SqliteFailure(_, Some(message)) = &err {
+            if message.starts_with("fts5: syntax error") || message == "unterminated string" {
+                return DatabaseError::Fts5Syntax(message.clone());
+            }
+        }
+        DatabaseError::Sqlite(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_fts5_syntax_errors_are_classified() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE VIRTUAL TABLE t USING fts5(body);").unwrap();
+
+        let err: DatabaseError = conn
+            .query_row("SELECT COUNT(*) FROM t WHERE t MATCH ?1", ["budget AND"], |row| row.get::<_, i64>(0))
+            .unwrap_err()
+            .into();
+        assert!(matches!(err, DatabaseError::Fts5Syntax(_)), "{:?}", err);
+
+        let err: DatabaseError = conn.execute("SELECT * FROM missing", []).unwrap_err().into();
+        assert!(matches!(err, DatabaseError::Sqlite(_)));
+        assert!(!err.is_busy());
+    }
 }
@@ -1,20 +1,116 @@
 //! memoire-db - Database layer for Memoire
 //!
 //! Handles SQLite database operations with FTS5 full-text search.
+//! Embedders that only need to read should use [`SearchClient`].
 
 mod schema;
-mod migrations;
+pub mod migrations;
 mod queries;
 mod error;
+mod client;
+mod sessions;
 
 pub use schema::*;
 pub use queries::*;
-pub use error::DatabaseError;
+pub use error::{DatabaseError, Result};
+pub use client::{SearchClient, SearchFilters};
+pub use sessions::{group_by_gap, ResultSession};
 
-use anyhow::Result;
-use rusqlite::Connection;
+use rusqlite::{Connection, OpenFlags, Transaction};
+use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::path::Path;
-use tracing::{info, debug};
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::{info, debug, warn};
+
+/// How long a connection waits for another one's write lock before failing
+/// with `SQLITE_BUSY`
+///
+/// WAL lets readers run alongside a writer but still allows only one writer
+/// at a time, so the recorder and the indexers queue behind each other.
+pub const BUSY_TIMEOUT: Duration = Duration::from_millis(5000);
+
+/// SQLite journal mode set when opening a database
+///
+/// WAL lets readers run alongside the writer, but needs shared memory that
+/// network filesystems and sync clients (OneDrive, Dropbox) don't provide
+/// reliably. The rollback journal modes work there at the cost of readers
+/// waiting for writes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JournalMode {
+    /// Write-ahead log (concurrent readers)
+    #[default]
+    Wal,
+    /// Rollback journal, deleted after each transaction
+    Delete,
+    /// Rollback journal, truncated after each transaction (fewer file deletions)
+    Truncate,
+}
+
+impl JournalMode {
+    /// All journal modes, default first
+    pub const ALL: [JournalMode; 3] = [JournalMode::Wal, JournalMode::Delete, JournalMode::Truncate];
+
+    /// Name used in configuration files, on the command line and in `PRAGMA journal_mode`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            JournalMode::Wal => "wal",
+            JournalMode::Delete => "delete",
+            JournalMode::Truncate => "truncate",
+        }
+    }
+}
+
+impl fmt::Display for JournalMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for JournalMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|m| m.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("unknown journal mode '{}' (expected wal, delete or truncate)", s))
+    }
+}
+
+/// Folder names of sync clients known to break WAL databases
+const SYNCED_FOLDERS: &[(&str, &str)] = &[
+    ("onedrive", "a OneDrive folder"),
+    ("dropbox", "a Dropbox folder"),
+    ("google drive", "a Google Drive folder"),
+    ("icloud drive", "an iCloud Drive folder"),
+    ("iclouddrive", "an iCloud Drive folder"),
+];
+
+/// Describe why `path` looks like a network or cloud-synced location, if it does
+///
+/// Recognizes UNC paths (`\\server\share`) and folders of common sync
+/// clients, such as `OneDrive - Contoso`. Mapped network drives look like
+/// local paths and are not detected.
+pub fn synced_location(path: &Path) -> Option<&'static str> {
+    let text = path.to_string_lossy();
+    let verbatim_unc = text.get(..8).is_some_and(|start| start.eq_ignore_ascii_case(r"\\?\UNC\"));
+    let unc = text.starts_with(r"\\") && !text.starts_with(r"\\?\") && !text.starts_with(r"\\.\");
+    if unc || verbatim_unc {
+        return Some("a network share");
+    }
+
+    // Split on both separators so Windows paths are recognized on every platform
+    text.split(['/', '\\']).find_map(|component| {
+        let name = component.to_lowercase();
+        SYNCED_FOLDERS
+            .iter()
+            .find(|(folder, _)| name.starts_with(folder))
+            .map(|(_, description)| *description)
+    })
+}
 
 /// Database connection wrapper with initialization
 pub struct Database {
@@ -22,20 +118,45 @@ pub struct Database {
 }
 
 impl Database {
-    /// Open or create database at the given path
+    /// Open or create database at the given path, in WAL mode
     pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::open_with_journal_mode(path, JournalMode::default())
+    }
+
+    /// Open or create database at the given path with a specific journal mode
+    ///
+    /// Every connection to a database should use the same mode: switching a
+    /// database out of WAL needs all other connections to be closed, and
+    /// opening it in WAL mode again switches it back. Warns when WAL is used
+    /// on a path that looks like a network share or synced folder.
+    pub fn open_with_journal_mode(path: impl AsRef<Path>, journal_mode: JournalMode) -> Result<Self> {
         let path = path.as_ref();
         info!("opening database at {:?}", path);
 
+        if journal_mode == JournalMode::Wal {
+            if let Some(location) = synced_location(path) {
+                warn!(
+                    "{:?} looks like {}; WAL mode can lock up or corrupt the database there, \
+                     consider journal mode 'delete'",
+                    path, location
+                );
+            }
+        }
+
         // Ensure parent directory exists
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
         }
 
         let conn = Connection::open(path)?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
 
-        // Enable WAL mode for concurrent reads
-        conn.execute_batch("PRAGMA journal_mode=WAL;")?;
+        // WAL by default for concurrent reads; SQLite reports the mode it
+        // actually switched to (filesystems without shared memory refuse WAL)
+        let applied: String = conn.pragma_update_and_check(None, "journal_mode", journal_mode.as_str(), |row| row.get(0))?;
+        if !applied.eq_ignore_ascii_case(journal_mode.as_str()) {
+            warn!("database at {:?} uses journal mode {} instead of {}", path, applied, journal_mode);
+        }
         conn.execute_batch("PRAGMA synchronous=NORMAL;")?;
         conn.execute_batch("PRAGMA foreign_keys=ON;")?;
 
@@ -45,10 +166,53 @@ impl Database {
         Ok(db)
     }
 
+    /// Open an existing database without applying migrations
+    /// (for inspecting or rolling back the schema)
+    pub fn open_unmigrated(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        info!("opening database at {:?} without migrations", path);
+
+        let conn = Connection::open(path)?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        conn.execute_batch("PRAGMA foreign_keys=ON;")?;
+
+        Ok(Self { conn })
+    }
+
+    /// Open an existing database read-only, e.g. a backup to inspect
+    ///
+    /// Neither migrations nor journal mode changes are applied, so the file
+    /// is left as it was (SQLite may still create `-wal`/`-shm` files next to
+    /// a WAL database). Fails if the schema is older than this build expects,
+    /// since a read-only connection cannot migrate it; writes fail with
+    /// "attempt to write a readonly database".
+    pub fn open_read_only(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        info!("opening database at {:?} read-only", path);
+
+        let conn = Connection::open_with_flags(
+            path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+
+        let version = migrations::current_version(&conn)?;
+        if version < migrations::SCHEMA_VERSION {
+            return Err(DatabaseError::Migration(format!(
+                "database schema is at version {}, expected {}; run memoire once to migrate it",
+                version,
+                migrations::SCHEMA_VERSION
+            )));
+        }
+
+        Ok(Self { conn })
+    }
+
     /// Open an in-memory database (for testing)
     pub fn open_in_memory() -> Result<Self> {
         debug!("opening in-memory database");
         let conn = Connection::open_in_memory()?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
         conn.execute_batch("PRAGMA foreign_keys=ON;")?;
 
         let mut db = Self { conn };
@@ -72,6 +236,46 @@ impl Database {
         self.conn
     }
 
+    /// Run `f` in a transaction, committing if it succeeds
+    ///
+    /// Any error returned by `f` rolls back everything it wrote. The query
+    /// functions take a `&Connection`, which the transaction derefs to:
+    ///
+    /// ```no_run
+    /// # use memoire_db::{Database, NewVideoChunk, NewFrame};
+    /// # fn demo(db: &Database, chunk: NewVideoChunk, frame: NewFrame) -> memoire_db::Result<()> {
+    /// db.transaction(|tx| {
+    ///     let chunk_id = memoire_db::insert_video_chunk(tx, &chunk)?;
+    ///     memoire_db::insert_frame(tx, &NewFrame { video_chunk_id: chunk_id, ..frame })?;
+    ///     Ok(chunk_id)
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// SQLite transactions don't nest, so batch helpers that open their own
+    /// (such as [`insert_frames_batch`]) fail inside `f`.
+    pub fn transaction<F, T>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&Transaction) -> Result<T>,
+    {
+        let tx = self.conn.unchecked_transaction()?;
+        // Dropping the transaction without committing rolls it back
+        let value = f(&tx)?;
+        tx.commit()?;
+        Ok(value)
+    }
+
+    /// Get the current schema version
+    pub fn schema_version(&self) -> Result<i64> {
+        migrations::current_version(&self.conn)
+    }
+
+    /// Migrate the schema up or down to a specific version
+    pub fn migrate_to(&self, version: i64) -> Result<()> {
+        migrations::migrate_to(&self.conn, version)
+    }
+
     /// Run all pending migrations
     fn run_migrations(&mut self) -> Result<()> {
         migrations::run_all(&self.conn)?;
@@ -88,4 +292,84 @@ mod tests {
         let db = Database::open_in_memory().unwrap();
         assert!(db.connection().is_autocommit());
     }
+
+    #[test]
+    fn test_journal_modes() {
+        for mode in JournalMode::ALL {
+            assert_eq!(mode.as_str().parse::<JournalMode>(), Ok(mode));
+
+            let path = std::env::temp_dir().join(format!("memoire-journal-{}-{}.db", mode, std::process::id()));
+            let _ = std::fs::remove_file(&path);
+            let db = Database::open_with_journal_mode(&path, mode).unwrap();
+            let applied: String = db.connection().query_row("PRAGMA journal_mode", [], |row| row.get(0)).unwrap();
+            assert_eq!(applied, mode.as_str());
+            drop(db);
+
+            for suffix in ["", "-wal", "-shm", "-journal"] {
+                let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+            }
+        }
+        assert_eq!(" DELETE ".parse::<JournalMode>(), Ok(JournalMode::Delete));
+        assert!("memory".parse::<JournalMode>().is_err());
+    }
+
+    #[test]
+    fn test_synced_location() {
+        assert_eq!(synced_location(Path::new(r"\\nas\home\Memoire")), Some("a network share"));
+        assert_eq!(
+            synced_location(Path::new(r"C:\Users\ana\OneDrive - Contoso\Documents\Memoire")),
+            Some("a OneDrive folder")
+        );
+        assert_eq!(synced_location(Path::new("/home/ana/Dropbox/memoire")), Some("a Dropbox folder"));
+        assert_eq!(synced_location(Path::new(r"C:\Users\ana\AppData\Local\Memoire")), None);
+        assert_eq!(synced_location(Path::new(r"\\?\C:\Memoire")), None);
+    }
+
+    #[test]
+    fn test_transaction_rolls_back_on_error() {
+        let db = Database::open_in_memory().unwrap();
+        let chunk = NewVideoChunk {
+            file_path: "videos/chunk_0.mp4".to_string(),
+            device_name: "monitor-0".to_string(),
+            width: Some(1920),
+            height: Some(1080),
+            fps: Some(1.0),
+        };
+
+        let result: Result<()> = db.transaction(|tx| {
+            insert_video_chunk(tx, &chunk)?;
+            Err(DatabaseError::InvalidData("frame rejected".to_string()))
+        });
+        assert!(matches!(result, Err(DatabaseError::InvalidData(_))));
+        assert!(db.connection().is_autocommit());
+        let chunks: i64 = db.connection().query_row("SELECT COUNT(*) FROM video_chunks", [], |row| row.get(0)).unwrap();
+        assert_eq!(chunks, 0);
+
+        let chunk_id = db.transaction(|tx| insert_video_chunk(tx, &chunk)).unwrap();
+        assert_eq!(get_video_chunk(db.connection(), chunk_id).unwrap().unwrap().file_path, chunk.file_path);
+    }
+
+    #[test]
+    fn test_open_read_only() {
+        let path = std::env::temp_dir().join(format!("memoire-read-only-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        drop(Database::open(&path).unwrap());
+        let modified = std::fs::metadata(&path).unwrap().modified().unwrap();
+
+        let db = Database::open_read_only(&path).unwrap();
+        assert_eq!(db.schema_version().unwrap(), migrations::SCHEMA_VERSION);
+        assert!(db.connection().execute("DELETE FROM frames", []).is_err());
+        drop(db);
+        assert_eq!(std::fs::metadata(&path).unwrap().modified().unwrap(), modified);
+
+        // An older schema is rejected rather than migrated
+        let old = Database::open_unmigrated(&path).unwrap();
+        old.connection().pragma_update(None, "user_version", 1).unwrap();
+        drop(old);
+        assert!(matches!(Database::open_read_only(&path), Err(DatabaseError::Migration(_))));
+
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
 }
@@ -1,38 +1,227 @@
 //! Database migrations
+//!
+//! Each migration has forward SQL and, where the change can be undone, rollback
+//! SQL. The current version lives in `PRAGMA user_version`; the
+//! `schema_version` table keeps a record of when each version was applied.
 
-use anyhow::Result;
 use rusqlite::Connection;
-use tracing::info;
+use tracing::{info, warn};
+
+use crate::error::{DatabaseError, Result};
 
 /// Current schema version
-const SCHEMA_VERSION: i64 = 3;
+pub const SCHEMA_VERSION: i64 = 16;
+
+/// A versioned schema change
+#[derive(Debug)]
+pub struct Migration {
+    pub version: i64,
+    pub description: &'static str,
+    up: &'static str,
+    /// Rollback SQL; `None` if the migration cannot be undone
+    down: Option<&'static str>,
+}
+
+impl Migration {
+    /// Whether this migration can be rolled back
+    pub fn reversible(&self) -> bool {
+        self.down.is_some()
+    }
+}
+
+/// All migrations, in version order
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "initial schema",
+        up: V1_UP,
+        // Dropping the initial schema would delete all data
+        down: None,
+    },
+    Migration {
+        version: 2,
+        description: "add video dimensions to video_chunks",
+        up: V2_UP,
+        down: Some(V2_DOWN),
+    },
+    Migration {
+        version: 3,
+        description: "add frame_hash for deduplication",
+        up: V3_UP,
+        down: Some(V3_DOWN),
+    },
+    Migration {
+        version: 4,
+        description: "add fps to video_chunks",
+        up: V4_UP,
+        down: Some(V4_DOWN),
+    },
+    Migration {
+        version: 5,
+        description: "add recording_stats",
+        up: V5_UP,
+        down: Some(V5_DOWN),
+    },
+    Migration {
+        version: 6,
+        description: "add ocr_lines",
+        up: V6_UP,
+        down: Some(V6_DOWN),
+    },
+    Migration {
+        version: 7,
+        description: "add same_as_frame_id to ocr_text",
+        up: V7_UP,
+        down: Some(V7_DOWN),
+    },
+    Migration {
+        version: 8,
+        description: "add encoded metadata to video_chunks",
+        up: V8_UP,
+        down: Some(V8_DOWN),
+    },
+    Migration {
+        version: 9,
+        description: "add language to ocr_text",
+        up: V9_UP,
+        down: Some(V9_DOWN),
+    },
+    Migration {
+        version: 10,
+        description: "add pruned_at to audio_chunks",
+        up: V10_UP,
+        down: Some(V10_DOWN),
+    },
+    Migration {
+        version: 11,
+        description: "index frames by chunk and offset",
+        up: V11_UP,
+        down: Some(V11_DOWN),
+    },
+    Migration {
+        version: 12,
+        description: "add language to audio_transcriptions",
+        up: V12_UP,
+        down: Some(V12_DOWN),
+    },
+    Migration {
+        version: 13,
+        description: "add trigram index for substring search",
+        up: V13_UP,
+        down: Some(V13_DOWN),
+    },
+    Migration {
+        version: 14,
+        description: "index frames by hash",
+        up: V14_UP,
+        down: Some(V14_DOWN),
+    },
+    Migration {
+        version: 15,
+        description: "add capture_index to frames",
+        up: V15_UP,
+        down: Some(V15_DOWN),
+    },
+    Migration {
+        version: 16,
+        description: "clear average frame hashes",
+        up: V16_UP,
+        down: Some(V16_DOWN),
+    },
+];
 
 /// Run all pending migrations
 pub fn run_all(conn: &Connection) -> Result<()> {
-    let current_version = get_schema_version(conn)?;
+    let current_version = current_version(conn)?;
 
-    if current_version < SCHEMA_VERSION {
-        info!("running migrations from v{} to v{}", current_version, SCHEMA_VERSION);
+    if current_version > SCHEMA_VERSION {
+        warn!(
+            "database schema v{} is newer than this build supports (v{}); \
+             roll it back with `memoire db-version --migrate-to {}` from the newer build",
+            current_version, SCHEMA_VERSION, SCHEMA_VERSION
+        );
+        return Ok(());
+    }
 
-        if current_version < 1 {
-            migrate_v1(conn)?;
-        }
+    migrate_to(conn, SCHEMA_VERSION)
+}
 
-        if current_version < 2 {
-            migrate_v2(conn)?;
-        }
+/// Migrate the schema up or down to `target`.
+///
+/// Each step runs in its own transaction, so a failing step leaves the
+/// database at the previous version (tables, FTS triggers and version number
+/// stay consistent). Rolling back past a migration without down SQL is an
+/// error and changes nothing.
+pub fn migrate_to(conn: &Connection, target: i64) -> Result<()> {
+    if !(0..=SCHEMA_VERSION).contains(&target) {
+        return Err(DatabaseError::Migration(format!(
+            "unknown schema version {} (latest is v{})",
+            target, SCHEMA_VERSION
+        )));
+    }
+
+    ensure_version_table(conn)?;
+    let current = current_version(conn)?;
+
+    if current > SCHEMA_VERSION {
+        return Err(DatabaseError::Migration(format!(
+            "database schema v{} is newer than this build (v{}); use the newer build to roll it back",
+            current, SCHEMA_VERSION
+        )));
+    }
 
-        if current_version < 3 {
-            migrate_v3(conn)?;
+    if target > current {
+        info!("running migrations from v{} to v{}", current, target);
+
+        for migration in MIGRATIONS.iter().filter(|m| m.version > current && m.version <= target) {
+            info!("applying migration v{}: {}", migration.version, migration.description);
+
+            let tx = conn.unchecked_transaction()?;
+            tx.execute_batch(migration.up)?;
+            tx.execute(
+                "INSERT OR REPLACE INTO schema_version (version, description) VALUES (?1, ?2)",
+                rusqlite::params![migration.version, migration.description],
+            )?;
+            set_schema_version(&tx, migration.version)?;
+            tx.commit()?;
+        }
+    } else if target < current {
+        let steps: Vec<&Migration> = MIGRATIONS
+            .iter()
+            .rev()
+            .filter(|m| m.version <= current && m.version > target)
+            .collect();
+
+        if let Some(blocking) = steps.iter().find(|m| !m.reversible()) {
+            return Err(DatabaseError::Migration(format!(
+                "migration v{} ({}) cannot be rolled back",
+                blocking.version, blocking.description
+            )));
         }
 
-        set_schema_version(conn, SCHEMA_VERSION)?;
+        info!("rolling back migrations from v{} to v{}", current, target);
+
+        for migration in steps {
+            info!("reverting migration v{}: {}", migration.version, migration.description);
+
+            let tx = conn.unchecked_transaction()?;
+            if let Some(down) = migration.down {
+                tx.execute_batch(down)?;
+            }
+            tx.execute(
+                "DELETE FROM schema_version WHERE version = ?1",
+                rusqlite::params![migration.version],
+            )?;
+            set_schema_version(&tx, migration.version - 1)?;
+            tx.commit()?;
+        }
     }
 
     Ok(())
 }
 
-fn get_schema_version(conn: &Connection) -> Result<i64> {
+/// Get the schema version of a database
+pub fn current_version(conn: &Connection) -> Result<i64> {
     let version: i64 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
     Ok(version)
 }
@@ -42,11 +231,30 @@ fn set_schema_version(conn: &Connection, version: i64) -> Result<()> {
     Ok(())
 }
 
-/// Initial schema (v1)
-fn migrate_v1(conn: &Connection) -> Result<()> {
-    info!("applying migration v1: initial schema");
-
+/// Create the `schema_version` history table, backfilling versions applied
+/// before it existed
+fn ensure_version_table(conn: &Connection) -> Result<()> {
     conn.execute_batch(r#"
+        CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            description TEXT NOT NULL,
+            applied_at TEXT DEFAULT (datetime('now'))
+        );
+    "#)?;
+
+    let current = current_version(conn)?;
+    for migration in MIGRATIONS.iter().filter(|m| m.version <= current) {
+        conn.execute(
+            "INSERT OR IGNORE INTO schema_version (version, description) VALUES (?1, ?2)",
+            rusqlite::params![migration.version, migration.description],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Initial schema (v1)
+const V1_UP: &str = r#"
         -- Video chunks (5-minute MP4 segments)
         CREATE TABLE IF NOT EXISTS video_chunks (
             id INTEGER PRIMARY KEY,
@@ -145,35 +353,368 @@ fn migrate_v1(conn: &Connection) -> Result<()> {
             INSERT INTO audio_fts(audio_fts, rowid, transcription) VALUES('delete', old.id, old.transcription);
             INSERT INTO audio_fts(rowid, transcription) VALUES (new.id, new.transcription);
         END;
-    "#)?;
-
-    Ok(())
-}
+"#;
 
 /// Migration v2: Add video dimensions to video_chunks
-fn migrate_v2(conn: &Connection) -> Result<()> {
-    info!("applying migration v2: add video dimensions to video_chunks");
-
-    conn.execute_batch(r#"
+const V2_UP: &str = r#"
         -- Add width and height columns for video metadata caching
         ALTER TABLE video_chunks ADD COLUMN width INTEGER;
         ALTER TABLE video_chunks ADD COLUMN height INTEGER;
-    "#)?;
+"#;
 
-    Ok(())
-}
+const V2_DOWN: &str = r#"
+        ALTER TABLE video_chunks DROP COLUMN height;
+        ALTER TABLE video_chunks DROP COLUMN width;
+"#;
 
 /// Migration v3: Add frame_hash column for deduplication
-fn migrate_v3(conn: &Connection) -> Result<()> {
-    info!("applying migration v3: add frame_hash for deduplication");
-
-    conn.execute_batch(r#"
+const V3_UP: &str = r#"
         -- Add perceptual hash column for frame deduplication
         ALTER TABLE frames ADD COLUMN frame_hash INTEGER;
 
         -- Index for efficient hash lookups within a video chunk
         CREATE INDEX IF NOT EXISTS idx_frames_hash ON frames(video_chunk_id, frame_hash);
-    "#)?;
+"#;
+
+const V3_DOWN: &str = r#"
+        DROP INDEX IF EXISTS idx_frames_hash;
+        ALTER TABLE frames DROP COLUMN frame_hash;
+"#;
+
+/// Migration v4: Add encoding frame rate to video_chunks
+const V4_UP: &str = r#"
+        -- Constant frame rate the chunk was encoded at (enables seek-based frame extraction)
+        ALTER TABLE video_chunks ADD COLUMN fps REAL;
+"#;
+
+const V4_DOWN: &str = r#"
+        ALTER TABLE video_chunks DROP COLUMN fps;
+"#;
+
+/// Migration v5: Per-chunk capture/deduplication counters
+const V5_UP: &str = r#"
+        -- Frames captured vs skipped as duplicates, per video chunk
+        CREATE TABLE IF NOT EXISTS recording_stats (
+            video_chunk_id INTEGER PRIMARY KEY,
+            captured_frames INTEGER NOT NULL DEFAULT 0,
+            skipped_frames INTEGER NOT NULL DEFAULT 0,
+            updated_at TEXT DEFAULT (datetime('now')),
+            FOREIGN KEY (video_chunk_id) REFERENCES video_chunks(id) ON DELETE CASCADE
+        );
+"#;
 
-    Ok(())
+const V5_DOWN: &str = r#"
+        DROP TABLE IF EXISTS recording_stats;
+"#;
+
+/// Migration v6: OCR lines with normalized bounding boxes (region search)
+const V6_UP: &str = r#"
+        -- OCR lines with bounding boxes normalized to frame dimensions
+        CREATE TABLE IF NOT EXISTS ocr_lines (
+            id INTEGER PRIMARY KEY,
+            ocr_text_id INTEGER NOT NULL,
+            frame_id INTEGER NOT NULL,
+            line_index INTEGER NOT NULL,
+            text TEXT NOT NULL,
+            confidence REAL,
+            x REAL NOT NULL,
+            y REAL NOT NULL,
+            w REAL NOT NULL,
+            h REAL NOT NULL,
+            FOREIGN KEY (ocr_text_id) REFERENCES ocr_text(id) ON DELETE CASCADE,
+            FOREIGN KEY (frame_id) REFERENCES frames(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_ocr_lines_frame ON ocr_lines(frame_id);
+"#;
+
+const V6_DOWN: &str = r#"
+        DROP INDEX IF EXISTS idx_ocr_lines_frame;
+        DROP TABLE IF EXISTS ocr_lines;
+"#;
+
+/// Migration v7: OCR rows that reuse an earlier frame's identical text
+const V7_UP: &str = r#"
+        -- Frame whose OCR text this row stands in for (text is stored empty)
+        ALTER TABLE ocr_text ADD COLUMN same_as_frame_id INTEGER;
+"#;
+
+const V7_DOWN: &str = r#"
+        -- Restore the referenced text so rows stay searchable without the column
+        UPDATE ocr_text
+        SET text = (SELECT src.text FROM ocr_text src WHERE src.frame_id = ocr_text.same_as_frame_id LIMIT 1),
+            text_json = (SELECT src.text_json FROM ocr_text src WHERE src.frame_id = ocr_text.same_as_frame_id LIMIT 1),
+            confidence = (SELECT src.confidence FROM ocr_text src WHERE src.frame_id = ocr_text.same_as_frame_id LIMIT 1)
+        WHERE same_as_frame_id IS NOT NULL
+          AND EXISTS (SELECT 1 FROM ocr_text src WHERE src.frame_id = ocr_text.same_as_frame_id);
+        ALTER TABLE ocr_text DROP COLUMN same_as_frame_id;
+"#;
+
+/// Migration v8: Codec, duration and file size probed from finished chunks
+const V8_UP: &str = r#"
+        ALTER TABLE video_chunks ADD COLUMN codec TEXT;
+        ALTER TABLE video_chunks ADD COLUMN duration_secs REAL;
+        ALTER TABLE video_chunks ADD COLUMN size_bytes INTEGER;
+"#;
+
+const V8_DOWN: &str = r#"
+        ALTER TABLE video_chunks DROP COLUMN size_bytes;
+        ALTER TABLE video_chunks DROP COLUMN duration_secs;
+        ALTER TABLE video_chunks DROP COLUMN codec;
+"#;
+
+/// Migration v9: OCR language chosen for each frame
+const V9_UP: &str = r#"
+        -- BCP47 tag of the recognizer whose result was stored
+        ALTER TABLE ocr_text ADD COLUMN language TEXT;
+"#;
+
+const V9_DOWN: &str = r#"
+        ALTER TABLE ocr_text DROP COLUMN language;
+"#;
+
+/// Migration v10: Audio files deleted by retention (transcriptions are kept)
+const V10_UP: &str = r#"
+        -- When the chunk's audio file was deleted; NULL while the file is kept
+        ALTER TABLE audio_chunks ADD COLUMN pruned_at TEXT;
+"#;
+
+const V10_DOWN: &str = r#"
+        ALTER TABLE audio_chunks DROP COLUMN pruned_at;
+"#;
+
+/// Migration v11: Composite frame index for per-chunk lookups
+///
+/// Frame lookups by chunk almost always order or filter by offset_index
+/// (timeline, seek, gap detection, MAX(offset_index)). The v1 single-column
+/// index is a prefix of the new one, so it is dropped.
+const V11_UP: &str = r#"
+        CREATE INDEX IF NOT EXISTS idx_frames_chunk ON frames(video_chunk_id, offset_index);
+        DROP INDEX IF EXISTS idx_frames_video_chunk;
+"#;
+
+const V11_DOWN: &str = r#"
+        CREATE INDEX IF NOT EXISTS idx_frames_video_chunk ON frames(video_chunk_id);
+        DROP INDEX IF EXISTS idx_frames_chunk;
+"#;
+
+/// Migration v12: Language of each transcription
+const V12_UP: &str = r#"
+        -- ISO 639-1 code, configured or detected from the text; NULL when unknown
+        ALTER TABLE audio_transcriptions ADD COLUMN language TEXT;
+"#;
+
+const V12_DOWN: &str = r#"
+        ALTER TABLE audio_transcriptions DROP COLUMN language;
+"#;
+
+/// Migration v13: Trigram index over OCR text
+///
+/// The unicode61 tokenizer of `ocr_text_fts` only matches whole words (or
+/// word prefixes); trigrams match any substring of 3+ characters, which is
+/// what searching code and identifiers needs. Existing rows are indexed by
+/// the rebuild.
+const V13_UP: &str = r#"
+        CREATE VIRTUAL TABLE IF NOT EXISTS ocr_text_trigram USING fts5(
+            text,
+            content='ocr_text',
+            content_rowid='id',
+            tokenize='trigram'
+        );
+
+        CREATE TRIGGER IF NOT EXISTS ocr_trigram_ai AFTER INSERT ON ocr_text BEGIN
+            INSERT INTO ocr_text_trigram(rowid, text) VALUES (new.id, new.text);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS ocr_trigram_ad AFTER DELETE ON ocr_text BEGIN
+            INSERT INTO ocr_text_trigram(ocr_text_trigram, rowid, text) VALUES('delete', old.id, old.text);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS ocr_trigram_au AFTER UPDATE ON ocr_text BEGIN
+            INSERT INTO ocr_text_trigram(ocr_text_trigram, rowid, text) VALUES('delete', old.id, old.text);
+            INSERT INTO ocr_text_trigram(rowid, text) VALUES (new.id, new.text);
+        END;
+
+        INSERT INTO ocr_text_trigram(ocr_text_trigram) VALUES('rebuild');
+"#;
+
+const V13_DOWN: &str = r#"
+        DROP TRIGGER IF EXISTS ocr_trigram_au;
+        DROP TRIGGER IF EXISTS ocr_trigram_ad;
+        DROP TRIGGER IF EXISTS ocr_trigram_ai;
+        DROP TABLE IF EXISTS ocr_text_trigram;
+"#;
+
+/// Migration v14: Frame hash index for cross-chunk lookups
+///
+/// `idx_frames_hash` leads with video_chunk_id, so it cannot serve the OCR
+/// cache, which looks up a hash across all chunks.
+const V14_UP: &str = r#"
+        CREATE INDEX IF NOT EXISTS idx_frames_frame_hash ON frames(frame_hash);
+"#;
+
+const V14_DOWN: &str = r#"
+        DROP INDEX IF EXISTS idx_frames_frame_hash;
+"#;
+
+/// Migration v15: Capture sequence number of each frame
+///
+/// `offset_index` is the frame's position in the video, which skips the
+/// positions of deduplicated frames, so dropped frames are found by gaps in
+/// `capture_index` instead. Existing rows have none: their offsets may
+/// already be padded.
+const V15_UP: &str = r#"
+        -- Stored frames counted from 0 per chunk; NULL for frames recorded before v15
+        ALTER TABLE frames ADD COLUMN capture_index INTEGER;
+"#;
+
+const V15_DOWN: &str = r#"
+        ALTER TABLE frames DROP COLUMN capture_index;
+"#;
+
+/// Migration v16: Drop hashes that may predate the DCT perceptual hash
+///
+/// Frames recorded before pHash hold average hashes, which are not comparable
+/// with new ones, and nothing records which kind a row has. Clearing them
+/// keeps similar-frame search and the OCR hash cache from matching across
+/// the two; those frames are treated like frames recorded without a hash.
+const V16_UP: &str = r#"
+        UPDATE frames SET frame_hash = NULL WHERE frame_hash IS NOT NULL;
+"#;
+
+const V16_DOWN: &str = r#"
+        -- Cleared hashes cannot be restored; v15 accepts frames without one
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table_exists(conn: &Connection, name: &str) -> bool {
+        conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?1",
+            [name],
+            |row| row.get::<_, i64>(0),
+        )
+        .unwrap()
+            > 0
+    }
+
+    #[test]
+    fn test_migrations_are_ordered() {
+        for (i, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version, i as i64 + 1);
+        }
+        assert_eq!(MIGRATIONS.last().map(|m| m.version), Some(SCHEMA_VERSION));
+    }
+
+    #[test]
+    fn test_migrate_down_and_up() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_all(&conn).unwrap();
+        assert_eq!(current_version(&conn).unwrap(), SCHEMA_VERSION);
+        assert!(table_exists(&conn, "ocr_lines"));
+        assert!(table_exists(&conn, "ocr_text_trigram"));
+
+        migrate_to(&conn, 4).unwrap();
+        assert_eq!(current_version(&conn).unwrap(), 4);
+        assert!(!table_exists(&conn, "ocr_lines"));
+        assert!(!table_exists(&conn, "ocr_text_trigram"));
+        assert!(!table_exists(&conn, "recording_stats"));
+
+        let recorded: i64 = conn
+            .query_row("SELECT MAX(version) FROM schema_version", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(recorded, 4);
+
+        // FTS triggers from v1 keep working after a rollback
+        conn.execute(
+            "INSERT INTO video_chunks (file_path, device_name) VALUES ('a.mp4', 'monitor-0')",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO frames (video_chunk_id, offset_index, timestamp) VALUES (1, 0, '2025-01-01T00:00:00+00:00')",
+            [],
+        )
+        .unwrap();
+        conn.execute("INSERT INTO ocr_text (frame_id, text) VALUES (1, 'hello world')", [])
+            .unwrap();
+        let hits: i64 = conn
+            .query_row("SELECT COUNT(*) FROM ocr_text_fts WHERE ocr_text_fts MATCH 'hello'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(hits, 1);
+
+        run_all(&conn).unwrap();
+        assert_eq!(current_version(&conn).unwrap(), SCHEMA_VERSION);
+        assert!(table_exists(&conn, "ocr_lines"));
+
+        // Rows written before the trigram index existed are indexed by its rebuild
+        let hits: i64 = conn
+            .query_row("SELECT COUNT(*) FROM ocr_text_trigram WHERE ocr_text_trigram MATCH 'ello wor'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(hits, 1);
+    }
+
+    #[test]
+    fn test_frame_queries_use_indexes() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_all(&conn).unwrap();
+
+        let plan = |sql: &str| -> String {
+            let mut stmt = conn.prepare(&format!("EXPLAIN QUERY PLAN {}", sql)).unwrap();
+            let rows = stmt
+                .query_map([], |row| row.get::<_, String>(3))
+                .unwrap()
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .unwrap();
+            rows.join("\n")
+        };
+
+        let by_chunk = plan("SELECT id FROM frames WHERE video_chunk_id = 1 ORDER BY offset_index");
+        assert!(by_chunk.contains("idx_frames_chunk"), "{}", by_chunk);
+        assert!(!by_chunk.contains("TEMP B-TREE"), "{}", by_chunk);
+
+        let by_time = plan("SELECT id FROM frames WHERE timestamp >= '2025-01-01' AND timestamp < '2025-01-02'");
+        assert!(by_time.contains("idx_frames_timestamp"), "{}", by_time);
+
+        let ocr = plan("SELECT f.id FROM frames f LEFT JOIN ocr_text o ON f.id = o.frame_id WHERE o.id IS NULL");
+        assert!(ocr.contains("idx_ocr_frame"), "{}", ocr);
+    }
+
+    #[test]
+    fn test_average_hashes_are_cleared() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate_to(&conn, 15).unwrap();
+        conn.execute(
+            "INSERT INTO video_chunks (file_path, device_name) VALUES ('a.mp4', 'monitor-0')",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO frames (video_chunk_id, offset_index, timestamp, frame_hash) VALUES (1, 0, '2025-01-01T00:00:00+00:00', 42)",
+            [],
+        )
+        .unwrap();
+
+        run_all(&conn).unwrap();
+        let hash: Option<i64> = conn
+            .query_row("SELECT frame_hash FROM frames WHERE id = 1", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(hash, None);
+
+        // Rolling back keeps the hashes cleared
+        migrate_to(&conn, 15).unwrap();
+        assert_eq!(current_version(&conn).unwrap(), 15);
+    }
+
+    #[test]
+    fn test_initial_schema_cannot_be_rolled_back() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_all(&conn).unwrap();
+
+        assert!(migrate_to(&conn, 0).is_err());
+        assert_eq!(current_version(&conn).unwrap(), SCHEMA_VERSION);
+        assert!(migrate_to(&conn, SCHEMA_VERSION + 1).is_err());
+    }
 }
@@ -1,9 +1,12 @@
 //! Database query functions
 
-use anyhow::Result;
 use chrono::{DateTime, Utc};
 use rusqlite::{params, Connection, Row};
+use std::path::Path;
+use std::time::Duration;
+use tracing::{info, warn};
 
+use crate::error::{DatabaseError, Result};
 use crate::schema::*;
 
 /// Sanitize a user query for FTS5 search
@@ -14,7 +17,7 @@ pub fn sanitize_fts5_query(query: &str) -> Result<String> {
     let trimmed = query.trim();
 
     if trimmed.is_empty() {
-        anyhow::bail!("Search query cannot be empty");
+        return Err(DatabaseError::InvalidQuery("search query cannot be empty".to_string()));
     }
 
     // Remove all FTS5 special characters that could be used for injection
@@ -36,28 +39,145 @@ pub fn sanitize_fts5_query(query: &str) -> Result<String> {
 
     // Verify we still have content after sanitization
     if sanitized.trim().is_empty() {
-        anyhow::bail!("Search query contains only special characters");
+        return Err(DatabaseError::InvalidQuery("search query contains only special characters".to_string()));
     }
 
     // Wrap in quotes for literal matching
     Ok(format!("\"{}\"", sanitized.trim()))
 }
 
+/// Sanitize a user query for FTS5 search in the given mode
+/// - `Exact`: same as [`sanitize_fts5_query`]
+/// - `Prefix`: each term quoted and suffixed with `*`, all terms required
+/// - `Fuzzy`: candidate query matching any term's first two characters;
+///   results still need filtering with [`fuzzy_matches`]
+/// - `Substring`: literal phrase for the trigram index (see
+///   [`search_ocr_substring`]), at least [`TRIGRAM_MIN_CHARS`] characters
+///
+/// Terms are always quoted after sanitizing, so no FTS5 syntax gets through.
+pub fn sanitize_fts5_query_with_mode(query: &str, mode: SearchMode) -> Result<String> {
+    if mode == SearchMode::Exact {
+        return sanitize_fts5_query(query);
+    }
+
+    if mode == SearchMode::Substring {
+        // Trigram phrases match anywhere, but can't match anything shorter than a trigram
+        let literal = sanitize_fts5_query(query)?;
+        if literal.trim_matches('"').chars().count() < TRIGRAM_MIN_CHARS {
+            return Err(DatabaseError::InvalidQuery(format!(
+                "substring search needs at least {} characters",
+                TRIGRAM_MIN_CHARS
+            )));
+        }
+        return Ok(literal);
+    }
+
+    // Reuse the literal sanitizer, then split into terms
+    let literal = sanitize_fts5_query(query)?;
+    let terms: Vec<&str> = literal.trim_matches('"').split_whitespace().collect();
+
+    let fts_terms: Vec<String> = match mode {
+        SearchMode::Prefix => terms.iter().map(|t| format!("\"{}\"*", t)).collect(),
+        _ => terms
+            .iter()
+            .map(|t| format!("\"{}\"*", t.chars().take(FUZZY_PREFIX_CHARS).collect::<String>()))
+            .collect(),
+    };
+
+    let joiner = if mode == SearchMode::Fuzzy { " OR " } else { " " };
+    Ok(fts_terms.join(joiner))
+}
+
+/// Shortest query the trigram index can match
+pub const TRIGRAM_MIN_CHARS: usize = 3;
+
+/// Leading characters of each term used to fetch fuzzy search candidates
+const FUZZY_PREFIX_CHARS: usize = 2;
+/// Maximum FTS candidates filtered by a fuzzy search
+pub const FUZZY_CANDIDATE_LIMIT: i64 = 2000;
+
+/// Check that every query term is close to some word in `text`
+///
+/// A term matches a word when it is within the allowed edit distance of the
+/// whole word or of the word's start, so typos and partial words both match.
+/// Allowed edits grow with term length: none up to 3 characters, 1 up to 7, else 2.
+pub fn fuzzy_matches(text: &str, query: &str) -> bool {
+    let words: Vec<Vec<char>> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase().chars().collect())
+        .collect();
+
+    let terms: Vec<Vec<char>> = query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_lowercase().chars().collect())
+        .collect();
+
+    !terms.is_empty()
+        && terms.iter().all(|term| {
+            let max_edits = match term.len() {
+                0..=3 => 0,
+                4..=7 => 1,
+                _ => 2,
+            };
+            words.iter().any(|word| {
+                let head = &word[..word.len().min(term.len())];
+                edit_distance(term, head) <= max_edits || edit_distance(term, word) <= max_edits
+            })
+        })
+}
+
+/// Levenshtein distance between two character sequences
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+
+    for (i, ca) in a.iter().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
+}
+
 /// Insert a new video chunk
 pub fn insert_video_chunk(conn: &Connection, chunk: &NewVideoChunk) -> Result<i64> {
     conn.execute(
-        "INSERT INTO video_chunks (file_path, device_name, width, height) VALUES (?1, ?2, ?3, ?4)",
-        params![chunk.file_path, chunk.device_name, chunk.width, chunk.height],
+        "INSERT INTO video_chunks (file_path, device_name, width, height, fps) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![chunk.file_path, chunk.device_name, chunk.width, chunk.height, chunk.fps],
     )?;
     Ok(conn.last_insert_rowid())
 }
 
+/// Record the actual dimensions and encoding details of a finished chunk
+pub fn update_chunk_metadata(conn: &Connection, chunk_id: i64, metadata: &VideoChunkMetadata) -> Result<()> {
+    conn.execute(
+        r#"UPDATE video_chunks
+           SET width = ?2, height = ?3, codec = ?4, duration_secs = ?5, size_bytes = ?6
+           WHERE id = ?1"#,
+        params![
+            chunk_id,
+            metadata.width,
+            metadata.height,
+            metadata.codec,
+            metadata.duration_secs,
+            metadata.size_bytes
+        ],
+    )?;
+    Ok(())
+}
+
 /// Insert a new frame
 pub fn insert_frame(conn: &Connection, frame: &NewFrame) -> Result<i64> {
     conn.execute(
         r#"INSERT INTO frames
-           (video_chunk_id, offset_index, timestamp, app_name, window_name, browser_url, focused, frame_hash)
-           VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"#,
+           (video_chunk_id, offset_index, timestamp, app_name, window_name, browser_url, focused, frame_hash, capture_index)
+           VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)"#,
         params![
             frame.video_chunk_id,
             frame.offset_index,
@@ -67,25 +187,58 @@ pub fn insert_frame(conn: &Connection, frame: &NewFrame) -> Result<i64> {
             frame.browser_url,
             frame.focused as i32,
             frame.frame_hash,
+            frame.capture_index,
         ],
     )?;
     Ok(conn.last_insert_rowid())
 }
 
+/// Retries of an operation that failed with `SQLITE_BUSY`
+pub const BUSY_RETRIES: u32 = 3;
+
+/// Wait before the first retry, growing with each attempt
+const BUSY_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// Run `op`, retrying when another connection holds the write lock
+///
+/// The busy timeout already waits for the lock, but a transaction that read
+/// before writing fails at once when another connection committed in
+/// between. `op` must be safe to repeat, e.g. a whole transaction, which is
+/// rolled back when it fails.
+pub fn with_busy_retry<T>(mut op: impl FnMut() -> Result<T>) -> Result<T> {
+    let mut attempt = 0;
+    loop {
+        match op() {
+            Err(e) if e.is_busy() && attempt < BUSY_RETRIES => {
+                attempt += 1;
+                warn!("database busy, retrying ({}/{})", attempt, BUSY_RETRIES);
+                std::thread::sleep(BUSY_RETRY_DELAY * attempt);
+            }
+            result => return result,
+        }
+    }
+}
+
 /// Batch insert multiple frames in a single transaction for better performance
+///
+/// Retried when the database is busy (see [`with_busy_retry`]).
 pub fn insert_frames_batch(conn: &Connection, frames: &[NewFrame]) -> Result<Vec<i64>> {
     if frames.is_empty() {
         return Ok(vec![]);
     }
 
+    with_busy_retry(|| insert_frames_tx(conn, frames))
+}
+
+fn insert_frames_tx(conn: &Connection, frames: &[NewFrame]) -> Result<Vec<i64>> {
     let tx = conn.unchecked_transaction()?;
     let mut ids = Vec::with_capacity(frames.len());
 
     {
         let mut stmt = tx.prepare_cached(
             r#"INSERT INTO frames
-               (video_chunk_id, offset_index, timestamp, app_name, window_name, browser_url, focused, frame_hash)
-               VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"#,
+               (video_chunk_id, offset_index, timestamp, app_name, window_name, browser_url, focused, frame_hash, capture_index)
+               VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)"#,
         )?;
 
         for frame in frames {
@@ -98,6 +251,7 @@ pub fn insert_frames_batch(conn: &Connection, frames: &[NewFrame]) -> Result<Vec
                 frame.browser_url,
                 frame.focused as i32,
                 frame.frame_hash,
+                frame.capture_index,
             ])?;
             ids.push(tx.last_insert_rowid());
         }
@@ -110,16 +264,233 @@ pub fn insert_frames_batch(conn: &Connection, frames: &[NewFrame]) -> Result<Vec
 /// Insert OCR text for a frame
 pub fn insert_ocr_text(conn: &Connection, ocr: &NewOcrText) -> Result<i64> {
     conn.execute(
-        "INSERT INTO ocr_text (frame_id, text, text_json, confidence) VALUES (?1, ?2, ?3, ?4)",
-        params![ocr.frame_id, ocr.text, ocr.text_json, ocr.confidence],
+        "INSERT INTO ocr_text (frame_id, text, text_json, confidence, language) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![ocr.frame_id, ocr.text, ocr.text_json, ocr.confidence, ocr.language],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Pages of FTS5 segments merged after a bulk insert
+const FTS_MERGE_PAGES: i64 = 500;
+
+/// Triggers indexing new OCR rows, one per full-text index over `ocr_text`
+const OCR_FTS_INSERT_TRIGGERS: [&str; 2] = ["ocr_text_ai", "ocr_trigram_ai"];
+
+/// Insert OCR text for many frames in one transaction
+///
+/// Returns the new row ids in input order. The FTS index is normally kept up
+/// to date and its new segments are merged once at the end. With `defer_fts`
+/// the insert trigger is suspended for the transaction instead, which is much
+/// faster for tens of thousands of rows, and the rows are not searchable (in
+/// either the word or the trigram index) until [`rebuild_fts_index`] runs.
+/// Rebuild before deleting or updating deferred rows, or their removal from
+/// the index fails.
+///
+/// Retried when the database is busy (see [`with_busy_retry`]).
+pub fn insert_ocr_text_batch(conn: &Connection, rows: &[NewOcrText], defer_fts: bool) -> Result<Vec<i64>> {
+    if rows.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let ids = with_busy_retry(|| insert_ocr_text_tx(conn, rows, defer_fts))?;
+
+    if !defer_fts {
+        with_busy_retry(|| {
+            conn.execute(
+                "INSERT INTO ocr_text_fts(ocr_text_fts, rank) VALUES ('merge', ?1)",
+                params![FTS_MERGE_PAGES],
+            )?;
+            conn.execute(
+                "INSERT INTO ocr_text_trigram(ocr_text_trigram, rank) VALUES ('merge', ?1)",
+                params![FTS_MERGE_PAGES],
+            )
+            .map_err(Into::into)
+        })?;
+    }
+
+    Ok(ids)
+}
+
+fn insert_ocr_text_tx(conn: &Connection, rows: &[NewOcrText], defer_fts: bool) -> Result<Vec<i64>> {
+    let tx = conn.unchecked_transaction()?;
+
+    // Restore the triggers from their stored definitions so the schema stays the single source
+    let mut triggers: Vec<String> = Vec::new();
+    if defer_fts {
+        for name in OCR_FTS_INSERT_TRIGGERS {
+            let sql = tx.query_row(
+                "SELECT sql FROM sqlite_master WHERE type = 'trigger' AND name = ?1",
+                [name],
+                |row| row.get(0),
+            )?;
+            tx.execute_batch(&format!("DROP TRIGGER {}", name))?;
+            triggers.push(sql);
+        }
+    }
+
+    let mut ids = Vec::with_capacity(rows.len());
+    {
+        let mut stmt = tx.prepare_cached(
+            "INSERT INTO ocr_text (frame_id, text, text_json, confidence, language) VALUES (?1, ?2, ?3, ?4, ?5)",
+        )?;
+
+        for ocr in rows {
+            stmt.execute(params![ocr.frame_id, ocr.text, ocr.text_json, ocr.confidence, ocr.language])?;
+            ids.push(tx.last_insert_rowid());
+        }
+    }
+
+    for sql in &triggers {
+        tx.execute_batch(sql)?;
+    }
+    tx.commit()?;
+    Ok(ids)
+}
+
+/// Rebuild the OCR (word and trigram) and audio full-text indexes from their tables
+///
+/// Needed after [`insert_ocr_text_batch`] with `defer_fts`, and repairs an
+/// index that drifted from its table. Takes time proportional to the whole
+/// table, so run it once after a bulk load rather than per batch.
+pub fn rebuild_fts_index(conn: &Connection) -> Result<()> {
+    info!("rebuilding full-text indexes");
+    let tx = conn.unchecked_transaction()?;
+    tx.execute_batch(
+        r#"INSERT INTO ocr_text_fts(ocr_text_fts) VALUES ('rebuild');
+           INSERT INTO ocr_text_trigram(ocr_text_trigram) VALUES ('rebuild');
+           INSERT INTO audio_fts(audio_fts) VALUES ('rebuild');"#,
+    )?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Full-text indexes checked against their content tables by [`integrity_check`]
+const FTS_TABLES: [&str; 3] = ["ocr_text_fts", "ocr_text_trigram", "audio_fts"];
+
+/// Check the database for corruption
+///
+/// Runs SQLite's `integrity_check` and `foreign_key_check` and verifies that
+/// each full-text index matches its table. Returns one message per problem;
+/// an empty list means the database is healthy. A file too damaged to query
+/// is reported as a problem rather than an error.
+pub fn integrity_check(conn: &Connection) -> Result<Vec<String>> {
+    let mut issues = Vec::new();
+
+    let pages = conn
+        .prepare("PRAGMA integrity_check")
+        .and_then(|mut stmt| stmt.query_map([], |row| row.get::<_, String>(0))?.collect::<rusqlite::Result<Vec<_>>>());
+    match pages {
+        Ok(messages) => issues.extend(messages.into_iter().filter(|m| m != "ok")),
+        Err(e) if is_corruption(&e) => {
+            issues.push(format!("integrity check failed: {}", e));
+            return Ok(issues);
+        }
+        Err(e) => return Err(e.into()),
+    }
+
+    let mut stmt = conn.prepare("PRAGMA foreign_key_check")?;
+    let orphans = stmt.query_map([], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, Option<i64>>(1)?, row.get::<_, String>(2)?))
+    })?;
+    for orphan in orphans {
+        let (table, rowid, parent) = orphan?;
+        let row = rowid.map_or_else(|| "a row".to_string(), |id| format!("row {}", id));
+        issues.push(format!("{} {} references a missing {} row", table, row, parent));
+    }
+
+    // A non-zero rank also compares the index with its external content table
+    for table in FTS_TABLES {
+        let sql = format!("INSERT INTO {0}({0}, rank) VALUES ('integrity-check', 1)", table);
+        match conn.execute(&sql, []) {
+            Ok(_) => {}
+            Err(e) if is_corruption(&e) => {
+                issues.push(format!("{} does not match its table (rebuild the full-text index)", table));
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Ok(issues)
+}
+
+/// Whether SQLite reported a damaged database (including FTS5 index mismatches)
+fn is_corruption(error: &rusqlite::Error) -> bool {
+    matches!(
+        error,
+        rusqlite::Error::SqliteFailure(e, _)
+            if matches!(e.code, rusqlite::ErrorCode::DatabaseCorrupt | rusqlite::ErrorCode::NotADatabase)
+    )
+}
+
+/// Record that a frame's OCR text is identical to an earlier frame's.
+///
+/// Stores an empty row pointing at `same_as_frame_id`, so the frame counts as
+/// processed without duplicating the text in the table and FTS index.
+pub fn insert_ocr_reference(conn: &Connection, frame_id: i64, same_as_frame_id: i64) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO ocr_text (frame_id, text, same_as_frame_id) VALUES (?1, '', ?2)",
+        params![frame_id, same_as_frame_id],
     )?;
     Ok(conn.last_insert_rowid())
 }
 
+/// Get the OCR text of the closest earlier frame in the same chunk.
+///
+/// Returns the frame that actually holds the text (references are followed)
+/// and the text itself, or `None` if no earlier frame has been indexed.
+pub fn get_previous_ocr_text(
+    conn: &Connection,
+    video_chunk_id: i64,
+    offset_index: i64,
+) -> Result<Option<(i64, String)>> {
+    let result = conn.query_row(
+        r#"SELECT COALESCE(o.same_as_frame_id, o.frame_id), COALESCE(src.text, o.text)
+           FROM frames f
+           JOIN ocr_text o ON o.frame_id = f.id
+           LEFT JOIN ocr_text src ON src.frame_id = o.same_as_frame_id
+           WHERE f.video_chunk_id = ?1 AND f.offset_index < ?2
+           ORDER BY f.offset_index DESC
+           LIMIT 1"#,
+        params![video_chunk_id, offset_index],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    );
+
+    match result {
+        Ok(r) => Ok(Some(r)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Get the OCR text of any frame with the given perceptual hash.
+///
+/// Returns the frame that actually holds the text (references are followed)
+/// and the text itself. Frames with empty text are ignored, since an empty
+/// row may be a failed OCR rather than a blank screen.
+pub fn get_ocr_by_hash(conn: &Connection, frame_hash: i64) -> Result<Option<(i64, String)>> {
+    let result = conn.query_row(
+        r#"SELECT COALESCE(o.same_as_frame_id, o.frame_id), COALESCE(src.text, o.text) AS text
+           FROM frames f
+           JOIN ocr_text o ON o.frame_id = f.id
+           LEFT JOIN ocr_text src ON src.frame_id = o.same_as_frame_id
+           WHERE f.frame_hash = ?1 AND COALESCE(src.text, o.text) != ''
+           ORDER BY f.timestamp DESC
+           LIMIT 1"#,
+        params![frame_hash],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    );
+
+    match result {
+        Ok(r) => Ok(Some(r)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
 /// Get video chunk by ID
 pub fn get_video_chunk(conn: &Connection, id: i64) -> Result<Option<VideoChunk>> {
     let mut stmt = conn.prepare(
-        "SELECT id, file_path, device_name, created_at, width, height FROM video_chunks WHERE id = ?1",
+        "SELECT id, file_path, device_name, created_at, width, height, fps, codec, duration_secs, size_bytes FROM video_chunks WHERE id = ?1",
     )?;
 
     let chunk = stmt.query_row(params![id], |row| {
@@ -130,6 +501,10 @@ pub fn get_video_chunk(conn: &Connection, id: i64) -> Result<Option<VideoChunk>>
             created_at: parse_datetime(row, 3)?,
             width: row.get::<_, Option<i64>>(4)?.map(|v| v as u32),
             height: row.get::<_, Option<i64>>(5)?.map(|v| v as u32),
+            fps: row.get(6)?,
+            codec: row.get(7)?,
+            duration_secs: row.get(8)?,
+            size_bytes: row.get(9)?,
         })
     });
 
@@ -158,25 +533,29 @@ pub fn get_frame(conn: &Connection, id: i64) -> Result<Option<Frame>> {
 }
 
 /// Get frames in time range
+///
+/// With `focused_only`, frames captured while the user was idle are skipped.
 pub fn get_frames_in_range(
     conn: &Connection,
     start: DateTime<Utc>,
     end: DateTime<Utc>,
     limit: i64,
     offset: i64,
+    focused_only: bool,
 ) -> Result<Vec<Frame>> {
     let mut stmt = conn.prepare(
         r#"SELECT id, video_chunk_id, offset_index, timestamp, app_name,
            window_name, browser_url, focused, frame_hash
            FROM frames
            WHERE timestamp >= ?1 AND timestamp <= ?2
+             AND (?5 = 0 OR focused = 1)
            ORDER BY timestamp DESC
            LIMIT ?3 OFFSET ?4"#,
     )?;
 
     let frames = stmt
         .query_map(
-            params![start.to_rfc3339(), end.to_rfc3339(), limit, offset],
+            params![start.to_rfc3339(), end.to_rfc3339(), limit, offset, focused_only],
             row_to_frame,
         )?
         .collect::<Result<Vec<_>, _>>()?;
@@ -185,51 +564,90 @@ pub fn get_frames_in_range(
 }
 
 /// Full-text search on OCR text
+///
+/// With `focused_only`, matches in frames captured while the user was idle are skipped.
 pub fn search_ocr(
     conn: &Connection,
     query: &str,
     limit: i64,
     offset: i64,
+    focused_only: bool,
 ) -> Result<Vec<(OcrText, Frame)>> {
-    let mut stmt = conn.prepare(
+    search_ocr_table(conn, "ocr_text_fts", query, limit, offset, focused_only)
+}
+
+/// Substring search on OCR text using the trigram index
+///
+/// `query` is a phrase from [`sanitize_fts5_query_with_mode`] with
+/// [`SearchMode::Substring`]; it matches inside words, case-insensitively.
+pub fn search_ocr_substring(
+    conn: &Connection,
+    query: &str,
+    limit: i64,
+    offset: i64,
+    focused_only: bool,
+) -> Result<Vec<(OcrText, Frame)>> {
+    search_ocr_table(conn, "ocr_text_trigram", query, limit, offset, focused_only)
+}
+
+/// OCR search against one of the FTS tables over `ocr_text`
+fn search_ocr_table(
+    conn: &Connection,
+    fts_table: &str,
+    query: &str,
+    limit: i64,
+    offset: i64,
+    focused_only: bool,
+) -> Result<Vec<(OcrText, Frame)>> {
+    let mut stmt = conn.prepare(&format!(
         r#"SELECT o.id, o.frame_id, o.text, o.text_json, o.confidence,
            f.id, f.video_chunk_id, f.offset_index, f.timestamp, f.app_name,
-           f.window_name, f.browser_url, f.focused, f.frame_hash
+           f.window_name, f.browser_url, f.focused, f.frame_hash, o.language
            FROM ocr_text o
-           JOIN ocr_text_fts fts ON o.id = fts.rowid
+           JOIN {table} fts ON o.id = fts.rowid
            JOIN frames f ON o.frame_id = f.id
-           WHERE ocr_text_fts MATCH ?1
+           WHERE {table} MATCH ?1
+             AND (?4 = 0 OR f.focused = 1)
            ORDER BY rank
            LIMIT ?2 OFFSET ?3"#,
-    )?;
+        table = fts_table
+    ))?;
 
     let results = stmt
-        .query_map(params![query, limit, offset], |row| {
-            let ocr = OcrText {
-                id: row.get(0)?,
-                frame_id: row.get(1)?,
-                text: row.get(2)?,
-                text_json: row.get(3)?,
-                confidence: row.get(4)?,
-            };
-            let frame = Frame {
-                id: row.get(5)?,
-                video_chunk_id: row.get(6)?,
-                offset_index: row.get(7)?,
-                timestamp: parse_datetime(row, 8)?,
-                app_name: row.get(9)?,
-                window_name: row.get(10)?,
-                browser_url: row.get(11)?,
-                focused: row.get::<_, i32>(12)? != 0,
-                frame_hash: row.get(13)?,
-            };
-            Ok((ocr, frame))
-        })?
+        .query_map(params![query, limit, offset, focused_only], row_to_ocr_with_frame)?
         .collect::<Result<Vec<_>, _>>()?;
 
     Ok(results)
 }
 
+/// Typo-tolerant OCR search
+///
+/// Fetches up to [`FUZZY_CANDIDATE_LIMIT`] FTS candidates sharing a prefix
+/// with the query terms, keeps those passing [`fuzzy_matches`] and pages
+/// through them in rank order. Returns the page and the total match count.
+pub fn search_ocr_fuzzy(
+    conn: &Connection,
+    query: &str,
+    limit: i64,
+    offset: i64,
+    focused_only: bool,
+) -> Result<(Vec<(OcrText, Frame)>, i64)> {
+    let candidate_query = sanitize_fts5_query_with_mode(query, SearchMode::Fuzzy)?;
+    let matches: Vec<(OcrText, Frame)> = search_ocr(conn, &candidate_query, FUZZY_CANDIDATE_LIMIT, 0, focused_only)?
+        .into_iter()
+        .filter(|(ocr, _)| fuzzy_matches(&ocr.text, query))
+        .collect();
+
+    let total = matches.len() as i64;
+    let page = matches
+        .into_iter()
+        .skip(offset.max(0) as usize)
+        .take(limit.max(0) as usize)
+        .collect();
+
+    Ok((page, total))
+}
+
 /// Get frames without OCR text (for batch processing)
 pub fn get_frames_without_ocr(conn: &Connection, limit: i64) -> Result<Vec<Frame>> {
     let mut stmt = conn.prepare(
@@ -250,6 +668,8 @@ pub fn get_frames_without_ocr(conn: &Connection, limit: i64) -> Result<Vec<Frame
 }
 
 /// Get frames from a specific video chunk that need OCR processing
+///
+/// Ordered by position in the video so extraction seeks forward through the file.
 pub fn get_frames_for_chunk_without_ocr(
     conn: &Connection,
     chunk_id: i64,
@@ -260,7 +680,7 @@ pub fn get_frames_for_chunk_without_ocr(
            FROM frames f
            LEFT JOIN ocr_text o ON f.id = o.frame_id
            WHERE o.id IS NULL AND f.video_chunk_id = ?1
-           ORDER BY f.timestamp ASC"#,
+           ORDER BY f.offset_index ASC"#,
     )?;
 
     let frames = stmt
@@ -285,7 +705,7 @@ pub fn get_frame_with_ocr(conn: &Connection, frame_id: i64) -> Result<Option<Fra
     let mut stmt = conn.prepare(
         r#"SELECT f.id, f.video_chunk_id, f.offset_index, f.timestamp, f.app_name,
            f.window_name, f.browser_url, f.focused, f.frame_hash,
-           o.id, o.frame_id, o.text, o.text_json, o.confidence
+           o.id, o.frame_id, o.text, o.text_json, o.confidence, o.language
            FROM frames f
            LEFT JOIN ocr_text o ON f.id = o.frame_id
            WHERE f.id = ?1"#,
@@ -299,6 +719,7 @@ pub fn get_frame_with_ocr(conn: &Connection, frame_id: i64) -> Result<Option<Fra
                 text: row.get(11)?,
                 text_json: row.get(12)?,
                 confidence: row.get(13)?,
+                language: row.get(14)?,
             })
         } else {
             None
@@ -336,7 +757,7 @@ pub fn get_frames_with_ocr_in_range(
     let mut stmt = conn.prepare(
         r#"SELECT f.id, f.video_chunk_id, f.offset_index, f.timestamp, f.app_name,
            f.window_name, f.browser_url, f.focused, f.frame_hash,
-           o.id, o.frame_id, o.text, o.text_json, o.confidence
+           o.id, o.frame_id, o.text, o.text_json, o.confidence, o.language
            FROM frames f
            LEFT JOIN ocr_text o ON f.id = o.frame_id
            WHERE f.timestamp >= ?1 AND f.timestamp <= ?2
@@ -347,38 +768,62 @@ pub fn get_frames_with_ocr_in_range(
     let frames = stmt
         .query_map(
             params![start.to_rfc3339(), end.to_rfc3339(), limit, offset],
-            |row| {
-                let ocr_text = if let Ok(ocr_id) = row.get::<_, i64>(9) {
-                    Some(OcrText {
-                        id: ocr_id,
-                        frame_id: row.get(10)?,
-                        text: row.get(11)?,
-                        text_json: row.get(12)?,
-                        confidence: row.get(13)?,
-                    })
-                } else {
-                    None
-                };
-
-                Ok(FrameWithOcr {
-                    id: row.get(0)?,
-                    video_chunk_id: row.get(1)?,
-                    offset_index: row.get(2)?,
-                    timestamp: parse_datetime(row, 3)?,
-                    app_name: row.get(4)?,
-                    window_name: row.get(5)?,
-                    browser_url: row.get(6)?,
-                    focused: row.get::<_, i32>(7)? != 0,
-                    frame_hash: row.get(8)?,
-                    ocr_text,
-                })
-            },
+            row_to_frame_with_ocr,
         )?
         .collect::<Result<Vec<_>, _>>()?;
 
     Ok(frames)
 }
 
+/// Get the most recently captured frames with their OCR text (newest first)
+pub fn get_recent_frames_with_ocr(conn: &Connection, limit: i64) -> Result<Vec<FrameWithOcr>> {
+    let mut stmt = conn.prepare(
+        r#"SELECT f.id, f.video_chunk_id, f.offset_index, f.timestamp, f.app_name,
+           f.window_name, f.browser_url, f.focused, f.frame_hash,
+           o.id, o.frame_id, o.text, o.text_json, o.confidence, o.language
+           FROM frames f
+           LEFT JOIN ocr_text o ON f.id = o.frame_id
+           ORDER BY f.timestamp DESC
+           LIMIT ?1"#,
+    )?;
+
+    let frames = stmt
+        .query_map(params![limit], row_to_frame_with_ocr)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(frames)
+}
+
+/// Get the newest frame of each monitor, with its OCR text, by device name
+///
+/// Each monitor's frame is found by walking the timestamp index backwards
+/// until one of its frames turns up, so monitors that are recording are
+/// found after a few rows. Monitors without frames are left out.
+pub fn get_latest_frame_per_monitor(conn: &Connection) -> Result<Vec<(String, FrameWithOcr)>> {
+    let mut stmt = conn.prepare(
+        r#"SELECT f.id, f.video_chunk_id, f.offset_index, f.timestamp, f.app_name,
+           f.window_name, f.browser_url, f.focused, f.frame_hash,
+           o.id, o.frame_id, o.text, o.text_json, o.confidence, o.language,
+           d.device_name
+           FROM (SELECT DISTINCT device_name FROM video_chunks) d
+           JOIN frames f ON f.id = (
+               SELECT latest.id FROM frames latest
+               JOIN video_chunks vc ON latest.video_chunk_id = vc.id
+               WHERE vc.device_name = d.device_name
+               ORDER BY latest.timestamp DESC
+               LIMIT 1
+           )
+           LEFT JOIN ocr_text o ON f.id = o.frame_id
+           ORDER BY d.device_name"#,
+    )?;
+
+    let frames = stmt
+        .query_map([], |row| Ok((row.get(15)?, row_to_frame_with_ocr(row)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(frames)
+}
+
 /// Get total frame count
 pub fn get_frame_count(conn: &Connection) -> Result<i64> {
     let count: i64 = conn.query_row("SELECT COUNT(*) FROM frames", [], |row| row.get(0))?;
@@ -388,7 +833,7 @@ pub fn get_frame_count(conn: &Connection) -> Result<i64> {
 /// Get latest video chunk
 pub fn get_latest_video_chunk(conn: &Connection) -> Result<Option<VideoChunk>> {
     let mut stmt = conn.prepare(
-        "SELECT id, file_path, device_name, created_at, width, height FROM video_chunks ORDER BY id DESC LIMIT 1",
+        "SELECT id, file_path, device_name, created_at, width, height, fps, codec, duration_secs, size_bytes FROM video_chunks ORDER BY id DESC LIMIT 1",
     )?;
 
     let chunk = stmt.query_row([], |row| {
@@ -399,6 +844,10 @@ pub fn get_latest_video_chunk(conn: &Connection) -> Result<Option<VideoChunk>> {
             created_at: parse_datetime(row, 3)?,
             width: row.get::<_, Option<i64>>(4)?.map(|v| v as u32),
             height: row.get::<_, Option<i64>>(5)?.map(|v| v as u32),
+            fps: row.get(6)?,
+            codec: row.get(7)?,
+            duration_secs: row.get(8)?,
+            size_bytes: row.get(9)?,
         })
     });
 
@@ -434,12 +883,12 @@ pub fn get_chunks_paginated(
     }
 
     if let Some(start) = start_date {
-        conditions.push("vc.created_at >= ?");
+        conditions.push("datetime(vc.created_at) >= datetime(?)");
         params.push(Box::new(start.to_rfc3339()));
     }
 
     if let Some(end) = end_date {
-        conditions.push("vc.created_at <= ?");
+        conditions.push("datetime(vc.created_at) <= datetime(?)");
         params.push(Box::new(end.to_rfc3339()));
     }
 
@@ -474,6 +923,23 @@ pub fn get_chunks_paginated(
     Ok(chunks)
 }
 
+/// Get all frames of a video chunk in video order
+pub fn get_frames_by_chunk(conn: &Connection, chunk_id: i64) -> Result<Vec<Frame>> {
+    let mut stmt = conn.prepare(
+        r#"SELECT id, video_chunk_id, offset_index, timestamp, app_name,
+           window_name, browser_url, focused, frame_hash
+           FROM frames
+           WHERE video_chunk_id = ?1
+           ORDER BY offset_index ASC"#,
+    )?;
+
+    let frames = stmt
+        .query_map(params![chunk_id], row_to_frame)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(frames)
+}
+
 /// Get frame count for a specific video chunk
 pub fn get_frame_count_by_chunk(conn: &Connection, chunk_id: i64) -> Result<i64> {
     let count: i64 = conn.query_row(
@@ -484,6 +950,33 @@ pub fn get_frame_count_by_chunk(conn: &Connection, chunk_id: i64) -> Result<i64>
     Ok(count)
 }
 
+/// Get ranges of missing `capture_index` values within a video chunk
+///
+/// Capture indices only advance for frames that were actually stored
+/// (duplicates are skipped before an index is assigned), so any gap here points
+/// to a capture or write failure rather than deduplication. Frames recorded
+/// before capture indices existed are ignored. Each range is inclusive:
+/// `(first, last)`.
+pub fn get_frame_index_gaps(conn: &Connection, chunk_id: i64) -> Result<Vec<(i64, i64)>> {
+    let mut stmt = conn.prepare(
+        r#"SELECT prev_index + 1, capture_index - 1
+           FROM (
+               SELECT capture_index,
+                      LAG(capture_index, 1, -1) OVER (ORDER BY capture_index) AS prev_index
+               FROM frames
+               WHERE video_chunk_id = ?1 AND capture_index IS NOT NULL
+           )
+           WHERE capture_index - prev_index > 1
+           ORDER BY capture_index"#,
+    )?;
+
+    let gaps = stmt
+        .query_map(params![chunk_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(gaps)
+}
+
 /// Get total chunk count with optional filters
 pub fn get_total_chunk_count(
     conn: &Connection,
@@ -502,12 +995,12 @@ pub fn get_total_chunk_count(
     }
 
     if let Some(start) = start_date {
-        conditions.push("created_at >= ?");
+        conditions.push("datetime(created_at) >= datetime(?)");
         params.push(Box::new(start.to_rfc3339()));
     }
 
     if let Some(end) = end_date {
-        conditions.push("created_at <= ?");
+        conditions.push("datetime(created_at) <= datetime(?)");
         params.push(Box::new(end.to_rfc3339()));
     }
 
@@ -555,10 +1048,16 @@ pub fn get_monitors_summary(conn: &Connection) -> Result<Vec<MonitorSummary>> {
     Ok(summaries)
 }
 
-/// Get OCR text for a specific frame
+/// Get OCR text for a specific frame (text deduplicated against an earlier
+/// frame is resolved to that frame's text)
 pub fn get_ocr_text_by_frame(conn: &Connection, frame_id: i64) -> Result<Option<OcrText>> {
     let mut stmt = conn.prepare(
-        "SELECT id, frame_id, text, text_json, confidence FROM ocr_text WHERE frame_id = ?1",
+        r#"SELECT o.id, o.frame_id, COALESCE(src.text, o.text),
+           COALESCE(src.text_json, o.text_json), COALESCE(src.confidence, o.confidence),
+           COALESCE(src.language, o.language)
+           FROM ocr_text o
+           LEFT JOIN ocr_text src ON src.frame_id = o.same_as_frame_id
+           WHERE o.frame_id = ?1"#,
     )?;
 
     let ocr = stmt.query_row(params![frame_id], |row| {
@@ -568,6 +1067,7 @@ pub fn get_ocr_text_by_frame(conn: &Connection, frame_id: i64) -> Result<Option<
             text: row.get(2)?,
             text_json: row.get(3)?,
             confidence: row.get(4)?,
+            language: row.get(5)?,
         })
     });
 
@@ -578,6 +1078,181 @@ pub fn get_ocr_text_by_frame(conn: &Connection, frame_id: i64) -> Result<Option<
     }
 }
 
+/// Insert the positioned lines of an OCR result
+pub fn insert_ocr_lines(
+    conn: &Connection,
+    ocr_text_id: i64,
+    frame_id: i64,
+    lines: &[OcrLineBox],
+) -> Result<()> {
+    let mut stmt = conn.prepare(
+        r#"INSERT INTO ocr_lines (ocr_text_id, frame_id, line_index, text, confidence, x, y, w, h)
+           VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)"#,
+    )?;
+
+    for (index, line) in lines.iter().enumerate() {
+        stmt.execute(params![
+            ocr_text_id,
+            frame_id,
+            index as i64,
+            line.text,
+            line.confidence,
+            line.bbox.x,
+            line.bbox.y,
+            line.bbox.w,
+            line.bbox.h,
+        ])?;
+    }
+
+    Ok(())
+}
+
+/// Get positioned OCR lines for a frame.
+///
+/// Frames indexed before `ocr_lines` existed fall back to the word boxes in
+/// `text_json`, normalized with the chunk dimensions.
+pub fn get_ocr_lines_by_frame(conn: &Connection, frame_id: i64) -> Result<Vec<OcrLineBox>> {
+    // Deduplicated frames share the lines of the frame holding the text
+    let frame_id = match conn.query_row(
+        "SELECT same_as_frame_id FROM ocr_text WHERE frame_id = ?1",
+        params![frame_id],
+        |row| row.get::<_, Option<i64>>(0),
+    ) {
+        Ok(Some(source)) => source,
+        Ok(None) | Err(rusqlite::Error::QueryReturnedNoRows) => frame_id,
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut stmt = conn.prepare(
+        r#"SELECT text, confidence, x, y, w, h FROM ocr_lines
+           WHERE frame_id = ?1
+           ORDER BY line_index"#,
+    )?;
+
+    let lines = stmt
+        .query_map(params![frame_id], row_to_ocr_line)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if !lines.is_empty() {
+        return Ok(lines);
+    }
+
+    let legacy = conn.query_row(
+        r#"SELECT o.text_json, c.width, c.height
+           FROM ocr_text o
+           JOIN frames f ON o.frame_id = f.id
+           JOIN video_chunks c ON f.video_chunk_id = c.id
+           WHERE o.frame_id = ?1"#,
+        params![frame_id],
+        |row| {
+            Ok((
+                row.get::<_, Option<String>>(0)?,
+                row.get::<_, Option<u32>>(1)?,
+                row.get::<_, Option<u32>>(2)?,
+            ))
+        },
+    );
+
+    match legacy {
+        Ok((Some(text_json), Some(width), Some(height))) => {
+            Ok(parse_ocr_text_json(&text_json, width, height))
+        }
+        Ok(_) | Err(rusqlite::Error::QueryReturnedNoRows) => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Find OCR lines whose center lies inside a normalized region, newest first.
+/// An optional `query` keeps only lines containing it (case-insensitive).
+pub fn search_ocr_in_region(
+    conn: &Connection,
+    region: &BoundingBox,
+    query: Option<&str>,
+    limit: i64,
+) -> Result<Vec<(OcrLineBox, Frame)>> {
+    let mut stmt = conn.prepare(
+        r#"SELECT f.id, f.video_chunk_id, f.offset_index, f.timestamp, f.app_name,
+           f.window_name, f.browser_url, f.focused, f.frame_hash,
+           l.text, l.confidence, l.x, l.y, l.w, l.h
+           FROM ocr_lines l
+           JOIN frames f ON l.frame_id = f.id
+           WHERE l.x + l.w / 2.0 BETWEEN ?1 AND ?1 + ?3
+             AND l.y + l.h / 2.0 BETWEEN ?2 AND ?2 + ?4
+             AND (?5 IS NULL OR instr(lower(l.text), lower(?5)) > 0)
+           ORDER BY f.timestamp DESC, l.line_index
+           LIMIT ?6"#,
+    )?;
+
+    let results = stmt
+        .query_map(
+            params![region.x, region.y, region.w, region.h, query, limit],
+            |row| {
+                let frame = row_to_frame(row)?;
+                let line = OcrLineBox {
+                    text: row.get(9)?,
+                    confidence: row.get(10)?,
+                    bbox: BoundingBox {
+                        x: row.get(11)?,
+                        y: row.get(12)?,
+                        w: row.get(13)?,
+                        h: row.get(14)?,
+                    },
+                };
+                Ok((line, frame))
+            },
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(results)
+}
+
+/// Parse the serialized OCR lines in `text_json` (pixel word boxes) into
+/// normalized line boxes. Malformed JSON yields no lines.
+fn parse_ocr_text_json(text_json: &str, width: u32, height: u32) -> Vec<OcrLineBox> {
+    if width == 0 || height == 0 {
+        return Vec::new();
+    }
+
+    let Ok(serde_json::Value::Array(lines)) = serde_json::from_str::<serde_json::Value>(text_json) else {
+        return Vec::new();
+    };
+
+    let (fw, fh) = (width as f64, height as f64);
+
+    lines
+        .iter()
+        .filter_map(|line| {
+            let words = line.get("words")?.as_array()?;
+            let mut bounds: Option<(f64, f64, f64, f64)> = None;
+            let mut confidence = 0.0;
+
+            for word in words {
+                let field = |name: &str| word.get(name).and_then(|v| v.as_f64()).unwrap_or(0.0);
+                let (x, y) = (field("x"), field("y"));
+                let (right, bottom) = (x + field("width"), y + field("height"));
+                confidence += field("confidence");
+
+                bounds = Some(match bounds {
+                    Some((l, t, r, b)) => (l.min(x), t.min(y), r.max(right), b.max(bottom)),
+                    None => (x, y, right, bottom),
+                });
+            }
+
+            let (left, top, right, bottom) = bounds?;
+            Some(OcrLineBox {
+                text: line.get("text")?.as_str()?.to_string(),
+                confidence: Some(confidence / words.len() as f64),
+                bbox: BoundingBox {
+                    x: left / fw,
+                    y: top / fh,
+                    w: (right - left) / fw,
+                    h: (bottom - top) / fh,
+                },
+            })
+        })
+        .collect()
+}
+
 /// Get OCR statistics
 pub fn get_ocr_stats(conn: &Connection) -> Result<OcrStats> {
     let total_frames: i64 = conn.query_row("SELECT COUNT(*) FROM frames", [], |row| row.get(0))?;
@@ -595,7 +1270,7 @@ pub fn get_ocr_stats(conn: &Connection) -> Result<OcrStats> {
         r#"SELECT COUNT(DISTINCT o.frame_id)
            FROM ocr_text o
            JOIN frames f ON o.frame_id = f.id
-           WHERE f.timestamp >= datetime('now', '-1 hour')"#,
+           WHERE datetime(f.timestamp) >= datetime('now', '-1 hour')"#,
         [],
         |row| row.get(0),
     )?;
@@ -625,13 +1300,27 @@ pub fn get_ocr_stats(conn: &Connection) -> Result<OcrStats> {
 }
 
 /// Get total count of search results
-pub fn get_search_count(conn: &Connection, query: &str) -> Result<i64> {
+pub fn get_search_count(conn: &Connection, query: &str, focused_only: bool) -> Result<i64> {
+    search_count_table(conn, "ocr_text_fts", query, focused_only)
+}
+
+/// Get total count of substring search results (see [`search_ocr_substring`])
+pub fn get_substring_search_count(conn: &Connection, query: &str, focused_only: bool) -> Result<i64> {
+    search_count_table(conn, "ocr_text_trigram", query, focused_only)
+}
+
+fn search_count_table(conn: &Connection, fts_table: &str, query: &str, focused_only: bool) -> Result<i64> {
     let count: i64 = conn.query_row(
-        r#"SELECT COUNT(*)
-           FROM ocr_text o
-           JOIN ocr_text_fts fts ON o.id = fts.rowid
-           WHERE ocr_text_fts MATCH ?1"#,
-        params![query],
+        &format!(
+            r#"SELECT COUNT(*)
+               FROM ocr_text o
+               JOIN {table} fts ON o.id = fts.rowid
+               JOIN frames f ON o.frame_id = f.id
+               WHERE {table} MATCH ?1
+                 AND (?2 = 0 OR f.focused = 1)"#,
+            table = fts_table
+        ),
+        params![query, focused_only],
         |row| row.get(0),
     )?;
     Ok(count)
@@ -655,24 +1344,198 @@ pub fn get_last_frame_hash(conn: &Connection, chunk_id: i64) -> Result<Option<i6
     }
 }
 
-/// Count duplicate frames skipped (frames with same hash as previous)
-pub fn get_skipped_frame_count(conn: &Connection) -> Result<i64> {
-    // Count frames where the previous frame in the same chunk has the same hash
-    let count: i64 = conn.query_row(
-        r#"SELECT COUNT(*) FROM frames f1
-           WHERE EXISTS (
-               SELECT 1 FROM frames f2
-               WHERE f2.video_chunk_id = f1.video_chunk_id
-               AND f2.offset_index = f1.offset_index - 1
-               AND f2.frame_hash = f1.frame_hash
-               AND f1.frame_hash IS NOT NULL
-           )"#,
-        [],
-        |row| row.get(0),
-    )?;
+/// Find frames whose perceptual hash is within `max_distance` bits of `target_hash`
+///
+/// Uses the same Hamming distance as the recorder's deduplication. Hashes are
+/// compared in Rust over a scan of the frames table, so this is linear in the
+/// number of frames; results are ordered by distance, then newest first.
+pub fn find_similar_frames(
+    conn: &Connection,
+    target_hash: i64,
+    max_distance: u32,
+    limit: i64,
+) -> Result<Vec<SimilarFrame>> {
+    let limit = limit.max(0) as usize;
+    if limit == 0 {
+        return Ok(Vec::new());
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT id, frame_hash FROM frames WHERE frame_hash IS NOT NULL",
+    )?;
+    let mut rows = stmt.query([])?;
+
+    let mut matches: Vec<(u32, i64)> = Vec::new();
+    while let Some(row) = rows.next()? {
+        let id: i64 = row.get(0)?;
+        let hash: i64 = row.get(1)?;
+        let distance = (hash ^ target_hash).count_ones();
+        if distance <= max_distance {
+            matches.push((distance, id));
+        }
+    }
+
+    matches.sort_by_key(|&(distance, id)| (distance, std::cmp::Reverse(id)));
+    matches.truncate(limit);
+
+    let mut frames = Vec::with_capacity(matches.len());
+    for (distance, id) in matches {
+        if let Some(frame) = get_frame(conn, id)? {
+            frames.push(SimilarFrame { frame, distance });
+        }
+    }
+
+    Ok(frames)
+}
+
+/// Record captured and skipped frame counts for a video chunk (replaces previous values)
+pub fn upsert_recording_stats(
+    conn: &Connection,
+    chunk_id: i64,
+    captured_frames: i64,
+    skipped_frames: i64,
+) -> Result<()> {
+    conn.execute(
+        r#"INSERT INTO recording_stats (video_chunk_id, captured_frames, skipped_frames, updated_at)
+           VALUES (?1, ?2, ?3, datetime('now'))
+           ON CONFLICT(video_chunk_id) DO UPDATE SET
+               captured_frames = excluded.captured_frames,
+               skipped_frames = excluded.skipped_frames,
+               updated_at = excluded.updated_at"#,
+        params![chunk_id, captured_frames, skipped_frames],
+    )?;
+    Ok(())
+}
+
+/// Get overall deduplication statistics from recorded counters
+pub fn get_dedup_stats(conn: &Connection) -> Result<DedupStats> {
+    let (captured_frames, skipped_frames, chunks): (i64, i64, i64) = conn.query_row(
+        r#"SELECT COALESCE(SUM(captured_frames), 0), COALESCE(SUM(skipped_frames), 0), COUNT(*)
+           FROM recording_stats"#,
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )?;
+
+    let total = captured_frames + skipped_frames;
+    let dedup_ratio = if total > 0 {
+        skipped_frames as f64 / total as f64
+    } else {
+        0.0
+    };
+
+    Ok(DedupStats {
+        captured_frames,
+        skipped_frames,
+        dedup_ratio,
+        chunks,
+    })
+}
+
+/// Count duplicate frames skipped (frames with same hash as previous)
+pub fn get_skipped_frame_count(conn: &Connection) -> Result<i64> {
+    // Count frames where the previous frame in the same chunk has the same hash
+    let count: i64 = conn.query_row(
+        r#"SELECT COUNT(*) FROM frames f1
+           WHERE EXISTS (
+               SELECT 1 FROM frames f2
+               WHERE f2.video_chunk_id = f1.video_chunk_id
+               AND f2.offset_index = f1.offset_index - 1
+               AND f2.frame_hash = f1.frame_hash
+               AND f1.frame_hash IS NOT NULL
+           )"#,
+        [],
+        |row| row.get(0),
+    )?;
     Ok(count)
 }
 
+/// Count frames per time bucket between `start` and `end`.
+///
+/// Buckets are aligned to multiples of `bucket_secs` since the Unix epoch, so
+/// a 3600 second bucket starts on the hour. Empty buckets are omitted. Each
+/// bucket is `(start, frames, focused frames)`; the difference is idle time.
+pub fn get_activity_histogram(
+    conn: &Connection,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    bucket_secs: i64,
+) -> Result<Vec<(DateTime<Utc>, i64, i64)>> {
+    if bucket_secs <= 0 {
+        return Err(DatabaseError::InvalidQuery(format!("bucket size must be positive, got {}", bucket_secs)));
+    }
+
+    let mut stmt = conn.prepare(
+        r#"SELECT (CAST(strftime('%s', timestamp) AS INTEGER) / ?3) * ?3 AS bucket,
+           COUNT(*), SUM(focused != 0)
+           FROM frames
+           WHERE timestamp >= ?1 AND timestamp <= ?2
+           GROUP BY bucket
+           ORDER BY bucket"#,
+    )?;
+
+    let buckets = stmt
+        .query_map(
+            params![start.to_rfc3339(), end.to_rfc3339(), bucket_secs],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, i64>(2)?)),
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(buckets
+        .into_iter()
+        .filter_map(|(secs, count, focused)| {
+            DateTime::from_timestamp(secs, 0).map(|dt| (dt, count, focused))
+        })
+        .collect())
+}
+
+/// Count frames per time bucket and app between `start` and `end`.
+///
+/// Same bucketing as [`get_activity_histogram`], with one row per app seen
+/// in each bucket (frames without an app name are grouped under `None`).
+pub fn get_activity_histogram_by_app(
+    conn: &Connection,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    bucket_secs: i64,
+) -> Result<Vec<ActivityBucket>> {
+    if bucket_secs <= 0 {
+        return Err(DatabaseError::InvalidQuery(format!("bucket size must be positive, got {}", bucket_secs)));
+    }
+
+    let mut stmt = conn.prepare(
+        r#"SELECT (CAST(strftime('%s', timestamp) AS INTEGER) / ?3) * ?3 AS bucket,
+           app_name, COUNT(*)
+           FROM frames
+           WHERE timestamp >= ?1 AND timestamp <= ?2
+           GROUP BY bucket, app_name
+           ORDER BY bucket, app_name"#,
+    )?;
+
+    let rows = stmt
+        .query_map(
+            params![start.to_rfc3339(), end.to_rfc3339(), bucket_secs],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, i64>(2)?,
+                ))
+            },
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|(secs, app_name, frame_count)| {
+            DateTime::from_timestamp(secs, 0).map(|bucket_start| ActivityBucket {
+                bucket_start,
+                app_name,
+                frame_count,
+            })
+        })
+        .collect())
+}
+
 // Helper functions
 
 fn row_to_frame(row: &Row) -> rusqlite::Result<Frame> {
@@ -689,13 +1552,99 @@ fn row_to_frame(row: &Row) -> rusqlite::Result<Frame> {
     })
 }
 
+fn row_to_frame_with_ocr(row: &Row) -> rusqlite::Result<FrameWithOcr> {
+    let ocr_text = if let Ok(ocr_id) = row.get::<_, i64>(9) {
+        Some(OcrText {
+            id: ocr_id,
+            frame_id: row.get(10)?,
+            text: row.get(11)?,
+            text_json: row.get(12)?,
+            confidence: row.get(13)?,
+            language: row.get(14)?,
+        })
+    } else {
+        None
+    };
+
+    Ok(FrameWithOcr {
+        id: row.get(0)?,
+        video_chunk_id: row.get(1)?,
+        offset_index: row.get(2)?,
+        timestamp: parse_datetime(row, 3)?,
+        app_name: row.get(4)?,
+        window_name: row.get(5)?,
+        browser_url: row.get(6)?,
+        focused: row.get::<_, i32>(7)? != 0,
+        frame_hash: row.get(8)?,
+        ocr_text,
+    })
+}
+
+fn row_to_ocr_with_frame(row: &Row) -> rusqlite::Result<(OcrText, Frame)> {
+    let ocr = OcrText {
+        id: row.get(0)?,
+        frame_id: row.get(1)?,
+        text: row.get(2)?,
+        text_json: row.get(3)?,
+        confidence: row.get(4)?,
+        language: row.get(14)?,
+    };
+    let frame = Frame {
+        id: row.get(5)?,
+        video_chunk_id: row.get(6)?,
+        offset_index: row.get(7)?,
+        timestamp: parse_datetime(row, 8)?,
+        app_name: row.get(9)?,
+        window_name: row.get(10)?,
+        browser_url: row.get(11)?,
+        focused: row.get::<_, i32>(12)? != 0,
+        frame_hash: row.get(13)?,
+    };
+    Ok((ocr, frame))
+}
+
+fn row_to_transcription_with_chunk(row: &Row) -> rusqlite::Result<(AudioTranscription, AudioChunk)> {
+    let transcription = AudioTranscription {
+        id: row.get(0)?,
+        audio_chunk_id: row.get(1)?,
+        transcription: row.get(2)?,
+        timestamp: parse_datetime(row, 3)?,
+        speaker_id: row.get(4)?,
+        start_time: row.get(5)?,
+        end_time: row.get(6)?,
+        language: row.get(13)?,
+    };
+    let chunk = AudioChunk {
+        id: row.get(7)?,
+        file_path: row.get(8)?,
+        device_name: row.get(9)?,
+        is_input_device: row.get::<_, Option<i32>>(10)?.map(|v| v != 0),
+        timestamp: parse_datetime(row, 11)?,
+        pruned: row.get(12)?,
+    };
+    Ok((transcription, chunk))
+}
+
+fn row_to_ocr_line(row: &Row) -> rusqlite::Result<OcrLineBox> {
+    Ok(OcrLineBox {
+        text: row.get(0)?,
+        confidence: row.get(1)?,
+        bbox: BoundingBox {
+            x: row.get(2)?,
+            y: row.get(3)?,
+            w: row.get(4)?,
+            h: row.get(5)?,
+        },
+    })
+}
+
 fn parse_datetime(row: &Row, idx: usize) -> rusqlite::Result<DateTime<Utc>> {
     let s: String = row.get(idx)?;
     DateTime::parse_from_rfc3339(&s)
         .map(|dt| dt.with_timezone(&Utc))
         .or_else(|_| {
-            // Try SQLite datetime format
-            chrono::NaiveDateTime::parse_from_str(&s, "%Y-%m-%d %H:%M:%S")
+            // Try SQLite datetime format (fractional seconds optional)
+            chrono::NaiveDateTime::parse_from_str(&s, "%Y-%m-%d %H:%M:%S%.f")
                 .map(|dt| dt.and_utc())
         })
         .map_err(|e| rusqlite::Error::FromSqlConversionFailure(
@@ -712,8 +1661,13 @@ fn parse_datetime(row: &Row, idx: usize) -> rusqlite::Result<DateTime<Utc>> {
 /// Insert a new audio chunk
 pub fn insert_audio_chunk(conn: &Connection, chunk: &NewAudioChunk) -> Result<i64> {
     conn.execute(
-        "INSERT INTO audio_chunks (file_path, device_name, is_input_device) VALUES (?1, ?2, ?3)",
-        params![chunk.file_path, chunk.device_name, chunk.is_input_device.map(|b| b as i32)],
+        "INSERT INTO audio_chunks (file_path, device_name, is_input_device, timestamp) VALUES (?1, ?2, ?3, ?4)",
+        params![
+            chunk.file_path,
+            chunk.device_name,
+            chunk.is_input_device.map(|b| b as i32),
+            chunk.timestamp.to_rfc3339(),
+        ],
     )?;
     Ok(conn.last_insert_rowid())
 }
@@ -721,7 +1675,7 @@ pub fn insert_audio_chunk(conn: &Connection, chunk: &NewAudioChunk) -> Result<i6
 /// Get audio chunk by ID
 pub fn get_audio_chunk(conn: &Connection, id: i64) -> Result<Option<AudioChunk>> {
     let mut stmt = conn.prepare(
-        "SELECT id, file_path, device_name, is_input_device, timestamp FROM audio_chunks WHERE id = ?1",
+        "SELECT id, file_path, device_name, is_input_device, timestamp, pruned_at IS NOT NULL FROM audio_chunks WHERE id = ?1",
     )?;
 
     let chunk = stmt.query_row(params![id], |row| {
@@ -731,6 +1685,7 @@ pub fn get_audio_chunk(conn: &Connection, id: i64) -> Result<Option<AudioChunk>>
             device_name: row.get(2)?,
             is_input_device: row.get::<_, Option<i32>>(3)?.map(|v| v != 0),
             timestamp: parse_datetime(row, 4)?,
+            pruned: row.get(5)?,
         })
     });
 
@@ -744,10 +1699,11 @@ pub fn get_audio_chunk(conn: &Connection, id: i64) -> Result<Option<AudioChunk>>
 /// Get audio chunks without transcription (for batch processing)
 pub fn get_audio_chunks_without_transcription(conn: &Connection, limit: i64) -> Result<Vec<AudioChunk>> {
     let mut stmt = conn.prepare(
-        r#"SELECT ac.id, ac.file_path, ac.device_name, ac.is_input_device, ac.timestamp
+        r#"SELECT ac.id, ac.file_path, ac.device_name, ac.is_input_device, ac.timestamp,
+           ac.pruned_at IS NOT NULL
            FROM audio_chunks ac
            LEFT JOIN audio_transcriptions at ON ac.id = at.audio_chunk_id
-           WHERE at.id IS NULL
+           WHERE at.id IS NULL AND ac.pruned_at IS NULL
            ORDER BY ac.timestamp ASC
            LIMIT ?1"#,
     )?;
@@ -760,6 +1716,7 @@ pub fn get_audio_chunks_without_transcription(conn: &Connection, limit: i64) ->
                 device_name: row.get(2)?,
                 is_input_device: row.get::<_, Option<i32>>(3)?.map(|v| v != 0),
                 timestamp: parse_datetime(row, 4)?,
+                pruned: row.get(5)?,
             })
         })?
         .collect::<Result<Vec<_>, _>>()?;
@@ -767,6 +1724,281 @@ pub fn get_audio_chunks_without_transcription(conn: &Connection, limit: i64) ->
     Ok(chunks)
 }
 
+/// Delete the audio files of chunks recorded before `cutoff`
+///
+/// The chunk rows, their transcriptions and the FTS index are kept so spoken
+/// text stays searchable; chunks are marked pruned and skipped by the indexer.
+/// `file_path` is resolved against `data_dir`. Files that are already missing
+/// are still marked.
+pub fn delete_audio_files_older_than(
+    conn: &Connection,
+    data_dir: &Path,
+    cutoff: DateTime<Utc>,
+) -> Result<PrunedAudio> {
+    let mut stmt = conn.prepare(
+        r#"SELECT id, file_path FROM audio_chunks
+           WHERE pruned_at IS NULL AND julianday(timestamp) < julianday(?1)
+           ORDER BY timestamp ASC"#,
+    )?;
+    let candidates = stmt
+        .query_map(params![cutoff.to_rfc3339()], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut pruned = PrunedAudio::default();
+    for (id, file_path) in candidates {
+        let path = data_dir.join(&file_path);
+        let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        match std::fs::remove_file(&path) {
+            Ok(()) => pruned.bytes_freed += size,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => {
+                return Err(std::io::Error::new(e.kind(), format!("failed to delete {}: {}", path.display(), e)).into())
+            }
+        }
+
+        conn.execute(
+            "UPDATE audio_chunks SET pruned_at = datetime('now') WHERE id = ?1",
+            params![id],
+        )?;
+        pruned.chunks += 1;
+    }
+
+    Ok(pruned)
+}
+
+/// Delete the oldest recordings until the database and media fit in `max_bytes`
+///
+/// Usage is the database file (with its WAL) plus the file of every video and
+/// audio chunk, resolved against `data_dir`. Chunks are removed oldest first
+/// across both kinds: a video chunk loses its file, frames and OCR text; an
+/// audio chunk loses only its file and is marked pruned, as with
+/// [`delete_audio_files_older_than`]. Video chunks in `protected_chunks` (the
+/// ones being written) are never removed, and chunks whose file can't be
+/// deleted are skipped. Rows deleted from the database free pages for reuse
+/// but don't shrink the file, so only media files count as freed.
+pub fn enforce_size_limit(
+    conn: &Connection,
+    data_dir: &Path,
+    max_bytes: u64,
+    protected_chunks: &[i64],
+) -> Result<PrunedStorage> {
+    let mut stmt = conn.prepare(
+        r#"SELECT 0, id, file_path, julianday(created_at) FROM video_chunks
+           UNION ALL
+           SELECT 1, id, file_path, julianday(timestamp) FROM audio_chunks WHERE pruned_at IS NULL
+           ORDER BY 4 ASC, 1 ASC, 2 ASC"#,
+    )?;
+    let chunks = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, bool>(0)?, row.get::<_, i64>(1)?, row.get::<_, String>(2)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let chunks: Vec<_> = chunks
+        .into_iter()
+        .map(|(is_audio, id, file_path)| {
+            let path = data_dir.join(file_path);
+            let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            (is_audio, id, path, size)
+        })
+        .collect();
+
+    let mut pruned = PrunedStorage {
+        bytes_used: database_size(conn) + chunks.iter().map(|(_, _, _, size)| size).sum::<u64>(),
+        ..PrunedStorage::default()
+    };
+
+    for (is_audio, id, path, size) in chunks {
+        if pruned.bytes_used <= max_bytes {
+            break;
+        }
+        if !is_audio && protected_chunks.contains(&id) {
+            continue;
+        }
+
+        match std::fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => {
+                warn!("size limit: failed to delete {}: {}", path.display(), e);
+                pruned.skipped += 1;
+                continue;
+            }
+        }
+
+        if is_audio {
+            conn.execute(
+                "UPDATE audio_chunks SET pruned_at = datetime('now') WHERE id = ?1",
+                params![id],
+            )?;
+            pruned.audio_chunks += 1;
+        } else {
+            delete_video_chunk(conn, id)?;
+            pruned.video_chunks += 1;
+        }
+
+        info!(
+            "size limit: removed {} chunk {} ({}, {:.1} MB)",
+            if is_audio { "audio" } else { "video" },
+            id,
+            path.display(),
+            size as f64 / (1024.0 * 1024.0)
+        );
+        pruned.bytes_used -= size;
+        pruned.bytes_freed += size;
+    }
+
+    Ok(pruned)
+}
+
+/// Delete a video chunk with its frames and OCR text
+///
+/// Deduplicated OCR rows in other chunks that point at this chunk's frames
+/// get a copy of the text first, so they stay searchable.
+fn delete_video_chunk(conn: &Connection, chunk_id: i64) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        r#"UPDATE ocr_text
+           SET text = (SELECT src.text FROM ocr_text src WHERE src.frame_id = ocr_text.same_as_frame_id LIMIT 1),
+               text_json = (SELECT src.text_json FROM ocr_text src WHERE src.frame_id = ocr_text.same_as_frame_id LIMIT 1),
+               confidence = (SELECT src.confidence FROM ocr_text src WHERE src.frame_id = ocr_text.same_as_frame_id LIMIT 1),
+               same_as_frame_id = NULL
+           WHERE same_as_frame_id IN (SELECT id FROM frames WHERE video_chunk_id = ?1)
+             AND frame_id NOT IN (SELECT id FROM frames WHERE video_chunk_id = ?1)"#,
+        params![chunk_id],
+    )?;
+    tx.execute(
+        "DELETE FROM ocr_text WHERE frame_id IN (SELECT id FROM frames WHERE video_chunk_id = ?1)",
+        params![chunk_id],
+    )?;
+    tx.execute("DELETE FROM frames WHERE video_chunk_id = ?1", params![chunk_id])?;
+    tx.execute("DELETE FROM video_chunks WHERE id = ?1", params![chunk_id])?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Chunks this recent are skipped by [`find_orphaned_chunks`], since the
+/// recorder may still be writing them
+const ORPHAN_GRACE_PERIOD: Duration = Duration::from_secs(10 * 60);
+
+/// Find chunk rows whose media file is missing, and media files with no row
+///
+/// `file_path` is resolved against `data_dir` as elsewhere. Pruned audio
+/// chunks have no file by design and aren't reported. Untracked files are the
+/// `.mp4` files under `videos/` and `.wav` files under `audio/` that no chunk
+/// refers to. Chunks and files from the last ten minutes are left out, as a
+/// running recorder may not have created the file or inserted the row yet.
+pub fn find_orphaned_chunks(conn: &Connection, data_dir: &Path) -> Result<OrphanedChunks> {
+    let cutoff = Utc::now() - chrono::Duration::seconds(ORPHAN_GRACE_PERIOD.as_secs() as i64);
+
+    let mut stmt = conn.prepare(
+        r#"SELECT 0, id, file_path, created_at, 0 FROM video_chunks
+           UNION ALL
+           SELECT 1, id, file_path, timestamp, pruned_at IS NOT NULL FROM audio_chunks
+           ORDER BY 1, 2"#,
+    )?;
+    let chunks = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, bool>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+                parse_datetime(row, 3)?,
+                row.get::<_, bool>(4)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut orphans = OrphanedChunks::default();
+    let mut tracked = std::collections::HashSet::new();
+    for (is_audio, id, file_path, created_at, pruned) in chunks {
+        let path = data_dir.join(&file_path);
+        let missing = !pruned && created_at < cutoff && !path.exists();
+        tracked.insert(path);
+        if !missing {
+            continue;
+        }
+
+        let orphan = OrphanedChunk { id, file_path };
+        if is_audio {
+            orphans.audio_chunks.push(orphan);
+        } else {
+            orphans.video_chunks.push(orphan);
+        }
+    }
+
+    let cutoff = std::time::SystemTime::now() - ORPHAN_GRACE_PERIOD;
+    let mut files = Vec::new();
+    collect_media_files(&data_dir.join("videos"), "mp4", &mut files)?;
+    collect_media_files(&data_dir.join("audio"), "wav", &mut files)?;
+    orphans.untracked_files = files
+        .into_iter()
+        .filter(|path| !tracked.contains(path))
+        .filter(|path| {
+            std::fs::metadata(path)
+                .and_then(|m| m.modified())
+                .map_or(true, |modified| modified < cutoff)
+        })
+        .collect();
+    orphans.untracked_files.sort();
+
+    Ok(orphans)
+}
+
+/// Files with `extension` below `dir`, skipping `_`-prefixed working directories
+fn collect_media_files(dir: &Path, extension: &str, files: &mut Vec<std::path::PathBuf>) -> Result<()> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+
+    for entry in entries {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            if !entry.file_name().to_string_lossy().starts_with('_') {
+                collect_media_files(&path, extension, files)?;
+            }
+        } else if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case(extension)) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Remove the chunk rows reported by [`find_orphaned_chunks`]
+///
+/// Video chunks are deleted with their frames and OCR text. Audio chunks are
+/// marked pruned instead, as when retention deletes their file, so their
+/// transcriptions stay searchable. Untracked files are left alone.
+pub fn remove_orphaned_chunks(conn: &Connection, orphans: &OrphanedChunks) -> Result<()> {
+    for chunk in &orphans.video_chunks {
+        delete_video_chunk(conn, chunk.id)?;
+    }
+    for chunk in &orphans.audio_chunks {
+        conn.execute(
+            "UPDATE audio_chunks SET pruned_at = datetime('now') WHERE id = ?1 AND pruned_at IS NULL",
+            params![chunk.id],
+        )?;
+    }
+    Ok(())
+}
+
+/// Size of the database file and its WAL (0 for in-memory databases)
+fn database_size(conn: &Connection) -> u64 {
+    let Some(path) = conn.path().filter(|p| !p.is_empty()) else {
+        return 0;
+    };
+    ["", "-wal"]
+        .iter()
+        .filter_map(|suffix| std::fs::metadata(format!("{}{}", path, suffix)).ok())
+        .map(|m| m.len())
+        .sum()
+}
+
 /// Get total audio chunk count
 pub fn get_audio_chunk_count(conn: &Connection) -> Result<i64> {
     let count: i64 = conn.query_row("SELECT COUNT(*) FROM audio_chunks", [], |row| row.get(0))?;
@@ -777,8 +2009,8 @@ pub fn get_audio_chunk_count(conn: &Connection) -> Result<i64> {
 pub fn insert_audio_transcription(conn: &Connection, transcription: &NewAudioTranscription) -> Result<i64> {
     conn.execute(
         r#"INSERT INTO audio_transcriptions
-           (audio_chunk_id, transcription, timestamp, speaker_id, start_time, end_time)
-           VALUES (?1, ?2, ?3, ?4, ?5, ?6)"#,
+           (audio_chunk_id, transcription, timestamp, speaker_id, start_time, end_time, language)
+           VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"#,
         params![
             transcription.audio_chunk_id,
             transcription.transcription,
@@ -786,6 +2018,7 @@ pub fn insert_audio_transcription(conn: &Connection, transcription: &NewAudioTra
             transcription.speaker_id,
             transcription.start_time,
             transcription.end_time,
+            transcription.language,
         ],
     )?;
     Ok(conn.last_insert_rowid())
@@ -794,7 +2027,7 @@ pub fn insert_audio_transcription(conn: &Connection, transcription: &NewAudioTra
 /// Get transcription by audio chunk ID
 pub fn get_transcription_by_chunk(conn: &Connection, chunk_id: i64) -> Result<Option<AudioTranscription>> {
     let mut stmt = conn.prepare(
-        r#"SELECT id, audio_chunk_id, transcription, timestamp, speaker_id, start_time, end_time
+        r#"SELECT id, audio_chunk_id, transcription, timestamp, speaker_id, start_time, end_time, language
            FROM audio_transcriptions WHERE audio_chunk_id = ?1"#,
     )?;
 
@@ -807,6 +2040,7 @@ pub fn get_transcription_by_chunk(conn: &Connection, chunk_id: i64) -> Result<Op
             speaker_id: row.get(4)?,
             start_time: row.get(5)?,
             end_time: row.get(6)?,
+            language: row.get(7)?,
         })
     });
 
@@ -820,7 +2054,7 @@ pub fn get_transcription_by_chunk(conn: &Connection, chunk_id: i64) -> Result<Op
 /// Get all transcriptions for an audio chunk (ordered by start_time)
 pub fn get_transcriptions_by_chunk(conn: &Connection, chunk_id: i64) -> Result<Vec<AudioTranscription>> {
     let mut stmt = conn.prepare(
-        r#"SELECT id, audio_chunk_id, transcription, timestamp, speaker_id, start_time, end_time
+        r#"SELECT id, audio_chunk_id, transcription, timestamp, speaker_id, start_time, end_time, language
            FROM audio_transcriptions
            WHERE audio_chunk_id = ?1
            ORDER BY start_time ASC NULLS LAST"#,
@@ -836,6 +2070,7 @@ pub fn get_transcriptions_by_chunk(conn: &Connection, chunk_id: i64) -> Result<V
                 speaker_id: row.get(4)?,
                 start_time: row.get(5)?,
                 end_time: row.get(6)?,
+                language: row.get(7)?,
             })
         })?
         .collect::<std::result::Result<Vec<_>, _>>()?;
@@ -844,19 +2079,39 @@ pub fn get_transcriptions_by_chunk(conn: &Connection, chunk_id: i64) -> Result<V
 }
 
 /// Get total count of audio chunks
-pub fn get_total_audio_chunk_count(conn: &Connection, device: Option<&str>) -> Result<i64> {
-    let count: i64 = if let Some(dev) = device {
-        conn.query_row(
-            "SELECT COUNT(*) FROM audio_chunks WHERE device_name = ?1",
-            params![dev],
-            |row| row.get(0),
-        )?
-    } else {
-        conn.query_row("SELECT COUNT(*) FROM audio_chunks", [], |row| row.get(0))?
-    };
+///
+/// Takes the same filters as [`get_audio_chunks_paginated`], so the count
+/// matches the pages it returns.
+pub fn get_total_audio_chunk_count(conn: &Connection, device: Option<&str>, is_input: Option<bool>) -> Result<i64> {
+    let (filter, params) = audio_chunk_filter(device, is_input);
+    let query = format!("SELECT COUNT(*) FROM audio_chunks ac{}", filter);
+    let params: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+    let count: i64 = conn.query_row(&query, params.as_slice(), |row| row.get(0))?;
     Ok(count)
 }
 
+/// WHERE clause (empty when unfiltered) over `audio_chunks ac` and its parameters
+fn audio_chunk_filter(device: Option<&str>, is_input: Option<bool>) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
+    let mut conditions = Vec::new();
+    let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+    if let Some(dev) = device {
+        conditions.push("ac.device_name = ?");
+        params.push(Box::new(dev.to_string()));
+    }
+
+    if let Some(input) = is_input {
+        conditions.push("ac.is_input_device = ?");
+        params.push(Box::new(input as i32));
+    }
+
+    if conditions.is_empty() {
+        (String::new(), params)
+    } else {
+        (format!(" WHERE {}", conditions.join(" AND ")), params)
+    }
+}
+
 /// Get count of chunks with transcription
 pub fn get_transcription_count(conn: &Connection) -> Result<i64> {
     let count: i64 = conn.query_row(
@@ -868,101 +2123,327 @@ pub fn get_transcription_count(conn: &Connection) -> Result<i64> {
 }
 
 /// Full-text search on audio transcriptions
+///
+/// `device` restricts matches to one capture device, `is_input` to
+/// microphone (`true`) or loopback (`false`) audio and `language` to one
+/// ISO 639-1 code; `None` matches any.
 pub fn search_transcriptions(
     conn: &Connection,
     query: &str,
     limit: i64,
     offset: i64,
+    device: Option<&str>,
+    is_input: Option<bool>,
+    language: Option<&str>,
 ) -> Result<Vec<(AudioTranscription, AudioChunk)>> {
     let mut stmt = conn.prepare(
         r#"SELECT at.id, at.audio_chunk_id, at.transcription, at.timestamp,
            at.speaker_id, at.start_time, at.end_time,
-           ac.id, ac.file_path, ac.device_name, ac.is_input_device, ac.timestamp
+           ac.id, ac.file_path, ac.device_name, ac.is_input_device, ac.timestamp,
+           ac.pruned_at IS NOT NULL, at.language
            FROM audio_transcriptions at
            JOIN audio_fts fts ON at.id = fts.rowid
            JOIN audio_chunks ac ON at.audio_chunk_id = ac.id
            WHERE audio_fts MATCH ?1
+             AND (?4 IS NULL OR ac.device_name = ?4)
+             AND (?5 IS NULL OR ac.is_input_device = ?5)
+             AND (?6 IS NULL OR at.language = ?6)
            ORDER BY rank
            LIMIT ?2 OFFSET ?3"#,
     )?;
 
     let results = stmt
-        .query_map(params![query, limit, offset], |row| {
-            let transcription = AudioTranscription {
-                id: row.get(0)?,
-                audio_chunk_id: row.get(1)?,
-                transcription: row.get(2)?,
-                timestamp: parse_datetime(row, 3)?,
-                speaker_id: row.get(4)?,
-                start_time: row.get(5)?,
-                end_time: row.get(6)?,
-            };
-            let chunk = AudioChunk {
-                id: row.get(7)?,
-                file_path: row.get(8)?,
-                device_name: row.get(9)?,
-                is_input_device: row.get::<_, Option<i32>>(10)?.map(|v| v != 0),
-                timestamp: parse_datetime(row, 11)?,
-            };
-            Ok((transcription, chunk))
-        })?
+        .query_map(params![query, limit, offset, device, is_input, language], row_to_transcription_with_chunk)?
         .collect::<Result<Vec<_>, _>>()?;
 
     Ok(results)
 }
 
-/// Unified search across OCR and transcriptions
-pub fn search_all(
-    conn: &Connection,
-    query: &str,
-    limit: i64,
-    offset: i64,
-) -> Result<Vec<SearchResult>> {
-    let mut results = Vec::new();
+/// Maximum frames returned by [`get_context_at`]
+pub const CONTEXT_FRAME_LIMIT: i64 = 100;
 
-    // Search OCR with full limit first
-    let ocr_results = search_ocr(conn, query, limit, 0)?;
-    for (ocr, frame) in ocr_results {
-        results.push(SearchResult::Ocr { ocr, frame });
-    }
+/// Assumed length of transcriptions stored without segment timings
+/// (the recorder's default audio chunk length)
+const UNTIMED_TRANSCRIPTION_SECS: f64 = 30.0;
 
-    // Search audio with remaining limit
-    let remaining_limit = limit.saturating_sub(results.len() as i64);
-    if remaining_limit > 0 {
-        let audio_results = search_transcriptions(conn, query, remaining_limit, 0)?;
-        for (transcription, chunk) in audio_results {
-            results.push(SearchResult::Audio { transcription, chunk });
-        }
-    }
+/// How far before the window a transcription's chunk may start and still overlap it
+const AUDIO_CONTEXT_LOOKBACK_SECS: i64 = 120;
 
-    // Sort by timestamp (newest first) and apply pagination
-    // Note: This is a simple implementation. For production, use UNION in SQL
-    results.sort_by(|a, b| {
-        let ts_a = match a {
-            SearchResult::Ocr { frame, .. } => frame.timestamp,
-            SearchResult::Audio { transcription, .. } => transcription.timestamp,
-        };
-        let ts_b = match b {
-            SearchResult::Ocr { frame, .. } => frame.timestamp,
-            SearchResult::Audio { transcription, .. } => transcription.timestamp,
-        };
-        ts_b.cmp(&ts_a)
-    });
+/// Get the screen and audio context within `window_secs` of `at`
+///
+/// Returns frames with OCR in the window and the transcription segments
+/// overlapping it. When no frame was stored in the window (deduplication
+/// skips static screens), the last frame before `at` is returned instead.
+pub fn get_context_at(conn: &Connection, at: DateTime<Utc>, window_secs: i64) -> Result<MomentContext> {
+    let window = chrono::Duration::seconds(window_secs.max(0));
+    let (start, end) = (at - window, at + window);
 
-    // Apply offset and limit
-    let start = offset as usize;
-    let end = (offset + limit) as usize;
-    let paginated: Vec<_> = results.into_iter().skip(start).take(end - start).collect();
+    let mut frames = get_frames_with_ocr_in_range(conn, start, end, CONTEXT_FRAME_LIMIT, 0)?;
+    frames.reverse();
 
-    Ok(paginated)
-}
+    if frames.is_empty() {
+        let mut stmt = conn.prepare(
+            r#"SELECT f.id, f.video_chunk_id, f.offset_index, f.timestamp, f.app_name,
+               f.window_name, f.browser_url, f.focused, f.frame_hash,
+               o.id, o.frame_id, o.text, o.text_json, o.confidence, o.language
+               FROM frames f
+               LEFT JOIN ocr_text o ON f.id = o.frame_id
+               WHERE f.timestamp <= ?1
+               ORDER BY f.timestamp DESC
+               LIMIT 1"#,
+        )?;
+        frames = stmt
+            .query_map(params![at.to_rfc3339()], row_to_frame_with_ocr)?
+            .collect::<Result<Vec<_>, _>>()?;
+    }
 
-/// Get audio indexing statistics
-pub fn get_audio_stats(conn: &Connection) -> Result<AudioStats> {
-    let total_chunks: i64 = conn.query_row("SELECT COUNT(*) FROM audio_chunks", [], |row| row.get(0))?;
+    let nearest_frame_id = frames
+        .iter()
+        .min_by_key(|f| (f.timestamp - at).num_milliseconds().abs())
+        .map(|f| f.id);
+
+    Ok(MomentContext {
+        at,
+        window_secs: window.num_seconds(),
+        frames,
+        nearest_frame_id,
+        audio: get_transcriptions_overlapping(conn, start, end)?,
+    })
+}
 
-    let chunks_with_transcription: i64 = conn.query_row(
-        "SELECT COUNT(DISTINCT audio_chunk_id) FROM audio_transcriptions",
+/// Get transcription segments that overlap `start..=end`, oldest first
+pub fn get_transcriptions_overlapping(
+    conn: &Connection,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Vec<TimedTranscription>> {
+    // Transcriptions are stamped with their chunk's time, so look back far
+    // enough to catch chunks that started before the range
+    let mut stmt = conn.prepare(
+        r#"SELECT at.id, at.audio_chunk_id, at.transcription, at.timestamp,
+           at.speaker_id, at.start_time, at.end_time,
+           ac.id, ac.file_path, ac.device_name, ac.is_input_device, ac.timestamp,
+           ac.pruned_at IS NOT NULL, at.language
+           FROM audio_transcriptions at
+           JOIN audio_chunks ac ON at.audio_chunk_id = ac.id
+           WHERE at.timestamp >= ?1 AND at.timestamp <= ?2 AND at.transcription != ''
+           ORDER BY at.timestamp ASC, at.start_time ASC"#,
+    )?;
+    let lookback = start - chrono::Duration::seconds(AUDIO_CONTEXT_LOOKBACK_SECS);
+    let rows = stmt
+        .query_map(params![lookback.to_rfc3339(), end.to_rfc3339()], row_to_transcription_with_chunk)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let offset = |secs: f64| chrono::Duration::milliseconds((secs * 1000.0) as i64);
+    let audio = rows
+        .into_iter()
+        .map(|(transcription, chunk)| {
+            let segment_start = transcription.start_time.unwrap_or(0.0);
+            let segment_end = transcription
+                .end_time
+                .unwrap_or(segment_start + UNTIMED_TRANSCRIPTION_SECS);
+            TimedTranscription {
+                start: transcription.timestamp + offset(segment_start),
+                end: transcription.timestamp + offset(segment_end),
+                transcription,
+                chunk,
+            }
+        })
+        .filter(|segment| segment.start <= end && segment.end >= start)
+        .collect();
+
+    Ok(audio)
+}
+
+/// Get subtitle cues for a video chunk from the transcriptions overlapping it
+///
+/// Chunks are constant frame rate, so the chunk starts `offset_index / fps`
+/// before any of its frames and lasts `(last offset + 1) / fps`. Cue times are
+/// relative to that start, and segments straddling either end are clamped to
+/// the chunk. Returns `None` if the chunk does not exist.
+pub fn get_chunk_subtitle_cues(conn: &Connection, chunk_id: i64) -> Result<Option<Vec<SubtitleCue>>> {
+    let Some(chunk) = get_video_chunk(conn, chunk_id)? else {
+        return Ok(None);
+    };
+    let fps = chunk.fps.filter(|f| *f > 0.0).unwrap_or(1.0);
+
+    let span = conn.query_row(
+        r#"SELECT offset_index, timestamp,
+           (SELECT MAX(offset_index) FROM frames WHERE video_chunk_id = ?1)
+           FROM frames
+           WHERE video_chunk_id = ?1
+           ORDER BY offset_index ASC
+           LIMIT 1"#,
+        params![chunk_id],
+        |row| Ok((row.get::<_, i64>(0)?, parse_datetime(row, 1)?, row.get::<_, i64>(2)?)),
+    );
+    let (first_offset, first_timestamp, last_offset) = match span {
+        Ok(span) => span,
+        Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(Some(Vec::new())),
+        Err(e) => return Err(e.into()),
+    };
+
+    let secs = |secs: f64| chrono::Duration::milliseconds((secs * 1000.0).round() as i64);
+    let chunk_start = first_timestamp - secs(first_offset as f64 / fps);
+    let duration = (last_offset + 1) as f64 / fps;
+    let chunk_end = chunk_start + secs(duration);
+
+    let cues = get_transcriptions_overlapping(conn, chunk_start, chunk_end)?
+        .into_iter()
+        .filter_map(|segment| {
+            let start = (segment.start - chunk_start).num_milliseconds() as f64 / 1000.0;
+            let end = (segment.end - chunk_start).num_milliseconds() as f64 / 1000.0;
+            let (start, end) = (start.clamp(0.0, duration), end.clamp(0.0, duration));
+            let text = segment.transcription.transcription.trim();
+            (end > start && !text.is_empty()).then(|| SubtitleCue {
+                start_secs: start,
+                end_secs: end,
+                text: text.to_string(),
+            })
+        })
+        .collect();
+
+    Ok(Some(cues))
+}
+
+/// Unified search across OCR and transcriptions, newest first
+pub fn search_all(
+    conn: &Connection,
+    query: &str,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<SearchResult>> {
+    search_merged(conn, query, &MergedSearch::default(), limit, offset)
+}
+
+/// Sources and filters for [`search_merged`]
+#[derive(Debug, Clone)]
+pub(crate) struct MergedSearch<'a> {
+    pub include_ocr: bool,
+    pub include_audio: bool,
+    /// Search OCR text with the trigram index (transcriptions have none)
+    pub substring: bool,
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+    /// Only OCR results from this application (ASCII case-insensitive)
+    pub app_name: Option<&'a str>,
+    pub focused_only: bool,
+}
+
+impl Default for MergedSearch<'_> {
+    fn default() -> Self {
+        Self {
+            include_ocr: true,
+            include_audio: true,
+            substring: false,
+            start: None,
+            end: None,
+            app_name: None,
+            focused_only: false,
+        }
+    }
+}
+
+/// Search OCR text and transcriptions, merged newest first
+///
+/// Filters and ordering are applied in SQL before paging, so pages neither
+/// overlap nor skip results.
+pub(crate) fn search_merged(
+    conn: &Connection,
+    query: &str,
+    search: &MergedSearch,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<SearchResult>> {
+    let mut arms = Vec::new();
+    if search.include_ocr {
+        let table = if search.substring { "ocr_text_trigram" } else { "ocr_text_fts" };
+        arms.push(format!(
+            r#"SELECT 0, o.id, f.timestamp
+               FROM ocr_text o
+               JOIN {table} fts ON o.id = fts.rowid
+               JOIN frames f ON o.frame_id = f.id
+               WHERE {table} MATCH ?1
+                 AND (?4 IS NULL OR f.timestamp >= ?4)
+                 AND (?5 IS NULL OR f.timestamp <= ?5)
+                 AND (?6 IS NULL OR f.app_name = ?6 COLLATE NOCASE)
+                 AND (?7 = 0 OR f.focused = 1)"#,
+            table = table
+        ));
+    }
+    if search.include_audio && !search.substring {
+        arms.push(
+            r#"SELECT 1, at.id, at.timestamp
+               FROM audio_transcriptions at
+               JOIN audio_fts fts ON at.id = fts.rowid
+               WHERE audio_fts MATCH ?1
+                 AND (?4 IS NULL OR at.timestamp >= ?4)
+                 AND (?5 IS NULL OR at.timestamp <= ?5)
+                 AND ?6 IS NULL"#
+                .to_string(),
+        );
+    }
+    if arms.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut stmt = conn.prepare(&format!(
+        "{} ORDER BY 3 DESC LIMIT ?2 OFFSET ?3",
+        arms.join(" UNION ALL ")
+    ))?;
+    let page = stmt
+        .query_map(
+            params![
+                query,
+                limit,
+                offset,
+                search.start.map(|t| t.to_rfc3339()),
+                search.end.map(|t| t.to_rfc3339()),
+                search.app_name,
+                search.focused_only,
+            ],
+            |row| Ok((row.get::<_, i64>(0)? == 1, row.get::<_, i64>(1)?)),
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut ocr_stmt = conn.prepare(
+        r#"SELECT o.id, o.frame_id, o.text, o.text_json, o.confidence,
+           f.id, f.video_chunk_id, f.offset_index, f.timestamp, f.app_name,
+           f.window_name, f.browser_url, f.focused, f.frame_hash, o.language
+           FROM ocr_text o
+           JOIN frames f ON o.frame_id = f.id
+           WHERE o.id = ?1"#,
+    )?;
+    let mut audio_stmt = conn.prepare(
+        r#"SELECT at.id, at.audio_chunk_id, at.transcription, at.timestamp,
+           at.speaker_id, at.start_time, at.end_time,
+           ac.id, ac.file_path, ac.device_name, ac.is_input_device, ac.timestamp,
+           ac.pruned_at IS NOT NULL, at.language
+           FROM audio_transcriptions at
+           JOIN audio_chunks ac ON at.audio_chunk_id = ac.id
+           WHERE at.id = ?1"#,
+    )?;
+
+    let mut results = Vec::with_capacity(page.len());
+    for (is_audio, id) in page {
+        if is_audio {
+            let (transcription, chunk) = audio_stmt.query_row(params![id], row_to_transcription_with_chunk)?;
+            results.push(SearchResult::Audio { transcription, chunk });
+        } else {
+            let (ocr, frame) = ocr_stmt.query_row(params![id], row_to_ocr_with_frame)?;
+            results.push(SearchResult::Ocr { ocr, frame });
+        }
+    }
+
+    Ok(results)
+}
+
+/// Get audio indexing statistics
+pub fn get_audio_stats(conn: &Connection) -> Result<AudioStats> {
+    let total_chunks: i64 = conn.query_row("SELECT COUNT(*) FROM audio_chunks", [], |row| row.get(0))?;
+
+    let chunks_with_transcription: i64 = conn.query_row(
+        "SELECT COUNT(DISTINCT audio_chunk_id) FROM audio_transcriptions",
         [],
         |row| row.get(0),
     )?;
@@ -974,7 +2455,7 @@ pub fn get_audio_stats(conn: &Connection) -> Result<AudioStats> {
         r#"SELECT COUNT(DISTINCT at.audio_chunk_id)
            FROM audio_transcriptions at
            JOIN audio_chunks ac ON at.audio_chunk_id = ac.id
-           WHERE ac.timestamp >= datetime('now', '-1 hour')"#,
+           WHERE datetime(ac.timestamp) >= datetime('now', '-1 hour')"#,
         [],
         |row| row.get(0),
     )?;
@@ -1003,6 +2484,112 @@ pub fn get_audio_stats(conn: &Connection) -> Result<AudioStats> {
     })
 }
 
+/// Number of apps listed in [`DashboardSummary::top_apps`]
+pub const DASHBOARD_TOP_APPS: i64 = 5;
+
+/// Summarize today's and this week's recordings (local time, weeks start on
+/// Monday), the busiest apps, the indexing backlog and disk usage
+///
+/// Media paths are resolved against the database's directory, which is the
+/// data directory; an in-memory database reports no disk usage.
+pub fn get_dashboard_summary(conn: &Connection) -> Result<DashboardSummary> {
+    use chrono::{Datelike, Local, NaiveTime, TimeZone};
+
+    let today = Local::now().date_naive();
+    let week = today - chrono::Duration::days(today.weekday().num_days_from_monday() as i64);
+    let local_midnight = |day: chrono::NaiveDate| {
+        Local
+            .from_local_datetime(&day.and_time(NaiveTime::MIN))
+            .earliest()
+            .map(|dt| dt.with_timezone(&Utc))
+            .unwrap_or_else(|| Utc.from_utc_datetime(&day.and_time(NaiveTime::MIN)))
+    };
+
+    dashboard_summary_since(conn, local_midnight(today), local_midnight(week))
+}
+
+fn dashboard_summary_since(
+    conn: &Connection,
+    today: DateTime<Utc>,
+    week: DateTime<Utc>,
+) -> Result<DashboardSummary> {
+    let period = |since: DateTime<Utc>| -> Result<PeriodActivity> {
+        let (frames, video_chunks, audio_chunks) = conn.query_row(
+            r#"SELECT (SELECT COUNT(*) FROM frames WHERE timestamp >= ?1),
+                      (SELECT COUNT(*) FROM video_chunks WHERE julianday(created_at) >= julianday(?1)),
+                      (SELECT COUNT(*) FROM audio_chunks WHERE julianday(timestamp) >= julianday(?1))"#,
+            params![since.to_rfc3339()],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+        Ok(PeriodActivity { since, frames, video_chunks, audio_chunks })
+    };
+
+    let mut stmt = conn.prepare(
+        r#"SELECT app_name, COUNT(*) FROM frames
+           WHERE timestamp >= ?1 AND app_name IS NOT NULL AND app_name != ''
+           GROUP BY app_name
+           ORDER BY 2 DESC, app_name
+           LIMIT ?2"#,
+    )?;
+    let top_apps = stmt
+        .query_map(params![week.to_rfc3339(), DASHBOARD_TOP_APPS], |row| {
+            Ok(AppUsage { app_name: row.get(0)?, frames: row.get(1)? })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let (ocr_backlog, transcription_backlog) = conn.query_row(
+        r#"SELECT (SELECT COUNT(*) FROM frames f
+                   WHERE NOT EXISTS (SELECT 1 FROM ocr_text o WHERE o.frame_id = f.id)),
+                  (SELECT COUNT(*) FROM audio_chunks ac
+                   WHERE ac.pruned_at IS NULL
+                     AND NOT EXISTS (SELECT 1 FROM audio_transcriptions at WHERE at.audio_chunk_id = ac.id))"#,
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+
+    Ok(DashboardSummary {
+        today: period(today)?,
+        this_week: period(week)?,
+        top_apps,
+        ocr_backlog,
+        transcription_backlog,
+        disk: disk_usage(conn)?,
+    })
+}
+
+/// Size of the database and of the media files it references
+fn disk_usage(conn: &Connection) -> Result<DiskUsage> {
+    let database_bytes = database_size(conn);
+    let Some(data_dir) = conn
+        .path()
+        .filter(|p| !p.is_empty())
+        .and_then(|p| Path::new(p).parent().map(Path::to_path_buf))
+    else {
+        return Ok(DiskUsage { database_bytes, total_bytes: database_bytes, ..DiskUsage::default() });
+    };
+
+    let files_size = |sql: &str| -> Result<u64> {
+        let mut stmt = conn.prepare(sql)?;
+        let paths = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(paths
+            .iter()
+            .filter_map(|file_path| std::fs::metadata(data_dir.join(file_path)).ok())
+            .map(|m| m.len())
+            .sum())
+    };
+    let video_bytes = files_size("SELECT file_path FROM video_chunks")?;
+    let audio_bytes = files_size("SELECT file_path FROM audio_chunks WHERE pruned_at IS NULL")?;
+
+    Ok(DiskUsage {
+        database_bytes,
+        video_bytes,
+        audio_bytes,
+        total_bytes: database_bytes + video_bytes + audio_bytes,
+    })
+}
+
 /// Get paginated audio chunks with optional filters
 pub fn get_audio_chunks_paginated(
     conn: &Connection,
@@ -1013,29 +2600,13 @@ pub fn get_audio_chunks_paginated(
 ) -> Result<Vec<AudioChunkWithTranscription>> {
     let mut query = String::from(
         r#"SELECT ac.id, ac.file_path, ac.device_name, ac.is_input_device, ac.timestamp,
-           COUNT(at.id) as transcription_count
+           COUNT(at.id) as transcription_count, ac.pruned_at IS NOT NULL
            FROM audio_chunks ac
            LEFT JOIN audio_transcriptions at ON ac.id = at.audio_chunk_id"#,
     );
 
-    let mut conditions = Vec::new();
-    let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
-
-    if let Some(dev) = device {
-        conditions.push("ac.device_name = ?");
-        params.push(Box::new(dev.to_string()));
-    }
-
-    if let Some(input) = is_input {
-        conditions.push("ac.is_input_device = ?");
-        params.push(Box::new(input as i32));
-    }
-
-    if !conditions.is_empty() {
-        query.push_str(" WHERE ");
-        query.push_str(&conditions.join(" AND "));
-    }
-
+    let (filter, params) = audio_chunk_filter(device, is_input);
+    query.push_str(&filter);
     query.push_str(" GROUP BY ac.id ORDER BY ac.timestamp DESC LIMIT ? OFFSET ?");
 
     let mut stmt = conn.prepare(&query)?;
@@ -1053,6 +2624,7 @@ pub fn get_audio_chunks_paginated(
                 is_input_device: row.get::<_, Option<i32>>(3)?.map(|v| v != 0),
                 timestamp: parse_datetime(row, 4)?,
                 transcription_count: row.get(5)?,
+                pruned: row.get(6)?,
             })
         })?
         .collect::<Result<Vec<_>, _>>()?;
@@ -1061,22 +2633,33 @@ pub fn get_audio_chunks_paginated(
 }
 
 /// Get total count of search results for audio
-pub fn get_audio_search_count(conn: &Connection, query: &str) -> Result<i64> {
+pub fn get_audio_search_count(
+    conn: &Connection,
+    query: &str,
+    device: Option<&str>,
+    is_input: Option<bool>,
+    language: Option<&str>,
+) -> Result<i64> {
     let count: i64 = conn.query_row(
         r#"SELECT COUNT(*)
            FROM audio_transcriptions at
            JOIN audio_fts fts ON at.id = fts.rowid
-           WHERE audio_fts MATCH ?1"#,
-        params![query],
+           JOIN audio_chunks ac ON at.audio_chunk_id = ac.id
+           WHERE audio_fts MATCH ?1
+             AND (?2 IS NULL OR ac.device_name = ?2)
+             AND (?3 IS NULL OR ac.is_input_device = ?3)
+             AND (?4 IS NULL OR at.language = ?4)"#,
+        params![query, device, is_input, language],
         |row| row.get(0),
     )?;
     Ok(count)
 }
 
-/// Delete all OCR records with empty text (for re-indexing after bug fixes)
+/// Delete all OCR records with empty text (for re-indexing after bug fixes).
+/// Rows referencing another frame's text are kept.
 pub fn reset_empty_ocr(conn: &Connection) -> Result<usize> {
     let deleted = conn.execute(
-        "DELETE FROM ocr_text WHERE text = ''",
+        "DELETE FROM ocr_text WHERE text = '' AND same_as_frame_id IS NULL",
         [],
     )?;
     Ok(deleted)
@@ -1087,3 +2670,1109 @@ pub fn reset_all_ocr(conn: &Connection) -> Result<usize> {
     let deleted = conn.execute("DELETE FROM ocr_text", [])?;
     Ok(deleted)
 }
+
+/// Delete the transcriptions of chunks that produced no text (failed or
+/// silent), so they are transcribed again
+///
+/// Chunks whose audio was pruned are left alone, since they can't be
+/// transcribed again.
+pub fn reset_empty_transcriptions(conn: &Connection) -> Result<usize> {
+    let deleted = conn.execute(
+        r#"DELETE FROM audio_transcriptions
+           WHERE audio_chunk_id IN (SELECT id FROM audio_chunks WHERE pruned_at IS NULL)
+             AND audio_chunk_id NOT IN
+                 (SELECT audio_chunk_id FROM audio_transcriptions WHERE transcription != '')"#,
+        [],
+    )?;
+    Ok(deleted)
+}
+
+/// Delete all transcriptions (for re-transcribing with another model)
+///
+/// Transcriptions of pruned chunks are kept: their audio is gone, so they
+/// could not be redone.
+pub fn reset_all_transcriptions(conn: &Connection) -> Result<usize> {
+    let deleted = conn.execute(
+        r#"DELETE FROM audio_transcriptions
+           WHERE audio_chunk_id IN (SELECT id FROM audio_chunks WHERE pruned_at IS NULL)"#,
+        [],
+    )?;
+    Ok(deleted)
+}
+
+/// Delete the OCR records of one video chunk's frames so they are indexed again
+pub fn reset_ocr_for_chunk(conn: &Connection, chunk_id: i64) -> Result<usize> {
+    let deleted = conn.execute(
+        "DELETE FROM ocr_text WHERE frame_id IN (SELECT id FROM frames WHERE video_chunk_id = ?1)",
+        params![chunk_id],
+    )?;
+    Ok(deleted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Database;
+    use chrono::TimeZone;
+
+    fn at(h: u32, m: u32, s: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, 1, h, m, s).unwrap()
+    }
+
+    fn seed_chunk(conn: &Connection, device: &str, created_at: &str) -> i64 {
+        let id = insert_video_chunk(conn, &NewVideoChunk {
+            file_path: format!("videos/{}-{}.mp4", device, created_at),
+            device_name: device.to_string(),
+            width: Some(1920),
+            height: Some(1080),
+            fps: Some(1.0),
+        }).unwrap();
+
+        // created_at is filled by SQLite's datetime('now'); pin it for the test
+        conn.execute(
+            "UPDATE video_chunks SET created_at = ?1 WHERE id = ?2",
+            params![created_at, id],
+        ).unwrap();
+        id
+    }
+
+    fn seed_frame(conn: &Connection, chunk_id: i64, offset_index: i64, timestamp: DateTime<Utc>) -> i64 {
+        insert_frame(conn, &NewFrame {
+            video_chunk_id: chunk_id,
+            offset_index,
+            capture_index: Some(offset_index),
+            timestamp,
+            app_name: Some("Code".to_string()),
+            window_name: None,
+            browser_url: None,
+            focused: true,
+            frame_hash: None,
+        }).unwrap()
+    }
+
+    fn seed_ocr(conn: &Connection, frame_id: i64, text: &str) {
+        insert_ocr_text(conn, &NewOcrText {
+            frame_id,
+            text: text.to_string(),
+            text_json: None,
+            confidence: Some(0.9),
+            language: Some("en-US".to_string()),
+        }).unwrap();
+    }
+
+    fn seed_transcription(conn: &Connection, text: &str, timestamp: DateTime<Utc>) {
+        let chunk_id = insert_audio_chunk(conn, &NewAudioChunk {
+            file_path: format!("audio/{}.wav", timestamp.timestamp()),
+            device_name: Some("Microphone".to_string()),
+            is_input_device: Some(true),
+            timestamp,
+        }).unwrap();
+
+        insert_audio_transcription(conn, &NewAudioTranscription {
+            audio_chunk_id: chunk_id,
+            transcription: text.to_string(),
+            timestamp,
+            speaker_id: None,
+            start_time: Some(0.0),
+            end_time: Some(30.0),
+            language: Some("en".to_string()),
+        }).unwrap();
+    }
+
+    fn parse(conn: &Connection, value: &str) -> rusqlite::Result<DateTime<Utc>> {
+        conn.query_row("SELECT ?1", params![value], |row| parse_datetime(row, 0))
+    }
+
+    #[test]
+    fn test_parse_datetime_formats() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+
+        assert_eq!(parse(conn, "2025-03-01T12:30:00+00:00").unwrap(), at(12, 30, 0));
+        assert_eq!(parse(conn, "2025-03-01T14:30:00+02:00").unwrap(), at(12, 30, 0));
+        assert_eq!(parse(conn, "2025-03-01 12:30:00").unwrap(), at(12, 30, 0));
+        assert_eq!(
+            parse(conn, "2025-03-01 12:30:00.250").unwrap(),
+            at(12, 30, 0) + chrono::Duration::milliseconds(250)
+        );
+        assert!(parse(conn, "2025-03-01").is_err());
+        assert!(parse(conn, "yesterday").is_err());
+    }
+
+    #[test]
+    fn test_get_frames_in_range_is_inclusive() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+        let chunk = seed_chunk(conn, "monitor-0", "2025-03-01 10:00:00");
+
+        for (i, minute) in [0, 1, 2].into_iter().enumerate() {
+            seed_frame(conn, chunk, i as i64, at(10, minute, 0));
+        }
+
+        let frames = get_frames_in_range(conn, at(10, 0, 0), at(10, 2, 0), 10, 0, false).unwrap();
+        let timestamps: Vec<_> = frames.iter().map(|f| f.timestamp).collect();
+        assert_eq!(timestamps, vec![at(10, 2, 0), at(10, 1, 0), at(10, 0, 0)]);
+
+        let frames = get_frames_in_range(conn, at(10, 0, 30), at(10, 1, 0), 10, 0, false).unwrap();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].timestamp, at(10, 1, 0));
+
+        let frames = get_frames_in_range(conn, at(10, 0, 0), at(10, 2, 0), 2, 1, false).unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].timestamp, at(10, 1, 0));
+    }
+
+    #[test]
+    fn test_focused_only_filters() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+        let chunk = seed_chunk(conn, "monitor-0", "2025-03-01 10:00:00");
+
+        let active = seed_frame(conn, chunk, 0, at(10, 0, 0));
+        let idle = seed_frame(conn, chunk, 1, at(10, 0, 30));
+        seed_ocr(conn, active, "quarterly report draft");
+        seed_ocr(conn, idle, "quarterly report draft");
+        conn.execute("UPDATE frames SET focused = 0 WHERE id = ?1", params![idle]).unwrap();
+
+        let frames = get_frames_in_range(conn, at(10, 0, 0), at(10, 1, 0), 10, 0, true).unwrap();
+        assert_eq!(frames.iter().map(|f| f.id).collect::<Vec<_>>(), vec![active]);
+        assert_eq!(get_frames_in_range(conn, at(10, 0, 0), at(10, 1, 0), 10, 0, false).unwrap().len(), 2);
+
+        let query = sanitize_fts5_query("quarterly").unwrap();
+        let results = search_ocr(conn, &query, 10, 0, true).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].1.id, active);
+        assert_eq!(get_search_count(conn, &query, true).unwrap(), 1);
+        assert_eq!(get_search_count(conn, &query, false).unwrap(), 2);
+
+        let buckets = get_activity_histogram(conn, at(10, 0, 0), at(11, 0, 0), 3600).unwrap();
+        assert_eq!(buckets, vec![(at(10, 0, 0), 2, 1)]);
+    }
+
+    #[test]
+    fn test_find_similar_frames() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+        let chunk = seed_chunk(conn, "monitor-0", "2025-03-01 10:00:00");
+
+        let target: i64 = 0x0F0F_0F0F_0F0F_0F0F;
+        for (i, hash) in [target, target ^ 0b1, target ^ 0b111, !target, target ^ 0b11].into_iter().enumerate() {
+            insert_frame(conn, &NewFrame {
+                video_chunk_id: chunk,
+                offset_index: i as i64,
+                capture_index: None,
+                timestamp: at(10, 0, i as u32),
+                app_name: None,
+                window_name: None,
+                browser_url: None,
+                focused: true,
+                frame_hash: Some(hash),
+            }).unwrap();
+        }
+        seed_frame(conn, chunk, 5, at(10, 0, 5));
+
+        let similar = find_similar_frames(conn, target, 2, 10).unwrap();
+        let distances: Vec<_> = similar.iter().map(|s| s.distance).collect();
+        assert_eq!(distances, vec![0, 1, 2]);
+        assert_eq!(similar[0].frame.offset_index, 0);
+        assert_eq!(similar[2].frame.offset_index, 4);
+
+        assert_eq!(find_similar_frames(conn, target, 64, 2).unwrap().len(), 2);
+        assert_eq!(find_similar_frames(conn, target, 64, 10).unwrap().len(), 5);
+    }
+
+    #[test]
+    fn test_insert_ocr_text_batch() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+        let chunk = seed_chunk(conn, "monitor-0", "2025-03-01 10:00:00");
+        let rows: Vec<NewOcrText> = (0..4)
+            .map(|i| NewOcrText {
+                frame_id: seed_frame(conn, chunk, i, at(10, 0, i as u32)),
+                text: format!("invoice number {}", i),
+                text_json: None,
+                confidence: Some(0.9),
+                language: None,
+            })
+            .collect();
+
+        // Indexed immediately by default
+        let ids = insert_ocr_text_batch(conn, &rows[..2], false).unwrap();
+        assert_eq!(ids.len(), 2);
+        assert!(ids[0] < ids[1]);
+        assert_eq!(get_search_count(conn, "\"invoice\"", false).unwrap(), 2);
+
+        // Deferred rows become searchable after a rebuild
+        insert_ocr_text_batch(conn, &rows[2..], true).unwrap();
+        assert_eq!(get_search_count(conn, "\"invoice\"", false).unwrap(), 2);
+        assert_eq!(get_substring_search_count(conn, "\"voice\"", false).unwrap(), 2);
+        rebuild_fts_index(conn).unwrap();
+        assert_eq!(get_search_count(conn, "\"invoice\"", false).unwrap(), 4);
+        assert_eq!(get_substring_search_count(conn, "\"voice\"", false).unwrap(), 4);
+
+        // The insert trigger is back for regular inserts
+        let frame = seed_frame(conn, chunk, 4, at(10, 0, 4));
+        seed_ocr(conn, frame, "invoice total");
+        assert_eq!(get_search_count(conn, "\"invoice\"", false).unwrap(), 5);
+    }
+
+    #[test]
+    fn test_busy_writes_are_retried() {
+        let path = std::env::temp_dir().join(format!("memoire-busy-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let db = Database::open(&path).unwrap();
+        let other = Database::open(&path).unwrap();
+        let chunk = seed_chunk(db.connection(), "monitor-0", "2025-03-01 10:00:00");
+
+        // Another connection holds the write lock past a short timeout
+        db.connection().busy_timeout(std::time::Duration::from_millis(10)).unwrap();
+        other.connection().execute_batch("BEGIN IMMEDIATE").unwrap();
+        let frame = NewFrame {
+            video_chunk_id: chunk,
+            offset_index: 0,
+            capture_index: None,
+            timestamp: at(10, 0, 0),
+            app_name: None,
+            window_name: None,
+            browser_url: None,
+            focused: true,
+            frame_hash: None,
+        };
+        let err = insert_frames_tx(db.connection(), std::slice::from_ref(&frame)).unwrap_err();
+        assert!(err.is_busy(), "{:?}", err);
+
+        // Released while the batch insert is retrying
+        let release = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            other.connection().execute_batch("COMMIT").unwrap();
+        });
+        assert_eq!(insert_frames_batch(db.connection(), &[frame]).unwrap().len(), 1);
+        release.join().unwrap();
+
+        drop(db);
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+
+    #[test]
+    fn test_get_latest_frame_per_monitor() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+        let left = seed_chunk(conn, "left", "2025-03-01 10:00:00");
+        let right = seed_chunk(conn, "right", "2025-03-01 10:00:00");
+        seed_chunk(conn, "unused", "2025-03-01 10:00:00");
+
+        seed_frame(conn, left, 0, at(10, 0, 0));
+        let newest_left = seed_frame(conn, left, 1, at(10, 0, 5));
+        let newest_right = seed_frame(conn, right, 0, at(10, 0, 2));
+        seed_ocr(conn, newest_right, "inbox");
+        // A newer chunk of the same monitor supersedes the older one
+        let left_again = seed_chunk(conn, "left", "2025-03-01 10:05:00");
+        let newest_left_again = seed_frame(conn, left_again, 0, at(10, 5, 0));
+
+        let latest = get_latest_frame_per_monitor(conn).unwrap();
+        let found: Vec<(&str, i64)> = latest.iter().map(|(device, f)| (device.as_str(), f.id)).collect();
+        assert_eq!(found, vec![("left", newest_left_again), ("right", newest_right)]);
+        assert_ne!(newest_left, newest_left_again);
+        assert_eq!(latest[1].1.ocr_text.as_ref().map(|o| o.text.as_str()), Some("inbox"));
+    }
+
+    #[test]
+    fn test_dashboard_summary() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+        let old = seed_chunk(conn, "monitor-0", "2025-02-20 09:00:00");
+        seed_frame(conn, old, 0, Utc.with_ymd_and_hms(2025, 2, 20, 9, 0, 0).unwrap());
+        let chunk = seed_chunk(conn, "monitor-0", "2025-03-01 10:00:00");
+        let first = seed_frame(conn, chunk, 0, at(10, 0, 0));
+        seed_frame(conn, chunk, 1, at(10, 0, 1));
+        seed_ocr(conn, first, "quarterly report");
+        let browser = insert_frame(conn, &NewFrame {
+            video_chunk_id: chunk,
+            offset_index: 2,
+            capture_index: None,
+            timestamp: at(10, 0, 2),
+            app_name: Some("Firefox".to_string()),
+            window_name: None,
+            browser_url: None,
+            focused: true,
+            frame_hash: None,
+        }).unwrap();
+        seed_ocr(conn, browser, "inbox");
+        seed_transcription(conn, "standup notes", at(9, 0, 0));
+        insert_audio_chunk(conn, &NewAudioChunk {
+            file_path: "audio/pending.wav".to_string(),
+            device_name: None,
+            is_input_device: None,
+            timestamp: at(9, 30, 0),
+        }).unwrap();
+
+        let today = at(0, 0, 0);
+        let week = Utc.with_ymd_and_hms(2025, 2, 24, 0, 0, 0).unwrap();
+        let summary = dashboard_summary_since(conn, today, week).unwrap();
+
+        assert_eq!(summary.today.since, today);
+        assert_eq!(summary.today.frames, 3);
+        assert_eq!(summary.today.video_chunks, 1);
+        assert_eq!(summary.this_week.frames, 3);
+        assert_eq!(summary.this_week.video_chunks, 1);
+
+        // Frames from before the week don't count towards the top apps
+        let apps: Vec<_> = summary.top_apps.iter().map(|a| (a.app_name.as_str(), a.frames)).collect();
+        assert_eq!(apps, vec![("Code", 2), ("Firefox", 1)]);
+
+        // The February frame and one of today's have no OCR; one audio chunk is untranscribed
+        assert_eq!(summary.ocr_backlog, 2);
+        assert_eq!(summary.transcription_backlog, 1);
+        assert_eq!(summary.disk.total_bytes, 0);
+    }
+
+    #[test]
+    fn test_integrity_check() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+        let chunk = seed_chunk(conn, "monitor-0", "2025-03-01 10:00:00");
+        let frame = seed_frame(conn, chunk, 0, at(10, 0, 0));
+        seed_ocr(conn, frame, "quarterly report");
+        assert!(integrity_check(conn).unwrap().is_empty());
+
+        // Rows loaded without indexing leave the full-text index behind its table
+        let row = NewOcrText {
+            frame_id: seed_frame(conn, chunk, 1, at(10, 0, 1)),
+            text: "unindexed".to_string(),
+            text_json: None,
+            confidence: None,
+            language: None,
+        };
+        insert_ocr_text_batch(conn, &[row], true).unwrap();
+        let issues = integrity_check(conn).unwrap();
+        assert_eq!(issues.len(), 2, "{:?}", issues);
+        assert!(issues[0].contains("ocr_text_fts"));
+        assert!(issues[1].contains("ocr_text_trigram"));
+
+        rebuild_fts_index(conn).unwrap();
+        assert!(integrity_check(conn).unwrap().is_empty());
+
+        // A frame whose chunk is gone
+        conn.execute_batch("PRAGMA foreign_keys=OFF;").unwrap();
+        conn.execute("DELETE FROM video_chunks WHERE id = ?1", params![chunk]).unwrap();
+        let issues = integrity_check(conn).unwrap();
+        assert!(issues.iter().any(|i| i.starts_with("frames row") && i.contains("video_chunks")), "{:?}", issues);
+    }
+
+    #[test]
+    fn test_audio_chunk_count_matches_filters() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+
+        for (i, (device, is_input)) in [("Microphone", true), ("Microphone", true), ("Speakers", false)].into_iter().enumerate() {
+            insert_audio_chunk(conn, &NewAudioChunk {
+                file_path: format!("audio/{}.wav", i),
+                device_name: Some(device.to_string()),
+                is_input_device: Some(is_input),
+                timestamp: at(10, i as u32, 0),
+            }).unwrap();
+        }
+
+        for (device, is_input) in [(None, None), (None, Some(true)), (None, Some(false)), (Some("Microphone"), Some(false))] {
+            let total = get_total_audio_chunk_count(conn, device, is_input).unwrap();
+            let page = get_audio_chunks_paginated(conn, 100, 0, device, is_input).unwrap();
+            assert_eq!(total, page.len() as i64, "device {:?}, is_input {:?}", device, is_input);
+        }
+        assert_eq!(get_total_audio_chunk_count(conn, None, Some(true)).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_delete_audio_files_older_than() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+        let data_dir = std::env::temp_dir().join(format!("memoire-prune-{}", std::process::id()));
+        std::fs::create_dir_all(data_dir.join("audio")).unwrap();
+
+        seed_transcription(conn, "old meeting notes", at(10, 0, 0));
+        seed_transcription(conn, "recent standup", at(11, 0, 0));
+        conn.execute("UPDATE audio_chunks SET timestamp = '2025-03-01 10:00:00' WHERE id = 1", []).unwrap();
+        conn.execute("UPDATE audio_chunks SET timestamp = '2025-03-01 11:00:00' WHERE id = 2", []).unwrap();
+
+        let old_path = data_dir.join(get_audio_chunk(conn, 1).unwrap().unwrap().file_path);
+        let new_path = data_dir.join(get_audio_chunk(conn, 2).unwrap().unwrap().file_path);
+        std::fs::write(&old_path, [0u8; 16]).unwrap();
+        std::fs::write(&new_path, [0u8; 16]).unwrap();
+
+        let pruned = delete_audio_files_older_than(conn, &data_dir, at(10, 30, 0)).unwrap();
+        assert_eq!(pruned.chunks, 1);
+        assert_eq!(pruned.bytes_freed, 16);
+        assert!(!old_path.exists());
+        assert!(new_path.exists());
+
+        // The chunk is flagged but its transcription stays searchable
+        assert!(get_audio_chunk(conn, 1).unwrap().unwrap().pruned);
+        assert!(!get_audio_chunk(conn, 2).unwrap().unwrap().pruned);
+        let results = search_transcriptions(conn, "meeting", 10, 0, None, None, None).unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].1.pruned);
+
+        // Already pruned chunks are not counted again
+        let pruned = delete_audio_files_older_than(conn, &data_dir, at(10, 30, 0)).unwrap();
+        assert_eq!(pruned.chunks, 0);
+
+        std::fs::remove_dir_all(&data_dir).unwrap();
+    }
+
+    #[test]
+    fn test_enforce_size_limit() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+        let data_dir = std::env::temp_dir().join(format!("memoire-size-limit-{}", std::process::id()));
+        std::fs::create_dir_all(data_dir.join("videos")).unwrap();
+        std::fs::create_dir_all(data_dir.join("audio")).unwrap();
+
+        let mut chunks = Vec::new();
+        for (name, created_at) in [("a", "2025-03-01 10:00:00"), ("b", "2025-03-01 10:05:00"), ("c", "2025-03-01 10:10:00")] {
+            let id = seed_chunk(conn, "monitor-0", created_at);
+            let file_path = format!("videos/{}.mp4", name);
+            conn.execute("UPDATE video_chunks SET file_path = ?1 WHERE id = ?2", params![file_path, id]).unwrap();
+            std::fs::write(data_dir.join(&file_path), [0u8; 100]).unwrap();
+            chunks.push(id);
+        }
+        seed_transcription(conn, "standup notes", at(10, 2, 0));
+        conn.execute("UPDATE audio_chunks SET timestamp = '2025-03-01 10:02:00' WHERE id = 1", []).unwrap();
+        let audio_path = data_dir.join(get_audio_chunk(conn, 1).unwrap().unwrap().file_path);
+        std::fs::write(&audio_path, [0u8; 50]).unwrap();
+
+        // A deduplicated frame in the newest chunk reuses OCR from the oldest one
+        let source = seed_frame(conn, chunks[0], 0, at(10, 0, 0));
+        seed_ocr(conn, source, "quarterly roadmap");
+        let duplicate = seed_frame(conn, chunks[2], 0, at(10, 10, 0));
+        insert_ocr_reference(conn, duplicate, source).unwrap();
+
+        // 350 bytes used: the oldest video chunk and then the audio chunk go
+        let pruned = enforce_size_limit(conn, &data_dir, 200, &[]).unwrap();
+        assert_eq!(pruned.video_chunks, 1);
+        assert_eq!(pruned.audio_chunks, 1);
+        assert_eq!(pruned.bytes_freed, 150);
+        assert_eq!(pruned.bytes_used, 200);
+        assert!(get_video_chunk(conn, chunks[0]).unwrap().is_none());
+        assert!(!data_dir.join("videos/a.mp4").exists());
+        assert!(!audio_path.exists());
+        assert!(get_audio_chunk(conn, 1).unwrap().unwrap().pruned);
+
+        // The duplicate kept its text although its source frame is gone
+        let results = search_ocr(conn, "roadmap", 10, 0, false).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].1.id, duplicate);
+
+        // The chunk being written is never removed, even over budget
+        let pruned = enforce_size_limit(conn, &data_dir, 0, &[chunks[2]]).unwrap();
+        assert_eq!(pruned.video_chunks, 1);
+        assert_eq!(pruned.bytes_used, 100);
+        assert!(get_video_chunk(conn, chunks[1]).unwrap().is_none());
+        assert!(get_video_chunk(conn, chunks[2]).unwrap().is_some());
+
+        std::fs::remove_dir_all(&data_dir).unwrap();
+    }
+
+    #[test]
+    fn test_find_orphaned_chunks() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+        let data_dir = std::env::temp_dir().join(format!("memoire-orphans-{}", std::process::id()));
+        std::fs::create_dir_all(data_dir.join("videos/monitor-0/_temp_frames")).unwrap();
+        std::fs::create_dir_all(data_dir.join("audio")).unwrap();
+
+        let present = seed_chunk(conn, "monitor-0", "2025-03-01 10:00:00");
+        let missing = seed_chunk(conn, "monitor-0", "2025-03-01 10:05:00");
+        for (id, name) in [(present, "a"), (missing, "b")] {
+            let file_path = format!("videos/monitor-0/{}.mp4", name);
+            conn.execute("UPDATE video_chunks SET file_path = ?1 WHERE id = ?2", params![file_path, id]).unwrap();
+        }
+        std::fs::write(data_dir.join("videos/monitor-0/a.mp4"), [0u8; 10]).unwrap();
+        let frame = seed_frame(conn, missing, 0, at(10, 5, 0));
+        seed_ocr(conn, frame, "lost recording");
+
+        // Still being recorded: no file yet, but too recent to report
+        let recording = seed_chunk(conn, "monitor-0", "2025-03-01 10:10:00");
+        conn.execute("UPDATE video_chunks SET created_at = datetime('now') WHERE id = ?1", [recording]).unwrap();
+
+        seed_transcription(conn, "standup notes", at(10, 0, 0));
+        seed_transcription(conn, "retro notes", at(10, 1, 0));
+        conn.execute("UPDATE audio_chunks SET timestamp = '2025-03-01 10:00:00'", []).unwrap();
+        conn.execute("UPDATE audio_chunks SET pruned_at = datetime('now') WHERE id = 2", []).unwrap();
+
+        std::fs::write(data_dir.join("videos/monitor-0/stray.mp4"), [0u8; 10]).unwrap();
+        std::fs::write(data_dir.join("videos/monitor-0/_temp_frames/frame.mp4"), [0u8; 10]).unwrap();
+        std::fs::write(data_dir.join("videos/monitor-0/notes.txt"), [0u8; 10]).unwrap();
+        let old = std::time::SystemTime::now() - std::time::Duration::from_secs(3600);
+        std::fs::File::options()
+            .write(true)
+            .open(data_dir.join("videos/monitor-0/stray.mp4"))
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+
+        let orphans = find_orphaned_chunks(conn, &data_dir).unwrap();
+        assert_eq!(orphans.video_chunks, vec![OrphanedChunk { id: missing, file_path: "videos/monitor-0/b.mp4".to_string() }]);
+        // The pruned chunk has no file by design
+        assert_eq!(orphans.audio_chunks.len(), 1);
+        assert_eq!(orphans.audio_chunks[0].id, 1);
+        assert_eq!(orphans.untracked_files, vec![data_dir.join("videos/monitor-0/stray.mp4")]);
+
+        remove_orphaned_chunks(conn, &orphans).unwrap();
+        assert!(get_video_chunk(conn, missing).unwrap().is_none());
+        assert!(get_video_chunk(conn, present).unwrap().is_some());
+        assert!(search_ocr(conn, "\"lost\"", 10, 0, false).unwrap().is_empty());
+        assert!(get_audio_chunk(conn, 1).unwrap().unwrap().pruned);
+        assert_eq!(search_transcriptions(conn, "\"standup\"", 10, 0, None, None, None).unwrap().len(), 1);
+
+        let orphans = find_orphaned_chunks(conn, &data_dir).unwrap();
+        assert!(orphans.video_chunks.is_empty() && orphans.audio_chunks.is_empty());
+        assert!(data_dir.join("videos/monitor-0/stray.mp4").exists());
+
+        std::fs::remove_dir_all(&data_dir).unwrap();
+    }
+
+    #[test]
+    fn test_reset_ocr_for_chunk() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+        let first = seed_chunk(conn, "monitor-0", "2025-03-01 10:00:00");
+        let second = seed_chunk(conn, "monitor-0", "2025-03-01 10:05:00");
+        for (chunk, minute) in [(first, 0), (second, 5)] {
+            for offset in 0..3 {
+                let frame = seed_frame(conn, chunk, offset, at(10, minute, offset as u32));
+                seed_ocr(conn, frame, "wrong language");
+            }
+        }
+
+        assert_eq!(reset_ocr_for_chunk(conn, first).unwrap(), 3);
+        assert_eq!(get_frames_for_chunk_without_ocr(conn, first).unwrap().len(), 3);
+        assert!(get_frames_for_chunk_without_ocr(conn, second).unwrap().is_empty());
+        assert_eq!(get_search_count(conn, "wrong", false).unwrap(), 3);
+
+        assert_eq!(reset_ocr_for_chunk(conn, first).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_reset_transcriptions() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+        seed_transcription(conn, "meeting notes", at(10, 0, 0));
+        seed_transcription(conn, "", at(10, 1, 0));
+        seed_transcription(conn, "old call", at(10, 2, 0));
+        conn.execute("UPDATE audio_chunks SET pruned_at = datetime('now') WHERE id = 3", []).unwrap();
+        let pending = |conn: &Connection| -> Vec<i64> {
+            get_audio_chunks_without_transcription(conn, 10).unwrap().iter().map(|c| c.id).collect()
+        };
+        assert!(pending(conn).is_empty());
+
+        // Only the chunk that produced no text is redone
+        assert_eq!(reset_empty_transcriptions(conn).unwrap(), 1);
+        assert_eq!(pending(conn), vec![2]);
+
+        assert_eq!(reset_all_transcriptions(conn).unwrap(), 1);
+        assert_eq!(pending(conn), vec![1, 2]);
+        assert!(search_transcriptions(conn, "meeting", 10, 0, None, None, None).unwrap().is_empty());
+
+        // The pruned chunk's audio is gone, so its text is kept
+        assert_eq!(search_transcriptions(conn, "old", 10, 0, None, None, None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_search_transcriptions_by_device() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+        seed_transcription(conn, "budget review said", at(10, 0, 0));
+        let loopback = insert_audio_chunk(conn, &NewAudioChunk {
+            file_path: "audio/loopback.wav".to_string(),
+            device_name: Some("Speakers".to_string()),
+            is_input_device: Some(false),
+            timestamp: at(10, 1, 0),
+        }).unwrap();
+        insert_audio_transcription(conn, &NewAudioTranscription {
+            audio_chunk_id: loopback,
+            transcription: "budget review heard".to_string(),
+            timestamp: at(10, 1, 0),
+            speaker_id: None,
+            start_time: None,
+            end_time: None,
+            language: None,
+        }).unwrap();
+
+        assert_eq!(search_transcriptions(conn, "budget", 10, 0, None, None, None).unwrap().len(), 2);
+        assert_eq!(get_audio_search_count(conn, "budget", None, None, None).unwrap(), 2);
+
+        let said = search_transcriptions(conn, "budget", 10, 0, None, Some(true), None).unwrap();
+        assert_eq!(said.len(), 1);
+        assert_eq!(said[0].0.transcription, "budget review said");
+        assert_eq!(get_audio_search_count(conn, "budget", None, Some(true), None).unwrap(), 1);
+
+        let heard = search_transcriptions(conn, "budget", 10, 0, Some("Speakers"), None, None).unwrap();
+        assert_eq!(heard.len(), 1);
+        assert_eq!(heard[0].0.transcription, "budget review heard");
+        assert_eq!(get_audio_search_count(conn, "budget", Some("Speakers"), Some(false), None).unwrap(), 1);
+        assert_eq!(get_audio_search_count(conn, "budget", Some("Speakers"), Some(true), None).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_search_transcriptions_by_language() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+        seed_transcription(conn, "budget review", at(10, 0, 0));
+        let chunk = insert_audio_chunk(conn, &NewAudioChunk {
+            file_path: "audio/fr.wav".to_string(),
+            device_name: Some("Microphone".to_string()),
+            is_input_device: Some(true),
+            timestamp: at(10, 1, 0),
+        }).unwrap();
+        insert_audio_transcription(conn, &NewAudioTranscription {
+            audio_chunk_id: chunk,
+            transcription: "revue du budget".to_string(),
+            timestamp: at(10, 1, 0),
+            speaker_id: None,
+            start_time: None,
+            end_time: None,
+            language: Some("fr".to_string()),
+        }).unwrap();
+
+        let french = search_transcriptions(conn, "budget", 10, 0, None, None, Some("fr")).unwrap();
+        assert_eq!(french.len(), 1);
+        assert_eq!(french[0].0.transcription, "revue du budget");
+        assert_eq!(french[0].0.language.as_deref(), Some("fr"));
+        assert_eq!(get_audio_search_count(conn, "budget", None, None, Some("en")).unwrap(), 1);
+        assert_eq!(get_audio_search_count(conn, "budget", None, None, Some("de")).unwrap(), 0);
+
+        let stored = get_transcription_by_chunk(conn, chunk).unwrap().unwrap();
+        assert_eq!(stored.language.as_deref(), Some("fr"));
+    }
+
+    #[test]
+    fn test_get_chunk_subtitle_cues() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+        let chunk = seed_chunk(conn, "monitor-0", "2025-03-01 10:00:00");
+
+        // 60 frames at 1 FPS covering 10:00:00..10:01:00
+        seed_frame(conn, chunk, 0, at(10, 0, 0));
+        seed_frame(conn, chunk, 59, at(10, 0, 59));
+
+        // Each segment spans 30 seconds from its timestamp
+        seed_transcription(conn, "before the chunk", at(9, 59, 0));
+        seed_transcription(conn, "straddles the start", at(9, 59, 50));
+        seed_transcription(conn, "inside", at(10, 0, 15));
+        seed_transcription(conn, "straddles the end", at(10, 0, 45));
+        seed_transcription(conn, "after the chunk", at(10, 1, 30));
+
+        let cues = get_chunk_subtitle_cues(conn, chunk).unwrap().unwrap();
+        let spans: Vec<_> = cues
+            .iter()
+            .map(|c| (c.text.as_str(), c.start_secs, c.end_secs))
+            .collect();
+        assert_eq!(spans, vec![
+            ("straddles the start", 0.0, 20.0),
+            ("inside", 15.0, 45.0),
+            ("straddles the end", 45.0, 60.0),
+        ]);
+
+        assert!(get_chunk_subtitle_cues(conn, chunk + 1).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_subtitle_cues_follow_audio_chunk_start() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+        let chunk = seed_chunk(conn, "monitor-0", "2025-03-01 10:00:00");
+        seed_frame(conn, chunk, 0, at(10, 0, 0));
+        seed_frame(conn, chunk, 59, at(10, 0, 59));
+
+        // Recorded 10:00:00..10:00:30 and saved once the chunk closed
+        let audio = insert_audio_chunk(conn, &NewAudioChunk {
+            file_path: "audio/standup.wav".to_string(),
+            device_name: Some("Microphone".to_string()),
+            is_input_device: Some(true),
+            timestamp: at(10, 0, 0),
+        }).unwrap();
+
+        // The audio indexer stamps segments with their chunk's time
+        let stored = get_audio_chunk(conn, audio).unwrap().unwrap();
+        assert_eq!(stored.timestamp, at(10, 0, 0));
+        for (text, start, end) in [("good morning", 2.0, 5.5), ("quick update", 20.0, 28.0)] {
+            insert_audio_transcription(conn, &NewAudioTranscription {
+                audio_chunk_id: audio,
+                transcription: text.to_string(),
+                timestamp: stored.timestamp,
+                speaker_id: None,
+                start_time: Some(start),
+                end_time: Some(end),
+                language: None,
+            }).unwrap();
+        }
+
+        let cues = get_chunk_subtitle_cues(conn, chunk).unwrap().unwrap();
+        let spans: Vec<_> = cues
+            .iter()
+            .map(|c| (c.text.as_str(), c.start_secs, c.end_secs))
+            .collect();
+        assert_eq!(spans, vec![("good morning", 2.0, 5.5), ("quick update", 20.0, 28.0)]);
+
+        let context = get_context_at(conn, at(10, 0, 3), 1).unwrap();
+        assert_eq!(context.audio.len(), 1);
+        assert_eq!(context.audio[0].transcription.transcription, "good morning");
+        assert_eq!(context.audio[0].start, at(10, 0, 2));
+    }
+
+    #[test]
+    fn test_get_context_at() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+        let chunk = seed_chunk(conn, "monitor-0", "2025-03-01 10:00:00");
+
+        for (i, second) in [0, 10, 20].into_iter().enumerate() {
+            let frame_id = seed_frame(conn, chunk, i as i64, at(10, 0, second));
+            seed_ocr(conn, frame_id, &format!("screen at {}", second));
+        }
+        seed_transcription(conn, "standup notes", at(9, 59, 50));
+        seed_transcription(conn, "after lunch", at(13, 0, 0));
+
+        let context = get_context_at(conn, at(10, 0, 12), 5).unwrap();
+        assert_eq!(context.frames.len(), 1);
+        assert_eq!(context.nearest_frame_id, Some(context.frames[0].id));
+        assert_eq!(context.frames[0].ocr_text.as_ref().unwrap().text, "screen at 10");
+        assert_eq!(context.audio.len(), 1);
+        assert_eq!(context.audio[0].transcription.transcription, "standup notes");
+        assert_eq!(context.audio[0].end, at(10, 0, 20));
+
+        // Static screen: fall back to the last frame before the moment
+        let context = get_context_at(conn, at(10, 30, 0), 5).unwrap();
+        assert_eq!(context.frames.len(), 1);
+        assert_eq!(context.frames[0].timestamp, at(10, 0, 20));
+        assert!(context.audio.is_empty());
+    }
+
+    #[test]
+    fn test_get_frames_for_chunk_without_ocr() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+        let chunk = seed_chunk(conn, "monitor-0", "2025-03-01 10:00:00");
+        let other = seed_chunk(conn, "monitor-1", "2025-03-01 10:00:00");
+
+        // Inserted out of order, with timestamps that disagree with the offsets
+        let third = seed_frame(conn, chunk, 2, at(10, 0, 0));
+        let first = seed_frame(conn, chunk, 0, at(10, 0, 2));
+        let second = seed_frame(conn, chunk, 1, at(10, 0, 1));
+        let done = seed_frame(conn, chunk, 3, at(10, 0, 3));
+        seed_ocr(conn, done, "already indexed");
+        seed_frame(conn, other, 0, at(10, 0, 0));
+
+        let frames = get_frames_for_chunk_without_ocr(conn, chunk).unwrap();
+        let ids: Vec<_> = frames.iter().map(|f| f.id).collect();
+        assert_eq!(ids, vec![first, second, third]);
+        assert!(frames.iter().all(|f| f.video_chunk_id == chunk));
+
+        seed_ocr(conn, second, "now indexed");
+        let offsets: Vec<_> = get_frames_for_chunk_without_ocr(conn, chunk)
+            .unwrap()
+            .iter()
+            .map(|f| f.offset_index)
+            .collect();
+        assert_eq!(offsets, vec![0, 2]);
+
+        assert!(get_frames_for_chunk_without_ocr(conn, other + 100).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_search_ocr() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+        let chunk = seed_chunk(conn, "monitor-0", "2025-03-01 10:00:00");
+
+        let first = seed_frame(conn, chunk, 0, at(10, 0, 0));
+        let second = seed_frame(conn, chunk, 1, at(10, 0, 1));
+        seed_ocr(conn, first, "cargo build failed with error E0308");
+        seed_ocr(conn, second, "All tests passed");
+
+        let query = sanitize_fts5_query("error").unwrap();
+        let results = search_ocr(conn, &query, 10, 0, false).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].1.id, first);
+        assert_eq!(results[0].0.language.as_deref(), Some("en-US"));
+        assert_eq!(get_search_count(conn, &query, false).unwrap(), 1);
+
+        let query = sanitize_fts5_query("deploy").unwrap();
+        assert!(search_ocr(conn, &query, 10, 0, false).unwrap().is_empty());
+        assert_eq!(get_search_count(conn, &query, false).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_sanitize_prefix_and_fuzzy_modes() {
+        assert_eq!(
+            sanitize_fts5_query_with_mode("config file", SearchMode::Prefix).unwrap(),
+            "\"config\"* \"file\"*"
+        );
+        // Operators and quotes are stripped or quoted, never passed through
+        assert_eq!(
+            sanitize_fts5_query_with_mode("a\" OR col:x* NOT", SearchMode::Prefix).unwrap(),
+            "\"a\"* \"OR\"* \"colx\"* \"NOT\"*"
+        );
+        assert_eq!(
+            sanitize_fts5_query_with_mode("confg server", SearchMode::Fuzzy).unwrap(),
+            "\"co\"* OR \"se\"*"
+        );
+        assert!(sanitize_fts5_query_with_mode("*()", SearchMode::Prefix).is_err());
+    }
+
+    #[test]
+    fn test_fuzzy_matches() {
+        assert!(fuzzy_matches("Edit configuration.toml", "config"));
+        assert!(fuzzy_matches("Edit configuration.toml", "confguration"));
+        assert!(fuzzy_matches("Deploy to production", "prodution deploy"));
+        assert!(!fuzzy_matches("Deploy to production", "staging"));
+        // Short terms must match exactly
+        assert!(!fuzzy_matches("cat", "cut"));
+    }
+
+    #[test]
+    fn test_search_modes() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+        let chunk = seed_chunk(conn, "monitor-0", "2025-03-01 10:00:00");
+
+        let first = seed_frame(conn, chunk, 0, at(10, 0, 0));
+        let second = seed_frame(conn, chunk, 1, at(10, 0, 1));
+        seed_ocr(conn, first, "Open configuration settings");
+        seed_ocr(conn, second, "Compile finished");
+
+        let exact = sanitize_fts5_query("config").unwrap();
+        assert!(search_ocr(conn, &exact, 10, 0, false).unwrap().is_empty());
+
+        let prefix = sanitize_fts5_query_with_mode("config", SearchMode::Prefix).unwrap();
+        let results = search_ocr(conn, &prefix, 10, 0, false).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].1.id, first);
+
+        let (results, total) = search_ocr_fuzzy(conn, "confguration", 10, 0, false).unwrap();
+        assert_eq!(total, 1);
+        assert_eq!(results[0].1.id, first);
+
+        let (results, total) = search_ocr_fuzzy(conn, "confguration", 10, 1, false).unwrap();
+        assert_eq!(total, 1);
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn test_search_ocr_substring() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+        let chunk = seed_chunk(conn, "monitor-0", "2025-03-01 10:00:00");
+
+        let first = seed_frame(conn, chunk, 0, at(10, 0, 0));
+        let second = seed_frame(conn, chunk, 1, at(10, 0, 1));
+        seed_ocr(conn, first, "let myConfigValue = load();");
+        seed_ocr(conn, second, "Compile finished");
+
+        // Word tokens never see the substring
+        let exact = sanitize_fts5_query("config").unwrap();
+        assert!(search_ocr(conn, &exact, 10, 0, false).unwrap().is_empty());
+
+        let query = sanitize_fts5_query_with_mode("config", SearchMode::Substring).unwrap();
+        let results = search_ocr_substring(conn, &query, 10, 0, false).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].1.id, first);
+        assert_eq!(get_substring_search_count(conn, &query, false).unwrap(), 1);
+
+        let query = sanitize_fts5_query_with_mode("ile fin", SearchMode::Substring).unwrap();
+        assert_eq!(search_ocr_substring(conn, &query, 10, 0, false).unwrap()[0].1.id, second);
+
+        // Updates reach the trigram index through its triggers
+        conn.execute("UPDATE ocr_text SET text = 'nothing here' WHERE frame_id = ?1", [first]).unwrap();
+        let query = sanitize_fts5_query_with_mode("config", SearchMode::Substring).unwrap();
+        assert_eq!(get_substring_search_count(conn, &query, false).unwrap(), 0);
+
+        assert!(sanitize_fts5_query_with_mode("ab", SearchMode::Substring).is_err());
+    }
+
+    #[test]
+    fn test_update_chunk_metadata() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+        let chunk = seed_chunk(conn, "monitor-0", "2025-03-01 10:00:00");
+
+        let before = get_video_chunk(conn, chunk).unwrap().unwrap();
+        assert_eq!(before.codec, None);
+
+        update_chunk_metadata(conn, chunk, &VideoChunkMetadata {
+            width: 2560,
+            height: 1440,
+            codec: "h264".to_string(),
+            duration_secs: 299.0,
+            size_bytes: 48_213_311,
+        }).unwrap();
+
+        let after = get_video_chunk(conn, chunk).unwrap().unwrap();
+        assert_eq!((after.width, after.height), (Some(2560), Some(1440)));
+        assert_eq!(after.codec.as_deref(), Some("h264"));
+        assert_eq!(after.duration_secs, Some(299.0));
+        assert_eq!(after.size_bytes, Some(48_213_311));
+    }
+
+    #[test]
+    fn test_get_chunks_paginated_filters() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+
+        let early = seed_chunk(conn, "monitor-0", "2025-03-01 09:00:00");
+        let late = seed_chunk(conn, "monitor-0", "2025-03-01 11:00:00");
+        let other = seed_chunk(conn, "monitor-1", "2025-03-01 10:00:00");
+        seed_frame(conn, late, 0, at(11, 0, 0));
+        seed_frame(conn, late, 1, at(11, 0, 1));
+
+        // No filters: newest first, with frame counts
+        let chunks = get_chunks_paginated(conn, 10, 0, None, None, None).unwrap();
+        let ids: Vec<_> = chunks.iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec![late, other, early]);
+        assert_eq!(chunks[0].frame_count, 2);
+        assert_eq!(chunks[2].frame_count, 0);
+
+        // Monitor filter
+        let chunks = get_chunks_paginated(conn, 10, 0, Some("monitor-1"), None, None).unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].id, other);
+        assert_eq!(get_total_chunk_count(conn, Some("monitor-1"), None, None).unwrap(), 1);
+
+        // Date filters are inclusive and compare correctly against SQLite-format created_at
+        let chunks = get_chunks_paginated(conn, 10, 0, None, Some(at(10, 0, 0)), Some(at(11, 0, 0))).unwrap();
+        let ids: Vec<_> = chunks.iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec![late, other]);
+        assert_eq!(get_total_chunk_count(conn, None, Some(at(10, 0, 0)), Some(at(11, 0, 0))).unwrap(), 2);
+
+        let chunks = get_chunks_paginated(conn, 10, 0, Some("monitor-0"), Some(at(9, 30, 0)), None).unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].id, late);
+
+        // Pagination
+        let chunks = get_chunks_paginated(conn, 1, 1, None, None, None).unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].id, other);
+    }
+
+    #[test]
+    fn test_ocr_text_references() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+        let chunk = seed_chunk(conn, "monitor-0", "2025-03-01 10:00:00");
+
+        let first = seed_frame(conn, chunk, 0, at(10, 0, 0));
+        let second = seed_frame(conn, chunk, 1, at(10, 0, 1));
+        let third = seed_frame(conn, chunk, 2, at(10, 0, 2));
+
+        assert!(get_previous_ocr_text(conn, chunk, 1).unwrap().is_none());
+
+        seed_ocr(conn, first, "static editor contents");
+        insert_ocr_reference(conn, second, first).unwrap();
+
+        // References resolve to the frame holding the text
+        assert_eq!(
+            get_previous_ocr_text(conn, chunk, 2).unwrap(),
+            Some((first, "static editor contents".to_string()))
+        );
+        let ocr = get_ocr_text_by_frame(conn, second).unwrap().unwrap();
+        assert_eq!(ocr.frame_id, second);
+        assert_eq!(ocr.text, "static editor contents");
+
+        // Referenced frames count as processed and survive an empty-text reset
+        let pending: Vec<_> = get_frames_without_ocr(conn, 10).unwrap().iter().map(|f| f.id).collect();
+        assert_eq!(pending, vec![third]);
+        assert_eq!(reset_empty_ocr(conn).unwrap(), 0);
+        assert_eq!(get_ocr_count(conn).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_get_ocr_by_hash() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+        let morning = seed_chunk(conn, "monitor-0", "2025-03-01 10:00:00");
+        let evening = seed_chunk(conn, "monitor-0", "2025-03-01 18:00:00");
+
+        let first = seed_frame(conn, morning, 0, at(10, 0, 0));
+        let second = seed_frame(conn, morning, 1, at(10, 0, 1));
+        let blank = seed_frame(conn, morning, 2, at(10, 0, 2));
+        let later = seed_frame(conn, evening, 0, at(18, 0, 0));
+        for (id, hash) in [(first, 42), (second, 42), (blank, 7), (later, 42)] {
+            conn.execute("UPDATE frames SET frame_hash = ?1 WHERE id = ?2", params![hash, id]).unwrap();
+        }
+
+        assert!(get_ocr_by_hash(conn, 42).unwrap().is_none());
+
+        seed_ocr(conn, first, "inbox zero");
+        insert_ocr_reference(conn, second, first).unwrap();
+        seed_ocr(conn, blank, "");
+
+        // Matches across chunks and resolves references to the source frame
+        assert_eq!(get_ocr_by_hash(conn, 42).unwrap(), Some((first, "inbox zero".to_string())));
+        // Empty text is never reused
+        assert!(get_ocr_by_hash(conn, 7).unwrap().is_none());
+        assert!(get_ocr_by_hash(conn, 99).unwrap().is_none());
+
+        // The most recent match wins
+        seed_ocr(conn, later, "inbox one");
+        assert_eq!(get_ocr_by_hash(conn, 42).unwrap(), Some((later, "inbox one".to_string())));
+    }
+
+    #[test]
+    fn test_frame_index_gaps_use_capture_index() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+        let chunk = seed_chunk(conn, "monitor-0", "2025-03-01 10:00:00");
+
+        // Padded video positions skip ahead over deduplicated frames; only the
+        // missing capture index 2 is a dropped frame
+        for (offset_index, capture_index) in [(0, Some(0)), (5, Some(1)), (9, Some(3)), (12, Some(4)), (14, None)] {
+            insert_frame(conn, &NewFrame {
+                video_chunk_id: chunk,
+                offset_index,
+                capture_index,
+                timestamp: at(10, 0, offset_index as u32),
+                app_name: None,
+                window_name: None,
+                browser_url: None,
+                focused: true,
+                frame_hash: None,
+            }).unwrap();
+        }
+
+        assert_eq!(get_frame_index_gaps(conn, chunk).unwrap(), vec![(2, 2)]);
+    }
+
+    #[test]
+    fn test_search_all_pagination() {
+        let db = Database::open_in_memory().unwrap();
+        let conn = db.connection();
+        let chunk = seed_chunk(conn, "monitor-0", "2025-03-01 10:00:00");
+
+        // Interleave OCR and audio results in time
+        for (i, minute) in [0, 2, 4].into_iter().enumerate() {
+            let frame = seed_frame(conn, chunk, i as i64, at(10, minute, 0));
+            seed_ocr(conn, frame, "weekly standup notes");
+        }
+        seed_transcription(conn, "standup starts now", at(10, 1, 0));
+        seed_transcription(conn, "standup is over", at(10, 3, 0));
+
+        let query = sanitize_fts5_query("standup").unwrap();
+        let timestamp = |r: &SearchResult| match r {
+            SearchResult::Ocr { frame, .. } => frame.timestamp,
+            SearchResult::Audio { transcription, .. } => transcription.timestamp,
+        };
+
+        let mut seen = Vec::new();
+        for offset in [0, 2, 4] {
+            let page = search_all(conn, &query, 2, offset).unwrap();
+            seen.extend(page.iter().map(timestamp));
+        }
+
+        assert_eq!(
+            seen,
+            vec![at(10, 4, 0), at(10, 3, 0), at(10, 2, 0), at(10, 1, 0), at(10, 0, 0)]
+        );
+        assert!(search_all(conn, &query, 2, 6).unwrap().is_empty());
+    }
+}
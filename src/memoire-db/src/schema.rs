@@ -12,6 +12,11 @@ pub struct VideoChunk {
     pub created_at: DateTime<Utc>,
     pub width: Option<u32>,
     pub height: Option<u32>,
+    pub fps: Option<f64>,
+    /// Set once the chunk is finalized (None for chunks still recording)
+    pub codec: Option<String>,
+    pub duration_secs: Option<f64>,
+    pub size_bytes: Option<i64>,
 }
 
 /// Frame metadata within a video chunk
@@ -28,6 +33,14 @@ pub struct Frame {
     pub frame_hash: Option<i64>,
 }
 
+/// Frame whose perceptual hash is close to a searched hash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimilarFrame {
+    pub frame: Frame,
+    /// Hamming distance between the hashes (0 = identical)
+    pub distance: u32,
+}
+
 /// OCR extracted text from a frame
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OcrText {
@@ -36,6 +49,44 @@ pub struct OcrText {
     pub text: String,
     pub text_json: Option<String>, // Bounding boxes as JSON
     pub confidence: Option<f64>,
+    /// BCP47 tag of the OCR language that produced the text
+    pub language: Option<String>,
+}
+
+/// Rectangle normalized to frame dimensions (all values in 0.0..=1.0)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BoundingBox {
+    pub x: f64,
+    pub y: f64,
+    pub w: f64,
+    pub h: f64,
+}
+
+impl BoundingBox {
+    /// Screen quadrant by name: `top-left`, `top-right`, `bottom-left`, `bottom-right`
+    pub fn quadrant(name: &str) -> Option<Self> {
+        let (x, y) = match name {
+            "top-left" => (0.0, 0.0),
+            "top-right" => (0.5, 0.0),
+            "bottom-left" => (0.0, 0.5),
+            "bottom-right" => (0.5, 0.5),
+            _ => return None,
+        };
+        Some(Self { x, y, w: 0.5, h: 0.5 })
+    }
+
+    /// Center point of the box
+    pub fn center(&self) -> (f64, f64) {
+        (self.x + self.w / 2.0, self.y + self.h / 2.0)
+    }
+}
+
+/// Single OCR line with its position in the frame
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrLineBox {
+    pub text: String,
+    pub confidence: Option<f64>,
+    pub bbox: BoundingBox,
 }
 
 /// Audio chunk metadata (30-second segments)
@@ -46,6 +97,8 @@ pub struct AudioChunk {
     pub device_name: Option<String>,
     pub is_input_device: Option<bool>,
     pub timestamp: DateTime<Utc>,
+    /// The audio file was deleted by retention; transcriptions remain
+    pub pruned: bool,
 }
 
 /// Audio transcription with timestamps
@@ -58,6 +111,8 @@ pub struct AudioTranscription {
     pub speaker_id: Option<i64>,
     pub start_time: Option<f64>,
     pub end_time: Option<f64>,
+    /// ISO 639-1 code (None when unknown)
+    pub language: Option<String>,
 }
 
 /// New video chunk to insert
@@ -67,13 +122,27 @@ pub struct NewVideoChunk {
     pub device_name: String,
     pub width: Option<u32>,
     pub height: Option<u32>,
+    pub fps: Option<f64>,
+}
+
+/// Metadata of a finished video chunk, as encoded
+#[derive(Debug, Clone)]
+pub struct VideoChunkMetadata {
+    pub width: u32,
+    pub height: u32,
+    pub codec: String,
+    pub duration_secs: f64,
+    pub size_bytes: i64,
 }
 
 /// New frame to insert
 #[derive(Debug, Clone)]
 pub struct NewFrame {
     pub video_chunk_id: i64,
+    /// Position in the video, in frames from the chunk start
     pub offset_index: i64,
+    /// Sequence number among the chunk's stored frames, for dropped-frame detection
+    pub capture_index: Option<i64>,
     pub timestamp: DateTime<Utc>,
     pub app_name: Option<String>,
     pub window_name: Option<String>,
@@ -89,6 +158,7 @@ pub struct NewOcrText {
     pub text: String,
     pub text_json: Option<String>,
     pub confidence: Option<f64>,
+    pub language: Option<String>,
 }
 
 /// Video chunk with frame count (for validation viewer)
@@ -112,6 +182,14 @@ pub struct MonitorSummary {
     pub latest_capture: Option<DateTime<Utc>>,
 }
 
+/// Frame count for one time bucket and app (activity histogram)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityBucket {
+    pub bucket_start: DateTime<Utc>,
+    pub app_name: Option<String>,
+    pub frame_count: i64,
+}
+
 /// Frame with optional OCR text (from LEFT JOIN)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FrameWithOcr {
@@ -137,12 +215,33 @@ pub struct OcrStats {
     pub last_updated: Option<DateTime<Utc>>,
 }
 
+/// Database totals returned by [`crate::SearchClient::stats`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseStats {
+    pub total_chunks: i64,
+    pub total_frames: i64,
+    pub ocr: OcrStats,
+    pub audio: AudioStats,
+}
+
+/// Frame deduplication statistics from recording_stats
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DedupStats {
+    pub captured_frames: i64,
+    pub skipped_frames: i64,
+    /// Fraction of frames skipped as duplicates (0.0 - 1.0)
+    pub dedup_ratio: f64,
+    pub chunks: i64,
+}
+
 /// New audio chunk to insert
 #[derive(Debug, Clone)]
 pub struct NewAudioChunk {
     pub file_path: String,
     pub device_name: Option<String>,
     pub is_input_device: Option<bool>,
+    /// When the chunk's first sample was captured
+    pub timestamp: DateTime<Utc>,
 }
 
 /// New audio transcription to insert
@@ -154,6 +253,51 @@ pub struct NewAudioTranscription {
     pub speaker_id: Option<i64>,
     pub start_time: Option<f64>,
     pub end_time: Option<f64>,
+    pub language: Option<String>,
+}
+
+/// Audio files removed by [`crate::delete_audio_files_older_than`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PrunedAudio {
+    pub chunks: usize,
+    pub bytes_freed: u64,
+}
+
+/// Chunk rows and media files that disagree, from [`crate::find_orphaned_chunks`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OrphanedChunks {
+    /// Video chunks whose file is missing
+    pub video_chunks: Vec<OrphanedChunk>,
+    /// Audio chunks whose file is missing (pruned chunks are expected to have none)
+    pub audio_chunks: Vec<OrphanedChunk>,
+    /// Media files under `videos/` and `audio/` that no chunk refers to
+    pub untracked_files: Vec<std::path::PathBuf>,
+}
+
+impl OrphanedChunks {
+    /// Whether the database and the files agree
+    pub fn is_empty(&self) -> bool {
+        self.video_chunks.is_empty() && self.audio_chunks.is_empty() && self.untracked_files.is_empty()
+    }
+}
+
+/// A chunk row whose media file is gone
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrphanedChunk {
+    pub id: i64,
+    pub file_path: String,
+}
+
+/// Recordings removed by [`crate::enforce_size_limit`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PrunedStorage {
+    pub video_chunks: usize,
+    pub audio_chunks: usize,
+    pub bytes_freed: u64,
+    /// Database and media size after pruning
+    pub bytes_used: u64,
+    /// Chunks whose file could not be deleted (e.g. open in the viewer)
+    pub skipped: usize,
 }
 
 /// Audio indexing statistics
@@ -166,6 +310,76 @@ pub struct AudioStats {
     pub last_updated: Option<DateTime<Utc>>,
 }
 
+/// Recordings made since the start of a period
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeriodActivity {
+    pub since: DateTime<Utc>,
+    pub frames: i64,
+    pub video_chunks: i64,
+    pub audio_chunks: i64,
+}
+
+/// Frames captured while an app was in the foreground
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppUsage {
+    pub app_name: String,
+    pub frames: i64,
+}
+
+/// Bytes used by the database and the recordings it references
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiskUsage {
+    pub database_bytes: u64,
+    pub video_bytes: u64,
+    pub audio_bytes: u64,
+    pub total_bytes: u64,
+}
+
+/// Recent activity, indexing backlog and disk usage in one call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DashboardSummary {
+    pub today: PeriodActivity,
+    pub this_week: PeriodActivity,
+    /// Apps with the most frames this week (frames without an app name are skipped)
+    pub top_apps: Vec<AppUsage>,
+    /// Frames waiting for OCR
+    pub ocr_backlog: i64,
+    /// Audio chunks waiting for transcription (pruned chunks excluded)
+    pub transcription_backlog: i64,
+    pub disk: DiskUsage,
+}
+
+/// Transcription segment placed on the wall clock
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimedTranscription {
+    pub transcription: AudioTranscription,
+    pub chunk: AudioChunk,
+    /// When the segment starts (chunk timestamp plus the segment offset)
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+/// Subtitle cue relative to the start of a video chunk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubtitleCue {
+    pub start_secs: f64,
+    pub end_secs: f64,
+    pub text: String,
+}
+
+/// Screen and audio activity around a point in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MomentContext {
+    pub at: DateTime<Utc>,
+    pub window_secs: i64,
+    /// Frames within the window, oldest first
+    pub frames: Vec<FrameWithOcr>,
+    /// Frame closest to `at`; precedes the window when the screen was static
+    pub nearest_frame_id: Option<i64>,
+    /// Transcription segments overlapping the window, in spoken order
+    pub audio: Vec<TimedTranscription>,
+}
+
 /// Audio chunk with transcription count
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AudioChunkWithTranscription {
@@ -175,6 +389,23 @@ pub struct AudioChunkWithTranscription {
     pub is_input_device: Option<bool>,
     pub timestamp: DateTime<Utc>,
     pub transcription_count: i64,
+    pub pruned: bool,
+}
+
+/// How a full-text search query is matched
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchMode {
+    /// Literal phrase match
+    #[default]
+    Exact,
+    /// Every term matches the start of a word ("config" finds "configuration")
+    Prefix,
+    /// Every term is within a small edit distance of a word, tolerating typos
+    Fuzzy,
+    /// The phrase appears anywhere, even inside a word ("config" finds
+    /// "myconfigvalue"); needs at least 3 characters. OCR text only.
+    Substring,
 }
 
 /// Unified search result type
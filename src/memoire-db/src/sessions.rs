@@ -0,0 +1,73 @@
+//! Grouping time-stamped results into sessions
+//!
+//! A session is a run of results where each one is at most a given gap after
+//! the previous, e.g. every stretch of work on one project in a result list.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+/// Results within one session, oldest first
+#[derive(Debug, Clone, Serialize)]
+pub struct ResultSession<T> {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub results: Vec<T>,
+}
+
+/// Sort `items` by time and split them wherever two neighbours are more than `gap` apart
+///
+/// Sessions are returned oldest first.
+pub fn group_by_gap<T>(
+    mut items: Vec<T>,
+    gap: Duration,
+    timestamp: impl Fn(&T) -> DateTime<Utc>,
+) -> Vec<ResultSession<T>> {
+    items.sort_by_key(|item| timestamp(item));
+
+    let mut sessions: Vec<ResultSession<T>> = Vec::new();
+    for item in items {
+        let at = timestamp(&item);
+        match sessions.last_mut() {
+            Some(session) if at - session.end <= gap => {
+                session.end = at;
+                session.results.push(item);
+            }
+            _ => sessions.push(ResultSession {
+                start: at,
+                end: at,
+                results: vec![item],
+            }),
+        }
+    }
+    sessions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(h: u32, m: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, 1, h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn test_group_by_gap() {
+        // Unordered, as search results come back by rank
+        let items = vec![at(10, 4), at(14, 0), at(10, 0), at(10, 8), at(14, 30)];
+
+        let sessions = group_by_gap(items, Duration::minutes(5), |t| *t);
+
+        assert_eq!(sessions.len(), 3);
+        assert_eq!((sessions[0].start, sessions[0].end), (at(10, 0), at(10, 8)));
+        assert_eq!(sessions[0].results, vec![at(10, 0), at(10, 4), at(10, 8)]);
+        assert_eq!(sessions[1].results, vec![at(14, 0)]);
+        assert_eq!((sessions[2].start, sessions[2].end), (at(14, 30), at(14, 30)));
+    }
+
+    #[test]
+    fn test_group_by_gap_empty() {
+        let sessions = group_by_gap(Vec::<DateTime<Utc>>::new(), Duration::minutes(5), |t| *t);
+        assert!(sessions.is_empty());
+    }
+}
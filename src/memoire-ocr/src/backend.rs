@@ -0,0 +1,140 @@
+//! Pluggable OCR backends
+//!
+//! The indexer only needs "frame in, text out". [`OcrBackend`] is that
+//! contract, so the Windows [`Processor`](crate::Processor) can be swapped
+//! for another engine (e.g. Tesseract on other platforms) or for
+//! [`MockBackend`] in tests.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::error::Result;
+use crate::types::{FrameData, OcrFrameResult, OcrLine, OcrWord};
+
+/// Recognizes the text of one RGBA frame
+///
+/// Futures are not required to be `Send`: Windows OCR runs on the calling
+/// thread, so callers drive backends on a local task set.
+pub trait OcrBackend {
+    /// Recognize the text of `frame`
+    ///
+    /// Word boxes are in `frame`'s pixel coordinates and the result's
+    /// `width`/`height` are the frame's.
+    fn process_frame(&self, frame: FrameData) -> impl Future<Output = Result<OcrFrameResult>>;
+}
+
+#[cfg(windows)]
+impl OcrBackend for crate::Processor {
+    async fn process_frame(&self, frame: FrameData) -> Result<OcrFrameResult> {
+        crate::Processor::process_frame(self, frame).await
+    }
+}
+
+/// Backend answering every frame with a closure, for tests
+///
+/// Counts the frames it was asked to recognize, so tests can check which
+/// frames reached OCR.
+pub struct MockBackend<F> {
+    recognize: F,
+    calls: AtomicUsize,
+}
+
+impl<F> MockBackend<F>
+where
+    F: Fn(&FrameData) -> Result<OcrFrameResult>,
+{
+    /// Backend returning `recognize(frame)` for each frame
+    pub fn new(recognize: F) -> Self {
+        Self { recognize, calls: AtomicUsize::new(0) }
+    }
+
+    /// Number of frames processed so far
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::Relaxed)
+    }
+}
+
+impl MockBackend<fn(&FrameData) -> Result<OcrFrameResult>> {
+    /// Backend that reads `"frame {width}x{height}"` as one full-width line
+    pub fn echo() -> Self {
+        Self::new(|frame| Ok(single_line_result(&format!("frame {}x{}", frame.width, frame.height), frame)))
+    }
+}
+
+impl<F> OcrBackend for MockBackend<F>
+where
+    F: Fn(&FrameData) -> Result<OcrFrameResult>,
+{
+    async fn process_frame(&self, frame: FrameData) -> Result<OcrFrameResult> {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        (self.recognize)(&frame)
+    }
+}
+
+/// A result holding `text` as a single line spanning the whole frame
+pub fn single_line_result(text: &str, frame: &FrameData) -> OcrFrameResult {
+    let word = OcrWord {
+        text: text.to_string(),
+        confidence: 1.0,
+        x: 0.0,
+        y: 0.0,
+        width: frame.width as f32,
+        height: frame.height as f32,
+    };
+    OcrFrameResult {
+        text: text.to_string(),
+        lines: vec![OcrLine { text: text.to_string(), words: vec![word], language: None }],
+        confidence: 1.0,
+        width: frame.width,
+        height: frame.height,
+        language: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::OcrError;
+
+    fn frame(width: u32, height: u32) -> FrameData {
+        FrameData { width, height, data: vec![0; (width * height * 4) as usize] }
+    }
+
+    /// Generic caller, as the indexer uses backends
+    async fn recognize_all<B: OcrBackend>(backend: &B, frames: Vec<FrameData>) -> Vec<Result<OcrFrameResult>> {
+        let mut results = Vec::new();
+        for frame in frames {
+            results.push(backend.process_frame(frame).await);
+        }
+        results
+    }
+
+    #[tokio::test]
+    async fn test_mock_backend() {
+        let backend = MockBackend::echo();
+        let results = recognize_all(&backend, vec![frame(4, 2), frame(8, 8)]).await;
+
+        assert_eq!(backend.calls(), 2);
+        let first = results[0].as_ref().unwrap();
+        assert_eq!(first.text, "frame 4x2");
+        assert_eq!((first.width, first.height), (4, 2));
+        assert_eq!(first.lines[0].bounding_box(), Some((0.0, 0.0, 4.0, 2.0)));
+        assert_eq!(results[1].as_ref().unwrap().text, "frame 8x8");
+    }
+
+    #[tokio::test]
+    async fn test_mock_backend_errors() {
+        let backend = MockBackend::new(|frame: &FrameData| {
+            if frame.width > 4 {
+                Err(OcrError::ProcessingError("too wide".to_string()))
+            } else {
+                Ok(single_line_result("ok", frame))
+            }
+        });
+        let results = recognize_all(&backend, vec![frame(2, 2), frame(8, 2)]).await;
+
+        assert_eq!(backend.calls(), 2);
+        assert!(results[0].is_ok());
+        assert!(matches!(results[1], Err(OcrError::ProcessingError(_))));
+    }
+}
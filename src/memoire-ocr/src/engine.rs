@@ -1,5 +1,5 @@
 use crate::error::{OcrError, Result};
-use serde::{Deserialize, Serialize};
+use crate::types::{OcrFrameResult, OcrLine, OcrWord};
 use tracing::debug;
 use windows::{
     Foundation::IAsyncOperation,
@@ -8,35 +8,10 @@ use windows::{
     Globalization::Language,
 };
 
-/// OCR word with bounding box and confidence
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct OcrWord {
-    pub text: String,
-    pub confidence: f32,
-    pub x: f32,
-    pub y: f32,
-    pub width: f32,
-    pub height: f32,
-}
-
-/// OCR line containing multiple words
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct OcrLine {
-    pub text: String,
-    pub words: Vec<OcrWord>,
-}
-
-/// Complete OCR result for a frame
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct OcrFrameResult {
-    pub text: String,
-    pub lines: Vec<OcrLine>,
-    pub confidence: f32,
-}
-
 /// Windows OCR engine wrapper
 pub struct Engine {
     engine: OcrEngine,
+    language: String,
 }
 
 impl Engine {
@@ -55,8 +30,19 @@ impl Engine {
                 .map_err(|e| OcrError::EngineInitFailed(format!("failed to create engine from user profile: {}", e)))?
         };
 
-        debug!("OCR engine initialized successfully");
-        Ok(Self { engine })
+        let language = engine
+            .RecognizerLanguage()
+            .and_then(|lang| lang.LanguageTag())
+            .map(|tag| tag.to_string())
+            .unwrap_or_else(|_| language_tag.unwrap_or_default().to_string());
+
+        debug!("OCR engine initialized successfully ({})", language);
+        Ok(Self { engine, language })
+    }
+
+    /// BCP47 tag of the language this engine recognizes
+    pub fn language_tag(&self) -> &str {
+        &self.language
     }
 
     /// Create default OCR engine using system language
@@ -80,12 +66,15 @@ impl Engine {
         let result = ocr_result.get()
             .map_err(|e| OcrError::ProcessingError(format!("OCR recognition failed: {}", e)))?;
 
+        let width = bitmap.PixelWidth()? as u32;
+        let height = bitmap.PixelHeight()? as u32;
+
         // Parse results
-        self.parse_result(&result)
+        self.parse_result(&result, width, height)
     }
 
     /// Parse Windows OcrResult into our structured format
-    fn parse_result(&self, result: &WinOcrResult) -> Result<OcrFrameResult> {
+    fn parse_result(&self, result: &WinOcrResult, width: u32, height: u32) -> Result<OcrFrameResult> {
         let mut lines = Vec::new();
         let mut all_text = String::new();
         let mut total_confidence = 0.0;
@@ -131,6 +120,7 @@ impl Engine {
                 lines.push(OcrLine {
                     text: line_text,
                     words: ocr_words,
+                    language: Some(self.language.clone()),
                 });
             }
         }
@@ -149,6 +139,9 @@ impl Engine {
             text: all_text,
             lines,
             confidence: avg_confidence,
+            width,
+            height,
+            language: Some(self.language.clone()),
         })
     }
 
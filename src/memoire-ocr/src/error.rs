@@ -11,6 +11,7 @@ pub enum OcrError {
     #[error("OCR processing error: {0}")]
     ProcessingError(String),
 
+    #[cfg(windows)]
     #[error("windows API error: {0}")]
     WindowsError(#[from] windows::core::Error),
 
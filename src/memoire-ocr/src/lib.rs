@@ -2,24 +2,49 @@
 //!
 //! This crate provides OCR (Optical Character Recognition) capabilities using the
 //! Windows.Media.Ocr API. It processes RGBA frames and extracts text with bounding
-//! boxes and confidence scores.
+//! boxes and confidence scores. The recognizer is Windows-only; on other
+//! platforms only the data types, the [`OcrBackend`] trait and the portable
+//! helpers are built.
 
+mod backend;
+#[cfg(windows)]
 mod engine;
 mod error;
+#[cfg(windows)]
 mod processor;
+mod types;
 
-pub use engine::{Engine, OcrFrameResult, OcrLine, OcrWord};
+pub use backend::{single_line_result, MockBackend, OcrBackend};
+#[cfg(windows)]
+pub use engine::Engine;
 pub use error::{OcrError, Result};
-pub use processor::{FrameData, Processor};
+#[cfg(windows)]
+pub use processor::Processor;
+pub use types::{FrameData, OcrFrameResult, OcrLine, OcrWord};
 
 /// Initialize OCR processor with default settings (English)
+#[cfg(windows)]
 pub fn create_processor() -> Result<Processor> {
     Processor::new()
 }
 
 /// Initialize OCR processor with custom language
+///
+/// Accepts a comma-separated list (e.g. `"en-US,ja-JP"`) to recognize each
+/// frame in every language and keep the most confident result.
+#[cfg(windows)]
 pub fn create_processor_with_language(language_tag: &str) -> Result<Processor> {
-    Processor::with_language(language_tag)
+    let tags: Vec<&str> = parse_language_list(language_tag);
+    Processor::with_languages(&tags)
+}
+
+/// Split a comma-separated list of language tags, dropping blanks
+pub fn parse_language_list(languages: &str) -> Vec<&str> {
+    languages
+        .split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .collect()
 }
 
 #[cfg(test)]
@@ -27,8 +52,16 @@ mod tests {
     use super::*;
 
     #[test]
+    #[cfg(windows)]
     fn test_create_processor() {
         let processor = create_processor();
         assert!(processor.is_ok());
     }
+
+    #[test]
+    fn test_parse_language_list() {
+        assert_eq!(parse_language_list("en-US, ja-JP,,"), vec!["en-US", "ja-JP"]);
+        assert_eq!(parse_language_list("fr-FR"), vec!["fr-FR"]);
+        assert!(parse_language_list(" , ").is_empty());
+    }
 }
@@ -1,44 +1,123 @@
-use crate::engine::{Engine, OcrFrameResult};
+use crate::engine::Engine;
+use crate::types::{FrameData, OcrFrameResult, OcrLine};
 use crate::error::{OcrError, Result};
-use tracing::{debug, warn};
+use tracing::{debug, info, warn};
 use windows::Graphics::Imaging::{
     BitmapAlphaMode, BitmapPixelFormat, SoftwareBitmap,
 };
 
-/// Frame data for OCR processing
-pub struct FrameData {
-    pub width: u32,
-    pub height: u32,
-    pub data: Vec<u8>, // RGBA format
-}
+/// Minimum overlap (intersection over the smaller box) for lines read by
+/// different languages to count as the same text
+const LINE_OVERLAP_THRESHOLD: f32 = 0.5;
 
 /// OCR processor that converts frames and performs recognition
+///
+/// With several languages every frame is recognized once per language and
+/// the result with the highest [`OcrFrameResult::score`] is kept, unless
+/// merging is enabled, in which case lines from all languages are combined.
 pub struct Processor {
-    engine: Engine,
+    engines: Vec<Engine>,
+    merge_languages: bool,
+    max_dimension: Option<u32>,
 }
 
 impl Processor {
     /// Create a new processor with default OCR engine
     pub fn new() -> Result<Self> {
         let engine = Engine::english()?;
-        Ok(Self { engine })
+        Ok(Self { engines: vec![engine], merge_languages: false, max_dimension: None })
     }
 
     /// Create processor with custom language
     pub fn with_language(language_tag: &str) -> Result<Self> {
-        let engine = Engine::new(Some(language_tag))?;
-        Ok(Self { engine })
+        Self::with_languages(&[language_tag])
+    }
+
+    /// Create processor that picks the best of several languages per frame
+    pub fn with_languages(language_tags: &[&str]) -> Result<Self> {
+        if language_tags.is_empty() {
+            return Err(OcrError::EngineInitFailed("no OCR language given".to_string()));
+        }
+
+        let engines = language_tags
+            .iter()
+            .map(|tag| Engine::new(Some(tag)))
+            .collect::<Result<Vec<_>>>()?;
+
+        if engines.len() > 1 {
+            info!("OCR languages: {}", language_tags.join(", "));
+        }
+
+        Ok(Self { engines, merge_languages: false, max_dimension: None })
+    }
+
+    /// Combine lines from every language instead of keeping the best result
+    ///
+    /// For frames mixing scripts (e.g. English UI around Japanese text), where
+    /// each recognizer only reads part of the screen. Overlapping lines keep the
+    /// higher-scoring reading; each line is tagged with its language.
+    pub fn set_merge_languages(&mut self, merge: bool) {
+        if merge && self.engines.len() > 1 {
+            info!("merging OCR lines across languages");
+        }
+        self.merge_languages = merge;
+    }
+
+    /// Downscale frames whose longest side exceeds `max_dimension` before OCR
+    ///
+    /// The aspect ratio is preserved and bounding boxes are scaled back to the
+    /// original frame, so stored coordinates don't depend on this setting.
+    /// `None` recognizes frames at full resolution.
+    pub fn set_max_dimension(&mut self, max_dimension: Option<u32>) {
+        self.max_dimension = max_dimension.filter(|&max| max > 0);
+        if let Some(max) = self.max_dimension {
+            info!("downscaling OCR frames to at most {}px", max);
+        }
+    }
+
+    /// Language tags of the configured recognizers
+    pub fn languages(&self) -> Vec<&str> {
+        self.engines.iter().map(Engine::language_tag).collect()
     }
 
     /// Process a single RGBA frame
     pub async fn process_frame(&self, frame: FrameData) -> Result<OcrFrameResult> {
         debug!("processing frame {}x{}", frame.width, frame.height);
 
+        // Shrink large frames, remembering the original size for the boxes
+        let (original_width, original_height) = (frame.width, frame.height);
+        let frame = match self.max_dimension.and_then(|max| downscaled_size(frame.width, frame.height, max)) {
+            Some((width, height)) => downscale(frame, width, height)?,
+            None => frame,
+        };
+        let scale = original_width as f32 / frame.width as f32;
+
         // Convert RGBA to SoftwareBitmap
         let bitmap = self.rgba_to_bitmap(frame)?;
 
-        // Perform OCR
-        let result = self.engine.recognize(&bitmap).await?;
+        // Perform OCR, keeping the best result when several languages are configured
+        let mut best: Option<OcrFrameResult> = None;
+        if self.merge_languages && self.engines.len() > 1 {
+            let mut results = Vec::with_capacity(self.engines.len());
+            for engine in &self.engines {
+                results.push(engine.recognize(&bitmap).await?);
+            }
+            best = merge_results(results);
+        } else {
+            for engine in &self.engines {
+                let result = engine.recognize(&bitmap).await?;
+                if best.as_ref().is_none_or(|b| result.score() > b.score()) {
+                    best = Some(result);
+                }
+            }
+        }
+
+        let mut result = best.ok_or_else(|| OcrError::ProcessingError("no OCR engine".to_string()))?;
+        if result.width != original_width || result.height != original_height {
+            scale_result(&mut result, scale, original_width, original_height);
+        }
+        result.text = normalize_text(&result.lines);
+        debug!("selected OCR language: {:?}", result.language);
 
         Ok(result)
     }
@@ -136,6 +215,147 @@ impl Processor {
     }
 }
 
+/// Size of a `width`x`height` frame fitted within `max` pixels on its longest
+/// side, keeping the aspect ratio, or `None` when it already fits
+fn downscaled_size(width: u32, height: u32, max: u32) -> Option<(u32, u32)> {
+    let longest = width.max(height);
+    if max == 0 || longest <= max {
+        return None;
+    }
+    let fit = |side: u32| ((side as u64 * max as u64 + longest as u64 / 2) / longest as u64).max(1) as u32;
+    Some((fit(width), fit(height)))
+}
+
+/// Resize an RGBA frame to `width`x`height`
+fn downscale(frame: FrameData, width: u32, height: u32) -> Result<FrameData> {
+    use image::{imageops, ImageBuffer, Rgba};
+
+    let (original_width, original_height) = (frame.width, frame.height);
+    let img = ImageBuffer::<Rgba<u8>, Vec<u8>>::from_raw(frame.width, frame.height, frame.data)
+        .ok_or_else(|| {
+            OcrError::ConversionError(format!(
+                "invalid frame data size for {}x{}",
+                original_width, original_height
+            ))
+        })?;
+    let resized = imageops::resize(&img, width, height, imageops::FilterType::Triangle);
+    debug!("downscaled frame {}x{} to {}x{}", original_width, original_height, width, height);
+
+    Ok(FrameData { width, height, data: resized.into_raw() })
+}
+
+/// Map word boxes from a downscaled frame back onto the original frame
+fn scale_result(result: &mut OcrFrameResult, scale: f32, width: u32, height: u32) {
+    for word in result.lines.iter_mut().flat_map(|line| line.words.iter_mut()) {
+        word.x *= scale;
+        word.y *= scale;
+        word.width *= scale;
+        word.height *= scale;
+    }
+    result.width = width;
+    result.height = height;
+}
+
+/// Combine the lines recognized by several languages into one result
+///
+/// Lines are considered highest score first; a line overlapping one already
+/// kept is a second reading of the same text and is dropped. The merged
+/// language lists the languages that contributed lines, in engine order.
+fn merge_results(results: Vec<OcrFrameResult>) -> Option<OcrFrameResult> {
+    let first = results.first()?;
+    let (width, height) = (first.width, first.height);
+    let fallback_language = first.language.clone();
+    let engine_languages: Vec<Option<String>> = results.iter().map(|r| r.language.clone()).collect();
+
+    let mut candidates: Vec<OcrLine> = results.into_iter().flat_map(|r| r.lines).collect();
+    candidates.sort_by(|a, b| b.score().total_cmp(&a.score()));
+
+    let mut kept: Vec<(OcrLine, (f32, f32, f32, f32))> = Vec::new();
+    for line in candidates {
+        let Some(bbox) = line.bounding_box() else { continue };
+        if kept.iter().any(|(_, other)| overlap_ratio(bbox, *other) >= LINE_OVERLAP_THRESHOLD) {
+            continue;
+        }
+        kept.push((line, bbox));
+    }
+    let lines: Vec<OcrLine> = kept.into_iter().map(|(line, _)| line).collect();
+
+    let contributing: Vec<String> = engine_languages
+        .into_iter()
+        .flatten()
+        .filter(|tag| lines.iter().any(|line| line.language.as_deref() == Some(tag.as_str())))
+        .collect();
+    let language = if contributing.is_empty() {
+        fallback_language
+    } else {
+        Some(contributing.join(","))
+    };
+
+    let word_count = lines.iter().map(|line| line.words.len()).sum::<usize>();
+    let confidence = if word_count > 0 {
+        lines.iter().flat_map(|line| &line.words).map(|w| w.confidence).sum::<f32>() / word_count as f32
+    } else {
+        0.0
+    };
+
+    Some(OcrFrameResult {
+        text: String::new(),
+        lines,
+        confidence,
+        width,
+        height,
+        language,
+    })
+}
+
+/// Intersection area divided by the smaller box's area, for (x, y, w, h) boxes
+fn overlap_ratio(a: (f32, f32, f32, f32), b: (f32, f32, f32, f32)) -> f32 {
+    let ix = ((a.0 + a.2).min(b.0 + b.2) - a.0.max(b.0)).max(0.0);
+    let iy = ((a.1 + a.3).min(b.1 + b.3) - a.1.max(b.1)).max(0.0);
+    let smaller = (a.2 * a.3).min(b.2 * b.3);
+    if smaller <= 0.0 {
+        return 0.0;
+    }
+    ix * iy / smaller
+}
+
+/// Build searchable text from OCR lines.
+///
+/// Lines are ordered top-to-bottom, then left-to-right within a visual row,
+/// so lines sharing a row keep their left-to-right order even when their tops
+/// differ by a few pixels. Whitespace runs collapse to a
+/// single space, blank lines are dropped and lines are joined with `\n`.
+/// The lines themselves (and their geometry) are left untouched.
+fn normalize_text(lines: &[OcrLine]) -> String {
+    let mut positioned: Vec<(&OcrLine, (f32, f32, f32, f32))> = lines
+        .iter()
+        .filter_map(|line| line.bounding_box().map(|bbox| (line, bbox)))
+        .collect();
+
+    positioned.sort_by(|a, b| a.1 .1.total_cmp(&b.1 .1));
+
+    // Group into rows: a line belongs to the current row when its vertical
+    // center falls within the row's first line
+    let mut rows: Vec<Vec<(&OcrLine, (f32, f32, f32, f32))>> = Vec::new();
+    for entry in positioned {
+        let center_y = entry.1 .1 + entry.1 .3 / 2.0;
+        match rows.last_mut() {
+            Some(row) if center_y <= row[0].1 .1 + row[0].1 .3 => row.push(entry),
+            _ => rows.push(vec![entry]),
+        }
+    }
+
+    rows.into_iter()
+        .flat_map(|mut row| {
+            row.sort_by(|a, b| a.1 .0.total_cmp(&b.1 .0));
+            row.into_iter()
+        })
+        .map(|(line, _)| line.text.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 impl Default for Processor {
     fn default() -> Self {
         Self::new().expect("failed to create default OCR processor")
@@ -160,4 +380,138 @@ mod tests {
         let result = processor.rgba_to_bitmap(invalid_frame);
         assert!(result.is_err());
     }
+
+    fn line(text: &str, x: f32, y: f32, width: f32) -> OcrLine {
+        OcrLine {
+            text: text.to_string(),
+            words: vec![crate::types::OcrWord {
+                text: text.to_string(),
+                confidence: 0.8,
+                x,
+                y,
+                width,
+                height: 12.0,
+            }],
+            language: None,
+        }
+    }
+
+    fn tagged(text: &str, x: f32, y: f32, width: f32, language: &str) -> OcrLine {
+        OcrLine {
+            language: Some(language.to_string()),
+            ..line(text, x, y, width)
+        }
+    }
+
+    fn result(lines: Vec<OcrLine>, language: &str) -> OcrFrameResult {
+        OcrFrameResult {
+            text: String::new(),
+            lines,
+            confidence: 0.0,
+            width: 1920,
+            height: 1080,
+            language: Some(language.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_downscaled_size_keeps_aspect_ratio() {
+        assert_eq!(downscaled_size(3840, 2160, 1920), Some((1920, 1080)));
+        assert_eq!(downscaled_size(2160, 3840, 1920), Some((1080, 1920)));
+        assert_eq!(downscaled_size(5120, 1440, 1920), Some((1920, 540)));
+        assert_eq!(downscaled_size(1920, 1080, 1920), None);
+        assert_eq!(downscaled_size(1280, 720, 1920), None);
+    }
+
+    #[test]
+    fn test_downscale_resizes_frame() {
+        let frame = FrameData { width: 40, height: 20, data: vec![255; 40 * 20 * 4] };
+        let small = downscale(frame, 20, 10).unwrap();
+        assert_eq!((small.width, small.height), (20, 10));
+        assert_eq!(small.data.len(), 20 * 10 * 4);
+
+        let invalid = FrameData { width: 40, height: 20, data: vec![0; 10] };
+        assert!(downscale(invalid, 20, 10).is_err());
+    }
+
+    #[test]
+    fn test_scale_result_restores_original_coordinates() {
+        let mut scaled = result(vec![line("Hello", 100.0, 50.0, 200.0)], "en-US");
+        scale_result(&mut scaled, 2.0, 3840, 2160);
+
+        let word = &scaled.lines[0].words[0];
+        assert_eq!((word.x, word.y, word.width, word.height), (200.0, 100.0, 400.0, 24.0));
+        assert_eq!((scaled.width, scaled.height), (3840, 2160));
+    }
+
+    #[test]
+    fn test_merge_results_combines_languages() {
+        let english = result(
+            vec![
+                tagged("File Edit View", 0.0, 0.0, 300.0, "en-US"),
+                tagged("?? ??", 0.0, 100.0, 80.0, "en-US"),
+            ],
+            "en-US",
+        );
+        let japanese = result(
+            vec![
+                tagged("ファイル編集表示", 0.0, 2.0, 280.0, "ja-JP"),
+                tagged("こんにちは世界", 0.0, 101.0, 90.0, "ja-JP"),
+            ],
+            "ja-JP",
+        );
+
+        let merged = merge_results(vec![english, japanese]).unwrap();
+        let texts: Vec<_> = merged.lines.iter().map(|l| l.text.as_str()).collect();
+
+        // Each overlap keeps the reading with more recognized characters
+        assert_eq!(texts.len(), 2);
+        assert!(texts.contains(&"File Edit View"));
+        assert!(texts.contains(&"こんにちは世界"));
+        assert_eq!(merged.language.as_deref(), Some("en-US,ja-JP"));
+        assert_eq!((merged.width, merged.height), (1920, 1080));
+    }
+
+    #[test]
+    fn test_merge_results_without_lines_keeps_first_language() {
+        let merged = merge_results(vec![result(vec![], "en-US"), result(vec![], "ja-JP")]).unwrap();
+        assert!(merged.lines.is_empty());
+        assert_eq!(merged.language.as_deref(), Some("en-US"));
+        assert!(merge_results(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn test_overlap_ratio() {
+        assert_eq!(overlap_ratio((0.0, 0.0, 10.0, 10.0), (20.0, 0.0, 10.0, 10.0)), 0.0);
+        assert_eq!(overlap_ratio((0.0, 0.0, 10.0, 10.0), (2.0, 2.0, 4.0, 4.0)), 1.0);
+        assert!((overlap_ratio((0.0, 0.0, 10.0, 10.0), (5.0, 0.0, 10.0, 10.0)) - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_normalize_text_orders_rows_then_columns() {
+        // Two columns; right column lines are offset by a few pixels
+        let lines = vec![
+            line("left one", 10.0, 100.0, 200.0),
+            line("left two", 10.0, 120.0, 200.0),
+            line("right one", 400.0, 98.0, 200.0),
+            line("right two", 400.0, 121.0, 200.0),
+            line("Title", 10.0, 50.0, 100.0),
+        ];
+
+        assert_eq!(
+            normalize_text(&lines),
+            "Title\nleft one\nright one\nleft two\nright two"
+        );
+    }
+
+    #[test]
+    fn test_normalize_text_collapses_whitespace() {
+        let lines = vec![
+            line("  hello \t  world ", 0.0, 0.0, 100.0),
+            line("   ", 0.0, 20.0, 100.0),
+            line("next\n\nline", 0.0, 40.0, 100.0),
+        ];
+
+        assert_eq!(normalize_text(&lines), "hello world\nnext line");
+    }
 }
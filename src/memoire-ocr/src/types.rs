@@ -0,0 +1,145 @@
+//! OCR data types shared by every backend
+
+use serde::{Deserialize, Serialize};
+
+/// Frame data for OCR processing
+pub struct FrameData {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>, // RGBA format
+}
+
+/// OCR word with bounding box and confidence
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrWord {
+    pub text: String,
+    pub confidence: f32,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// OCR line containing multiple words
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrLine {
+    pub text: String,
+    pub words: Vec<OcrWord>,
+    /// BCP47 tag of the recognizer that read this line
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+}
+
+impl OcrLine {
+    /// Bounding box enclosing all words, as (x, y, width, height) in pixels
+    pub fn bounding_box(&self) -> Option<(f32, f32, f32, f32)> {
+        let first = self.words.first()?;
+        let (mut left, mut top) = (first.x, first.y);
+        let (mut right, mut bottom) = (first.x + first.width, first.y + first.height);
+
+        for word in &self.words[1..] {
+            left = left.min(word.x);
+            top = top.min(word.y);
+            right = right.max(word.x + word.width);
+            bottom = bottom.max(word.y + word.height);
+        }
+
+        Some((left, top, right - left, bottom - top))
+    }
+
+    /// Average word confidence
+    pub fn confidence(&self) -> f32 {
+        if self.words.is_empty() {
+            return 0.0;
+        }
+        self.words.iter().map(|w| w.confidence).sum::<f32>() / self.words.len() as f32
+    }
+
+    /// Word confidence weighted by character count (see [`OcrFrameResult::score`])
+    pub fn score(&self) -> f32 {
+        self.words
+            .iter()
+            .map(|word| word.confidence * word.text.chars().count() as f32)
+            .sum()
+    }
+}
+
+/// Complete OCR result for a frame
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrFrameResult {
+    pub text: String,
+    pub lines: Vec<OcrLine>,
+    pub confidence: f32,
+    /// Frame dimensions the word bounding boxes are relative to
+    #[serde(default)]
+    pub width: u32,
+    #[serde(default)]
+    pub height: u32,
+    /// BCP47 tag of the recognizer that produced this result, or a
+    /// comma-separated list when lines from several languages were merged
+    #[serde(default)]
+    pub language: Option<String>,
+}
+
+impl OcrFrameResult {
+    /// Aggregate confidence used to compare results from different languages
+    ///
+    /// Word confidence weighted by the number of characters recognized, so a
+    /// recognizer that reads more of the frame with plausible words wins over
+    /// one that returns a few confident fragments.
+    pub fn score(&self) -> f32 {
+        self.lines.iter().map(OcrLine::score).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_bounding_box() {
+        let word = |x: f32, y: f32, width: f32, height: f32| OcrWord {
+            text: "word".to_string(),
+            confidence: 0.8,
+            x,
+            y,
+            width,
+            height,
+        };
+
+        let line = OcrLine {
+            text: "two words".to_string(),
+            words: vec![word(10.0, 20.0, 30.0, 10.0), word(50.0, 18.0, 20.0, 14.0)],
+            language: None,
+        };
+        assert_eq!(line.bounding_box(), Some((10.0, 18.0, 60.0, 14.0)));
+
+        let empty = OcrLine { text: String::new(), words: Vec::new(), language: None };
+        assert_eq!(empty.bounding_box(), None);
+    }
+
+    #[test]
+    fn test_result_score_weights_by_length() {
+        let word = |text: &str, confidence: f32| OcrWord {
+            text: text.to_string(),
+            confidence,
+            x: 0.0,
+            y: 0.0,
+            width: 10.0,
+            height: 10.0,
+        };
+        let result = |words: Vec<OcrWord>| OcrFrameResult {
+            text: String::new(),
+            lines: vec![OcrLine { text: String::new(), words, language: None }],
+            confidence: 0.0,
+            width: 100,
+            height: 100,
+            language: None,
+        };
+
+        let fragments = result(vec![word("ab", 0.9), word("c", 0.9)]);
+        let sentence = result(vec![word("recognized", 0.8), word("sentence", 0.8)]);
+        assert!(sentence.score() > fragments.score());
+        assert!((fragments.score() - 2.7).abs() < 1e-6);
+    }
+}
@@ -1,7 +1,8 @@
 //! Audio encoding and chunk management
 //!
 //! Manages audio chunks similar to VideoEncoder, saving WAV files
-//! at configured intervals.
+//! at configured intervals. Optionally closes chunks on a pause in speech
+//! instead of an exact sample count, so words are not cut in half.
 
 use anyhow::Result;
 use chrono::{DateTime, Utc};
@@ -10,6 +11,15 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use tracing::{debug, info};
 
+/// Default RMS level below which audio counts as silence
+pub const DEFAULT_SILENCE_RMS_THRESHOLD: f32 = 0.01;
+/// Fraction of the chunk duration after which a chunk may close on silence
+const SILENCE_SPLIT_MIN_FRACTION: f32 = 0.8;
+/// Fraction of the chunk duration at which a chunk is always closed
+const SILENCE_SPLIT_MAX_FRACTION: f32 = 1.5;
+/// Trailing audio that must be silent to close a chunk (milliseconds)
+const SILENCE_WINDOW_MS: usize = 300;
+
 /// Audio encoder configuration
 #[derive(Debug, Clone)]
 pub struct AudioEncoderConfig {
@@ -21,6 +31,10 @@ pub struct AudioEncoderConfig {
     pub sample_rate: u32,
     /// Number of channels (1 = mono, 2 = stereo)
     pub channels: u16,
+    /// Close chunks on a silence gap between 0.8x and 1.5x `chunk_duration_secs`
+    pub split_on_silence: bool,
+    /// RMS level below which audio counts as silence
+    pub silence_threshold: f32,
 }
 
 impl Default for AudioEncoderConfig {
@@ -30,10 +44,21 @@ impl Default for AudioEncoderConfig {
             chunk_duration_secs: 30,
             sample_rate: 16000,
             channels: 1,
+            split_on_silence: false,
+            silence_threshold: DEFAULT_SILENCE_RMS_THRESHOLD,
         }
     }
 }
 
+/// A finished audio chunk
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioChunkInfo {
+    /// Path of the WAV file
+    pub path: PathBuf,
+    /// When the chunk's first sample was captured
+    pub start_time: DateTime<Utc>,
+}
+
 /// Audio encoder that accumulates samples and creates WAV chunks
 pub struct AudioEncoder {
     config: AudioEncoderConfig,
@@ -68,8 +93,8 @@ impl AudioEncoder {
     }
 
     /// Add audio samples to current chunk
-    /// Returns the path to a completed chunk if one was finalized
-    pub fn add_samples(&mut self, samples: &[f32], timestamp: DateTime<Utc>) -> Result<Option<PathBuf>> {
+    /// Returns any chunks completed by these samples
+    pub fn add_samples(&mut self, samples: &[f32], timestamp: DateTime<Utc>) -> Result<Vec<AudioChunkInfo>> {
         // Set chunk start time if this is the first samples
         if self.chunk_start_time.is_none() {
             self.chunk_start_time = Some(timestamp);
@@ -79,24 +104,43 @@ impl AudioEncoder {
         self.current_samples.extend_from_slice(samples);
 
         // Calculate expected samples per chunk
-        let samples_per_chunk = self.config.chunk_duration_secs as usize
-            * self.config.sample_rate as usize
-            * self.config.channels as usize;
-
-        // Check if we have enough samples for a complete chunk
-        if self.current_samples.len() >= samples_per_chunk {
-            return self.finalize_chunk();
+        let samples_per_sec = self.config.sample_rate as usize * self.config.channels as usize;
+        let samples_per_chunk = self.config.chunk_duration_secs as usize * samples_per_sec;
+
+        let mut completed = Vec::new();
+
+        if self.config.split_on_silence {
+            // Buffered audio may span several chunks when fed large buffers
+            while let Some(split) = silence_split_point(
+                &self.current_samples,
+                self.config.channels as usize,
+                samples_per_sec,
+                samples_per_chunk,
+                self.config.silence_threshold,
+            ) {
+                completed.push(self.write_chunk(split)?);
+            }
+        } else if self.current_samples.len() >= samples_per_chunk {
+            // Check if we have enough samples for a complete chunk
+            completed.extend(self.finalize_chunk()?);
         }
 
-        Ok(None)
+        Ok(completed)
     }
 
     /// Force finalize the current chunk (even if not full)
-    pub fn finalize_chunk(&mut self) -> Result<Option<PathBuf>> {
+    pub fn finalize_chunk(&mut self) -> Result<Option<AudioChunkInfo>> {
         if self.current_samples.is_empty() {
             return Ok(None);
         }
 
+        let count = self.current_samples.len();
+        self.write_chunk(count).map(Some)
+    }
+
+    /// Write the first `count` buffered samples as a chunk and keep the rest
+    /// as the start of the next one
+    fn write_chunk(&mut self, count: usize) -> Result<AudioChunkInfo> {
         let start_time = match self.chunk_start_time {
             Some(t) => t,
             None => Utc::now(),
@@ -113,26 +157,35 @@ impl AudioEncoder {
         // Output path
         let output_path = date_dir.join(format!("chunk_{}_{}.wav", time_str, self.chunk_index));
 
+        let samples_per_sec = self.config.sample_rate as f32 * self.config.channels as f32;
         info!(
             "saving audio chunk: {:?} ({} samples, {:.1}s)",
             output_path,
-            self.current_samples.len(),
-            self.current_samples.len() as f32 / self.config.sample_rate as f32 / self.config.channels as f32
+            count,
+            count as f32 / samples_per_sec
         );
 
         // Write WAV file
-        self.save_wav(&output_path)?;
-
-        // Reset state for next chunk
-        self.current_samples.clear();
-        self.chunk_start_time = None;
+        self.save_wav(&output_path, &self.current_samples[..count])?;
+
+        // Reset state for next chunk, carrying over audio past the split
+        self.current_samples.drain(..count);
+        self.chunk_start_time = if self.current_samples.is_empty() {
+            None
+        } else {
+            let elapsed_ms = (count as f32 / samples_per_sec * 1000.0) as i64;
+            Some(start_time + chrono::Duration::milliseconds(elapsed_ms))
+        };
         self.chunk_index += 1;
 
-        Ok(Some(output_path))
+        Ok(AudioChunkInfo {
+            path: output_path,
+            start_time,
+        })
     }
 
-    /// Save current samples as WAV file
-    fn save_wav(&self, path: &Path) -> Result<()> {
+    /// Save samples as WAV file
+    fn save_wav(&self, path: &Path, samples: &[f32]) -> Result<()> {
         let spec = WavSpec {
             channels: self.config.channels,
             sample_rate: self.config.sample_rate,
@@ -143,7 +196,7 @@ impl AudioEncoder {
         let mut writer = WavWriter::create(path, spec)?;
 
         // Convert f32 samples to i16
-        for &sample in &self.current_samples {
+        for &sample in samples {
             let clamped = sample.clamp(-1.0, 1.0);
             let i16_sample = (clamped * i16::MAX as f32) as i16;
             writer.write_sample(i16_sample)?;
@@ -187,6 +240,53 @@ impl Drop for AudioEncoder {
     }
 }
 
+/// Find where to close a chunk when splitting on silence.
+///
+/// Once at least 0.8x the target length is buffered, returns the end of the
+/// first 300ms window whose RMS is below `threshold`. Windows are scanned on a
+/// grid of half-window steps. At 1.5x the target the chunk is closed
+/// regardless. Positions are in samples and fall on frame boundaries; `None`
+/// means keep buffering.
+fn silence_split_point(
+    samples: &[f32],
+    channels: usize,
+    samples_per_sec: usize,
+    samples_per_chunk: usize,
+    threshold: f32,
+) -> Option<usize> {
+    let channels = channels.max(1);
+    let align = |n: usize| n - n % channels;
+
+    let min = align((samples_per_chunk as f32 * SILENCE_SPLIT_MIN_FRACTION) as usize);
+    let max = align((samples_per_chunk as f32 * SILENCE_SPLIT_MAX_FRACTION) as usize);
+    let window = align(samples_per_sec * SILENCE_WINDOW_MS / 1000).max(channels);
+    let step = align(window / 2).max(channels);
+
+    if samples.len() < min.max(1) {
+        return None;
+    }
+
+    let limit = samples.len().min(max);
+    // Start on the step grid so split points don't depend on `min`
+    let mut end = min.max(window).div_ceil(step) * step;
+    while end <= limit {
+        if rms(&samples[end - window..end]) < threshold {
+            return Some(end);
+        }
+        end += step;
+    }
+
+    (samples.len() >= max).then_some(max)
+}
+
+/// Root mean square level of a block of samples
+fn rms(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
 /// Windows reserved device names that cannot be used as filenames
 const WINDOWS_RESERVED_NAMES: &[&str] = &[
     "CON", "PRN", "AUX", "NUL",
@@ -254,5 +354,36 @@ mod tests {
         assert_eq!(config.chunk_duration_secs, 30);
         assert_eq!(config.sample_rate, 16000);
         assert_eq!(config.channels, 1);
+        assert!(!config.split_on_silence);
+    }
+
+    /// `secs` seconds of mono audio at 100 samples/sec, loud except for `pause`
+    fn speech_with_pause(secs: usize, pause: std::ops::Range<usize>) -> Vec<f32> {
+        (0..secs * 100)
+            .map(|i| if pause.contains(&i) { 0.0 } else { 0.5 })
+            .collect()
+    }
+
+    #[test]
+    fn test_silence_split_waits_for_minimum() {
+        // Pause at 5s is before 0.8 * 10s, so it is ignored
+        let samples = speech_with_pause(7, 500..560);
+        assert_eq!(silence_split_point(&samples, 1, 100, 1000, 0.01), None);
+    }
+
+    #[test]
+    fn test_silence_split_on_pause() {
+        // Pause from 9.0s to 9.6s: split at the end of the first quiet 300ms window
+        let samples = speech_with_pause(12, 900..960);
+        assert_eq!(silence_split_point(&samples, 1, 100, 1000, 0.01), Some(930));
+    }
+
+    #[test]
+    fn test_silence_split_hard_cap() {
+        let samples = speech_with_pause(14, 0..0);
+        assert_eq!(silence_split_point(&samples, 1, 100, 1000, 0.01), None);
+
+        let samples = speech_with_pause(16, 0..0);
+        assert_eq!(silence_split_point(&samples, 1, 100, 1000, 0.01), Some(1500));
     }
 }
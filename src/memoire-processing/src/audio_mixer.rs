@@ -0,0 +1,106 @@
+//! Mixing two audio sources into one mono stream
+//!
+//! Used to record the microphone and system audio (loopback) as a single
+//! track, so a call produces one transcript. Both sources must already be
+//! mono at the same sample rate; each is scaled by its own gain and the sum
+//! is clipped to [-1.0, 1.0].
+
+use std::collections::VecDeque;
+
+/// How far one source may run ahead before the other is treated as silent
+pub const DEFAULT_MAX_LAG_SECS: f32 = 2.0;
+
+/// Two-input mono mixer with a gain per source
+///
+/// Samples are mixed once both sources delivered them. Loopback capture
+/// delivers nothing while nothing is playing, so a source lagging more than
+/// the maximum lag is filled with silence instead of holding the other back.
+pub struct AudioMixer {
+    gains: [f32; 2],
+    pending: [VecDeque<f32>; 2],
+    max_lag: usize,
+}
+
+impl AudioMixer {
+    /// Create a mixer for two sources at `sample_rate`
+    pub fn new(gains: [f32; 2], sample_rate: u32) -> Self {
+        Self::with_max_lag(gains, (DEFAULT_MAX_LAG_SECS * sample_rate as f32) as usize)
+    }
+
+    /// Create a mixer filling a source with silence once it lags `max_lag` samples
+    pub fn with_max_lag(gains: [f32; 2], max_lag: usize) -> Self {
+        Self {
+            gains,
+            pending: [VecDeque::new(), VecDeque::new()],
+            max_lag,
+        }
+    }
+
+    /// Queue samples from source 0 or 1
+    pub fn push(&mut self, source: usize, samples: &[f32]) {
+        self.pending[source].extend(samples.iter().copied());
+    }
+
+    /// Mix the samples both sources delivered (or that waited too long for the other)
+    pub fn mix(&mut self) -> Vec<f32> {
+        let (a, b) = (self.pending[0].len(), self.pending[1].len());
+        let ready = a.min(b).max(a.max(b).saturating_sub(self.max_lag));
+        self.take(ready)
+    }
+
+    /// Mix everything queued, padding the shorter source with silence
+    pub fn flush(&mut self) -> Vec<f32> {
+        let queued = self.pending[0].len().max(self.pending[1].len());
+        self.take(queued)
+    }
+
+    fn take(&mut self, count: usize) -> Vec<f32> {
+        let [first, second] = &mut self.pending;
+        (0..count)
+            .map(|_| {
+                let a = first.pop_front().unwrap_or(0.0);
+                let b = second.pop_front().unwrap_or(0.0);
+                (a * self.gains[0] + b * self.gains[1]).clamp(-1.0, 1.0)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mixes_with_gain() {
+        let mut mixer = AudioMixer::with_max_lag([1.0, 0.5], 100);
+        mixer.push(0, &[0.1, 0.2, 0.3]);
+        mixer.push(1, &[0.4, 0.4]);
+
+        let mixed = mixer.mix();
+        assert_eq!(mixed.len(), 2);
+        assert!((mixed[0] - 0.3).abs() < 1e-6);
+        assert!((mixed[1] - 0.4).abs() < 1e-6);
+
+        // The unmatched sample waits for the other source
+        assert!(mixer.mix().is_empty());
+        assert_eq!(mixer.flush().len(), 1);
+    }
+
+    #[test]
+    fn test_silent_source_does_not_stall() {
+        let mut mixer = AudioMixer::with_max_lag([1.0, 1.0], 4);
+        mixer.push(0, &[0.5; 10]);
+
+        // Only the audio beyond the allowed lag is released
+        assert_eq!(mixer.mix(), vec![0.5; 6]);
+        assert!(mixer.mix().is_empty());
+    }
+
+    #[test]
+    fn test_clips_sum() {
+        let mut mixer = AudioMixer::with_max_lag([1.0, 1.0], 100);
+        mixer.push(0, &[0.8, -0.8]);
+        mixer.push(1, &[0.8, -0.8]);
+        assert_eq!(mixer.mix(), vec![1.0, -1.0]);
+    }
+}
@@ -0,0 +1,161 @@
+//! Rolling audio track for muxing into video chunks
+//!
+//! Recorded audio is appended with the wall-clock time it was captured, and
+//! video encoders cut out the span their chunk covers when it is finalized.
+//! Audio and video are timed by the same clock, so a slice starting at a
+//! chunk's first frame plays in sync with the video. Capture gaps (paused
+//! recording, a device that stopped delivering) are filled with silence, and
+//! only the most recent audio is kept.
+
+use chrono::{DateTime, Utc};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Gap between the end of the track and new audio filled with silence
+///
+/// Smaller differences are capture timestamp jitter and are ignored.
+const GAP_TOLERANCE_MS: i64 = 200;
+
+/// Mono audio shared between the audio pipeline and video encoders
+///
+/// Clones share the same track.
+#[derive(Debug, Clone)]
+pub struct AudioTrack {
+    inner: Arc<Mutex<TrackState>>,
+    sample_rate: u32,
+}
+
+#[derive(Debug)]
+struct TrackState {
+    /// Capture time of the first sample kept
+    start: Option<DateTime<Utc>>,
+    samples: VecDeque<f32>,
+    max_samples: usize,
+}
+
+impl AudioTrack {
+    /// Create a track of `sample_rate` audio keeping the last `max_secs` seconds
+    pub fn new(sample_rate: u32, max_secs: u64) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(TrackState {
+                start: None,
+                samples: VecDeque::new(),
+                max_samples: (max_secs * sample_rate as u64) as usize,
+            })),
+            sample_rate,
+        }
+    }
+
+    /// Sample rate of the track
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Append samples captured at `timestamp`
+    pub fn push(&self, samples: &[f32], timestamp: DateTime<Utc>) {
+        if samples.is_empty() {
+            return;
+        }
+        let mut state = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+
+        match state.start {
+            None => state.start = Some(timestamp),
+            Some(start) => {
+                let end_ms = state.samples.len() as i64 * 1000 / self.sample_rate as i64;
+                let gap_ms = (timestamp - start).num_milliseconds() - end_ms;
+                if gap_ms > GAP_TOLERANCE_MS {
+                    let silence = (gap_ms * self.sample_rate as i64 / 1000) as usize;
+                    state.samples.extend(std::iter::repeat_n(0.0, silence));
+                }
+            }
+        }
+        state.samples.extend(samples.iter().copied());
+
+        let excess = state.samples.len().saturating_sub(state.max_samples);
+        if excess > 0 {
+            state.samples.drain(..excess);
+            let dropped_ms = excess as i64 * 1000 / self.sample_rate as i64;
+            state.start = state.start.map(|start| start + chrono::Duration::milliseconds(dropped_ms));
+        }
+    }
+
+    /// Audio from `start` for `duration_secs`, silent where none was recorded
+    pub fn slice(&self, start: DateTime<Utc>, duration_secs: f64) -> Vec<f32> {
+        let state = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let len = (duration_secs.max(0.0) * self.sample_rate as f64).round() as usize;
+
+        let Some(track_start) = state.start else {
+            return vec![0.0; len];
+        };
+        let offset = (start - track_start).num_milliseconds() * self.sample_rate as i64 / 1000;
+
+        (0..len as i64)
+            .map(|i| {
+                usize::try_from(offset + i)
+                    .ok()
+                    .and_then(|index| state.samples.get(index).copied())
+                    .unwrap_or(0.0)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(ms: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(1_700_000_000, 0).unwrap() + chrono::Duration::milliseconds(ms)
+    }
+
+    #[test]
+    fn test_slice_aligns_with_capture_time() {
+        let track = AudioTrack::new(10, 60);
+        track.push(&[1.0; 10], at(0));
+        track.push(&[2.0; 10], at(1000));
+
+        assert_eq!(track.slice(at(500), 1.0), [vec![1.0; 5], vec![2.0; 5]].concat());
+        // Before and after the recorded audio is silent
+        assert_eq!(track.slice(at(-300), 0.5), [vec![0.0; 3], vec![1.0; 2]].concat());
+        assert_eq!(track.slice(at(1800), 0.5), [vec![2.0; 2], vec![0.0; 3]].concat());
+    }
+
+    #[test]
+    fn test_gaps_are_filled_with_silence() {
+        let track = AudioTrack::new(10, 60);
+        track.push(&[1.0; 10], at(0));
+        // Jitter is not a gap
+        track.push(&[2.0; 10], at(1100));
+        track.push(&[3.0; 10], at(3000));
+
+        let audio = track.slice(at(0), 4.0);
+        assert_eq!(audio.len(), 40);
+        assert_eq!(&audio[10..20], &[2.0; 10]);
+        assert_eq!(&audio[20..30], &[0.0; 10]);
+        assert_eq!(&audio[30..40], &[3.0; 10]);
+    }
+
+    #[test]
+    fn test_keeps_recent_audio_only() {
+        let track = AudioTrack::new(10, 2);
+        track.push(&[1.0; 10], at(0));
+        track.push(&[2.0; 10], at(1000));
+        track.push(&[3.0; 10], at(2000));
+
+        assert_eq!(track.slice(at(0), 1.0), vec![0.0; 10]);
+        assert_eq!(track.slice(at(1000), 2.0), [vec![2.0; 10], vec![3.0; 10]].concat());
+
+        // Clones share the track
+        let clone = track.clone();
+        clone.push(&[4.0; 10], at(3000));
+        assert_eq!(track.slice(at(3000), 1.0), vec![4.0; 10]);
+    }
+
+    #[test]
+    fn test_empty_track_is_silent() {
+        let track = AudioTrack::new(16000, 10);
+        assert_eq!(track.slice(at(0), 0.5), vec![0.0; 8000]);
+        assert_eq!(track.sample_rate(), 16000);
+    }
+}
@@ -3,15 +3,137 @@
 //! Supports two encoding modes:
 //! - Piped: Raw frames piped directly to FFmpeg stdin (default, faster)
 //! - PNG: Frames saved to disk then encoded (fallback)
+//!
+//! Finished chunks are probed with ffprobe so callers can store the real
+//! resolution, codec and duration of the file.
+//!
+//! Chunks are constant frame rate, but capture is not: deduplication drops
+//! frames while the screen is static. Gaps are filled by repeating the last
+//! frame until each frame's timestamp lines up with its position in the
+//! video, so playback time matches wall-clock time and a frame at position
+//! `n` is always at `n / fps` seconds.
+//!
+//! With an [`AudioTrack`] configured, the audio recorded while a chunk was
+//! captured is muxed into it as AAC once the video is finished. The audio is
+//! cut from the chunk's first frame, so both streams start together.
 
 use anyhow::Result;
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::ffi::OsString;
 use std::fs;
-use std::io::Write;
+use std::io::{BufRead, BufReader, Read, Write};
 use std::path::{Path, PathBuf};
-use std::process::{Child, ChildStdin, Command, Stdio};
+use std::process::{Child, ChildStdin, ExitStatus, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 
+use crate::audio_track::AudioTrack;
+use crate::ffmpeg::{ffmpeg_command, ffprobe_command};
+
+/// Default CRF/CQ value (visually good quality at moderate file size)
+pub const DEFAULT_QUALITY: u32 = 23;
+
+/// Highest CRF value accepted by libx264 and NVENC's constant quality mode
+pub const MAX_QUALITY: u32 = 51;
+
+/// FFmpeg stderr lines kept for error reports
+const STDERR_TAIL_LINES: usize = 20;
+
+/// How long FFmpeg may take to finish a chunk after its input is closed
+/// before it is killed
+const FFMPEG_EXIT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Interval between checks whether FFmpeg exited
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// AAC bitrate of muxed audio (mono speech)
+const AUDIO_BITRATE: &str = "64k";
+
+/// Encoder speed/compression preset
+///
+/// Slower presets spend more CPU (or GPU) time per frame to produce smaller files
+/// at the same quality. At 1 FPS screen capture even `slow` is cheap with NVENC;
+/// with libx264 prefer `fast` or quicker on low-end CPUs. Names follow libx264;
+/// the NVENC `p1`-`p7` names are accepted too and map onto the nearest equivalent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase", try_from = "String")]
+pub enum EncoderPreset {
+    Ultrafast,
+    Superfast,
+    Veryfast,
+    Faster,
+    #[default]
+    Fast,
+    Medium,
+    Slow,
+    Slower,
+    Veryslow,
+}
+
+impl EncoderPreset {
+    /// Preset name understood by libx264
+    pub fn x264_name(&self) -> &'static str {
+        match self {
+            EncoderPreset::Ultrafast => "ultrafast",
+            EncoderPreset::Superfast => "superfast",
+            EncoderPreset::Veryfast => "veryfast",
+            EncoderPreset::Faster => "faster",
+            EncoderPreset::Fast => "fast",
+            EncoderPreset::Medium => "medium",
+            EncoderPreset::Slow => "slow",
+            EncoderPreset::Slower => "slower",
+            EncoderPreset::Veryslow => "veryslow",
+        }
+    }
+
+    /// Preset name understood by h264_nvenc (p1 = fastest, p7 = best compression)
+    pub fn nvenc_name(&self) -> &'static str {
+        match self {
+            EncoderPreset::Ultrafast | EncoderPreset::Superfast => "p1",
+            EncoderPreset::Veryfast => "p2",
+            EncoderPreset::Faster => "p3",
+            EncoderPreset::Fast => "p4",
+            EncoderPreset::Medium => "p5",
+            EncoderPreset::Slow => "p6",
+            EncoderPreset::Slower | EncoderPreset::Veryslow => "p7",
+        }
+    }
+}
+
+impl std::str::FromStr for EncoderPreset {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "ultrafast" | "p1" => Ok(EncoderPreset::Ultrafast),
+            "superfast" => Ok(EncoderPreset::Superfast),
+            "veryfast" | "p2" => Ok(EncoderPreset::Veryfast),
+            "faster" | "p3" => Ok(EncoderPreset::Faster),
+            "fast" | "p4" => Ok(EncoderPreset::Fast),
+            "medium" | "p5" => Ok(EncoderPreset::Medium),
+            "slow" | "p6" => Ok(EncoderPreset::Slow),
+            "slower" | "p7" => Ok(EncoderPreset::Slower),
+            "veryslow" => Ok(EncoderPreset::Veryslow),
+            other => Err(format!(
+                "unknown preset '{}' (expected ultrafast..veryslow or p1..p7)",
+                other
+            )),
+        }
+    }
+}
+
+impl TryFrom<String> for EncoderPreset {
+    type Error = String;
+
+    fn try_from(s: String) -> std::result::Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
 /// Video encoder configuration
 #[derive(Debug, Clone)]
 pub struct EncoderConfig {
@@ -19,14 +141,27 @@ pub struct EncoderConfig {
     pub output_dir: PathBuf,
     /// Chunk duration in seconds
     pub chunk_duration_secs: u64,
-    /// Target framerate
-    pub fps: u32,
+    /// Target framerate (may be fractional, e.g. 0.5 for one frame every 2 seconds)
+    pub fps: f64,
     /// Use hardware encoding (NVENC)
     pub use_hw_encoding: bool,
     /// Video quality (CRF value, lower = better, 18-28 typical)
+    ///
+    /// Each +6 roughly halves the file size: 18 is near-lossless for archival,
+    /// 30 trades visible softness in text for much smaller long-retention files.
     pub quality: u32,
+    /// Encoder speed/compression preset
+    pub preset: EncoderPreset,
     /// Use piped encoding (raw frames to FFmpeg stdin) instead of PNG intermediate
     pub use_piped_encoding: bool,
+    /// Frames between keyframes (None = one keyframe per second of video)
+    ///
+    /// A short GOP lets `-ss` seeks in frame extraction and the viewer decode
+    /// only a few frames. At 1 FPS or less the default makes every frame a
+    /// keyframe, which makes chunks somewhat larger.
+    pub keyframe_interval: Option<u32>,
+    /// Audio muxed into each chunk (None = video only)
+    pub audio: Option<AudioTrack>,
 }
 
 impl Default for EncoderConfig {
@@ -34,32 +169,73 @@ impl Default for EncoderConfig {
         Self {
             output_dir: PathBuf::from("videos"),
             chunk_duration_secs: 300, // 5 minutes
-            fps: 1,                    // 1 FPS for screen recording
+            fps: 1.0,                  // 1 FPS for screen recording
             use_hw_encoding: true,
-            quality: 23,
+            quality: DEFAULT_QUALITY,
+            preset: EncoderPreset::default(),
             use_piped_encoding: true, // Default to piped for better performance
+            keyframe_interval: None,
+            audio: None,
         }
     }
 }
 
+/// Metadata of a finished video chunk
+#[derive(Debug, Clone, PartialEq)]
+pub struct EncodedChunkInfo {
+    /// Path of the MP4 file
+    pub path: PathBuf,
+    pub width: u32,
+    pub height: u32,
+    /// Codec name as reported by ffprobe (e.g. "h264")
+    pub codec: String,
+    pub duration_secs: f64,
+    pub size_bytes: u64,
+}
+
+/// Stream and container fields read from ffprobe's JSON output
+#[derive(Debug, Default, PartialEq)]
+struct ProbeResult {
+    width: Option<u32>,
+    height: Option<u32>,
+    codec: Option<String>,
+    duration_secs: Option<f64>,
+    size_bytes: Option<u64>,
+}
+
 /// Video encoder that accumulates frames and creates MP4 chunks
 pub struct VideoEncoder {
     config: EncoderConfig,
     current_chunk_dir: PathBuf,
+    /// Frames written to the current chunk, including repeated ones
     frame_count: u64,
     chunk_start_time: Option<DateTime<Utc>>,
     chunk_index: u64,
     // Piped encoding state
     ffmpeg_process: Option<Child>,
     ffmpeg_stdin: Option<ChildStdin>,
+    /// Drains FFmpeg's stderr while frames are written
+    ffmpeg_stderr: Option<StderrTail>,
     current_output_path: Option<PathBuf>,
     frame_width: Option<u32>,
     frame_height: Option<u32>,
+    /// Dimensions of the frames in the current chunk (any encoding mode)
+    chunk_dimensions: Option<(u32, u32)>,
+    /// Last frame written to the pipe, repeated to fill capture gaps
+    last_frame: Vec<u8>,
 }
 
 impl VideoEncoder {
     /// Create a new video encoder
     pub fn new(config: EncoderConfig) -> Result<Self> {
+        if config.quality > MAX_QUALITY {
+            return Err(anyhow::anyhow!(
+                "invalid quality {}: CRF must be between 0 and {}",
+                config.quality,
+                MAX_QUALITY
+            ));
+        }
+
         // Ensure output directory exists
         fs::create_dir_all(&config.output_dir)?;
 
@@ -75,18 +251,100 @@ impl VideoEncoder {
             chunk_index: 0,
             ffmpeg_process: None,
             ffmpeg_stdin: None,
+            ffmpeg_stderr: None,
             current_output_path: None,
             frame_width: None,
             frame_height: None,
+            chunk_dimensions: None,
+            last_frame: Vec::new(),
         })
     }
 
+    /// Whether a frame of this size cannot be added to the current chunk
+    ///
+    /// FFmpeg is started for a fixed frame size, so a resolution change
+    /// needs a new chunk.
+    pub fn frame_size_changed(&self, width: u32, height: u32) -> bool {
+        let pipe_size = self.frame_width.zip(self.frame_height);
+        (self.frame_count > 0 && self.chunk_dimensions.is_some_and(|d| d != (width, height)))
+            || pipe_size.is_some_and(|d| d != (width, height))
+    }
+
+    /// Position a frame captured at `timestamp` will take in the current chunk
+    ///
+    /// Callers store this as the frame's offset so it can be located in the
+    /// video by index or by `position / fps`.
+    pub fn frame_position(&self, timestamp: DateTime<Utc>) -> u64 {
+        match self.chunk_start_time {
+            Some(start) => padded_position(
+                (timestamp - start).num_milliseconds(),
+                self.config.fps,
+                self.frame_count,
+                self.config.chunk_duration_secs,
+            ),
+            None => 0,
+        }
+    }
+
+    /// Whether the current chunk reached its duration by `now`
+    ///
+    /// Chunks are normally closed by the frame that fills them; after a
+    /// capture pause this tells whether the open chunk ran out meanwhile.
+    pub fn chunk_expired(&self, now: DateTime<Utc>) -> bool {
+        self.chunk_start_time
+            .is_some_and(|start| (now - start).num_seconds() >= self.config.chunk_duration_secs as i64)
+    }
+
     /// Add a frame to the current chunk
-    pub fn add_frame(&mut self, frame_data: &[u8], width: u32, height: u32, timestamp: DateTime<Utc>) -> Result<()> {
+    ///
+    /// The previous frame is repeated first if frames were skipped since it,
+    /// so the new frame lands at [`frame_position`](Self::frame_position).
+    ///
+    /// Returns the chunk closed by this call: the previous chunk when the
+    /// frame size changed, or the current one once it reaches its duration.
+    ///
+    /// A buffer that is not `width * height * 4` bytes would desynchronize the
+    /// raw video stream, so it is logged and dropped (see [`is_valid_frame`]).
+    pub fn add_frame(
+        &mut self,
+        frame_data: &[u8],
+        width: u32,
+        height: u32,
+        timestamp: DateTime<Utc>,
+    ) -> Result<Option<EncodedChunkInfo>> {
+        if !is_valid_frame(frame_data, width, height) {
+            warn!(
+                "dropping frame: {} bytes is not a {}x{} RGBA frame ({} bytes)",
+                frame_data.len(),
+                width,
+                height,
+                rgba_frame_len(width, height)
+            );
+            return Ok(None);
+        }
+
+        let mut closed = None;
+        if self.frame_size_changed(width, height) {
+            info!(
+                "frame size changed from {:?} to {}x{}, starting a new chunk",
+                self.chunk_dimensions, width, height
+            );
+            closed = self.finalize_chunk()?;
+        }
+
         // Set chunk start time if this is the first frame
         if self.chunk_start_time.is_none() {
             self.chunk_start_time = Some(timestamp);
         }
+        self.chunk_dimensions.get_or_insert((width, height));
+
+        let position = self.frame_position(timestamp);
+        if position > self.frame_count {
+            debug!(
+                "repeating last frame {} times to cover capture gap",
+                position - self.frame_count
+            );
+        }
 
         if self.config.use_piped_encoding {
             // Initialize FFmpeg pipe on first frame
@@ -94,9 +352,26 @@ impl VideoEncoder {
                 self.start_ffmpeg_pipe(width, height)?;
             }
 
+            while self.frame_count < position {
+                let last_frame = std::mem::take(&mut self.last_frame);
+                let written = self.write_frame_to_pipe(&last_frame);
+                self.last_frame = last_frame;
+                written?;
+                self.frame_count += 1;
+            }
+
             // Write raw RGBA frame to FFmpeg stdin
             self.write_frame_to_pipe(frame_data)?;
+            self.last_frame.clear();
+            self.last_frame.extend_from_slice(frame_data);
         } else {
+            // Repeated frames are copies of the previous PNG
+            while self.frame_count < position {
+                let previous = self.current_chunk_dir.join(format!("frame_{:08}.png", self.frame_count - 1));
+                fs::copy(&previous, self.current_chunk_dir.join(format!("frame_{:08}.png", self.frame_count)))?;
+                self.frame_count += 1;
+            }
+
             // Fallback: Save frame as PNG
             let frame_path = self.current_chunk_dir.join(format!("frame_{:08}.png", self.frame_count));
             let img = image::ImageBuffer::<image::Rgba<u8>, _>::from_raw(width, height, frame_data.to_vec())
@@ -111,11 +386,11 @@ impl VideoEncoder {
             let elapsed = (timestamp - start).num_seconds() as u64;
             if elapsed >= self.config.chunk_duration_secs {
                 debug!("chunk duration reached, finalizing");
-                self.finalize_chunk()?;
+                return self.finalize_chunk();
             }
         }
 
-        Ok(())
+        Ok(closed)
     }
 
     /// Start FFmpeg process with piped input
@@ -133,7 +408,7 @@ impl VideoEncoder {
 
         info!("starting piped encoding to {:?} ({}x{})", output_path, width, height);
 
-        let mut cmd = Command::new("ffmpeg");
+        let mut cmd = ffmpeg_command();
         cmd.arg("-y") // Overwrite output
             .arg("-f").arg("rawvideo")
             .arg("-pix_fmt").arg("rgba")
@@ -145,16 +420,17 @@ impl VideoEncoder {
         // Use NVENC if available
         if self.config.use_hw_encoding {
             cmd.arg("h264_nvenc")
-                .arg("-preset").arg("p4")
+                .arg("-preset").arg(self.config.preset.nvenc_name())
                 .arg("-rc").arg("vbr")
                 .arg("-cq").arg(self.config.quality.to_string());
         } else {
             cmd.arg("libx264")
                 .arg("-crf").arg(self.config.quality.to_string())
-                .arg("-preset").arg("fast");
+                .arg("-preset").arg(self.config.preset.x264_name());
         }
 
-        cmd.arg("-pix_fmt").arg("yuv420p")
+        cmd.arg("-g").arg(self.gop_size().to_string())
+            .arg("-pix_fmt").arg("yuv420p")
             .arg(&output_path)
             .stdin(Stdio::piped())
             .stdout(Stdio::null())
@@ -166,8 +442,13 @@ impl VideoEncoder {
         let stdin = child.stdin.take()
             .ok_or_else(|| anyhow::anyhow!("failed to open ffmpeg stdin"))?;
 
+        // FFmpeg blocks once the stderr pipe buffer is full, which would stall
+        // the stdin writes above, so stderr must be read while encoding
+        let stderr = child.stderr.take().map(StderrTail::spawn).transpose()?;
+
         self.ffmpeg_process = Some(child);
         self.ffmpeg_stdin = Some(stdin);
+        self.ffmpeg_stderr = stderr;
         self.current_output_path = Some(output_path);
         self.frame_width = Some(width);
         self.frame_height = Some(height);
@@ -176,9 +457,19 @@ impl VideoEncoder {
     }
 
     /// Write raw frame data to FFmpeg stdin
+    ///
+    /// A failed write usually means FFmpeg exited; its last output is
+    /// included in the error.
     fn write_frame_to_pipe(&mut self, frame_data: &[u8]) -> Result<()> {
         if let Some(ref mut stdin) = self.ffmpeg_stdin {
-            stdin.write_all(frame_data)?;
+            if let Err(e) = stdin.write_all(frame_data) {
+                let tail = self.ffmpeg_stderr.as_ref().map(StderrTail::lines).unwrap_or_default();
+                return Err(anyhow::anyhow!(
+                    "failed to write frame to ffmpeg: {}{}",
+                    e,
+                    format_stderr_tail(&tail)
+                ));
+            }
         }
         Ok(())
     }
@@ -188,22 +479,19 @@ impl VideoEncoder {
         // Close stdin to signal EOF to FFmpeg
         self.ffmpeg_stdin.take();
 
-        if let Some(child) = self.ffmpeg_process.take() {
-            let output = child.wait_with_output()?;
-
-            if !output.status.success() {
-                let stderr = String::from_utf8_lossy(&output.stderr);
+        if let Some(mut child) = self.ffmpeg_process.take() {
+            let status = wait_or_kill(&mut child, FFMPEG_EXIT_TIMEOUT)?;
+            let stderr = self.ffmpeg_stderr.take().map(StderrTail::finish).unwrap_or_default();
 
+            if !status.success() {
                 // Check if NVENC failed
-                if self.config.use_hw_encoding && stderr.contains("nvenc") {
+                if self.config.use_hw_encoding && stderr.iter().any(|line| line.contains("nvenc")) {
                     warn!("NVENC pipe failed, will use PNG fallback for next chunk");
                     // Don't error out - the partial file may be usable
                 }
 
                 // Log the error but don't fail if we got some output
-                if !stderr.is_empty() {
-                    warn!("ffmpeg stderr: {}", stderr.chars().take(500).collect::<String>());
-                }
+                warn!("ffmpeg exited with {}{}", status, format_stderr_tail(&stderr));
             }
         }
 
@@ -215,11 +503,18 @@ impl VideoEncoder {
     }
 
     /// Finalize the current chunk and create MP4
-    pub fn finalize_chunk(&mut self) -> Result<Option<PathBuf>> {
+    ///
+    /// The returned metadata comes from probing the file, falling back to the
+    /// parameters the chunk was encoded with where ffprobe is unavailable.
+    pub fn finalize_chunk(&mut self) -> Result<Option<EncodedChunkInfo>> {
         if self.frame_count == 0 {
             return Ok(None);
         }
 
+        let frame_count = self.frame_count;
+        let dimensions = self.chunk_dimensions.take();
+        let start_time = self.chunk_start_time;
+
         let output_path = if self.config.use_piped_encoding && self.ffmpeg_stdin.is_some() {
             // Finalize piped encoding
             info!("finalizing piped encoding of {} frames", self.frame_count);
@@ -232,9 +527,42 @@ impl VideoEncoder {
         // Reset state for next chunk
         self.frame_count = 0;
         self.chunk_start_time = None;
+        self.last_frame.clear();
         self.chunk_index += 1;
 
-        Ok(output_path)
+        if let (Some(path), Some(audio), Some(start)) = (&output_path, &self.config.audio, start_time) {
+            // A chunk without its audio is still a usable recording
+            if let Err(e) = mux_audio(path, audio, start, frame_count as f64 / self.config.fps) {
+                warn!("failed to mux audio into {:?}, keeping video only: {}", path, e);
+            }
+        }
+
+        Ok(output_path.map(|path| self.chunk_info(path, frame_count, dimensions)))
+    }
+
+    /// Describe a finished chunk, preferring ffprobe's view of the file
+    fn chunk_info(&self, path: PathBuf, frame_count: u64, dimensions: Option<(u32, u32)>) -> EncodedChunkInfo {
+        let probe = probe_video(&path).unwrap_or_else(|e| {
+            debug!("ffprobe failed for {:?}, using encoder parameters: {}", path, e);
+            ProbeResult::default()
+        });
+
+        let (width, height) = dimensions.unwrap_or((0, 0));
+        let size_bytes = probe
+            .size_bytes
+            .or_else(|| fs::metadata(&path).ok().map(|m| m.len()))
+            .unwrap_or(0);
+
+        EncodedChunkInfo {
+            width: probe.width.unwrap_or(width),
+            height: probe.height.unwrap_or(height),
+            codec: probe.codec.unwrap_or_else(|| "h264".to_string()),
+            duration_secs: probe
+                .duration_secs
+                .unwrap_or(frame_count as f64 / self.config.fps),
+            size_bytes,
+            path,
+        }
     }
 
     /// Finalize PNG-based encoding (legacy method)
@@ -253,7 +581,7 @@ impl VideoEncoder {
         info!("encoding {} frames to {:?} (PNG method)", self.frame_count, output_path);
 
         // Build FFmpeg command
-        let mut cmd = Command::new("ffmpeg");
+        let mut cmd = ffmpeg_command();
         cmd.arg("-y") // Overwrite output
             .arg("-framerate").arg(self.config.fps.to_string())
             .arg("-i").arg(self.current_chunk_dir.join("frame_%08d.png"))
@@ -262,16 +590,17 @@ impl VideoEncoder {
         // Use NVENC if available
         if self.config.use_hw_encoding {
             cmd.arg("h264_nvenc")
-                .arg("-preset").arg("p4")
+                .arg("-preset").arg(self.config.preset.nvenc_name())
                 .arg("-rc").arg("vbr")
                 .arg("-cq").arg(self.config.quality.to_string());
         } else {
             cmd.arg("libx264")
                 .arg("-crf").arg(self.config.quality.to_string())
-                .arg("-preset").arg("fast");
+                .arg("-preset").arg(self.config.preset.x264_name());
         }
 
-        cmd.arg("-pix_fmt").arg("yuv420p")
+        cmd.arg("-g").arg(self.gop_size().to_string())
+            .arg("-pix_fmt").arg("yuv420p")
             .arg(&output_path);
 
         debug!("running ffmpeg: {:?}", cmd);
@@ -297,13 +626,14 @@ impl VideoEncoder {
     }
 
     fn encode_software(&mut self, output_path: &Path) -> Result<Option<PathBuf>> {
-        let mut cmd = Command::new("ffmpeg");
+        let mut cmd = ffmpeg_command();
         cmd.arg("-y")
             .arg("-framerate").arg(self.config.fps.to_string())
             .arg("-i").arg(self.current_chunk_dir.join("frame_%08d.png"))
             .arg("-c:v").arg("libx264")
             .arg("-crf").arg(self.config.quality.to_string())
-            .arg("-preset").arg("fast")
+            .arg("-preset").arg(self.config.preset.x264_name())
+            .arg("-g").arg(self.gop_size().to_string())
             .arg("-pix_fmt").arg("yuv420p")
             .arg(output_path);
 
@@ -329,6 +659,11 @@ impl VideoEncoder {
         Ok(())
     }
 
+    /// Frames between keyframes passed to FFmpeg as `-g`
+    fn gop_size(&self) -> u32 {
+        keyframe_interval_frames(self.config.fps, self.config.keyframe_interval)
+    }
+
     /// Get the output directory
     pub fn output_dir(&self) -> &Path {
         &self.config.output_dir
@@ -343,14 +678,255 @@ impl Drop for VideoEncoder {
                 warn!("failed to finalize chunk on drop: {}", e);
             }
         }
+
+        // Finalizing can fail before FFmpeg is reaped, and a pipe can be open
+        // without frames; never leave the process behind
+        self.ffmpeg_stdin.take();
+        if let Some(mut child) = self.ffmpeg_process.take() {
+            if let Err(e) = wait_or_kill(&mut child, FFMPEG_EXIT_TIMEOUT) {
+                warn!("failed to stop ffmpeg on drop: {}", e);
+            }
+        }
+        if let Some(stderr) = self.ffmpeg_stderr.take() {
+            stderr.finish();
+        }
+
         // Clean up temp directory
         let _ = fs::remove_dir_all(&self.current_chunk_dir);
     }
 }
 
+/// Wait for a child process to exit, killing it once `timeout` has passed
+fn wait_or_kill(child: &mut Child, timeout: Duration) -> std::io::Result<ExitStatus> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(status);
+        }
+        if Instant::now() >= deadline {
+            warn!("ffmpeg did not exit within {:?}, killing it", timeout);
+            // Only fails if the process exited in the meantime; wait reaps it either way
+            let _ = child.kill();
+            return child.wait();
+        }
+        std::thread::sleep(EXIT_POLL_INTERVAL);
+    }
+}
+
+/// Background reader keeping the last lines of a child process's stderr
+struct StderrTail {
+    lines: Arc<Mutex<VecDeque<String>>>,
+    reader: JoinHandle<()>,
+}
+
+impl StderrTail {
+    fn spawn(stderr: impl Read + Send + 'static) -> Result<Self> {
+        let lines = Arc::new(Mutex::new(VecDeque::with_capacity(STDERR_TAIL_LINES)));
+        let sink = lines.clone();
+
+        let reader = std::thread::Builder::new()
+            .name("ffmpeg-stderr".to_string())
+            .spawn(move || {
+                // FFmpeg separates progress updates with '\r', so split on both
+                let mut reader = BufReader::new(stderr);
+                let mut buf = Vec::new();
+                while let Ok(n) = reader.read_until(b'\n', &mut buf) {
+                    if n == 0 {
+                        break;
+                    }
+                    let text = String::from_utf8_lossy(&buf);
+                    for line in text.split('\r').map(str::trim).filter(|l| !l.is_empty()) {
+                        push_bounded(&sink, line.to_string());
+                    }
+                    buf.clear();
+                }
+            })?;
+
+        Ok(Self { lines, reader })
+    }
+
+    /// Lines read so far
+    fn lines(&self) -> Vec<String> {
+        snapshot(&self.lines)
+    }
+
+    /// Wait for the process to close stderr and return the last lines
+    fn finish(self) -> Vec<String> {
+        let Self { lines, reader } = self;
+        let _ = reader.join();
+        snapshot(&lines)
+    }
+}
+
+fn snapshot(lines: &Mutex<VecDeque<String>>) -> Vec<String> {
+    match lines.lock() {
+        Ok(lines) => lines.iter().cloned().collect(),
+        Err(poisoned) => poisoned.into_inner().iter().cloned().collect(),
+    }
+}
+
+fn push_bounded(lines: &Mutex<VecDeque<String>>, line: String) {
+    let mut lines = match lines.lock() {
+        Ok(lines) => lines,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    if lines.len() == STDERR_TAIL_LINES {
+        lines.pop_front();
+    }
+    lines.push_back(line);
+}
+
+/// Format captured stderr lines for a log or error message
+fn format_stderr_tail(lines: &[String]) -> String {
+    if lines.is_empty() {
+        String::new()
+    } else {
+        format!("\nffmpeg output:\n  {}", lines.join("\n  "))
+    }
+}
+
+/// Mux the audio recorded from `start` for `duration_secs` into `video`
+///
+/// The audio is written to a temporary WAV next to the video and FFmpeg
+/// reads both, copying the video stream and encoding the audio as AAC. The
+/// original file is only replaced once muxing succeeded.
+fn mux_audio(video: &Path, audio: &AudioTrack, start: DateTime<Utc>, duration_secs: f64) -> Result<()> {
+    let samples = audio.slice(start, duration_secs);
+    let wav_path = video.with_extension("audio.wav");
+    let muxed_path = video.with_extension("muxing.mp4");
+
+    let result = write_wav(&wav_path, &samples, audio.sample_rate()).and_then(|()| {
+        let mut cmd = ffmpeg_command();
+        cmd.args(audio_mux_args(video, &wav_path, &muxed_path))
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped());
+        debug!("muxing audio: {:?}", cmd);
+
+        let output = cmd.output()?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow::anyhow!("ffmpeg audio mux failed: {}", stderr.trim()));
+        }
+        fs::rename(&muxed_path, video)?;
+        Ok(())
+    });
+
+    let _ = fs::remove_file(&wav_path);
+    if result.is_err() {
+        let _ = fs::remove_file(&muxed_path);
+    } else {
+        debug!("muxed {:.1}s of audio into {:?}", duration_secs, video);
+    }
+    result
+}
+
+/// FFmpeg arguments muxing `audio` into `video` as `output`
+///
+/// Both inputs start at zero, so the streams stay aligned. The video is
+/// copied, and `-shortest` keeps rounding from extending the chunk.
+fn audio_mux_args(video: &Path, audio: &Path, output: &Path) -> Vec<OsString> {
+    let mut args: Vec<OsString> = vec!["-y".into(), "-i".into(), video.into(), "-i".into(), audio.into()];
+    args.extend(
+        [
+            "-map", "0:v:0",
+            "-map", "1:a:0",
+            "-c:v", "copy",
+            "-c:a", "aac",
+            "-b:a", AUDIO_BITRATE,
+            "-shortest",
+        ]
+        .map(OsString::from),
+    );
+    args.push(output.into());
+    args
+}
+
+/// Save mono samples as a 16-bit WAV file
+fn write_wav(path: &Path, samples: &[f32], sample_rate: u32) -> Result<()> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(path, spec)?;
+    for &sample in samples {
+        writer.write_sample((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)?;
+    }
+    writer.finalize()?;
+    Ok(())
+}
+
+/// Size in bytes of a `width`x`height` RGBA frame
+fn rgba_frame_len(width: u32, height: u32) -> usize {
+    width as usize * height as usize * 4
+}
+
+/// Whether `frame_data` is a complete `width`x`height` RGBA frame
+///
+/// FFmpeg reads raw video in fixed-size frames, so a short or padded buffer
+/// shifts every following frame.
+pub fn is_valid_frame(frame_data: &[u8], width: u32, height: u32) -> bool {
+    width > 0 && height > 0 && frame_data.len() == rgba_frame_len(width, height)
+}
+
+/// GOP length in frames: `interval`, or one second of video at `fps`
+fn keyframe_interval_frames(fps: f64, interval: Option<u32>) -> u32 {
+    interval.unwrap_or_else(|| fps.ceil() as u32).max(1)
+}
+
+/// Position of a frame captured `elapsed_ms` after the first frame of a chunk
+///
+/// Frames are placed at the nearest slot of the constant-rate timeline, never
+/// before the next free slot (`written`) and never past the chunk's last slot,
+/// so a long idle gap cannot pad a chunk beyond its duration.
+fn padded_position(elapsed_ms: i64, fps: f64, written: u64, chunk_duration_secs: u64) -> u64 {
+    let slot = (elapsed_ms.max(0) as f64 / 1000.0 * fps).round() as u64;
+    let last_slot = ((chunk_duration_secs as f64 * fps).ceil() as u64).max(written);
+    slot.min(last_slot).max(written)
+}
+
+/// Read resolution, codec, duration and size of a video file with ffprobe
+fn probe_video(path: &Path) -> Result<ProbeResult> {
+    let output = ffprobe_command()
+        .args(["-v", "error", "-select_streams", "v:0"])
+        .args(["-show_entries", "stream=width,height,codec_name:format=duration,size"])
+        .args(["-of", "json"])
+        .arg(path)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "ffprobe failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    parse_probe_output(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parse ffprobe `-of json` output (duration and size are reported as strings)
+fn parse_probe_output(json: &str) -> Result<ProbeResult> {
+    let value: serde_json::Value = serde_json::from_str(json)?;
+    let stream = &value["streams"][0];
+    let format = &value["format"];
+
+    let number = |v: &serde_json::Value| -> Option<f64> {
+        v.as_f64().or_else(|| v.as_str().and_then(|s| s.parse().ok()))
+    };
+
+    Ok(ProbeResult {
+        width: stream["width"].as_u64().map(|w| w as u32),
+        height: stream["height"].as_u64().map(|h| h as u32),
+        codec: stream["codec_name"].as_str().map(str::to_string),
+        duration_secs: number(&format["duration"]),
+        size_bytes: number(&format["size"]).map(|s| s as u64),
+    })
+}
+
 /// Check if FFmpeg is available
 pub fn check_ffmpeg() -> bool {
-    Command::new("ffmpeg")
+    ffmpeg_command()
         .arg("-version")
         .output()
         .map(|o| o.status.success())
@@ -359,9 +935,159 @@ pub fn check_ffmpeg() -> bool {
 
 /// Check if NVENC is available
 pub fn check_nvenc() -> bool {
-    Command::new("ffmpeg")
+    ffmpeg_command()
         .args(["-hide_banner", "-encoders"])
         .output()
         .map(|o| String::from_utf8_lossy(&o.stdout).contains("h264_nvenc"))
         .unwrap_or(false)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wait_or_kill_stops_hung_process() {
+        // Stands in for an FFmpeg that never exits
+        let mut cmd = if cfg!(windows) {
+            let mut cmd = std::process::Command::new("ping");
+            cmd.args(["-n", "60", "127.0.0.1"]);
+            cmd
+        } else {
+            let mut cmd = std::process::Command::new("sleep");
+            cmd.arg("60");
+            cmd
+        };
+        let mut child = cmd.stdout(Stdio::null()).spawn().unwrap();
+
+        let started = Instant::now();
+        let status = wait_or_kill(&mut child, Duration::from_millis(200)).unwrap();
+        assert!(!status.success());
+        assert!(started.elapsed() < Duration::from_secs(30));
+        assert!(child.try_wait().unwrap().is_some(), "process was reaped");
+    }
+
+    #[test]
+    fn test_audio_mux_args() {
+        let args = audio_mux_args(Path::new("chunk.mp4"), Path::new("chunk.audio.wav"), Path::new("out.mp4"));
+        let args: Vec<_> = args.iter().map(|a| a.to_str().unwrap()).collect();
+
+        // Video from the first input, copied; audio from the second, as AAC
+        assert_eq!(&args[..5], ["-y", "-i", "chunk.mp4", "-i", "chunk.audio.wav"]);
+        assert!(args.windows(2).any(|w| w == ["-map", "0:v:0"]));
+        assert!(args.windows(2).any(|w| w == ["-map", "1:a:0"]));
+        assert!(args.windows(2).any(|w| w == ["-c:v", "copy"]));
+        assert!(args.windows(2).any(|w| w == ["-c:a", "aac"]));
+        assert_eq!(args.last(), Some(&"out.mp4"));
+    }
+
+    #[test]
+    fn test_parse_probe_output() {
+        let json = r#"{
+            "programs": [],
+            "streams": [{ "codec_name": "h264", "width": 2560, "height": 1440 }],
+            "format": { "duration": "299.000000", "size": "48213311" }
+        }"#;
+
+        let probe = parse_probe_output(json).unwrap();
+        assert_eq!(probe.width, Some(2560));
+        assert_eq!(probe.height, Some(1440));
+        assert_eq!(probe.codec.as_deref(), Some("h264"));
+        assert_eq!(probe.duration_secs, Some(299.0));
+        assert_eq!(probe.size_bytes, Some(48213311));
+    }
+
+    #[test]
+    fn test_padded_position() {
+        // Consecutive frames at 1 FPS take consecutive slots
+        assert_eq!(padded_position(0, 1.0, 0, 300), 0);
+        assert_eq!(padded_position(1_000, 1.0, 1, 300), 1);
+
+        // A 10 second gap after frame 1 leaves slots 2..=10 for repeats
+        assert_eq!(padded_position(11_000, 1.0, 2, 300), 11);
+
+        // Early frames still take the next free slot
+        assert_eq!(padded_position(400, 1.0, 1, 300), 1);
+
+        // Fractional framerates round to the nearest slot
+        assert_eq!(padded_position(9_000, 0.25, 1, 300), 2);
+
+        // Idle gaps past the chunk duration are capped at its last slot
+        assert_eq!(padded_position(3_600_000, 1.0, 5, 300), 300);
+    }
+
+    #[test]
+    fn test_keyframe_interval_frames() {
+        // One keyframe per second by default: every frame at 1 FPS or less
+        assert_eq!(keyframe_interval_frames(1.0, None), 1);
+        assert_eq!(keyframe_interval_frames(0.25, None), 1);
+        assert_eq!(keyframe_interval_frames(2.5, None), 3);
+        assert_eq!(keyframe_interval_frames(30.0, None), 30);
+
+        // An explicit interval wins, but is never 0
+        assert_eq!(keyframe_interval_frames(1.0, Some(10)), 10);
+        assert_eq!(keyframe_interval_frames(1.0, Some(0)), 1);
+    }
+
+    #[test]
+    fn test_preset_deserializes_nvenc_names() {
+        let preset: EncoderPreset = serde_json::from_str("\"p4\"").unwrap();
+        assert_eq!(preset, EncoderPreset::Fast);
+        let preset: EncoderPreset = serde_json::from_str("\"Medium\"").unwrap();
+        assert_eq!(preset, EncoderPreset::Medium);
+        assert!(serde_json::from_str::<EncoderPreset>("\"p8\"").is_err());
+
+        // Saved as the libx264 name
+        assert_eq!(serde_json::to_string(&EncoderPreset::Slow).unwrap(), "\"slow\"");
+    }
+
+    #[test]
+    fn test_mismatched_frame_is_dropped() {
+        assert!(is_valid_frame(&[0; 16], 2, 2));
+        assert!(!is_valid_frame(&[0; 15], 2, 2));
+        // Row padding from the capture API (pitch wider than the frame)
+        assert!(!is_valid_frame(&[0; 24], 2, 2));
+        assert!(!is_valid_frame(&[], 0, 0));
+
+        let output_dir = std::env::temp_dir().join(format!("memoire-encoder-test-{}", std::process::id()));
+        let mut encoder = VideoEncoder::new(EncoderConfig {
+            output_dir: output_dir.clone(),
+            ..EncoderConfig::default()
+        })
+        .unwrap();
+
+        // Rejected before FFmpeg is started or the chunk is touched
+        let closed = encoder.add_frame(&[0; 15], 2, 2, Utc::now()).unwrap();
+        assert!(closed.is_none());
+        assert_eq!(encoder.frame_position(Utc::now()), 0);
+        assert!(!encoder.frame_size_changed(4, 4));
+        assert!(!encoder.chunk_expired(Utc::now() + chrono::Duration::hours(1)));
+
+        drop(encoder);
+        let _ = fs::remove_dir_all(&output_dir);
+    }
+
+    #[test]
+    fn test_stderr_tail_keeps_last_lines() {
+        let output: String = (0..STDERR_TAIL_LINES + 5)
+            .map(|i| format!("line {}\n", i))
+            .collect::<String>()
+            + "frame=1\rframe=2\r\n";
+
+        let tail = StderrTail::spawn(std::io::Cursor::new(output.into_bytes()))
+            .unwrap()
+            .finish();
+
+        assert_eq!(tail.len(), STDERR_TAIL_LINES);
+        assert_eq!(tail[0], "line 7");
+        assert_eq!(tail[STDERR_TAIL_LINES - 2], "frame=1");
+        assert_eq!(tail[STDERR_TAIL_LINES - 1], "frame=2");
+    }
+
+    #[test]
+    fn test_parse_probe_output_missing_fields() {
+        let probe = parse_probe_output(r#"{ "streams": [], "format": {} }"#).unwrap();
+        assert_eq!(probe, ProbeResult::default());
+        assert!(parse_probe_output("not json").is_err());
+    }
+}
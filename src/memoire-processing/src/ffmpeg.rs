@@ -0,0 +1,157 @@
+//! FFmpeg binary discovery
+//!
+//! Resolves `ffmpeg` (and `ffprobe` when available) once per process so every
+//! subprocess spawn uses the same binary instead of relying on PATH each time.
+//! Lookup order: explicit path (`--ffmpeg-path`), bundled next to the
+//! executable, then PATH.
+
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+use tracing::{debug, info};
+
+/// Process-wide resolved FFmpeg location
+static LOCATOR: OnceLock<FfmpegLocator> = OnceLock::new();
+
+/// Resolved FFmpeg/FFprobe binary paths
+#[derive(Debug, Clone)]
+pub struct FfmpegLocator {
+    ffmpeg: PathBuf,
+    ffprobe: Option<PathBuf>,
+}
+
+impl FfmpegLocator {
+    /// Resolve FFmpeg without caching the result.
+    ///
+    /// `explicit` may point at the `ffmpeg` binary itself or at the directory
+    /// containing it. When given, no other location is tried.
+    pub fn locate(explicit: Option<&Path>) -> Result<Self> {
+        let ffmpeg = match explicit {
+            Some(path) => {
+                let candidate = if path.is_dir() {
+                    path.join(binary_name("ffmpeg"))
+                } else {
+                    path.to_path_buf()
+                };
+                if !is_working_ffmpeg(&candidate) {
+                    return Err(anyhow::anyhow!(
+                        "FFmpeg not found at {:?} (from --ffmpeg-path)",
+                        candidate
+                    ));
+                }
+                candidate
+            }
+            None => search_candidates("ffmpeg")
+                .into_iter()
+                .find(|candidate| is_working_ffmpeg(candidate))
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "FFmpeg not found. Install FFmpeg and add it to PATH, place {} next to the memoire executable, or pass --ffmpeg-path",
+                        binary_name("ffmpeg")
+                    )
+                })?,
+        };
+
+        // Prefer the ffprobe shipped alongside the resolved ffmpeg
+        let sibling = ffmpeg.with_file_name(binary_name("ffprobe"));
+        let ffprobe = if sibling.is_file() {
+            Some(sibling)
+        } else {
+            search_candidates("ffprobe").into_iter().find(|p| p.is_file())
+        };
+
+        debug!("resolved ffmpeg: {:?}, ffprobe: {:?}", ffmpeg, ffprobe);
+
+        Ok(Self { ffmpeg, ffprobe })
+    }
+
+    /// Resolve FFmpeg once for the whole process.
+    ///
+    /// Subsequent calls return the cached locator and ignore `explicit`.
+    pub fn init(explicit: Option<&Path>) -> Result<&'static FfmpegLocator> {
+        if let Some(locator) = LOCATOR.get() {
+            return Ok(locator);
+        }
+
+        let locator = Self::locate(explicit)?;
+        info!("using ffmpeg at {:?}", locator.ffmpeg);
+        Ok(LOCATOR.get_or_init(|| locator))
+    }
+
+    /// Get the cached locator, if `init` succeeded
+    pub fn global() -> Option<&'static FfmpegLocator> {
+        LOCATOR.get()
+    }
+
+    /// Path to the ffmpeg binary
+    pub fn ffmpeg(&self) -> &Path {
+        &self.ffmpeg
+    }
+
+    /// Path to the ffprobe binary, if one was found
+    pub fn ffprobe(&self) -> Option<&Path> {
+        self.ffprobe.as_deref()
+    }
+}
+
+/// Create a `Command` for ffmpeg using the resolved path (falls back to PATH)
+pub fn ffmpeg_command() -> Command {
+    match FfmpegLocator::global() {
+        Some(locator) => Command::new(locator.ffmpeg()),
+        None => Command::new("ffmpeg"),
+    }
+}
+
+/// Create a `Command` for ffprobe using the resolved path (falls back to PATH)
+pub fn ffprobe_command() -> Command {
+    match FfmpegLocator::global().and_then(|l| l.ffprobe()) {
+        Some(path) => Command::new(path),
+        None => Command::new("ffprobe"),
+    }
+}
+
+/// Platform-specific executable file name
+fn binary_name(name: &str) -> String {
+    format!("{}{}", name, std::env::consts::EXE_SUFFIX)
+}
+
+/// Candidate locations for a binary: bundled next to the executable, then PATH
+fn search_candidates(name: &str) -> Vec<PathBuf> {
+    let file_name = binary_name(name);
+    let mut candidates = Vec::new();
+
+    if let Some(exe_dir) = std::env::current_exe().ok().and_then(|p| p.parent().map(Path::to_path_buf)) {
+        candidates.push(exe_dir.join(&file_name));
+        candidates.push(exe_dir.join("ffmpeg").join(&file_name));
+        candidates.push(exe_dir.join("ffmpeg").join("bin").join(&file_name));
+    }
+
+    if let Some(path) = std::env::var_os("PATH") {
+        candidates.extend(std::env::split_paths(&path).map(|dir| dir.join(&file_name)));
+    }
+
+    candidates
+}
+
+/// Check that a path is an ffmpeg binary that runs
+fn is_working_ffmpeg(path: &Path) -> bool {
+    path.is_file()
+        && Command::new(path)
+            .arg("-version")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explicit_missing_path_fails() {
+        let result = FfmpegLocator::locate(Some(Path::new("/nonexistent/ffmpeg")));
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("--ffmpeg-path"));
+    }
+}
@@ -1,9 +1,16 @@
 //! memoire-processing - Video and audio processing for Memoire
 //!
-//! Handles video encoding and audio chunk management.
+//! Handles video encoding, audio chunk management, audio mixing and muxing
+//! audio into video chunks.
 
 pub mod encoder;
 pub mod audio_encoder;
+pub mod audio_mixer;
+pub mod audio_track;
+pub mod ffmpeg;
 
-pub use encoder::VideoEncoder;
-pub use audio_encoder::{AudioEncoder, AudioEncoderConfig};
+pub use encoder::{is_valid_frame, EncodedChunkInfo, EncoderPreset, VideoEncoder};
+pub use audio_encoder::{AudioChunkInfo, AudioEncoder, AudioEncoderConfig};
+pub use audio_mixer::AudioMixer;
+pub use audio_track::AudioTrack;
+pub use ffmpeg::FfmpegLocator;
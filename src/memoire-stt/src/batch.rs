@@ -0,0 +1,158 @@
+//! Concurrent transcription of many files
+//!
+//! Each worker thread loads its own engine once and pulls files from a
+//! bounded queue, so catching up on a backlog costs one model load per
+//! worker instead of one per file. Every worker holds a full copy of the
+//! model, which bounds useful concurrency by memory (and by GPU memory when a
+//! GPU provider is active).
+
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use tracing::{debug, info, warn};
+
+use crate::engine::{SttConfig, SttEngine, TranscriptionResult};
+use crate::error::{Result, SttError};
+
+/// Files queued per worker ahead of the one being transcribed
+const QUEUE_DEPTH_PER_WORKER: usize = 2;
+
+/// Transcribe `files` on `concurrency` worker threads
+///
+/// Results are returned in the order of `files`, with an error for each file
+/// that could not be transcribed. Unlike [`SttEngine::new`], a missing model
+/// is an error rather than placeholder text. ONNX Runtime must already be
+/// configured (see [`configure_onnx_runtime`](crate::configure_onnx_runtime)).
+pub async fn transcribe_batch(
+    files: Vec<PathBuf>,
+    config: SttConfig,
+    concurrency: usize,
+) -> Vec<(PathBuf, Result<TranscriptionResult>)> {
+    let paths = files.clone();
+    match tokio::task::spawn_blocking(move || run_batch(files, config, concurrency)).await {
+        Ok(results) => results,
+        Err(e) => paths
+            .into_iter()
+            .map(|path| (path, Err(SttError::InferenceError(format!("transcription batch failed: {}", e)))))
+            .collect(),
+    }
+}
+
+fn run_batch(
+    files: Vec<PathBuf>,
+    config: SttConfig,
+    concurrency: usize,
+) -> Vec<(PathBuf, Result<TranscriptionResult>)> {
+    if files.is_empty() {
+        return Vec::new();
+    }
+
+    let workers = concurrency.clamp(1, files.len());
+    info!("transcribing {} files on {} workers", files.len(), workers);
+
+    let (job_tx, job_rx) = mpsc::sync_channel::<(usize, PathBuf)>(workers * QUEUE_DEPTH_PER_WORKER);
+    let job_rx = Arc::new(Mutex::new(job_rx));
+    let (result_tx, result_rx) = mpsc::channel();
+
+    let mut results: Vec<Option<(PathBuf, Result<TranscriptionResult>)>> = Vec::new();
+    results.resize_with(files.len(), || None);
+
+    thread::scope(|scope| {
+        for worker in 0..workers {
+            let job_rx = job_rx.clone();
+            let result_tx = result_tx.clone();
+            let config = config.clone();
+            scope.spawn(move || {
+                let mut engine = load_engine(config);
+                while let Some((index, path)) = next_job(&job_rx) {
+                    let result = match engine.as_mut() {
+                        Ok(engine) => engine.transcribe_file(&path),
+                        Err(e) => Err(load_failure(e)),
+                    };
+                    if result_tx.send((index, path, result)).is_err() {
+                        break;
+                    }
+                }
+                debug!("batch worker {} finished", worker);
+            });
+        }
+        drop(result_tx);
+
+        // Blocks while the queue is full, so at most a few files wait per worker
+        for job in files.iter().cloned().enumerate() {
+            if job_tx.send(job).is_err() {
+                warn!("all transcription workers stopped");
+                break;
+            }
+        }
+        drop(job_tx);
+
+        for (index, path, result) in result_rx {
+            results[index] = Some((path, result));
+        }
+    });
+
+    results
+        .into_iter()
+        .zip(files)
+        .map(|(result, path)| result.unwrap_or((path, Err(SttError::WorkerStopped))))
+        .collect()
+}
+
+/// Load an engine for one worker, failing when the model is missing
+fn load_engine(config: SttConfig) -> Result<SttEngine> {
+    let model_dir = config.model_dir.clone();
+    let engine = SttEngine::new(config)?;
+    if !engine.is_model_loaded() {
+        return Err(SttError::ModelNotFound {
+            path: model_dir.display().to_string(),
+            message: "run 'memoire download-models' first".to_string(),
+        });
+    }
+    Ok(engine)
+}
+
+/// The error reported for each file when a worker's engine failed to load
+fn load_failure(error: &SttError) -> SttError {
+    match error {
+        SttError::ModelNotFound { path, message } => SttError::ModelNotFound {
+            path: path.clone(),
+            message: message.clone(),
+        },
+        other => SttError::ModelLoadError(other.to_string()),
+    }
+}
+
+fn next_job(jobs: &Mutex<Receiver<(usize, PathBuf)>>) -> Option<(usize, PathBuf)> {
+    jobs.lock().unwrap_or_else(PoisonError::into_inner).recv().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_batch_without_model() {
+        let config = SttConfig {
+            model_dir: std::env::temp_dir().join("memoire-stt-missing-model"),
+            ..SttConfig::default().cpu_only()
+        };
+        let files: Vec<PathBuf> = (0..5).map(|i| PathBuf::from(format!("chunk_{}.wav", i))).collect();
+
+        let results = transcribe_batch(files.clone(), config, 8).await;
+
+        // Every file is reported, in input order, as failed rather than placeholder text
+        assert_eq!(results.len(), files.len());
+        for ((path, result), expected) in results.iter().zip(&files) {
+            assert_eq!(path, expected);
+            assert!(matches!(result, Err(SttError::ModelNotFound { .. })));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_empty_batch() {
+        let results = transcribe_batch(Vec::new(), SttConfig::default().cpu_only(), 4).await;
+        assert!(results.is_empty());
+    }
+}
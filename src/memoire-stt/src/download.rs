@@ -4,7 +4,6 @@
 //! Uses the csukuangfj/sherpa-onnx-nemo-parakeet-tdt-0.6b-v2-int8 repository.
 //! Also downloads the required ONNX Runtime DLL (v1.22.x) for compatibility.
 
-use anyhow::{Context, Result};
 use futures_util::StreamExt;
 use indicatif::{ProgressBar, ProgressStyle};
 use std::path::{Path, PathBuf};
@@ -12,6 +11,8 @@ use tokio::fs::File;
 use tokio::io::AsyncWriteExt;
 use tracing::{debug, info, warn};
 
+use crate::error::{Result, SttError};
+
 /// Base URL for HuggingFace model repository
 const HF_BASE_URL: &str = "https://huggingface.co/csukuangfj/sherpa-onnx-nemo-parakeet-tdt-0.6b-v2-int8/resolve/main";
 
@@ -81,7 +82,7 @@ impl ModelDownloader {
         // Create model directory if it doesn't exist
         tokio::fs::create_dir_all(&self.model_dir)
             .await
-            .context("Failed to create model directory")?;
+            .map_err(failed("Failed to create model directory"))?;
 
         info!("Downloading Parakeet TDT models to {:?}", self.model_dir);
 
@@ -133,7 +134,7 @@ impl ModelDownloader {
         // Create model directory if it doesn't exist
         tokio::fs::create_dir_all(&self.model_dir)
             .await
-            .context("Failed to create model directory")?;
+            .map_err(failed("Failed to create model directory"))?;
 
         info!("Downloading ONNX Runtime 1.22.0 for Windows x64...");
 
@@ -163,16 +164,16 @@ impl ModelDownloader {
         // Read the zip file synchronously (zip crate doesn't support async)
         let zip_data = tokio::fs::read(zip_path)
             .await
-            .context("Failed to read zip file")?;
+            .map_err(failed("Failed to read zip file"))?;
 
         let reader = std::io::Cursor::new(zip_data);
         let mut archive = zip::ZipArchive::new(reader)
-            .context("Failed to open zip archive")?;
+            .map_err(failed("Failed to open zip archive"))?;
 
         // Find and extract onnxruntime.dll
         // The DLL is typically at: onnxruntime-win-x64-1.22.0/lib/onnxruntime.dll
         for i in 0..archive.len() {
-            let mut file = archive.by_index(i)?;
+            let mut file = archive.by_index(i).map_err(failed("Failed to read zip archive"))?;
             let name = file.name();
 
             if name.ends_with("onnxruntime.dll") && !name.contains("providers") {
@@ -180,17 +181,17 @@ impl ModelDownloader {
 
                 let mut contents = Vec::new();
                 file.read_to_end(&mut contents)
-                    .context("Failed to read DLL from archive")?;
+                    .map_err(failed("Failed to read DLL from archive"))?;
 
                 tokio::fs::write(dll_path, &contents)
                     .await
-                    .context("Failed to write DLL file")?;
+                    .map_err(failed("Failed to write DLL file"))?;
 
                 return Ok(());
             }
         }
 
-        Err(anyhow::anyhow!("onnxruntime.dll not found in archive"))
+        Err(SttError::DownloadError("onnxruntime.dll not found in archive".to_string()))
     }
 
     /// Download a single file with progress reporting
@@ -207,14 +208,14 @@ impl ModelDownloader {
             .get(url)
             .send()
             .await
-            .context("Failed to start download")?;
+            .map_err(failed("Failed to start download"))?;
 
         // Check for successful response
         if !response.status().is_success() {
-            return Err(anyhow::anyhow!(
+            return Err(SttError::DownloadError(format!(
                 "Download failed with status: {}",
                 response.status()
-            ));
+            )));
         }
 
         // Get content length for progress bar
@@ -233,30 +234,30 @@ impl ModelDownloader {
         let temp_path = local_path.with_extension("tmp");
         let mut file = File::create(&temp_path)
             .await
-            .context("Failed to create temp file")?;
+            .map_err(failed("Failed to create temp file"))?;
 
         // Stream the download
         let mut stream = response.bytes_stream();
         let mut downloaded: u64 = 0;
 
         while let Some(chunk) = stream.next().await {
-            let chunk = chunk.context("Error reading download stream")?;
+            let chunk = chunk.map_err(failed("Error reading download stream"))?;
             file.write_all(&chunk)
                 .await
-                .context("Error writing to file")?;
+                .map_err(failed("Error writing to file"))?;
 
             downloaded += chunk.len() as u64;
             pb.set_position(downloaded);
         }
 
         // Flush and close the file
-        file.flush().await.context("Failed to flush file")?;
+        file.flush().await.map_err(failed("Failed to flush file"))?;
         drop(file);
 
         // Rename temp file to final name
         tokio::fs::rename(&temp_path, local_path)
             .await
-            .context("Failed to rename temp file")?;
+            .map_err(failed("Failed to rename temp file"))?;
 
         pb.finish_with_message("done");
         info!(
@@ -269,6 +270,11 @@ impl ModelDownloader {
     }
 }
 
+/// Wrap an error from a download step with what was being done
+fn failed<E: std::fmt::Display>(context: &'static str) -> impl FnOnce(E) -> SttError {
+    move |e| SttError::DownloadError(format!("{}: {}", context, e))
+}
+
 /// Format bytes as human-readable string
 #[allow(dead_code)]
 fn format_bytes(bytes: u64) -> String {
@@ -8,38 +8,166 @@
 //! TDT (Token-and-Duration Transducer) extends standard transducers by also
 //! predicting how many frames to skip, enabling faster decoding.
 
-use anyhow::{Context, Result};
 use ort::session::Session;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use tracing::{debug, info, warn};
 
-use crate::error::SttError;
-use crate::mel::{MelSpectrogram, ENCODER_FRAME_DURATION_SEC, SAMPLE_RATE};
+use crate::error::{Result, SttError};
+use crate::langid::detect_language;
+use crate::model_config::{DecoderNames, ModelConfig};
+use crate::mel::{MelConfig, MelSpectrogram, ENCODER_FRAME_DURATION_SEC};
+use crate::resample::{resample_to_model_rate, ResampleQuality};
 use crate::tokenizer::Tokenizer;
 
+/// Default number of intra-op threads for CPU inference
+pub const DEFAULT_NUM_THREADS: usize = 4;
+
+/// Default RMS level audio is normalized to (about -20 dBFS)
+pub const DEFAULT_NORMALIZE_TARGET_RMS: f32 = 0.1;
+
+/// Default highest sample magnitude after normalization
+pub const DEFAULT_NORMALIZE_PEAK_CEILING: f32 = 0.95;
+
+/// Largest gain applied by normalization (about 30 dB), so noise is not
+/// turned into speech-level hiss
+const MAX_NORMALIZE_GAIN: f32 = 30.0;
+
+/// Below this RMS a clip is treated as silence and left as is
+const SILENCE_RMS: f32 = 1e-4;
+
+/// ONNX Runtime execution provider used for inference
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExecutionProvider {
+    /// NVIDIA TensorRT (fastest on NVIDIA, needs the TensorRT libraries)
+    TensorRt,
+    /// NVIDIA CUDA
+    Cuda,
+    /// DirectML (any DirectX 12 GPU on Windows, including AMD and Intel)
+    DirectMl,
+    /// CPU, always available
+    Cpu,
+}
+
+impl ExecutionProvider {
+    /// Default order in which providers are tried
+    pub const DEFAULT_ORDER: [ExecutionProvider; 4] = [
+        ExecutionProvider::TensorRt,
+        ExecutionProvider::Cuda,
+        ExecutionProvider::DirectMl,
+        ExecutionProvider::Cpu,
+    ];
+
+    /// Whether this provider runs on a GPU
+    pub fn is_gpu(self) -> bool {
+        self != ExecutionProvider::Cpu
+    }
+
+    /// Name used in configuration files and on the command line
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ExecutionProvider::TensorRt => "tensorrt",
+            ExecutionProvider::Cuda => "cuda",
+            ExecutionProvider::DirectMl => "directml",
+            ExecutionProvider::Cpu => "cpu",
+        }
+    }
+}
+
+impl fmt::Display for ExecutionProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ExecutionProvider {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::DEFAULT_ORDER
+            .into_iter()
+            .find(|p| p.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("unknown execution provider '{}' (expected tensorrt, cuda, directml or cpu)", s))
+    }
+}
+
+fn default_providers() -> Vec<ExecutionProvider> {
+    ExecutionProvider::DEFAULT_ORDER.to_vec()
+}
+
 /// Configuration for the STT engine
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SttConfig {
     /// Path to the ONNX model directory
     pub model_dir: PathBuf,
-    /// Whether to use GPU acceleration
-    pub use_gpu: bool,
+    /// Execution providers to try, in order
+    ///
+    /// The first one that registers is used. The CPU is always the final
+    /// fallback; providers listed after `Cpu` are never tried.
+    #[serde(default = "default_providers")]
+    pub providers: Vec<ExecutionProvider>,
+    /// Device index for GPU providers (see nvidia-smi or the DirectX adapter list)
+    #[serde(default)]
+    pub gpu_device: i32,
     /// Language code (e.g., "en", "fr", "de")
     pub language: Option<String>,
     /// Number of threads for CPU inference
     pub num_threads: usize,
+    /// Scale each clip to `normalize_target_rms` before feature extraction
+    /// (helps quiet or distant microphones)
+    #[serde(default)]
+    pub normalize_audio: bool,
+    /// RMS level normalization aims for
+    #[serde(default = "default_normalize_target_rms")]
+    pub normalize_target_rms: f32,
+    /// Highest sample magnitude normalization may produce, to avoid clipping
+    #[serde(default = "default_normalize_peak_ceiling")]
+    pub normalize_peak_ceiling: f32,
+    /// Resampler for audio not recorded at 16kHz (8kHz sources always use sinc)
+    #[serde(default)]
+    pub resample_quality: ResampleQuality,
+}
+
+fn default_normalize_target_rms() -> f32 {
+    DEFAULT_NORMALIZE_TARGET_RMS
+}
+
+fn default_normalize_peak_ceiling() -> f32 {
+    DEFAULT_NORMALIZE_PEAK_CEILING
 }
 
 impl Default for SttConfig {
     fn default() -> Self {
         Self {
             model_dir: crate::default_model_dir(),
-            use_gpu: true,
+            providers: default_providers(),
+            gpu_device: 0,
             language: None, // Auto-detect
-            num_threads: 4,
+            num_threads: DEFAULT_NUM_THREADS,
+            normalize_audio: false,
+            normalize_target_rms: DEFAULT_NORMALIZE_TARGET_RMS,
+            normalize_peak_ceiling: DEFAULT_NORMALIZE_PEAK_CEILING,
+            resample_quality: ResampleQuality::default(),
+        }
+    }
+}
+
+impl SttConfig {
+    /// Run inference on the CPU only
+    pub fn cpu_only(self) -> Self {
+        Self {
+            providers: vec![ExecutionProvider::Cpu],
+            ..self
         }
     }
+
+    /// Whether any GPU provider will be tried
+    pub fn wants_gpu(&self) -> bool {
+        self.providers.first().is_some_and(|p| p.is_gpu())
+    }
 }
 
 /// A segment of transcription with timing information
@@ -62,7 +190,8 @@ pub struct TranscriptionResult {
     pub text: String,
     /// Individual segments with timestamps
     pub segments: Vec<TranscriptionSegment>,
-    /// Detected language (if auto-detected)
+    /// ISO 639-1 code: the configured language, or the one detected from
+    /// the text (None when too short or ambiguous to tell)
     pub language: Option<String>,
     /// Processing time in milliseconds
     pub processing_time_ms: u64,
@@ -77,6 +206,8 @@ struct ParakeetModel {
     pred_rnn_layers: usize,
     /// Hidden size of decoder RNN
     pred_hidden: usize,
+    /// Decoder tensor names, resolved against the decoder session
+    decoder_names: DecoderNames,
 }
 
 /// Speech-to-text engine
@@ -85,7 +216,7 @@ pub struct SttEngine {
     model: Option<ParakeetModel>,
     tokenizer: Option<Tokenizer>,
     mel_extractor: MelSpectrogram,
-    is_gpu_enabled: bool,
+    active_provider: ExecutionProvider,
 }
 
 impl SttEngine {
@@ -93,7 +224,10 @@ impl SttEngine {
     pub fn new(config: SttConfig) -> Result<Self> {
         info!("initializing STT engine");
         info!("model directory: {:?}", config.model_dir);
-        info!("GPU enabled: {}", config.use_gpu);
+        info!(
+            "execution providers: {}",
+            config.providers.iter().map(|p| p.as_str()).collect::<Vec<_>>().join(", ")
+        );
 
         // Check if model files exist
         let encoder_path = config.model_dir.join("encoder.onnx");
@@ -112,41 +246,42 @@ impl SttEngine {
             warn!("Run 'memoire download-models' to download the Parakeet TDT model");
 
             // Create mel extractor with default 80 bins (will be updated when model loads)
-            let mel_extractor = MelSpectrogram::new(80, true);
+            let mel_extractor = MelSpectrogram::new(MelConfig::parakeet());
 
             return Ok(Self {
                 config,
                 model: None,
                 tokenizer: None,
                 mel_extractor,
-                is_gpu_enabled: false,
+                active_provider: ExecutionProvider::Cpu,
             });
         }
 
         // Load tokenizer
-        let tokenizer = Tokenizer::from_file(&tokens_path)
-            .context("failed to load tokenizer")?;
+        let tokenizer = Tokenizer::from_file(&tokens_path)?;
         info!("loaded tokenizer: vocab_size={}", tokenizer.vocab_size());
 
         // Determine feature dimension from model metadata or use default
         // Parakeet TDT 0.6b-v2 uses 128-dim features
         let num_mels = 128;
-        let mel_extractor = MelSpectrogram::new(num_mels, true);
+        let mel_extractor = MelSpectrogram::new(MelConfig { num_mels, ..MelConfig::parakeet() });
 
-        // Initialize ONNX Runtime sessions
-        let mut is_gpu_enabled = false;
+        // Create encoder session; it does most of the work, so it picks the provider
+        let (encoder, active_provider) = Self::create_session(&encoder_path, &config, &config.providers)
+            .map_err(|e| SttError::ModelLoadError(format!("encoder: {}", e)))?;
 
-        // Create encoder session
-        let encoder = Self::create_session(&encoder_path, config.use_gpu, config.num_threads, &mut is_gpu_enabled)
-            .context("failed to load encoder model")?;
+        // Decoder and joiner run on the same provider (or fall back to CPU)
+        let (decoder, _) = Self::create_session(&decoder_path, &config, &[active_provider])
+            .map_err(|e| SttError::ModelLoadError(format!("decoder: {}", e)))?;
 
-        // Create decoder session
-        let decoder = Self::create_session(&decoder_path, config.use_gpu, config.num_threads, &mut is_gpu_enabled)
-            .context("failed to load decoder model")?;
+        let (joiner, _) = Self::create_session(&joiner_path, &config, &[active_provider])
+            .map_err(|e| SttError::ModelLoadError(format!("joiner: {}", e)))?;
 
-        // Create joiner session
-        let joiner = Self::create_session(&joiner_path, config.use_gpu, config.num_threads, &mut is_gpu_enabled)
-            .context("failed to load joiner model")?;
+        // Tensor names differ between exports; model.json may override them
+        let model_config = ModelConfig::load(&config.model_dir)?;
+        let decoder_inputs: Vec<&str> = decoder.inputs.iter().map(|i| i.name.as_str()).collect();
+        let decoder_outputs: Vec<&str> = decoder.outputs.iter().map(|o| o.name.as_str()).collect();
+        let decoder_names = model_config.decoder.resolve(&decoder_inputs, &decoder_outputs)?;
 
         // Get decoder dimensions from model metadata
         // Default values for Parakeet TDT
@@ -159,6 +294,7 @@ impl SttEngine {
             joiner,
             pred_rnn_layers,
             pred_hidden,
+            decoder_names,
         };
 
         // Log model I/O names and shapes for debugging
@@ -188,51 +324,75 @@ impl SttEngine {
         }
 
         info!("STT engine initialized successfully");
-        info!("execution provider: {}", if is_gpu_enabled { "CUDA" } else { "CPU" });
+        info!("execution provider: {}", active_provider);
 
         Ok(Self {
             config,
             model: Some(model),
             tokenizer: Some(tokenizer),
             mel_extractor,
-            is_gpu_enabled,
+            active_provider,
         })
     }
 
-    /// Create an ONNX session with optional GPU acceleration
+    /// Create an ONNX session on the first of `providers` that works
+    ///
+    /// Falls back to the CPU when no GPU provider registers or the model
+    /// fails to load on it. Returns the provider the session runs on.
     fn create_session(
         path: &Path,
-        use_gpu: bool,
-        num_threads: usize,
-        is_gpu_enabled: &mut bool,
-    ) -> Result<Session> {
-        let builder = Session::builder()?
-            .with_intra_threads(num_threads)?;
-
-        let builder = if use_gpu {
-            match builder.with_execution_providers([
-                ort::execution_providers::CUDAExecutionProvider::default().build(),
-            ]) {
-                Ok(b) => {
-                    *is_gpu_enabled = true;
-                    info!("CUDA execution provider enabled");
-                    b
-                }
-                Err(e) => {
-                    warn!("failed to enable CUDA, falling back to CPU: {}", e);
-                    Session::builder()?.with_intra_threads(num_threads)?
+        config: &SttConfig,
+        providers: &[ExecutionProvider],
+    ) -> Result<(Session, ExecutionProvider)> {
+        use ort::execution_providers::{
+            CUDAExecutionProvider, DirectMLExecutionProvider, TensorRTExecutionProvider,
+        };
+
+        for &provider in providers.iter().take_while(|p| p.is_gpu()) {
+            let builder = Session::builder()?.with_intra_threads(config.num_threads)?;
+            let dispatch = match provider {
+                ExecutionProvider::TensorRt => TensorRTExecutionProvider::default()
+                    .with_device_id(config.gpu_device)
+                    .build(),
+                ExecutionProvider::Cuda => CUDAExecutionProvider::default()
+                    .with_device_id(config.gpu_device)
+                    .build(),
+                ExecutionProvider::DirectMl => DirectMLExecutionProvider::default()
+                    .with_device_id(config.gpu_device)
+                    .build(),
+                ExecutionProvider::Cpu => unreachable!(),
+            };
+
+            // DirectML does not support memory patterns or parallel execution
+            let builder = if provider == ExecutionProvider::DirectMl {
+                builder.with_memory_pattern(false)?.with_parallel_execution(false)?
+            } else {
+                builder
+            };
+
+            // Without error_on_failure ONNX Runtime silently falls back to CPU
+            let session = builder
+                .with_execution_providers([dispatch.error_on_failure()])
+                .and_then(|b| b.commit_from_file(path));
+
+            match session {
+                Ok(session) => {
+                    debug!("{} execution provider enabled on device {} for {:?}", provider, config.gpu_device, path);
+                    return Ok((session, provider));
                 }
+                Err(e) => warn!("failed to enable {} execution provider: {}", provider, e),
             }
-        } else {
-            builder
-        };
+        }
 
-        builder.commit_from_file(path).map_err(Into::into)
+        let session = Session::builder()?
+            .with_intra_threads(config.num_threads)?
+            .commit_from_file(path)?;
+        Ok((session, ExecutionProvider::Cpu))
     }
 
-    /// Check if GPU acceleration is enabled
-    pub fn is_gpu_enabled(&self) -> bool {
-        self.is_gpu_enabled
+    /// Execution provider the model runs on (`Cpu` when no model is loaded)
+    pub fn active_provider(&self) -> ExecutionProvider {
+        self.active_provider
     }
 
     /// Check if the model is loaded
@@ -272,7 +432,7 @@ impl SttEngine {
             });
         }
 
-        // Preprocess audio (ensure 16kHz mono)
+        // Preprocess audio (ensure 16kHz mono, optionally normalized)
         let processed_samples = self.preprocess_audio(samples, sample_rate)?;
 
         // Run inference
@@ -291,70 +451,33 @@ impl SttEngine {
 
     /// Load audio from a WAV file
     fn load_audio(&self, path: &Path) -> Result<AudioData> {
-        let reader = hound::WavReader::open(path)
-            .context("failed to open WAV file")?;
-
-        let spec = reader.spec();
-        let sample_rate = spec.sample_rate;
-        let channels = spec.channels as usize;
-
-        debug!("loading audio: {} Hz, {} channels", sample_rate, channels);
-
-        let samples: Vec<f32> = match spec.sample_format {
-            hound::SampleFormat::Float => {
-                reader.into_samples::<f32>()
-                    .filter_map(|s| s.ok())
-                    .collect()
-            }
-            hound::SampleFormat::Int => {
-                let bits = spec.bits_per_sample;
-                let max_val = (1 << (bits - 1)) as f32;
-                reader.into_samples::<i32>()
-                    .filter_map(|s| s.ok())
-                    .map(|s| s as f32 / max_val)
-                    .collect()
-            }
-        };
+        let wav = memoire_capture::wav::read_wav(path)
+            .map_err(|e| SttError::AudioError(format!("{:#}", e)))?;
 
-        // Convert to mono if stereo
-        let mono_samples = if channels > 1 {
-            samples
-                .chunks(channels)
-                .map(|chunk| chunk.iter().sum::<f32>() / channels as f32)
-                .collect()
-        } else {
-            samples
-        };
+        debug!(
+            "loading audio: {} Hz, {} channels, {} bits",
+            wav.sample_rate, wav.channels, wav.bits_per_sample
+        );
 
         Ok(AudioData {
-            samples: mono_samples,
-            sample_rate,
+            samples: wav.to_mono(),
+            sample_rate: wav.sample_rate,
         })
     }
 
-    /// Preprocess audio for the model (resample to 16kHz if needed)
+    /// Preprocess audio for the model (resample to 16kHz if needed, then
+    /// normalize when enabled)
     fn preprocess_audio(&self, samples: &[f32], sample_rate: u32) -> Result<Vec<f32>> {
-        if sample_rate == SAMPLE_RATE {
-            return Ok(samples.to_vec());
+        let mut samples = resample_to_model_rate(samples, sample_rate, self.config.resample_quality)?;
+        if self.config.normalize_audio {
+            let gain = normalize_samples(
+                &mut samples,
+                self.config.normalize_target_rms,
+                self.config.normalize_peak_ceiling,
+            );
+            debug!("normalized audio with gain {:.2}", gain);
         }
-
-        // Resample to 16kHz
-        debug!("resampling from {} Hz to {} Hz", sample_rate, SAMPLE_RATE);
-
-        use rubato::{FftFixedIn, Resampler};
-
-        let mut resampler = FftFixedIn::<f32>::new(
-            sample_rate as usize,
-            SAMPLE_RATE as usize,
-            samples.len(),
-            1, // chunk size
-            1, // channels (mono)
-        )?;
-
-        let input = vec![samples.to_vec()];
-        let output = resampler.process(&input, None)?;
-
-        Ok(output.into_iter().flatten().collect())
+        Ok(samples)
     }
 
     /// Run TDT model inference
@@ -411,7 +534,7 @@ impl SttEngine {
             // Encoder output shape: [batch, hidden_dim=1024, time]
             // Note: NeMo/Parakeet uses [batch, hidden, time] convention, NOT [batch, time, hidden]
             let encoder_out = encoder_outputs.get("outputs").or_else(|| encoder_outputs.get("logits"))
-                .ok_or_else(|| anyhow::anyhow!("encoder output not found"))?;
+                .ok_or_else(|| SttError::InferenceError("encoder output not found".to_string()))?;
 
             let (encoder_shape, data) = encoder_out.try_extract_tensor::<f32>()?;
             let encoder_dim = encoder_shape[1] as usize;  // hidden dimension (1024)
@@ -460,10 +583,17 @@ impl SttEngine {
             })
             .collect();
 
+        // The configured language, or the one the text reads as
+        let language = self
+            .config
+            .language
+            .clone()
+            .or_else(|| detect_language(&text).map(str::to_string));
+
         Ok(TranscriptionResult {
             text,
             segments,
-            language: self.config.language.clone(),
+            language,
             processing_time_ms: 0, // Will be set by caller
         })
     }
@@ -525,30 +655,30 @@ impl SttEngine {
                 c_state.clone().into_boxed_slice(),
             ))?;
 
-            // Note: The Parakeet TDT decoder model input/output names from sherpa-onnx:
-            // Inputs: "targets", "target_length", "states.1", "onnx::Slice_3"
-            // Outputs: "outputs", "prednet_lengths", "states", "162"
+            // Names default to the sherpa-onnx export (see model_config)
+            let names = &model.decoder_names;
             let decoder_outputs = model.decoder.run(ort::inputs![
-                "targets" => decoder_input,
-                "target_length" => decoder_length,
-                "states.1" => h_input,
-                "onnx::Slice_3" => c_input,
+                names.targets.as_str() => decoder_input,
+                names.target_length.as_str() => decoder_length,
+                names.h_in.as_str() => h_input,
+                names.c_in.as_str() => c_input,
             ])?;
 
             // Get decoder output and updated states
-            let decoder_out = decoder_outputs.get("outputs")
-                .ok_or_else(|| anyhow::anyhow!("decoder output not found"))?;
+            let decoder_out = decoder_outputs.get(names.output.as_str()).ok_or_else(|| {
+                SttError::InferenceError(format!("decoder output '{}' not found", names.output))
+            })?;
             let (decoder_shape, decoder_data) = decoder_out.try_extract_tensor::<f32>()?;
 
             // Decoder output shape: [batch=1, hidden_dim=640, seq=1]
             let decoder_dim = decoder_shape[1] as usize;  // 640
 
             // Update states if present (output names from model)
-            if let Some(new_h) = decoder_outputs.get("states") {
+            if let Some(new_h) = decoder_outputs.get(names.h_out.as_str()) {
                 let (_, h_data) = new_h.try_extract_tensor::<f32>()?;
                 h_state = h_data.to_vec();
             }
-            if let Some(new_c) = decoder_outputs.get("162") {
+            if let Some(new_c) = decoder_outputs.get(names.c_out.as_str()) {
                 let (_, c_data) = new_c.try_extract_tensor::<f32>()?;
                 c_state = c_data.to_vec();
             }
@@ -575,7 +705,7 @@ impl SttEngine {
             ])?;
 
             let logits = joiner_outputs.get("outputs")
-                .ok_or_else(|| anyhow::anyhow!("joiner output not found"))?;
+                .ok_or_else(|| SttError::InferenceError("joiner output not found".to_string()))?;
             let (logits_shape, logits_data) = logits.try_extract_tensor::<f32>()?;
 
             // Shape is [batch=1, vocab_size + num_durations, 1]
@@ -645,14 +775,78 @@ struct AudioData {
     sample_rate: u32,
 }
 
+/// Scale `samples` so their RMS reaches `target_rms`, returning the gain applied
+///
+/// The gain is limited so no sample exceeds `peak_ceiling` and to at most
+/// 30 dB. Silent clips are left unchanged.
+pub fn normalize_samples(samples: &mut [f32], target_rms: f32, peak_ceiling: f32) -> f32 {
+    if samples.is_empty() {
+        return 1.0;
+    }
+
+    let rms = (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+    let peak = samples.iter().fold(0.0f32, |max, s| max.max(s.abs()));
+    if rms < SILENCE_RMS {
+        return 1.0;
+    }
+
+    let gain = (target_rms / rms).min(peak_ceiling / peak).min(MAX_NORMALIZE_GAIN);
+    for sample in samples.iter_mut() {
+        *sample *= gain;
+    }
+    gain
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_normalize_samples() {
+        // A quiet tone is raised to the target RMS
+        let mut quiet: Vec<f32> = (0..1600).map(|i| 0.01 * (i as f32 * 0.1).sin()).collect();
+        let gain = normalize_samples(&mut quiet, 0.1, 0.95);
+        let rms = (quiet.iter().map(|s| s * s).sum::<f32>() / quiet.len() as f32).sqrt();
+        assert!(gain > 1.0);
+        assert!((rms - 0.1).abs() < 1e-3, "rms {}", rms);
+
+        // A single loud click caps the gain instead of clipping
+        let mut spiky = vec![0.001f32; 1600];
+        spiky[800] = 0.5;
+        normalize_samples(&mut spiky, 0.1, 0.95);
+        assert!(spiky.iter().all(|s| s.abs() <= 0.95 + 1e-6));
+
+        // Silence stays silent
+        let mut silence = vec![0.0f32; 1600];
+        assert_eq!(normalize_samples(&mut silence, 0.1, 0.95), 1.0);
+        assert!(silence.iter().all(|s| *s == 0.0));
+    }
+
     #[test]
     fn test_config_default() {
         let config = SttConfig::default();
-        assert!(config.use_gpu);
-        assert_eq!(config.num_threads, 4);
+        assert_eq!(config.providers, ExecutionProvider::DEFAULT_ORDER);
+        assert_eq!(config.gpu_device, 0);
+        assert_eq!(config.num_threads, DEFAULT_NUM_THREADS);
+        assert!(config.wants_gpu());
+        assert!(!config.cpu_only().wants_gpu());
+    }
+
+    #[test]
+    fn test_execution_provider_names() {
+        for provider in ExecutionProvider::DEFAULT_ORDER {
+            assert_eq!(provider.as_str().parse::<ExecutionProvider>(), Ok(provider));
+        }
+        assert_eq!("DirectML".parse::<ExecutionProvider>(), Ok(ExecutionProvider::DirectMl));
+        assert!("rocm".parse::<ExecutionProvider>().is_err());
+
+        let config: SttConfig = serde_json::from_str(
+            r#"{"model_dir": "models", "providers": ["directml", "cpu"], "language": null, "num_threads": 2}"#,
+        )
+        .unwrap();
+        assert_eq!(config.providers, [ExecutionProvider::DirectMl, ExecutionProvider::Cpu]);
+        assert_eq!(config.gpu_device, 0);
+        assert!(!config.normalize_audio);
+        assert_eq!(config.normalize_target_rms, DEFAULT_NORMALIZE_TARGET_RMS);
     }
 }
@@ -2,6 +2,9 @@
 
 use thiserror::Error;
 
+/// Result type of the speech-to-text crate
+pub type Result<T, E = SttError> = std::result::Result<T, E>;
+
 /// Errors that can occur during speech-to-text operations
 #[derive(Error, Debug)]
 pub enum SttError {
@@ -31,6 +34,14 @@ pub enum SttError {
     /// ONNX Runtime error
     #[error("ONNX Runtime error: {0}")]
     OrtError(String),
+
+    /// Model or ONNX Runtime download failed
+    #[error("download failed: {0}")]
+    DownloadError(String),
+
+    /// The STT worker thread is no longer running
+    #[error("STT worker thread has stopped")]
+    WorkerStopped,
 }
 
 impl From<ort::Error> for SttError {
@@ -0,0 +1,76 @@
+//! Language identification of transcribed text
+//!
+//! Parakeet TDT transcribes many European languages but does not report
+//! which one it heard. The language is guessed from the decoded text by
+//! counting common function words ("the", "und", "que", ...), which is cheap
+//! and reliable once a transcript has a sentence or two. Short or mixed text
+//! is left undetected rather than guessed.
+
+/// Function words per ISO 639-1 code
+const STOPWORDS: &[(&str, &[&str])] = &[
+    ("en", &["the", "and", "is", "are", "of", "to", "that", "it", "you", "was", "for", "with", "this", "have", "not", "we", "they", "what", "be", "on"]),
+    ("fr", &["le", "les", "et", "est", "un", "une", "des", "du", "que", "qui", "pas", "pour", "dans", "ce", "je", "vous", "nous", "sur", "avec", "mais"]),
+    ("de", &["der", "die", "das", "und", "ist", "nicht", "ich", "sie", "es", "ein", "eine", "zu", "mit", "den", "auf", "wir", "auch", "sich", "dass", "von"]),
+    ("es", &["el", "los", "las", "y", "es", "que", "en", "un", "una", "por", "para", "con", "no", "se", "lo", "del", "como", "pero", "muy", "está"]),
+    ("it", &["il", "gli", "e", "è", "che", "di", "un", "una", "per", "non", "con", "sono", "della", "come", "anche", "ma", "questo", "nel", "alla", "si"]),
+    ("pt", &["o", "os", "as", "e", "é", "que", "um", "uma", "para", "com", "não", "do", "da", "em", "se", "mais", "como", "mas", "isso", "você"]),
+    ("nl", &["de", "het", "een", "en", "is", "van", "niet", "dat", "ik", "je", "we", "op", "met", "voor", "zijn", "maar", "ook", "er", "dit", "wat"]),
+];
+
+/// Function words needed before a language is reported
+const MIN_MATCHES: usize = 3;
+
+/// How much more often the best language must match than the runner-up
+const MIN_MARGIN: f32 = 1.5;
+
+/// Guess the language of `text`, returning its ISO 639-1 code
+///
+/// Returns `None` when the text is too short or too ambiguous to tell.
+pub fn detect_language(text: &str) -> Option<&'static str> {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+
+    let mut scores: Vec<(&'static str, usize)> = STOPWORDS
+        .iter()
+        .map(|(code, stopwords)| {
+            let matches = words.iter().filter(|w| stopwords.contains(&w.as_str())).count();
+            (*code, matches)
+        })
+        .collect();
+    scores.sort_by_key(|s| std::cmp::Reverse(s.1));
+
+    let (code, best) = scores[0];
+    let runner_up = scores.get(1).map_or(0, |s| s.1);
+    (best >= MIN_MATCHES && best as f32 >= runner_up as f32 * MIN_MARGIN).then_some(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_common_languages() {
+        let samples = [
+            ("en", "So the plan is that we ship it on Friday and you review the draft with them."),
+            ("fr", "Je pense que le budget est trop serré pour nous, mais on peut en parler avec vous."),
+            ("de", "Ich glaube, dass wir das nicht mit den Kunden auf die Woche verschieben können."),
+            ("es", "Creo que el presupuesto es muy bajo para los clientes, pero lo vemos con ellos."),
+            ("it", "Penso che il progetto non sia pronto per la consegna, ma anche questo è normale."),
+            ("pt", "Eu acho que o projeto não está pronto para o cliente, mas isso é normal com você."),
+            ("nl", "Ik denk dat het project niet klaar is voor de klant, maar we kijken er nog naar."),
+        ];
+        for (expected, text) in samples {
+            assert_eq!(detect_language(text), Some(expected), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_short_text_is_undetected() {
+        assert_eq!(detect_language(""), None);
+        assert_eq!(detect_language("okay thanks"), None);
+        assert_eq!(detect_language("Kubernetes Grafana Prometheus"), None);
+    }
+}
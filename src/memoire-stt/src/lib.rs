@@ -1,27 +1,66 @@
 //! memoire-stt - Speech-to-Text for Memoire
 //!
 //! Provides speech-to-text transcription using Parakeet TDT via ONNX Runtime.
-//! Supports GPU acceleration via CUDA with CPU fallback.
+//! Supports GPU acceleration via TensorRT, CUDA or DirectML with CPU fallback.
+//!
+//! For one-off transcription use [`transcribe_wav`], which takes care of ONNX
+//! Runtime setup. Use [`SttEngine`] directly to keep a loaded model around and
+//! transcribe many files or streamed samples, or [`SttEngine::spawn_worker`]
+//! to do so from async code without blocking the runtime. To catch up on
+//! many files, [`transcribe_batch`] runs several engines concurrently.
+//!
+//! Fallible functions return [`SttError`], so callers can tell a missing model
+//! apart from unreadable audio or an inference failure.
+//!
+//! ```no_run
+//! use memoire_stt::{transcribe_wav, SttConfig};
+//!
+//! let model_dir = memoire_stt::default_model_dir();
+//! let result = transcribe_wav(&model_dir, "meeting.wav", SttConfig::default())?;
+//! println!("{}", result.text);
+//! # Ok::<(), memoire_stt::SttError>(())
+//! ```
 
+mod batch;
 mod download;
 mod engine;
 mod error;
+mod langid;
 mod mel;
+mod model_config;
+mod resample;
 mod tokenizer;
+mod worker;
 
+pub use batch::transcribe_batch;
 pub use download::{ModelDownloader, ORT_DLL_NAME};
-pub use engine::{SttEngine, SttConfig, TranscriptionResult, TranscriptionSegment};
-pub use mel::{MelSpectrogram, ENCODER_FRAME_DURATION_SEC, SAMPLE_RATE};
+pub use engine::{
+    normalize_samples, ExecutionProvider, SttEngine, SttConfig, TranscriptionResult, TranscriptionSegment,
+    DEFAULT_NORMALIZE_PEAK_CEILING, DEFAULT_NORMALIZE_TARGET_RMS, DEFAULT_NUM_THREADS,
+};
+pub use langid::detect_language;
+pub use mel::{MelConfig, MelNormalization, MelSpectrogram, ENCODER_FRAME_DURATION_SEC, SAMPLE_RATE};
+pub use model_config::{DecoderNames, ModelConfig, MODEL_CONFIG_FILE};
+pub use resample::{resample_to_model_rate, ResampleQuality, NARROWBAND_MAX_RATE};
 pub use tokenizer::Tokenizer;
-pub use error::SttError;
+pub use error::{Result, SttError};
+pub use worker::SttWorker;
 
 use std::path::Path;
 
 /// Get the default model directory
+///
+/// `models` inside `MEMOIRE_DATA_DIR` when set, otherwise inside the platform
+/// data directory (%LOCALAPPDATA%\Memoire on Windows).
 pub fn default_model_dir() -> std::path::PathBuf {
-    dirs::data_local_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join("Memoire")
+    std::env::var_os("MEMOIRE_DATA_DIR")
+        .filter(|dir| !dir.is_empty())
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|| {
+            dirs::data_local_dir()
+                .unwrap_or_else(|| std::path::PathBuf::from("."))
+                .join("Memoire")
+        })
         .join("models")
 }
 
@@ -32,14 +71,14 @@ pub fn default_model_dir() -> std::path::PathBuf {
 /// have an older version (e.g., 1.17.1 from screenpipe or other applications).
 ///
 /// Returns `Ok(())` if the DLL path was set, or an error if the DLL doesn't exist.
-pub fn configure_onnx_runtime(model_dir: &Path) -> anyhow::Result<()> {
+pub fn configure_onnx_runtime(model_dir: &Path) -> Result<()> {
     let dll_path = model_dir.join(ORT_DLL_NAME);
 
     if !dll_path.exists() {
-        return Err(anyhow::anyhow!(
-            "ONNX Runtime DLL not found at {:?}. Run 'memoire download-models' first.",
-            dll_path
-        ));
+        return Err(SttError::ModelNotFound {
+            path: dll_path.display().to_string(),
+            message: "ONNX Runtime DLL missing, run 'memoire download-models' first".to_string(),
+        });
     }
 
     // Set the ORT_DYLIB_PATH environment variable before ort initializes
@@ -49,6 +88,51 @@ pub fn configure_onnx_runtime(model_dir: &Path) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Length of a WAV file in seconds, read from its header
+pub fn wav_duration_secs(path: impl AsRef<Path>) -> Result<f64> {
+    memoire_capture::wav::read_wav(path.as_ref())
+        .map(|wav| wav.duration_secs())
+        .map_err(|e| SttError::AudioError(format!("{:#}", e)))
+}
+
+/// Transcribe a WAV file in one call.
+///
+/// This is the library entry point for transcription: it points ONNX Runtime
+/// at the bundled DLL in `model_dir` when present (falling back to the system
+/// installation otherwise), loads the Parakeet model from `model_dir` and
+/// transcribes `wav_path`. `model_dir` overrides `config.model_dir`.
+///
+/// Loading the model is the expensive part; to transcribe many files, create
+/// an [`SttEngine`] once and call [`SttEngine::transcribe_file`] instead.
+pub fn transcribe_wav(
+    model_dir: impl AsRef<Path>,
+    wav_path: impl AsRef<Path>,
+    config: SttConfig,
+) -> Result<TranscriptionResult> {
+    let model_dir = model_dir.as_ref();
+
+    if has_bundled_onnx_runtime(model_dir) {
+        configure_onnx_runtime(model_dir)?;
+    } else {
+        tracing::warn!("bundled ONNX Runtime not found in {:?}, using system DLL", model_dir);
+    }
+
+    let mut engine = SttEngine::new(SttConfig {
+        model_dir: model_dir.to_path_buf(),
+        ..config
+    })?;
+
+    // The engine tolerates missing models for the indexer; a direct call should not
+    if !engine.is_model_loaded() {
+        return Err(SttError::ModelNotFound {
+            path: model_dir.display().to_string(),
+            message: "run 'memoire download-models' first".to_string(),
+        });
+    }
+
+    engine.transcribe_file(wav_path)
+}
+
 /// Check if the bundled ONNX Runtime DLL exists
 pub fn has_bundled_onnx_runtime(model_dir: &Path) -> bool {
     model_dir.join(ORT_DLL_NAME).exists()
@@ -1,7 +1,8 @@
-//! Mel spectrogram feature extraction for Parakeet TDT models
+//! Mel spectrogram feature extraction
 //!
-//! Computes 80-bin or 128-bin mel filterbank features from audio samples.
-//! Uses the same parameters as sherpa-onnx/NeMo:
+//! Computes log-mel filterbank features from audio samples. The STFT and
+//! filterbank parameters come from a [`MelConfig`]; [`MelConfig::parakeet`]
+//! matches sherpa-onnx/NeMo for the Parakeet TDT models:
 //! - Sample rate: 16kHz
 //! - Window: 25ms (400 samples)
 //! - Hop: 10ms (160 samples)
@@ -9,6 +10,7 @@
 //! - Mel bins: 80 (default) or 128 (parakeet-tdt-0.6b-v2)
 //! - Low freq: 0 Hz
 //! - High freq: 8000 Hz (Nyquist for 16kHz)
+//! - Per-feature normalization
 
 use std::f32::consts::PI;
 use tracing::debug;
@@ -16,17 +18,17 @@ use tracing::debug;
 /// Default sample rate expected by the model
 pub const SAMPLE_RATE: u32 = 16000;
 
-/// Window size in samples (25ms at 16kHz)
-const WINDOW_SIZE: usize = 400;
+/// Parakeet window size in samples (25ms at 16kHz)
+const PARAKEET_WINDOW_SIZE: usize = 400;
 
-/// Hop size in samples (10ms at 16kHz)
-const HOP_SIZE: usize = 160;
+/// Parakeet hop size in samples (10ms at 16kHz)
+const PARAKEET_HOP_SIZE: usize = 160;
 
-/// FFT size (next power of 2 >= window size)
-const FFT_SIZE: usize = 512;
+/// Parakeet FFT size (next power of 2 >= window size)
+const PARAKEET_FFT_SIZE: usize = 512;
 
-/// Frame duration in seconds (based on hop size)
-pub const FRAME_DURATION_SEC: f64 = HOP_SIZE as f64 / SAMPLE_RATE as f64; // 0.01s = 10ms
+/// Frame duration in seconds (based on the Parakeet hop size)
+pub const FRAME_DURATION_SEC: f64 = PARAKEET_HOP_SIZE as f64 / SAMPLE_RATE as f64; // 0.01s = 10ms
 
 /// Subsampling factor of the encoder (frames to encoder output ratio)
 /// For Parakeet TDT, this is typically 8
@@ -35,60 +37,144 @@ pub const SUBSAMPLING_FACTOR: usize = 8;
 /// Duration per encoder output frame in seconds
 pub const ENCODER_FRAME_DURATION_SEC: f64 = FRAME_DURATION_SEC * SUBSAMPLING_FACTOR as f64; // 0.08s = 80ms
 
+/// How log-mel features are scaled after extraction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MelNormalization {
+    /// Natural log of the magnitude filterbank energies, unscaled
+    None,
+    /// Natural log, then zero mean and unit variance per mel bin over the utterance (NeMo)
+    PerFeature,
+    /// log10 of the power filterbank energies, clamped to 8 below the
+    /// maximum and mapped with (x + 4) / 4 (Whisper)
+    Whisper,
+}
+
+/// STFT and filterbank parameters of a [`MelSpectrogram`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MelConfig {
+    /// Sample rate of the input audio in Hz
+    pub sample_rate: u32,
+    /// Analysis window length in samples
+    pub window_size: usize,
+    /// Samples between the starts of consecutive frames
+    pub hop_size: usize,
+    /// FFT length; windows are zero-padded up to it (at least `window_size`)
+    pub fft_size: usize,
+    /// Number of mel filter banks
+    pub num_mels: usize,
+    /// Lowest filterbank frequency in Hz
+    pub fmin: f32,
+    /// Highest filterbank frequency in Hz (capped at Nyquist)
+    pub fmax: f32,
+    pub normalization: MelNormalization,
+}
+
+impl MelConfig {
+    /// Parakeet TDT / NeMo features: 16kHz, 25ms window, 10ms hop, 512-point
+    /// FFT, 80 mels over 0-8000 Hz, per-feature normalization
+    ///
+    /// parakeet-tdt-0.6b-v2 needs 128 mels; set `num_mels` accordingly.
+    pub fn parakeet() -> Self {
+        Self {
+            sample_rate: SAMPLE_RATE,
+            window_size: PARAKEET_WINDOW_SIZE,
+            hop_size: PARAKEET_HOP_SIZE,
+            fft_size: PARAKEET_FFT_SIZE,
+            num_mels: 80,
+            fmin: 0.0,
+            fmax: SAMPLE_RATE as f32 / 2.0,
+            normalization: MelNormalization::PerFeature,
+        }
+    }
+
+    /// Duration of one feature frame in seconds
+    pub fn frame_duration_sec(&self) -> f64 {
+        self.hop_size as f64 / self.sample_rate as f64
+    }
+
+    /// Clamp the parameters into a usable range
+    fn sanitized(mut self) -> Self {
+        self.sample_rate = self.sample_rate.max(1);
+        self.window_size = self.window_size.max(2);
+        self.hop_size = self.hop_size.max(1);
+        self.fft_size = self.fft_size.max(self.window_size);
+        self.num_mels = self.num_mels.max(1);
+        self.fmax = self.fmax.min(self.sample_rate as f32 / 2.0);
+        self.fmin = self.fmin.clamp(0.0, self.fmax);
+        self
+    }
+}
+
+impl Default for MelConfig {
+    fn default() -> Self {
+        Self::parakeet()
+    }
+}
+
 /// Mel spectrogram feature extractor
 pub struct MelSpectrogram {
-    /// Number of mel filter banks
-    num_mels: usize,
+    config: MelConfig,
     /// Precomputed mel filterbank matrix [num_mels, fft_size/2 + 1]
     mel_filters: Vec<Vec<f32>>,
     /// Hann window for STFT
     window: Vec<f32>,
-    /// Whether to normalize features
-    normalize: bool,
 }
 
 impl MelSpectrogram {
-    /// Create a new mel spectrogram extractor
+    /// Create a mel spectrogram extractor
     ///
-    /// # Arguments
-    /// * `num_mels` - Number of mel filter banks (80 or 128)
-    /// * `normalize` - Whether to normalize features per utterance
-    pub fn new(num_mels: usize, normalize: bool) -> Self {
-        let mel_filters = create_mel_filterbank(num_mels, FFT_SIZE, SAMPLE_RATE, 0.0, 8000.0);
-        let window = create_hann_window(WINDOW_SIZE);
+    /// Out-of-range parameters are clamped: the FFT is at least as long as the
+    /// window, the hop at least one sample and `fmax` at most Nyquist.
+    pub fn new(config: MelConfig) -> Self {
+        let config = config.sanitized();
+        let mel_filters = create_mel_filterbank(
+            config.num_mels,
+            config.fft_size,
+            config.sample_rate,
+            config.fmin,
+            config.fmax,
+        );
+        let window = create_hann_window(config.window_size);
 
         debug!(
-            "created mel spectrogram extractor: num_mels={}, fft_size={}, window={}, hop={}",
-            num_mels, FFT_SIZE, WINDOW_SIZE, HOP_SIZE
+            "created mel spectrogram extractor: num_mels={}, fft_size={}, window={}, hop={}, {}-{} Hz, {:?}",
+            config.num_mels, config.fft_size, config.window_size, config.hop_size,
+            config.fmin, config.fmax, config.normalization
         );
 
         Self {
-            num_mels,
+            config,
             mel_filters,
             window,
-            normalize,
         }
     }
 
+    /// Parameters in use (after clamping)
+    pub fn config(&self) -> &MelConfig {
+        &self.config
+    }
+
     /// Extract mel spectrogram features from audio samples
     ///
     /// # Arguments
-    /// * `samples` - Audio samples at 16kHz, normalized to [-1, 1]
+    /// * `samples` - Audio samples at the configured sample rate, normalized to [-1, 1]
     ///
     /// # Returns
     /// Feature matrix of shape [num_frames, num_mels]
     pub fn extract(&self, samples: &[f32]) -> Vec<Vec<f32>> {
-        if samples.len() < WINDOW_SIZE {
+        let window_size = self.config.window_size;
+        let hop_size = self.config.hop_size;
+        if samples.len() < window_size {
             return Vec::new();
         }
 
-        let num_frames = (samples.len() - WINDOW_SIZE) / HOP_SIZE + 1;
+        let num_frames = (samples.len() - window_size) / hop_size + 1;
         let mut features = Vec::with_capacity(num_frames);
 
         // Compute STFT and mel features for each frame
         for frame_idx in 0..num_frames {
-            let start = frame_idx * HOP_SIZE;
-            let frame = &samples[start..start + WINDOW_SIZE];
+            let start = frame_idx * hop_size;
+            let frame = &samples[start..start + window_size];
 
             // Apply window and compute FFT magnitude spectrum
             let spectrum = self.compute_spectrum(frame);
@@ -99,9 +185,10 @@ impl MelSpectrogram {
             features.push(mel_frame);
         }
 
-        // Normalize if requested
-        if self.normalize && !features.is_empty() {
-            self.normalize_features(&mut features);
+        match self.config.normalization {
+            MelNormalization::None => {}
+            MelNormalization::PerFeature => self.normalize_features(&mut features),
+            MelNormalization::Whisper => scale_whisper(&mut features),
         }
 
         features
@@ -116,7 +203,7 @@ impl MelSpectrogram {
 
         let flat: Vec<f32> = features.into_iter().flatten().collect();
 
-        (flat, num_frames, self.num_mels)
+        (flat, num_frames, self.config.num_mels)
     }
 
     /// Compute magnitude spectrum using real-valued FFT
@@ -129,7 +216,7 @@ impl MelSpectrogram {
             .collect();
 
         // Zero-pad to FFT size
-        windowed.resize(FFT_SIZE, 0.0);
+        windowed.resize(self.config.fft_size, 0.0);
 
         // Compute FFT magnitude spectrum
         // Using a simple DFT implementation (for correctness over speed)
@@ -141,17 +228,24 @@ impl MelSpectrogram {
 
     /// Apply mel filterbank to magnitude spectrum
     fn apply_mel_filterbank(&self, spectrum: &[f32]) -> Vec<f32> {
-        let mut mel_energies = vec![0.0f32; self.num_mels];
+        let whisper = self.config.normalization == MelNormalization::Whisper;
+        let mut mel_energies = vec![0.0f32; self.config.num_mels];
 
         for (mel_idx, filter) in self.mel_filters.iter().enumerate() {
             let mut energy = 0.0f32;
             for (bin_idx, &weight) in filter.iter().enumerate() {
                 if bin_idx < spectrum.len() && weight > 0.0 {
-                    energy += spectrum[bin_idx] * weight;
+                    // Whisper filters the power spectrum
+                    let value = if whisper { spectrum[bin_idx] * spectrum[bin_idx] } else { spectrum[bin_idx] };
+                    energy += value * weight;
                 }
             }
             // Apply log with floor to avoid -inf
-            mel_energies[mel_idx] = (energy.max(1e-10)).ln();
+            mel_energies[mel_idx] = if whisper {
+                energy.max(1e-10).log10()
+            } else {
+                energy.max(1e-10).ln()
+            };
         }
 
         mel_energies
@@ -182,6 +276,20 @@ impl MelSpectrogram {
     }
 }
 
+/// Whisper scaling: limit the dynamic range to 8 (log10) below the loudest
+/// value, then map to roughly [-1, 1]
+fn scale_whisper(features: &mut [Vec<f32>]) {
+    let max = features
+        .iter()
+        .flatten()
+        .copied()
+        .fold(f32::NEG_INFINITY, f32::max);
+
+    for value in features.iter_mut().flatten() {
+        *value = (value.max(max - 8.0) + 4.0) / 4.0;
+    }
+}
+
 /// Create a Hann window of specified length
 fn create_hann_window(length: usize) -> Vec<f32> {
     (0..length)
@@ -301,7 +409,7 @@ mod tests {
 
     #[test]
     fn test_mel_spectrogram_basic() {
-        let mel = MelSpectrogram::new(80, true);
+        let mel = MelSpectrogram::new(MelConfig::parakeet());
 
         // Create a simple test signal (1 second of sine wave)
         let samples: Vec<f32> = (0..16000)
@@ -328,16 +436,73 @@ mod tests {
 
     #[test]
     fn test_empty_input() {
-        let mel = MelSpectrogram::new(80, true);
+        let mel = MelSpectrogram::new(MelConfig::parakeet());
         let features = mel.extract(&[]);
         assert!(features.is_empty());
     }
 
     #[test]
     fn test_short_input() {
-        let mel = MelSpectrogram::new(80, true);
+        let mel = MelSpectrogram::new(MelConfig::parakeet());
         // Input shorter than window size
         let features = mel.extract(&[0.0; 100]);
         assert!(features.is_empty());
     }
+
+    #[test]
+    fn test_custom_config() {
+        // Whisper-style parameters: 400-point FFT, 80 mels, log10 scaling
+        let config = MelConfig {
+            window_size: 400,
+            hop_size: 160,
+            fft_size: 400,
+            num_mels: 80,
+            normalization: MelNormalization::Whisper,
+            ..MelConfig::parakeet()
+        };
+        let mel = MelSpectrogram::new(config.clone());
+        assert_eq!(mel.config(), &config);
+
+        let samples: Vec<f32> = (0..8000)
+            .map(|i| 0.5 * (2.0 * PI * 1000.0 * i as f32 / 16000.0).sin())
+            .collect();
+        let features = mel.extract(&samples);
+        assert_eq!(features.len(), (8000 - 400) / 160 + 1);
+        assert_eq!(features[0].len(), 80);
+
+        // Whisper scaling keeps values within a dynamic range of 2 after mapping
+        let max = features.iter().flatten().copied().fold(f32::NEG_INFINITY, f32::max);
+        let min = features.iter().flatten().copied().fold(f32::INFINITY, f32::min);
+        assert!(max - min <= 2.0 + 1e-4, "range {} to {}", min, max);
+    }
+
+    #[test]
+    fn test_hop_and_window_change_frame_count() {
+        let config = MelConfig {
+            window_size: 800,
+            hop_size: 320,
+            fft_size: 1024,
+            sample_rate: 32000,
+            fmax: 16000.0,
+            ..MelConfig::parakeet()
+        };
+        assert!((config.frame_duration_sec() - 0.01).abs() < 1e-9);
+
+        let mel = MelSpectrogram::new(config);
+        let features = mel.extract(&vec![0.1; 32000]);
+        assert_eq!(features.len(), (32000 - 800) / 320 + 1);
+    }
+
+    #[test]
+    fn test_invalid_config_is_clamped() {
+        let mel = MelSpectrogram::new(MelConfig {
+            fft_size: 256,
+            hop_size: 0,
+            fmax: 20000.0,
+            ..MelConfig::parakeet()
+        });
+        assert_eq!(mel.config().fft_size, 400);
+        assert_eq!(mel.config().hop_size, 1);
+        assert_eq!(mel.config().fmax, 8000.0);
+    }
 }
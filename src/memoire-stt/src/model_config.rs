@@ -0,0 +1,181 @@
+//! Optional per-model settings
+//!
+//! ONNX tensor names depend on how a model was exported: the sherpa-onnx
+//! Parakeet TDT export names its decoder state tensors `states.1`,
+//! `onnx::Slice_3`, `states` and `162`. A re-exported model can describe its
+//! own names in a `model.json` next to the ONNX files:
+//!
+//! ```json
+//! { "decoder": { "h_in": "h", "c_in": "c", "h_out": "h_next", "c_out": "c_next" } }
+//! ```
+//!
+//! Fields left out keep their sherpa-onnx defaults, and names missing from
+//! the loaded session fall back to the tensor at the same position.
+
+use serde::Deserialize;
+use std::path::Path;
+use tracing::{info, warn};
+
+use crate::error::{Result, SttError};
+
+/// File in the model directory holding a [`ModelConfig`]
+pub const MODEL_CONFIG_FILE: &str = "model.json";
+
+/// Settings read from [`MODEL_CONFIG_FILE`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct ModelConfig {
+    /// Decoder tensor names
+    pub decoder: DecoderNames,
+}
+
+impl ModelConfig {
+    /// Load `model.json` from `model_dir`, or the defaults when there is none
+    pub fn load(model_dir: &Path) -> Result<Self> {
+        let path = model_dir.join(MODEL_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(&path)?;
+        let config = serde_json::from_str(&content)
+            .map_err(|e| SttError::ModelLoadError(format!("{}: {}", path.display(), e)))?;
+        info!("loaded model config from {:?}", path);
+        Ok(config)
+    }
+}
+
+/// Names of the decoder (prediction network) inputs and outputs
+///
+/// Declared in the export's input and output order, which is what names
+/// missing from the session are resolved by.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct DecoderNames {
+    /// Input: previous token, `[batch, 1]`
+    pub targets: String,
+    /// Input: target lengths, `[batch]`
+    pub target_length: String,
+    /// Input: LSTM hidden state, `[layers, batch, hidden]`
+    pub h_in: String,
+    /// Input: LSTM cell state, `[layers, batch, hidden]`
+    pub c_in: String,
+    /// Output: prediction network output, `[batch, hidden, 1]`
+    pub output: String,
+    /// Output: target lengths (unused)
+    pub output_length: String,
+    /// Output: updated hidden state
+    pub h_out: String,
+    /// Output: updated cell state
+    pub c_out: String,
+}
+
+impl Default for DecoderNames {
+    fn default() -> Self {
+        // sherpa-onnx export of Parakeet TDT 0.6b
+        Self {
+            targets: "targets".to_string(),
+            target_length: "target_length".to_string(),
+            h_in: "states.1".to_string(),
+            c_in: "onnx::Slice_3".to_string(),
+            output: "outputs".to_string(),
+            output_length: "prednet_lengths".to_string(),
+            h_out: "states".to_string(),
+            c_out: "162".to_string(),
+        }
+    }
+}
+
+impl DecoderNames {
+    /// Match the names against a loaded decoder's inputs and outputs
+    ///
+    /// A name the session doesn't have is replaced by the session's tensor at
+    /// that name's position. Fails when an input or the main output can't be
+    /// found either way; missing state outputs are left as they are, so the
+    /// states simply aren't carried over.
+    pub fn resolve(&self, inputs: &[&str], outputs: &[&str]) -> Result<Self> {
+        let required = |name: &str, available: &[&str], position: usize, kind: &str| {
+            pick(name, available, position).ok_or_else(|| {
+                SttError::ModelLoadError(format!(
+                    "decoder has no {} '{}' (has {:?}); set its name in {}",
+                    kind, name, available, MODEL_CONFIG_FILE
+                ))
+            })
+        };
+        let optional = |name: &str, position: usize| {
+            pick(name, outputs, position).unwrap_or_else(|| name.to_string())
+        };
+
+        Ok(Self {
+            targets: required(&self.targets, inputs, 0, "input")?,
+            target_length: required(&self.target_length, inputs, 1, "input")?,
+            h_in: required(&self.h_in, inputs, 2, "input")?,
+            c_in: required(&self.c_in, inputs, 3, "input")?,
+            output: required(&self.output, outputs, 0, "output")?,
+            output_length: optional(&self.output_length, 1),
+            h_out: optional(&self.h_out, 2),
+            c_out: optional(&self.c_out, 3),
+        })
+    }
+}
+
+/// `name` if `available` has it, otherwise the tensor at `position`
+fn pick(name: &str, available: &[&str], position: usize) -> Option<String> {
+    if available.contains(&name) {
+        return Some(name.to_string());
+    }
+    let fallback = available.get(position)?;
+    warn!("decoder tensor '{}' not found, using '{}' at position {}", name, fallback, position);
+    Some(fallback.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHERPA_INPUTS: [&str; 4] = ["targets", "target_length", "states.1", "onnx::Slice_3"];
+    const SHERPA_OUTPUTS: [&str; 4] = ["outputs", "prednet_lengths", "states", "162"];
+
+    #[test]
+    fn test_defaults_match_sherpa_export() {
+        let names = DecoderNames::default();
+        assert_eq!(names.resolve(&SHERPA_INPUTS, &SHERPA_OUTPUTS).unwrap(), names);
+    }
+
+    #[test]
+    fn test_partial_config_keeps_defaults() {
+        let config: ModelConfig =
+            serde_json::from_str(r#"{"decoder": {"h_in": "h", "c_in": "c"}}"#).unwrap();
+        assert_eq!(config.decoder.h_in, "h");
+        assert_eq!(config.decoder.c_in, "c");
+        assert_eq!(config.decoder.targets, "targets");
+        assert_eq!(config.decoder.c_out, "162");
+
+        let empty: ModelConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(empty, ModelConfig::default());
+    }
+
+    #[test]
+    fn test_resolve_falls_back_to_position() {
+        let inputs = ["targets", "target_length", "h.1", "c.1"];
+        let outputs = ["outputs", "lengths", "h", "c"];
+        let names = DecoderNames::default().resolve(&inputs, &outputs).unwrap();
+        assert_eq!(names.h_in, "h.1");
+        assert_eq!(names.c_in, "c.1");
+        assert_eq!(names.h_out, "h");
+        assert_eq!(names.c_out, "c");
+        assert_eq!(names.output, "outputs");
+    }
+
+    #[test]
+    fn test_resolve_fails_without_inputs() {
+        let err = DecoderNames::default()
+            .resolve(&["targets", "target_length"], &SHERPA_OUTPUTS)
+            .unwrap_err();
+        assert!(err.to_string().contains("states.1"), "{}", err);
+
+        // Missing state outputs aren't fatal
+        let names = DecoderNames::default().resolve(&SHERPA_INPUTS, &["outputs"]).unwrap();
+        assert_eq!(names.c_out, "162");
+    }
+}
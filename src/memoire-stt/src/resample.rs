@@ -0,0 +1,250 @@
+//! Resampling of input audio to the model's 16kHz
+//!
+//! Clips are streamed through one resampler in fixed blocks, so the filter
+//! state carries across block boundaries instead of restarting (which is what
+//! produces clicks). The resampler's output delay is trimmed and the result is
+//! cut to the exact length of the clip at 16kHz, keeping transcription
+//! timestamps aligned with the source.
+//!
+//! Narrowband sources (8kHz telephony and below) always use a windowed sinc
+//! interpolator whose cutoff sits just under the source's Nyquist frequency,
+//! so upsampling adds no images above 4kHz for the mel features to pick up.
+
+use rubato::{
+    FftFixedIn, Resampler, SincFixedIn, SincInterpolationParameters, SincInterpolationType, WindowFunction,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use tracing::debug;
+
+use crate::error::{Result, SttError};
+use crate::mel::SAMPLE_RATE;
+
+/// Highest source rate treated as narrowband (telephony) audio
+pub const NARROWBAND_MAX_RATE: u32 = 8000;
+
+/// Input frames fed to the resampler per block
+const RESAMPLE_CHUNK_SIZE: usize = 1024;
+
+/// Blocks of silence fed after the clip before giving up on further output
+const MAX_FLUSH_BLOCKS: usize = 8;
+
+/// Resampler used to convert audio to 16kHz
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResampleQuality {
+    /// FFT resampler (fast, fine for 44.1kHz/48kHz microphone audio)
+    #[default]
+    Fast,
+    /// Windowed sinc interpolation (slower, sharper anti-aliasing filter)
+    High,
+}
+
+impl ResampleQuality {
+    /// All qualities, fastest first
+    pub const ALL: [ResampleQuality; 2] = [ResampleQuality::Fast, ResampleQuality::High];
+
+    /// Name used in configuration files and on the command line
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ResampleQuality::Fast => "fast",
+            ResampleQuality::High => "high",
+        }
+    }
+}
+
+impl fmt::Display for ResampleQuality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ResampleQuality {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|q| q.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("unknown resample quality '{}' (expected fast or high)", s))
+    }
+}
+
+/// Resample mono audio from `sample_rate` to the model's 16kHz
+///
+/// The output holds exactly `ceil(len * 16000 / sample_rate)` samples and is
+/// aligned with the input (no leading delay).
+pub fn resample_to_model_rate(samples: &[f32], sample_rate: u32, quality: ResampleQuality) -> Result<Vec<f32>> {
+    if sample_rate == 0 {
+        return Err(SttError::AudioError("sample rate is 0".to_string()));
+    }
+    if sample_rate == SAMPLE_RATE || samples.is_empty() {
+        return Ok(samples.to_vec());
+    }
+
+    let expected = (samples.len() as u64 * SAMPLE_RATE as u64).div_ceil(sample_rate as u64) as usize;
+
+    if sample_rate <= NARROWBAND_MAX_RATE || quality == ResampleQuality::High {
+        debug!("resampling from {} Hz to {} Hz (sinc)", sample_rate, SAMPLE_RATE);
+        let parameters = SincInterpolationParameters {
+            sinc_len: 256,
+            f_cutoff: 0.95,
+            interpolation: SincInterpolationType::Cubic,
+            oversampling_factor: 256,
+            window: WindowFunction::BlackmanHarris2,
+        };
+        let ratio = SAMPLE_RATE as f64 / sample_rate as f64;
+        let resampler = SincFixedIn::<f32>::new(
+            ratio,
+            1.0,
+            parameters,
+            RESAMPLE_CHUNK_SIZE,
+            1, // mono
+        )
+        .map_err(|e| SttError::AudioError(format!("failed to create resampler: {}", e)))?;
+        // The sinc resampler starts interpolating half a filter before the
+        // first sample, so rather than the delay it reports, its output only
+        // lags by `ratio - 1` samples
+        let delay = (ratio - 1.0).round().max(0.0) as usize;
+        stream_through(resampler, samples, expected, delay)
+    } else {
+        debug!("resampling from {} Hz to {} Hz (fft)", sample_rate, SAMPLE_RATE);
+        let resampler = FftFixedIn::<f32>::new(
+            sample_rate as usize,
+            SAMPLE_RATE as usize,
+            RESAMPLE_CHUNK_SIZE,
+            1, // sub chunks
+            1, // mono
+        )
+        .map_err(|e| SttError::AudioError(format!("failed to create resampler: {}", e)))?;
+        let delay = resampler.output_delay();
+        stream_through(resampler, samples, expected, delay)
+    }
+}
+
+/// Feed `samples` block by block (padding the end with silence), then drop
+/// the first `delay` output samples and cut the output to `expected` samples
+///
+/// The FFT resampler buffers internally and returns nothing for some blocks,
+/// so an empty block only ends the loop once the clip has been fed.
+fn stream_through<R: Resampler<f32>>(
+    mut resampler: R,
+    samples: &[f32],
+    expected: usize,
+    delay: usize,
+) -> Result<Vec<f32>> {
+    let mut output = Vec::with_capacity(expected + delay + RESAMPLE_CHUNK_SIZE);
+    let mut block = Vec::with_capacity(RESAMPLE_CHUNK_SIZE);
+    let mut position = 0;
+    let mut flush_blocks = 0;
+
+    while output.len() < expected + delay && flush_blocks <= MAX_FLUSH_BLOCKS {
+        if position == samples.len() {
+            flush_blocks += 1;
+        }
+        let needed = resampler.input_frames_next();
+        block.clear();
+        let end = (position + needed).min(samples.len());
+        block.extend_from_slice(&samples[position..end]);
+        block.resize(needed, 0.0);
+        position = end;
+
+        let produced = resampler
+            .process(&[&block[..]], None)
+            .map_err(|e| SttError::AudioError(format!("resampling failed: {}", e)))?;
+        if let Some(channel) = produced.into_iter().next() {
+            output.extend(channel);
+        }
+    }
+
+    output.drain(..delay.min(output.len()));
+    output.resize(expected, 0.0);
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::PI;
+
+    fn sine(rate: u32, frequency: f32, seconds: f32) -> Vec<f32> {
+        let len = (rate as f32 * seconds) as usize;
+        (0..len).map(|i| 0.5 * (2.0 * PI * frequency * i as f32 / rate as f32).sin()).collect()
+    }
+
+    /// Largest deviation from the ideal tone at 16kHz, and largest jump
+    /// between neighbouring samples, away from the clip edges
+    fn tone_errors(resampled: &[f32], frequency: f32) -> (f32, f32) {
+        let ideal = sine(SAMPLE_RATE, frequency, resampled.len() as f32 / SAMPLE_RATE as f32);
+        let interior = 400..resampled.len() - 400;
+        let deviation = interior
+            .clone()
+            .map(|i| (resampled[i] - ideal[i]).abs())
+            .fold(0.0, f32::max);
+        let jump = interior
+            .map(|i| (resampled[i] - resampled[i - 1]).abs())
+            .fold(0.0, f32::max);
+        (deviation, jump)
+    }
+
+    #[test]
+    fn test_resample_common_rates() {
+        // 440 Hz moves at most 0.5 * 2π * 440 / 16000 ≈ 0.086 per output sample
+        let max_jump = 0.5 * 2.0 * PI * 440.0 / SAMPLE_RATE as f32 * 1.1;
+
+        for rate in [8000, 44100, 48000] {
+            for quality in ResampleQuality::ALL {
+                let input = sine(rate, 440.0, 1.5);
+                let output = resample_to_model_rate(&input, rate, quality).unwrap();
+
+                let expected = (input.len() as u64 * SAMPLE_RATE as u64).div_ceil(rate as u64) as usize;
+                assert_eq!(output.len(), expected, "{} Hz {}", rate, quality);
+
+                let (deviation, jump) = tone_errors(&output, 440.0);
+                // Allows for a sub-sample delay the resampler can't report
+                assert!(deviation < 0.06, "{} Hz {}: deviation {}", rate, quality, deviation);
+                assert!(jump < max_jump, "{} Hz {}: click of {}", rate, quality, jump);
+            }
+        }
+    }
+
+    #[test]
+    fn test_resample_keeps_clicks_in_place() {
+        for rate in [8000, 44100, 48000] {
+            for quality in ResampleQuality::ALL {
+                // A click 0.1s in must come out 1600 samples in at 16kHz
+                let mut input = vec![0.0; rate as usize];
+                input[rate as usize / 10] = 1.0;
+                let output = resample_to_model_rate(&input, rate, quality).unwrap();
+
+                let peak = (0..output.len())
+                    .max_by(|&a, &b| output[a].total_cmp(&output[b]))
+                    .unwrap();
+                assert!(peak.abs_diff(1600) <= 1, "{} Hz {}: click at {}", rate, quality, peak);
+            }
+        }
+    }
+
+    #[test]
+    fn test_resample_passthrough_and_errors() {
+        let input = sine(SAMPLE_RATE, 440.0, 0.1);
+        assert_eq!(resample_to_model_rate(&input, SAMPLE_RATE, ResampleQuality::High).unwrap(), input);
+        assert!(resample_to_model_rate(&[], 48000, ResampleQuality::Fast).unwrap().is_empty());
+        assert!(resample_to_model_rate(&input, 0, ResampleQuality::Fast).is_err());
+
+        // Clips shorter than one block still come out at the right length
+        let short = sine(8000, 440.0, 0.01);
+        assert_eq!(resample_to_model_rate(&short, 8000, ResampleQuality::Fast).unwrap().len(), 160);
+    }
+
+    #[test]
+    fn test_resample_quality_names() {
+        for quality in ResampleQuality::ALL {
+            assert_eq!(quality.as_str().parse::<ResampleQuality>(), Ok(quality));
+        }
+        assert_eq!(" HIGH ".parse::<ResampleQuality>(), Ok(ResampleQuality::High));
+        assert!("best".parse::<ResampleQuality>().is_err());
+        assert_eq!(ResampleQuality::default(), ResampleQuality::Fast);
+    }
+}
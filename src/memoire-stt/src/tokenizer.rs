@@ -7,6 +7,8 @@ use std::collections::HashMap;
 use std::path::Path;
 use tracing::{debug, info};
 
+use crate::error::{Result, SttError};
+
 /// Word boundary marker used by SentencePiece
 const WORD_BOUNDARY: char = '\u{2581}'; // ▁
 
@@ -31,18 +33,18 @@ impl Tokenizer {
     /// ▁the 5
     /// <blk> 1024
     /// ```
-    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
         let path = path.as_ref();
         debug!("loading tokenizer from {:?}", path);
 
         let content = std::fs::read_to_string(path)
-            .map_err(|e| anyhow::anyhow!("failed to read tokens file {:?}: {}", path, e))?;
+            .map_err(|e| SttError::ModelLoadError(format!("failed to read tokens file {:?}: {}", path, e)))?;
 
         Self::from_str(&content)
     }
 
     /// Load tokenizer from string content
-    pub fn from_str(content: &str) -> anyhow::Result<Self> {
+    pub fn from_str(content: &str) -> Result<Self> {
         let mut id_to_token = HashMap::new();
         let mut max_id: i32 = -1;
         let mut blank_id: Option<i32> = None;
@@ -61,7 +63,7 @@ impl Tokenizer {
             }
 
             let id: i32 = parts[0].parse().map_err(|e| {
-                anyhow::anyhow!("failed to parse token ID '{}': {}", parts[0], e)
+                SttError::ModelLoadError(format!("failed to parse token ID '{}': {}", parts[0], e))
             })?;
             let token = parts[1].to_string();
 
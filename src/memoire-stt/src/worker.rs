@@ -0,0 +1,148 @@
+//! Async front end for the STT engine
+//!
+//! ONNX sessions are expensive to create and not safe to move between
+//! threads, so the engine lives on one dedicated OS thread. Async callers
+//! send samples or WAV paths over a channel and await the result without
+//! blocking the tokio executor.
+
+use std::path::PathBuf;
+use std::thread;
+use tokio::sync::{mpsc, oneshot};
+use tracing::{debug, info};
+
+use crate::engine::{ExecutionProvider, SttConfig, SttEngine, TranscriptionResult};
+use crate::error::{Result, SttError};
+
+/// Audio to transcribe
+enum Input {
+    Samples { samples: Vec<f32>, sample_rate: u32 },
+    File(PathBuf),
+}
+
+/// A transcription request handled by the worker thread
+struct Job {
+    input: Input,
+    reply: oneshot::Sender<Result<TranscriptionResult>>,
+}
+
+/// Handle to an STT engine running on a dedicated thread
+///
+/// Cheap to clone; the worker thread exits once every handle is dropped.
+#[derive(Clone)]
+pub struct SttWorker {
+    tx: mpsc::UnboundedSender<Job>,
+    active_provider: ExecutionProvider,
+    is_model_loaded: bool,
+}
+
+impl SttEngine {
+    /// Load an engine on a dedicated thread and return an async handle to it
+    ///
+    /// Returns once the engine is initialized, so model loading errors are
+    /// reported here rather than on the first transcription.
+    pub fn spawn_worker(config: SttConfig) -> Result<SttWorker> {
+        let (tx, mut rx) = mpsc::unbounded_channel::<Job>();
+        let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+
+        thread::Builder::new()
+            .name("memoire-stt".to_string())
+            .spawn(move || {
+                let mut engine = match SttEngine::new(config) {
+                    Ok(engine) => {
+                        let _ = ready_tx.send(Ok((engine.active_provider(), engine.is_model_loaded())));
+                        engine
+                    }
+                    Err(e) => {
+                        let _ = ready_tx.send(Err(e));
+                        return;
+                    }
+                };
+
+                while let Some(job) = rx.blocking_recv() {
+                    let result = match job.input {
+                        Input::Samples { samples, sample_rate } => {
+                            engine.transcribe_samples(&samples, sample_rate)
+                        }
+                        Input::File(path) => engine.transcribe_file(&path),
+                    };
+                    // The caller may have stopped waiting
+                    let _ = job.reply.send(result);
+                }
+
+                debug!("STT worker thread exiting");
+            })?;
+
+        let (active_provider, is_model_loaded) = ready_rx
+            .recv()
+            .map_err(|_| SttError::WorkerStopped)??;
+
+        info!(
+            "STT worker started (provider: {}, model loaded: {})",
+            active_provider, is_model_loaded
+        );
+
+        Ok(SttWorker {
+            tx,
+            active_provider,
+            is_model_loaded,
+        })
+    }
+}
+
+impl SttWorker {
+    /// Execution provider the model runs on (`Cpu` when no model is loaded)
+    pub fn active_provider(&self) -> ExecutionProvider {
+        self.active_provider
+    }
+
+    /// Check if the model is loaded
+    pub fn is_model_loaded(&self) -> bool {
+        self.is_model_loaded
+    }
+
+    /// Transcribe audio samples on the worker thread
+    pub async fn transcribe(&self, samples: Vec<f32>, sample_rate: u32) -> Result<TranscriptionResult> {
+        self.submit(Input::Samples { samples, sample_rate }).await
+    }
+
+    /// Load and transcribe a WAV file on the worker thread
+    pub async fn transcribe_file(&self, path: impl Into<PathBuf>) -> Result<TranscriptionResult> {
+        self.submit(Input::File(path.into())).await
+    }
+
+    async fn submit(&self, input: Input) -> Result<TranscriptionResult> {
+        let (reply, rx) = oneshot::channel();
+
+        self.tx
+            .send(Job { input, reply })
+            .map_err(|_| SttError::WorkerStopped)?;
+
+        rx.await.map_err(|_| SttError::WorkerStopped)?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_worker_without_model() {
+        let config = SttConfig {
+            model_dir: std::env::temp_dir().join("memoire-stt-missing-model"),
+            ..SttConfig::default().cpu_only()
+        };
+
+        let worker = SttEngine::spawn_worker(config).unwrap();
+        assert!(!worker.is_model_loaded());
+
+        // Placeholder result, but it must come back through the channel
+        let result = worker.transcribe(vec![0.0; 16000], 16000).await.unwrap();
+        assert_eq!(result.segments.len(), 1);
+        assert!((result.segments[0].end - 1.0).abs() < 1e-9);
+
+        // Clones share the same thread
+        let clone = worker.clone();
+        drop(worker);
+        assert!(clone.transcribe(vec![0.0; 1600], 16000).await.is_ok());
+    }
+}
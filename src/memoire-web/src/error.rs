@@ -77,6 +77,18 @@ impl IntoResponse for ApiError {
     }
 }
 
+/// Map database errors to statuses: bad input is the client's fault
+impl From<memoire_db::DatabaseError> for ApiError {
+    fn from(err: memoire_db::DatabaseError) -> Self {
+        use memoire_db::DatabaseError;
+        match err {
+            DatabaseError::NotFound(msg) => ApiError::NotFound(msg),
+            DatabaseError::Fts5Syntax(_) | DatabaseError::InvalidQuery(_) => ApiError::BadRequest(err.to_string()),
+            other => ApiError::Database(other.to_string()),
+        }
+    }
+}
+
 /// Convert database errors to API errors
 impl From<rusqlite::Error> for ApiError {
     fn from(err: rusqlite::Error) -> Self {
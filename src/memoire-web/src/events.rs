@@ -0,0 +1,206 @@
+//! Live chunk events for the viewer
+//!
+//! The recorder publishes an event whenever it inserts a video or audio chunk.
+//! Each event gets a sequential id so SSE clients can resume with
+//! `Last-Event-ID`; a small backlog of recent events is kept for replay.
+//!
+//! OCR results are far more frequent and carry whole screens of text, so they
+//! go through a separate channel, only to clients that ask for them, and are
+//! not replayed.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+
+/// Number of recent events kept for `Last-Event-ID` replay
+const REPLAY_CAPACITY: usize = 256;
+
+/// Broadcast channel capacity for connected clients
+const CHANNEL_CAPACITY: usize = 100;
+
+/// Broadcast channel capacity for OCR results (the indexer stores them in batches)
+const OCR_CHANNEL_CAPACITY: usize = 256;
+
+/// A newly recorded chunk
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChunkEvent {
+    /// A video chunk was finalized
+    VideoChunk {
+        chunk_id: i64,
+        monitor: String,
+        file_path: String,
+    },
+    /// An audio chunk was saved
+    AudioChunk {
+        chunk_id: i64,
+        device: Option<String>,
+        file_path: String,
+    },
+    /// OCR text was stored for a frame
+    OcrText {
+        frame_id: i64,
+        timestamp: DateTime<Utc>,
+        app_name: Option<String>,
+        text: String,
+    },
+}
+
+/// A chunk event with its stream id
+#[derive(Debug, Clone)]
+pub struct LiveEvent {
+    pub id: u64,
+    pub event: ChunkEvent,
+}
+
+#[derive(Default)]
+struct Backlog {
+    next_id: u64,
+    recent: VecDeque<LiveEvent>,
+}
+
+/// Fan-out of chunk events to SSE clients
+#[derive(Clone)]
+pub struct EventHub {
+    tx: broadcast::Sender<LiveEvent>,
+    ocr_tx: broadcast::Sender<LiveEvent>,
+    backlog: Arc<Mutex<Backlog>>,
+}
+
+impl EventHub {
+    /// Create an empty hub
+    pub fn new() -> Self {
+        let (tx, _rx) = broadcast::channel(CHANNEL_CAPACITY);
+        let (ocr_tx, _rx) = broadcast::channel(OCR_CHANNEL_CAPACITY);
+        Self {
+            tx,
+            ocr_tx,
+            backlog: Arc::new(Mutex::new(Backlog {
+                next_id: 1,
+                recent: VecDeque::with_capacity(REPLAY_CAPACITY),
+            })),
+        }
+    }
+
+    /// Assign an id to an event, remember it and send it to connected clients
+    pub fn publish(&self, event: ChunkEvent) -> u64 {
+        let mut backlog = match self.backlog.lock() {
+            Ok(backlog) => backlog,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        let live = LiveEvent {
+            id: backlog.next_id,
+            event,
+        };
+        backlog.next_id += 1;
+
+        if backlog.recent.len() == REPLAY_CAPACITY {
+            backlog.recent.pop_front();
+        }
+        backlog.recent.push_back(live.clone());
+
+        // Sent while holding the lock so subscribe() never sees an event twice
+        // or misses one between the replay and the live stream
+        let _ = self.tx.send(live.clone());
+        live.id
+    }
+
+    /// Send an OCR result to the clients subscribed with [`subscribe_ocr`](Self::subscribe_ocr)
+    ///
+    /// The event is not kept for replay. Returns its id, or `None` when no
+    /// client wants OCR results and the event was dropped.
+    pub fn publish_ocr(&self, event: ChunkEvent) -> Option<u64> {
+        if self.ocr_tx.receiver_count() == 0 {
+            return None;
+        }
+
+        let mut backlog = match self.backlog.lock() {
+            Ok(backlog) => backlog,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        let live = LiveEvent {
+            id: backlog.next_id,
+            event,
+        };
+        backlog.next_id += 1;
+
+        let id = live.id;
+        let _ = self.ocr_tx.send(live);
+        Some(id)
+    }
+
+    /// Subscribe to OCR results published from now on
+    pub fn subscribe_ocr(&self) -> broadcast::Receiver<LiveEvent> {
+        self.ocr_tx.subscribe()
+    }
+
+    /// Subscribe to new events, returning buffered events after `last_event_id`
+    pub fn subscribe(&self, last_event_id: Option<u64>) -> (Vec<LiveEvent>, broadcast::Receiver<LiveEvent>) {
+        let backlog = match self.backlog.lock() {
+            Ok(backlog) => backlog,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        let replay = match last_event_id {
+            Some(last) => backlog.recent.iter().filter(|e| e.id > last).cloned().collect(),
+            None => Vec::new(),
+        };
+
+        (replay, self.tx.subscribe())
+    }
+}
+
+impl Default for EventHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn video(chunk_id: i64) -> ChunkEvent {
+        ChunkEvent::VideoChunk {
+            chunk_id,
+            monitor: "monitor-0".to_string(),
+            file_path: format!("videos/{}.mp4", chunk_id),
+        }
+    }
+
+    fn ocr(frame_id: i64) -> ChunkEvent {
+        ChunkEvent::OcrText {
+            frame_id,
+            timestamp: Utc::now(),
+            app_name: None,
+            text: "hello".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_ocr_events_are_opt_in_and_not_replayed() {
+        let hub = EventHub::new();
+        let (_, mut chunks) = hub.subscribe(None);
+
+        // Dropped without an id while no client wants OCR results
+        assert_eq!(hub.publish_ocr(ocr(1)), None);
+
+        let mut ocr_rx = hub.subscribe_ocr();
+        let first = hub.publish(video(1));
+        let ocr_id = hub.publish_ocr(ocr(2)).unwrap();
+        let second = hub.publish(video(2));
+        assert!(first < ocr_id && ocr_id < second);
+
+        assert_eq!(ocr_rx.try_recv().unwrap().id, ocr_id);
+        assert!(ocr_rx.try_recv().is_err());
+        assert_eq!(chunks.try_recv().unwrap().id, first);
+        assert_eq!(chunks.try_recv().unwrap().id, second);
+
+        let (replay, _) = hub.subscribe(Some(0));
+        assert_eq!(replay.iter().map(|e| e.id).collect::<Vec<_>>(), vec![first, second]);
+    }
+}
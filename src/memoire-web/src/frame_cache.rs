@@ -0,0 +1,215 @@
+//! In-memory LRU cache of extracted frame images
+//!
+//! Extracting a frame spawns FFmpeg, which dominates the latency of
+//! interactive browsing where the same frames are requested repeatedly.
+//! Encoded images are kept up to a byte budget and the least recently used
+//! ones are evicted first.
+
+use bytes::Bytes;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+/// Default cache budget (64 MiB, a few hundred 1080p JPEGs)
+pub const DEFAULT_FRAME_CACHE_BYTES: usize = 64 * 1024 * 1024;
+
+/// Identifies a frame by its position in a video chunk and its encoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FrameKey {
+    pub chunk_id: i64,
+    pub offset_index: i64,
+    pub encoding: FrameEncoding,
+}
+
+/// Image encoding of a cached frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FrameEncoding {
+    /// Lossy JPEG at a quality of 1-100
+    Jpeg { quality: u8 },
+    Png,
+    /// Lossless WebP
+    Webp,
+}
+
+impl FrameEncoding {
+    /// MIME type of the encoded image
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            FrameEncoding::Jpeg { .. } => "image/jpeg",
+            FrameEncoding::Png => "image/png",
+            FrameEncoding::Webp => "image/webp",
+        }
+    }
+}
+
+struct Entry {
+    image: Bytes,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct Inner {
+    entries: HashMap<FrameKey, Entry>,
+    /// Entries ordered by last use (tick -> key), oldest first
+    recency: BTreeMap<u64, FrameKey>,
+    tick: u64,
+    size_bytes: usize,
+}
+
+impl Inner {
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+}
+
+/// Shared LRU cache of encoded frame images
+#[derive(Clone)]
+pub struct FrameCache {
+    inner: Arc<Mutex<Inner>>,
+    budget_bytes: usize,
+}
+
+impl FrameCache {
+    /// Create a cache holding at most `budget_bytes` of images (0 disables it)
+    pub fn new(budget_bytes: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner::default())),
+            budget_bytes,
+        }
+    }
+
+    /// Look up a frame, marking it as recently used
+    pub fn get(&self, key: &FrameKey) -> Option<Bytes> {
+        let mut inner = self.lock();
+        let tick = inner.next_tick();
+
+        let entry = inner.entries.get_mut(key)?;
+        let previous = std::mem::replace(&mut entry.last_used, tick);
+        let image = entry.image.clone();
+
+        inner.recency.remove(&previous);
+        inner.recency.insert(tick, *key);
+        Some(image)
+    }
+
+    /// Store a frame, evicting the least recently used frames to stay within budget
+    ///
+    /// Images larger than the whole budget are not cached.
+    pub fn insert(&self, key: FrameKey, image: Bytes) {
+        if image.len() > self.budget_bytes {
+            return;
+        }
+
+        let mut inner = self.lock();
+        let tick = inner.next_tick();
+
+        if let Some(old) = inner.entries.remove(&key) {
+            inner.recency.remove(&old.last_used);
+            inner.size_bytes -= old.image.len();
+        }
+
+        while inner.size_bytes + image.len() > self.budget_bytes {
+            let Some((_, oldest)) = inner.recency.pop_first() else {
+                break;
+            };
+            if let Some(evicted) = inner.entries.remove(&oldest) {
+                inner.size_bytes -= evicted.image.len();
+            }
+        }
+
+        inner.size_bytes += image.len();
+        inner.recency.insert(tick, key);
+        inner.entries.insert(key, Entry { image, last_used: tick });
+    }
+
+    /// Number of cached frames and their total size in bytes
+    pub fn usage(&self) -> (usize, usize) {
+        let inner = self.lock();
+        (inner.entries.len(), inner.size_bytes)
+    }
+
+    /// Configured budget in bytes
+    pub fn budget_bytes(&self) -> usize {
+        self.budget_bytes
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        match self.inner.lock() {
+            Ok(inner) => inner,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+impl Default for FrameCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_FRAME_CACHE_BYTES)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(offset_index: i64) -> FrameKey {
+        FrameKey {
+            chunk_id: 1,
+            offset_index,
+            encoding: FrameEncoding::Jpeg { quality: 80 },
+        }
+    }
+
+    fn image(len: usize) -> Bytes {
+        Bytes::from(vec![0u8; len])
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let cache = FrameCache::new(30);
+        cache.insert(key(0), image(10));
+        cache.insert(key(1), image(10));
+        cache.insert(key(2), image(10));
+
+        // Touching frame 0 makes frame 1 the oldest
+        assert!(cache.get(&key(0)).is_some());
+        cache.insert(key(3), image(10));
+
+        assert!(cache.get(&key(1)).is_none());
+        assert!(cache.get(&key(0)).is_some());
+        assert!(cache.get(&key(2)).is_some());
+        assert!(cache.get(&key(3)).is_some());
+        assert_eq!(cache.usage(), (3, 30));
+
+        // A larger image evicts as many frames as it needs
+        cache.insert(key(4), image(25));
+        assert_eq!(cache.usage(), (1, 25));
+        assert!(cache.get(&key(4)).is_some());
+    }
+
+    #[test]
+    fn test_replacing_an_entry_updates_size() {
+        let cache = FrameCache::new(30);
+        cache.insert(key(0), image(10));
+        cache.insert(key(0), image(20));
+        assert_eq!(cache.usage(), (1, 20));
+    }
+
+    #[test]
+    fn test_entry_larger_than_budget_is_not_cached() {
+        let cache = FrameCache::new(30);
+        cache.insert(key(0), image(10));
+        cache.insert(key(1), image(31));
+
+        assert!(cache.get(&key(1)).is_none());
+        // Existing entries are kept
+        assert_eq!(cache.usage(), (1, 10));
+    }
+
+    #[test]
+    fn test_zero_budget_disables_cache() {
+        let cache = FrameCache::new(0);
+        cache.insert(key(0), image(1));
+        assert!(cache.get(&key(0)).is_none());
+        assert_eq!(cache.usage(), (0, 0));
+    }
+}
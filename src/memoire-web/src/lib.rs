@@ -1,10 +1,15 @@
 //! Memoire web viewer - REST API and validation interface
 
 pub mod error;
+pub mod events;
+pub mod frame_cache;
 pub mod routes;
 pub mod server;
 pub mod state;
+mod trace;
 
 pub use error::ApiError;
-pub use server::serve;
-pub use state::AppState;
+pub use events::{ChunkEvent, EventHub};
+pub use frame_cache::{FrameCache, DEFAULT_FRAME_CACHE_BYTES};
+pub use server::{serve, serve_with_state, DEFAULT_HOST};
+pub use state::{AppState, RequestLimits};
@@ -7,6 +7,10 @@ use axum::{
 };
 use memoire_db;
 use serde::{Deserialize, Serialize};
+use tracing::info;
+
+/// Largest gap accepted for grouping search results into sessions (one day)
+const MAX_SESSION_GAP_SECS: i64 = 86_400;
 
 // ============================================================================
 // Audio API types
@@ -33,6 +37,15 @@ pub struct AudioSearchQuery {
     pub limit: Option<i64>,
     #[serde(default)]
     pub offset: Option<i64>,
+    /// Only match audio from this capture device
+    #[serde(default)]
+    pub device: Option<String>,
+    /// `true` for microphone audio, `false` for loopback (what was heard)
+    #[serde(default)]
+    pub is_input: Option<bool>,
+    /// Only match transcriptions in this language (ISO 639-1 code, e.g. `fr`)
+    #[serde(default)]
+    pub language: Option<String>,
 }
 
 /// Response for audio chunk listing
@@ -40,6 +53,9 @@ pub struct AudioSearchQuery {
 pub struct AudioChunksResponse {
     pub chunks: Vec<AudioChunkWithMetadata>,
     pub total: i64,
+    pub has_more: bool,
+    pub limit: i64,
+    pub offset: i64,
 }
 
 #[derive(Debug, Serialize)]
@@ -76,6 +92,41 @@ pub struct FramesQuery {
     offset: Option<i64>,
 }
 
+/// Query parameters for activity histogram
+#[derive(Debug, Deserialize)]
+pub struct ActivityQuery {
+    #[serde(default)]
+    start: Option<String>,
+    #[serde(default)]
+    end: Option<String>,
+    /// Bucket size in seconds
+    #[serde(default)]
+    bucket: Option<i64>,
+    /// Include per-app breakdown
+    #[serde(default)]
+    by_app: Option<bool>,
+}
+
+/// Query parameters for similar frame search
+#[derive(Debug, Deserialize)]
+pub struct SimilarFramesQuery {
+    /// Maximum Hamming distance between perceptual hashes (0-64, default 10)
+    #[serde(default)]
+    max_distance: Option<u32>,
+    #[serde(default)]
+    limit: Option<i64>,
+}
+
+/// Query parameters for point-in-time context
+#[derive(Debug, Deserialize)]
+pub struct ContextQuery {
+    /// RFC3339 timestamp of the moment
+    at: String,
+    /// Seconds before and after `at` to include (default 30)
+    #[serde(default)]
+    window: Option<i64>,
+}
+
 /// Query parameters for search
 #[derive(Debug, Deserialize)]
 pub struct SearchQuery {
@@ -84,6 +135,37 @@ pub struct SearchQuery {
     limit: Option<i64>,
     #[serde(default)]
     offset: Option<i64>,
+    /// Matching mode: `exact` (default), `prefix`, `fuzzy` or `substring`
+    #[serde(default)]
+    mode: memoire_db::SearchMode,
+    /// Skip frames captured while the user was idle
+    #[serde(default)]
+    focused_only: bool,
+    /// Group the page into sessions split by gaps longer than this many seconds
+    #[serde(default)]
+    group_by_gap: Option<i64>,
+}
+
+/// Query parameters for region search
+#[derive(Debug, Deserialize)]
+pub struct RegionSearchQuery {
+    /// Named quadrant (`top-left`, `top-right`, `bottom-left`, `bottom-right`)
+    #[serde(default)]
+    quadrant: Option<String>,
+    /// Explicit region, normalized to frame dimensions
+    #[serde(default)]
+    x: Option<f64>,
+    #[serde(default)]
+    y: Option<f64>,
+    #[serde(default)]
+    w: Option<f64>,
+    #[serde(default)]
+    h: Option<f64>,
+    /// Optional text the line must contain
+    #[serde(default)]
+    q: Option<String>,
+    #[serde(default)]
+    limit: Option<i64>,
 }
 
 /// Response for chunk listing
@@ -110,7 +192,7 @@ pub async fn get_chunks(
     let db = state.db.lock()
         .map_err(|_| ApiError::Internal(anyhow::anyhow!("database lock poisoned")))?;
 
-    let limit = params.limit.unwrap_or(50).max(1).min(100);
+    let limit = state.limits.page_size(params.limit);
     let offset = params.offset.unwrap_or(0).max(0);
 
     let chunks = memoire_db::get_chunks_paginated(
@@ -149,6 +231,8 @@ pub async fn get_chunks(
 }
 
 /// GET /api/chunks/:id
+///
+/// `gaps` lists ranges of capture indices with no stored frame (dropped frames).
 pub async fn get_chunk(
     State(state): State<AppState>,
     Path(id): Path<i64>,
@@ -162,12 +246,62 @@ pub async fn get_chunk(
     let frame_count = memoire_db::get_frame_count_by_chunk(&db, id)
         .map_err(|e| ApiError::Database(e.to_string()))?;
 
+    let gaps: Vec<serde_json::Value> = memoire_db::get_frame_index_gaps(&db, id)
+        .map_err(|e| ApiError::Database(e.to_string()))?
+        .into_iter()
+        .map(|(start, end)| serde_json::json!({ "start": start, "end": end }))
+        .collect();
+
     Ok(Json(serde_json::json!({
         "id": chunk.id,
         "file_path": chunk.file_path,
         "device_name": chunk.device_name,
         "created_at": chunk.created_at.to_rfc3339(),
+        "width": chunk.width,
+        "height": chunk.height,
+        "fps": chunk.fps,
+        "codec": chunk.codec,
+        "duration_secs": chunk.duration_secs,
+        "size_bytes": chunk.size_bytes,
         "frame_count": frame_count,
+        "gaps": gaps,
+    })))
+}
+
+/// POST /api/chunks/:id/reindex - delete the chunk's OCR so its frames are indexed again
+///
+/// The in-process indexer is notified to process the chunk right away; a
+/// separately running `memoire index` picks the frames up on its next poll.
+pub async fn reindex_chunk(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    if state.read_only {
+        return Err(ApiError::Forbidden("viewer is read-only".to_string()));
+    }
+
+    let deleted = {
+        let db = state.db.lock()
+            .map_err(|_| ApiError::Internal(anyhow::anyhow!("database lock poisoned")))?;
+
+        memoire_db::get_video_chunk(&db, id)?
+            .ok_or_else(|| ApiError::NotFound(format!("chunk {} not found", id)))?;
+
+        memoire_db::reset_ocr_for_chunk(&db, id)
+            .map_err(|e| ApiError::Database(e.to_string()))?
+    };
+
+    let indexer_notified = state
+        .reindex_tx
+        .as_ref()
+        .is_some_and(|tx| tx.send(id).is_ok());
+
+    info!("chunk {} queued for re-indexing ({} OCR rows deleted)", id, deleted);
+
+    Ok(Json(serde_json::json!({
+        "chunk_id": id,
+        "ocr_deleted": deleted,
+        "indexer_notified": indexer_notified,
     })))
 }
 
@@ -227,16 +361,55 @@ pub async fn get_frame(
 
     // Add OCR data if available
     if let Some(ocr_data) = ocr {
+        let lines = memoire_db::get_ocr_lines_by_frame(&db, id)
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+
         response["ocr"] = serde_json::json!({
             "text": ocr_data.text,
             "text_json": ocr_data.text_json,
             "confidence": ocr_data.confidence,
+            "language": ocr_data.language,
+            "lines": lines,
         });
     }
 
     Ok(Json(response))
 }
 
+/// GET /api/frames/:id/similar - Frames that look like this one, closest first
+pub async fn get_similar_frames(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Query(params): Query<SimilarFramesQuery>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let max_distance = params.max_distance.unwrap_or(10).min(64);
+    let limit = state.limits.page_size(params.limit);
+
+    let db = state.db.lock()
+        .map_err(|_| ApiError::Internal(anyhow::anyhow!("database lock poisoned")))?;
+
+    let frame = memoire_db::get_frame(&db, id)?
+        .ok_or_else(|| ApiError::NotFound(format!("frame {} not found", id)))?;
+    let hash = frame
+        .frame_hash
+        .ok_or_else(|| ApiError::NotFound(format!("frame {} has no perceptual hash", id)))?;
+
+    // One extra result since the frame always matches itself
+    let results: Vec<_> = memoire_db::find_similar_frames(&db, hash, max_distance, limit + 1)
+        .map_err(|e| ApiError::Database(e.to_string()))?
+        .into_iter()
+        .filter(|similar| similar.frame.id != id)
+        .take(limit as usize)
+        .collect();
+
+    Ok(Json(serde_json::json!({
+        "frame_id": id,
+        "max_distance": max_distance,
+        "results": results,
+        "limit": limit,
+    })))
+}
+
 /// GET /api/stats
 pub async fn get_stats(
     State(state): State<AppState>,
@@ -257,6 +430,19 @@ pub async fn get_stats(
     })))
 }
 
+/// GET /api/dashboard - Today's and this week's activity, top apps, backlog and disk usage
+pub async fn get_dashboard(
+    State(state): State<AppState>,
+) -> Result<Json<memoire_db::DashboardSummary>, ApiError> {
+    let db = state.db.lock()
+        .map_err(|_| ApiError::Internal(anyhow::anyhow!("database lock poisoned")))?;
+
+    let summary = memoire_db::get_dashboard_summary(&db)
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(Json(summary))
+}
+
 /// GET /api/monitors
 pub async fn get_monitors(
     State(state): State<AppState>,
@@ -272,33 +458,238 @@ pub async fn get_monitors(
     })))
 }
 
+/// GET /api/latest - The newest frame of each monitor, for a live preview
+pub async fn get_latest_frames(
+    State(state): State<AppState>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let db = state.db.lock()
+        .map_err(|_| ApiError::Internal(anyhow::anyhow!("database lock poisoned")))?;
+
+    let latest = memoire_db::get_latest_frame_per_monitor(&db)
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let monitors: Vec<serde_json::Value> = latest
+        .into_iter()
+        .map(|(device_name, frame)| {
+            serde_json::json!({
+                "device_name": device_name,
+                "image_url": format!("/api/frames/{}/image", frame.id),
+                "frame": frame,
+            })
+        })
+        .collect();
+
+    Ok(Json(serde_json::json!({
+        "monitors": monitors,
+    })))
+}
+
+/// Parse an RFC3339 timestamp query parameter
+fn parse_time_param(name: &str, value: &str) -> Result<chrono::DateTime<chrono::Utc>, ApiError> {
+    chrono::DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&chrono::Utc))
+        .map_err(|_| ApiError::BadRequest(format!("invalid {} timestamp (expected RFC3339)", name)))
+}
+
+/// GET /api/activity - Frame counts per time bucket (defaults to the last 24 hours, hourly)
+pub async fn get_activity(
+    State(state): State<AppState>,
+    Query(params): Query<ActivityQuery>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let end = match params.end.as_deref() {
+        Some(s) => parse_time_param("end", s)?,
+        None => chrono::Utc::now(),
+    };
+    let start = match params.start.as_deref() {
+        Some(s) => parse_time_param("start", s)?,
+        None => end - chrono::Duration::hours(24),
+    };
+
+    if start > end {
+        return Err(ApiError::BadRequest("start must be before end".to_string()));
+    }
+
+    // Clamp bucket size between 1 minute and 1 day
+    let bucket_secs = params.bucket.unwrap_or(3600).clamp(60, 86400);
+
+    let db = state.db.lock()
+        .map_err(|_| ApiError::Internal(anyhow::anyhow!("database lock poisoned")))?;
+
+    let buckets = memoire_db::get_activity_histogram(&db, start, end, bucket_secs)
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let mut response = serde_json::json!({
+        "start": start.to_rfc3339(),
+        "end": end.to_rfc3339(),
+        "bucket_secs": bucket_secs,
+        "buckets": buckets
+            .iter()
+            .map(|(bucket_start, count, active)| serde_json::json!({
+                "start": bucket_start.to_rfc3339(),
+                "count": count,
+                "active": active,
+                "idle": count - active,
+            }))
+            .collect::<Vec<_>>(),
+    });
+
+    if params.by_app.unwrap_or(false) {
+        let by_app = memoire_db::get_activity_histogram_by_app(&db, start, end, bucket_secs)
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+        response["by_app"] = serde_json::json!(by_app);
+    }
+
+    Ok(Json(response))
+}
+
+/// GET /api/context - Screen OCR and transcribed speech around a moment
+pub async fn get_context(
+    State(state): State<AppState>,
+    Query(params): Query<ContextQuery>,
+) -> Result<Json<memoire_db::MomentContext>, ApiError> {
+    let at = parse_time_param("at", &params.at)?;
+
+    // Clamp window between 1 second and 1 hour
+    let window = params.window.unwrap_or(30).clamp(1, 3600);
+
+    let db = state.db.lock()
+        .map_err(|_| ApiError::Internal(anyhow::anyhow!("database lock poisoned")))?;
+
+    let context = memoire_db::get_context_at(&db, at, window)
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(Json(context))
+}
+
 /// GET /api/search
 pub async fn search_ocr(
     State(state): State<AppState>,
     Query(params): Query<SearchQuery>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
+    let group_by_gap = match params.group_by_gap {
+        Some(secs) if secs <= 0 => {
+            return Err(ApiError::BadRequest("group_by_gap must be a positive number of seconds".to_string()))
+        }
+        Some(secs) => Some(chrono::Duration::seconds(secs.min(MAX_SESSION_GAP_SECS))),
+        None => None,
+    };
+
     let db = state.db.lock()
         .map_err(|_| ApiError::Internal(anyhow::anyhow!("database lock poisoned")))?;
 
-    let limit = params.limit.unwrap_or(50).max(1).min(100);
+    let limit = state.limits.page_size(params.limit);
     let offset = params.offset.unwrap_or(0).max(0);
 
     // Sanitize the search query for FTS5
-    let sanitized_query = memoire_db::sanitize_fts5_query(&params.q)
-        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    let sanitized_query = memoire_db::sanitize_fts5_query_with_mode(&params.q, params.mode)?;
 
-    // Get total count
-    let total = memoire_db::get_search_count(&db, &sanitized_query)
-        .map_err(|e| ApiError::Database(e.to_string()))?;
+    let (results, total) = if params.mode == memoire_db::SearchMode::Fuzzy {
+        // Fuzzy matches are filtered in Rust, so the count comes from the same pass
+        memoire_db::search_ocr_fuzzy(&db, &params.q, limit, offset, params.focused_only)
+            .map_err(|e| ApiError::Database(e.to_string()))?
+    } else if params.mode == memoire_db::SearchMode::Substring {
+        let total = memoire_db::get_substring_search_count(&db, &sanitized_query, params.focused_only)?;
+        let results = memoire_db::search_ocr_substring(&db, &sanitized_query, limit, offset, params.focused_only)?;
 
-    // Get search results
-    let results = memoire_db::search_ocr(&db, &sanitized_query, limit, offset)
-        .map_err(|e| ApiError::Database(e.to_string()))?;
+        (results, total)
+    } else {
+        // Get total count
+        let total = memoire_db::get_search_count(&db, &sanitized_query, params.focused_only)?;
+
+        // Get search results
+        let results = memoire_db::search_ocr(&db, &sanitized_query, limit, offset, params.focused_only)?;
+
+        (results, total)
+    };
 
     // Transform results into response format
+    let result_json = |(ocr, frame): (memoire_db::OcrText, memoire_db::Frame)| {
+        serde_json::json!({
+            "frame": {
+                "id": frame.id,
+                "timestamp": frame.timestamp.to_rfc3339(),
+                "app_name": frame.app_name,
+                "window_name": frame.window_name,
+                "browser_url": frame.browser_url,
+            },
+            "ocr": {
+                "text": ocr.text,
+                "confidence": ocr.confidence,
+                "language": ocr.language,
+            },
+        })
+    };
+
+    let has_more = offset + limit < total;
+
+    let mut response = serde_json::json!({
+        "total": total,
+        "has_more": has_more,
+        "limit": limit,
+        "offset": offset,
+        "mode": params.mode,
+    });
+
+    // Sessions replace the flat list; they cover this page only
+    match group_by_gap {
+        Some(gap) => {
+            let sessions: Vec<serde_json::Value> = memoire_db::group_by_gap(results, gap, |(_, frame)| frame.timestamp)
+                .into_iter()
+                .map(|session| {
+                    serde_json::json!({
+                        "start": session.start.to_rfc3339(),
+                        "end": session.end.to_rfc3339(),
+                        "results": session.results.into_iter().map(result_json).collect::<Vec<_>>(),
+                    })
+                })
+                .collect();
+            response["sessions"] = sessions.into();
+        }
+        None => {
+            response["results"] = results.into_iter().map(result_json).collect::<Vec<_>>().into();
+        }
+    }
+
+    Ok(Json(response))
+}
+
+/// GET /api/search/region
+pub async fn search_ocr_region(
+    State(state): State<AppState>,
+    Query(params): Query<RegionSearchQuery>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let region = match (&params.quadrant, params.x, params.y, params.w, params.h) {
+        (Some(name), _, _, _, _) => memoire_db::BoundingBox::quadrant(name)
+            .ok_or_else(|| ApiError::BadRequest(format!("unknown quadrant: {}", name)))?,
+        (None, Some(x), Some(y), Some(w), Some(h)) => memoire_db::BoundingBox { x, y, w, h },
+        _ => {
+            return Err(ApiError::BadRequest(
+                "either quadrant or all of x, y, w, h are required".to_string(),
+            ))
+        }
+    };
+
+    let in_range = |v: f64| (0.0..=1.0).contains(&v);
+    if !in_range(region.x) || !in_range(region.y) || !in_range(region.w) || !in_range(region.h) {
+        return Err(ApiError::BadRequest("region values must be between 0 and 1".to_string()));
+    }
+
+    let query = params.q.as_deref().map(str::trim).filter(|q| !q.is_empty());
+    if query.is_some_and(|q| q.len() > 500) {
+        return Err(ApiError::BadRequest("search query too long (max 500 chars)".to_string()));
+    }
+
+    let limit = state.limits.page_size(params.limit);
+
+    let db = state.db.lock()
+        .map_err(|_| ApiError::Internal(anyhow::anyhow!("database lock poisoned")))?;
+
+    let results = memoire_db::search_ocr_in_region(&db, &region, query, limit)
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
     let results_json: Vec<serde_json::Value> = results
         .into_iter()
-        .map(|(ocr, frame)| {
+        .map(|(line, frame)| {
             serde_json::json!({
                 "frame": {
                     "id": frame.id,
@@ -307,22 +698,15 @@ pub async fn search_ocr(
                     "window_name": frame.window_name,
                     "browser_url": frame.browser_url,
                 },
-                "ocr": {
-                    "text": ocr.text,
-                    "confidence": ocr.confidence,
-                },
+                "line": line,
             })
         })
         .collect();
 
-    let has_more = offset + limit < total;
-
     Ok(Json(serde_json::json!({
+        "region": region,
         "results": results_json,
-        "total": total,
-        "has_more": has_more,
         "limit": limit,
-        "offset": offset,
     })))
 }
 
@@ -345,6 +729,24 @@ pub async fn get_ocr_stats(
     })))
 }
 
+/// GET /api/stats/dedup - Duplicate frame skip statistics
+pub async fn get_dedup_stats(
+    State(state): State<AppState>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let db = state.db.lock()
+        .map_err(|_| ApiError::Internal(anyhow::anyhow!("database lock poisoned")))?;
+
+    let stats = memoire_db::get_dedup_stats(&db)
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    Ok(Json(serde_json::json!({
+        "captured_frames": stats.captured_frames,
+        "skipped_frames": stats.skipped_frames,
+        "dedup_ratio": stats.dedup_ratio,
+        "chunks": stats.chunks,
+    })))
+}
+
 // ============================================================================
 // Audio API handlers
 // ============================================================================
@@ -364,8 +766,7 @@ pub async fn get_audio_chunks(
     let db = state.db.lock()
         .map_err(|_| ApiError::Internal(anyhow::anyhow!("database lock poisoned")))?;
 
-    // Validate and clamp limit to reasonable range
-    let limit = params.limit.unwrap_or(50).max(1).min(100);
+    let limit = state.limits.page_size(params.limit);
 
     // Validate offset is non-negative
     let offset = match params.offset {
@@ -385,7 +786,7 @@ pub async fn get_audio_chunks(
     )
     .map_err(|e| ApiError::Database(e.to_string()))?;
 
-    let total = memoire_db::get_total_audio_chunk_count(&db, params.device.as_deref())
+    let total = memoire_db::get_total_audio_chunk_count(&db, params.device.as_deref(), params.is_input)
         .map_err(|e| ApiError::Database(e.to_string()))?;
 
     let chunks_with_metadata = chunks
@@ -403,6 +804,9 @@ pub async fn get_audio_chunks(
     Ok(Json(AudioChunksResponse {
         chunks: chunks_with_metadata,
         total,
+        has_more: offset + limit < total,
+        limit,
+        offset,
     }))
 }
 
@@ -468,8 +872,7 @@ pub async fn search_audio(
     let db = state.db.lock()
         .map_err(|_| ApiError::Internal(anyhow::anyhow!("database lock poisoned")))?;
 
-    // Validate and clamp limit to reasonable range
-    let limit = params.limit.unwrap_or(50).max(1).min(100);
+    let limit = state.limits.page_size(params.limit);
 
     // Validate offset is non-negative
     let offset = match params.offset {
@@ -481,16 +884,17 @@ pub async fn search_audio(
     };
 
     // Sanitize the search query for FTS5
-    let sanitized_query = memoire_db::sanitize_fts5_query(&params.q)
-        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    let sanitized_query = memoire_db::sanitize_fts5_query(&params.q)?;
 
     // Get total count
-    let total = memoire_db::get_audio_search_count(&db, &sanitized_query)
-        .map_err(|e| ApiError::Database(e.to_string()))?;
+    let device = params.device.as_deref();
+    let language = params.language.as_deref();
+    let total = memoire_db::get_audio_search_count(&db, &sanitized_query, device, params.is_input, language)?;
 
     // Get search results
-    let results = memoire_db::search_transcriptions(&db, &sanitized_query, limit, offset)
-        .map_err(|e| ApiError::Database(e.to_string()))?;
+    let results = memoire_db::search_transcriptions(
+        &db, &sanitized_query, limit, offset, device, params.is_input, language,
+    )?;
 
     // Transform results into response format
     let results_json: Vec<serde_json::Value> = results
@@ -501,6 +905,7 @@ pub async fn search_audio(
                     "id": chunk.id,
                     "file_path": chunk.file_path,
                     "device_name": chunk.device_name,
+                    "is_input_device": chunk.is_input_device,
                     "timestamp": chunk.timestamp.to_rfc3339(),
                 },
                 "transcription": {
@@ -508,6 +913,7 @@ pub async fn search_audio(
                     "start_time": transcription.start_time,
                     "end_time": transcription.end_time,
                     "speaker_id": transcription.speaker_id,
+                    "language": transcription.language,
                 },
             })
         })
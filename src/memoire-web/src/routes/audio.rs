@@ -54,6 +54,11 @@ pub async fn stream_audio(
             .ok_or_else(|| ApiError::NotFound(format!("audio chunk {} not found", chunk_id)))?
     };
 
+    // Retention deleted the file; the transcriptions are still served elsewhere
+    if chunk.pruned {
+        return Err(ApiError::NotFound(format!("audio chunk {} file pruned", chunk_id)));
+    }
+
     // Resolve file path (prevent path traversal)
     let file_path = state.data_dir.join(&chunk.file_path);
 
@@ -0,0 +1,80 @@
+//! Server-Sent Events stream of newly recorded chunks, optionally with OCR results
+
+use crate::events::LiveEvent;
+use crate::AppState;
+use axum::{
+    extract::{Query, State},
+    http::HeaderMap,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures::stream::{self, Stream, StreamExt};
+use serde::Deserialize;
+use std::convert::Infallible;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tracing::debug;
+
+/// Convert a chunk event to an SSE message
+fn to_sse(live: &LiveEvent) -> Event {
+    let id = live.id.to_string();
+    Event::default()
+        .id(id.clone())
+        .json_data(&live.event)
+        .unwrap_or_else(|_| Event::default().id(id))
+}
+
+/// Query parameters for `/api/events`
+#[derive(Debug, Default, Deserialize)]
+pub struct EventsQuery {
+    /// Also stream OCR results as frames are indexed (never replayed)
+    #[serde(default)]
+    pub ocr: bool,
+}
+
+/// Stream events from a receiver until the hub is dropped
+fn receive(rx: broadcast::Receiver<LiveEvent>) -> impl Stream<Item = Result<Event, Infallible>> {
+    stream::unfold(rx, |mut rx| async move {
+        loop {
+            match rx.recv().await {
+                Ok(e) => return Some((Ok(to_sse(&e)), rx)),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    debug!("SSE client lagged, {} events dropped", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    })
+}
+
+/// GET /api/events - stream chunk insertions as Server-Sent Events
+///
+/// Clients that reconnect with a `Last-Event-ID` header first receive any
+/// buffered events they missed. With `?ocr=true` OCR results are streamed
+/// too; they are not buffered, so a reconnecting client misses those.
+pub async fn stream_events(
+    State(state): State<AppState>,
+    Query(params): Query<EventsQuery>,
+    headers: HeaderMap,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok());
+
+    let ocr = params.ocr.then(|| receive(state.events.subscribe_ocr()));
+    let (replay, rx) = state.events.subscribe(last_event_id);
+    debug!(
+        "SSE client connected (last event: {:?}, replaying {}, OCR: {})",
+        last_event_id,
+        replay.len(),
+        params.ocr
+    );
+
+    let replay = stream::iter(replay.into_iter().map(|e| Ok(to_sse(&e))));
+    let live = match ocr {
+        Some(ocr) => stream::select(receive(rx), ocr).left_stream(),
+        None => receive(rx).right_stream(),
+    };
+
+    Sse::new(replay.chain(live)).keep_alive(KeepAlive::new().interval(Duration::from_secs(15)))
+}
@@ -0,0 +1,209 @@
+//! Single frame images extracted from video chunks
+//!
+//! FFmpeg extracts the frame losslessly as PNG; JPEG and WebP are encoded
+//! from it with the `image` crate.
+
+use crate::frame_cache::{FrameEncoding, FrameKey};
+use crate::{ApiError, AppState};
+use axum::{
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+};
+use bytes::Bytes;
+use image::codecs::{jpeg::JpegEncoder, webp::WebPEncoder};
+use memoire_processing::ffmpeg::ffmpeg_command;
+use serde::Deserialize;
+use std::process::Stdio;
+use tracing::debug;
+
+/// JPEG quality when a request sets none (suits thumbnails and grid views)
+pub const DEFAULT_JPEG_QUALITY: u8 = 80;
+
+/// Image formats a frame can be served as
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FrameFormat {
+    #[default]
+    #[serde(alias = "jpg")]
+    Jpeg,
+    Png,
+    Webp,
+}
+
+/// Query parameters for frame images
+#[derive(Debug, Deserialize)]
+pub struct FrameImageQuery {
+    /// `jpeg` (default), `png` (lossless) or `webp` (lossless, smaller than PNG)
+    #[serde(default)]
+    pub format: Option<FrameFormat>,
+    /// JPEG quality 1-100 (default 80); other formats are lossless
+    #[serde(default)]
+    pub quality: Option<u8>,
+}
+
+impl FrameImageQuery {
+    fn encoding(&self) -> Result<FrameEncoding, ApiError> {
+        if let Some(quality) = self.quality {
+            if !(1..=100).contains(&quality) {
+                return Err(ApiError::BadRequest("quality must be between 1 and 100".to_string()));
+            }
+        }
+        Ok(match self.format.unwrap_or_default() {
+            FrameFormat::Jpeg => FrameEncoding::Jpeg {
+                quality: self.quality.unwrap_or(DEFAULT_JPEG_QUALITY),
+            },
+            FrameFormat::Png => FrameEncoding::Png,
+            FrameFormat::Webp => FrameEncoding::Webp,
+        })
+    }
+}
+
+/// GET /api/frames/:id/image - the frame as JPEG, PNG or WebP (`?format=png`, `?quality=90`),
+/// served from the frame cache when possible
+pub async fn get_frame_image(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Query(params): Query<FrameImageQuery>,
+) -> Result<Response, ApiError> {
+    let encoding = params.encoding()?;
+
+    let (frame, chunk) = {
+        let db = state.db.lock()
+            .map_err(|_| ApiError::Internal(anyhow::anyhow!("database lock poisoned")))?;
+        let frame = memoire_db::get_frame(&db, id)?
+            .ok_or_else(|| ApiError::NotFound(format!("frame {} not found", id)))?;
+        let chunk = memoire_db::get_video_chunk(&db, frame.video_chunk_id)?
+            .ok_or_else(|| ApiError::NotFound("chunk not found".to_string()))?;
+        (frame, chunk)
+    };
+
+    let key = FrameKey {
+        chunk_id: chunk.id,
+        offset_index: frame.offset_index,
+        encoding,
+    };
+
+    let image = match state.frame_cache.get(&key) {
+        Some(image) => image,
+        None => {
+            let video_path = state.data_dir.join(&chunk.file_path);
+            if !video_path.starts_with(&state.data_dir) {
+                return Err(ApiError::Forbidden("path traversal detected".to_string()));
+            }
+            if !video_path.exists() {
+                return Err(ApiError::NotFound(format!("video file not found: {}", chunk.file_path)));
+            }
+
+            // Chunks are constant frame rate, so the offset maps directly to a time
+            let fps = chunk.fps.filter(|f| *f > 0.0).unwrap_or(1.0);
+            let seek = format!("{:.3}", frame.offset_index as f64 / fps);
+
+            let output = tokio::task::spawn_blocking(move || {
+                ffmpeg_command()
+                    .arg("-ss")
+                    .arg(&seek)
+                    .arg("-i")
+                    .arg(&video_path)
+                    .arg("-frames:v")
+                    .arg("1")
+                    .arg("-f")
+                    .arg("image2pipe")
+                    .arg("-c:v")
+                    .arg("png")
+                    .arg("pipe:1")
+                    .stdin(Stdio::null())
+                    .output()
+            })
+            .await
+            .map_err(|e| ApiError::Internal(anyhow::anyhow!("task join error: {}", e)))??;
+
+            if !output.status.success() || output.stdout.is_empty() {
+                debug!("ffmpeg frame output: {}", String::from_utf8_lossy(&output.stderr));
+                return Err(ApiError::Internal(anyhow::anyhow!(
+                    "ffmpeg failed to extract frame {}",
+                    id
+                )));
+            }
+
+            let image = match encoding {
+                FrameEncoding::Png => output.stdout,
+                _ => tokio::task::spawn_blocking(move || encode_frame(&output.stdout, encoding))
+                    .await
+                    .map_err(|e| ApiError::Internal(anyhow::anyhow!("task join error: {}", e)))??,
+            };
+            let image = Bytes::from(image);
+            state.frame_cache.insert(key, image.clone());
+            image
+        }
+    };
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, encoding.content_type()),
+            (header::CACHE_CONTROL, "public, max-age=3600"),
+        ],
+        image,
+    )
+        .into_response())
+}
+
+/// Re-encode an extracted PNG frame as JPEG or WebP
+///
+/// PNG requests are served as extracted and never get here.
+fn encode_frame(png: &[u8], encoding: FrameEncoding) -> anyhow::Result<Vec<u8>> {
+    // Frames are opaque; JPEG has no alpha channel
+    let frame = image::load_from_memory_with_format(png, image::ImageFormat::Png)?.to_rgb8();
+
+    let mut encoded = Vec::new();
+    match encoding {
+        FrameEncoding::Jpeg { quality } => {
+            frame.write_with_encoder(JpegEncoder::new_with_quality(&mut encoded, quality))?
+        }
+        _ => frame.write_with_encoder(WebPEncoder::new_lossless(&mut encoded))?,
+    }
+    Ok(encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Uri;
+
+    fn encoding(query: &str) -> Result<FrameEncoding, ApiError> {
+        let uri: Uri = format!("/api/frames/1/image?{}", query).parse().unwrap();
+        let Query(params) = Query::<FrameImageQuery>::try_from_uri(&uri)
+            .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+        params.encoding()
+    }
+
+    #[test]
+    fn test_default_is_jpeg_q80() {
+        assert_eq!(
+            encoding("").unwrap(),
+            FrameEncoding::Jpeg { quality: DEFAULT_JPEG_QUALITY }
+        );
+        assert_eq!(encoding("quality=60").unwrap(), FrameEncoding::Jpeg { quality: 60 });
+    }
+
+    #[test]
+    fn test_format_parsing() {
+        assert_eq!(encoding("format=png").unwrap(), FrameEncoding::Png);
+        assert_eq!(encoding("format=webp").unwrap(), FrameEncoding::Webp);
+        assert_eq!(encoding("format=jpg&quality=90").unwrap(), FrameEncoding::Jpeg { quality: 90 });
+        assert_eq!(encoding("format=jpeg").unwrap(), FrameEncoding::Jpeg { quality: 80 });
+        assert!(encoding("format=gif").is_err());
+        assert!(encoding("format=PNG").is_err());
+    }
+
+    #[test]
+    fn test_quality_bounds() {
+        assert_eq!(encoding("quality=1").unwrap(), FrameEncoding::Jpeg { quality: 1 });
+        assert_eq!(encoding("quality=100").unwrap(), FrameEncoding::Jpeg { quality: 100 });
+        assert!(encoding("quality=0").is_err());
+        assert!(encoding("quality=101").is_err());
+        assert!(encoding("quality=300").is_err());
+        // Checked even when the format ignores it
+        assert!(encoding("format=png&quality=0").is_err());
+    }
+}
@@ -0,0 +1,83 @@
+//! Prometheus metrics endpoint
+
+use crate::{ApiError, AppState};
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use std::fmt::Write;
+use std::sync::atomic::Ordering;
+
+/// Append a single metric with HELP/TYPE headers in Prometheus text format
+fn write_metric(out: &mut String, name: &str, kind: &str, help: &str, samples: &[(&str, i64)]) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+    for (labels, value) in samples {
+        let _ = writeln!(out, "{}{} {}", name, labels, value);
+    }
+}
+
+/// GET /metrics - Prometheus text exposition format
+pub async fn get_metrics(State(state): State<AppState>) -> Result<impl IntoResponse, ApiError> {
+    let (total_frames, ocr_stats, audio_stats) = {
+        let db = state.db.lock()
+            .map_err(|_| ApiError::Internal(anyhow::anyhow!("database lock poisoned")))?;
+
+        let total_frames = memoire_db::get_frame_count(&db)?;
+        let ocr_stats = memoire_db::get_ocr_stats(&db)
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+        let audio_stats = memoire_db::get_audio_stats(&db)
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+
+        (total_frames, ocr_stats, audio_stats)
+    };
+
+    let duplicate_frames = state.skipped_frames.load(Ordering::Relaxed) as i64;
+
+    let mut body = String::new();
+    write_metric(
+        &mut body,
+        "memoire_frames_total",
+        "counter",
+        "Total frames recorded",
+        &[("", total_frames)],
+    );
+    write_metric(
+        &mut body,
+        "memoire_ocr_pending",
+        "gauge",
+        "Frames waiting for OCR",
+        &[("", ocr_stats.pending_frames)],
+    );
+    write_metric(
+        &mut body,
+        "memoire_audio_pending",
+        "gauge",
+        "Audio chunks waiting for transcription",
+        &[("", audio_stats.pending_chunks)],
+    );
+    write_metric(
+        &mut body,
+        "memoire_duplicate_frames_total",
+        "counter",
+        "Frames skipped as duplicates since the recorder started",
+        &[("", duplicate_frames)],
+    );
+    write_metric(
+        &mut body,
+        "memoire_processing_rate",
+        "gauge",
+        "Items processed in the last hour",
+        &[
+            ("{pipeline=\"ocr\"}", ocr_stats.processing_rate),
+            ("{pipeline=\"audio\"}", audio_stats.processing_rate),
+        ],
+    );
+
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "text/plain; version=0.0.4; charset=utf-8")
+        .body(body)
+        .unwrap())
+}
@@ -2,10 +2,20 @@
 
 pub mod api;
 pub mod audio;
+pub mod events;
+pub mod frame_image;
+pub mod metrics;
+pub mod sprite;
 pub mod static_files;
+pub mod subtitles;
 pub mod video;
 
 pub use api::*;
 pub use audio::*;
+pub use events::*;
+pub use frame_image::*;
+pub use metrics::*;
+pub use sprite::*;
 pub use static_files::*;
+pub use subtitles::*;
 pub use video::*;
@@ -0,0 +1,261 @@
+//! Thumbnail sprite sheets for chunk scrubbing
+//!
+//! One JPEG per chunk holds a grid of downscaled frames, with a JSON map from
+//! frame to tile position (the WebVTT thumbnail pattern used by video
+//! players). Both are generated with FFmpeg on first request and cached in
+//! `<data_dir>/sprites`.
+
+use crate::{ApiError, AppState};
+use axum::{
+    extract::{Path, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use memoire_processing::ffmpeg::ffmpeg_command;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::process::Stdio;
+use tracing::{debug, info};
+
+/// Grid size of a sprite sheet; long chunks sample every Nth frame to fit
+const SPRITE_COLUMNS: i64 = 10;
+const SPRITE_MAX_ROWS: i64 = 10;
+
+/// Tile width in pixels (height follows the chunk's aspect ratio)
+const TILE_WIDTH: u32 = 160;
+
+/// Position of one frame in the sprite sheet
+///
+/// A tile shows the video at a multiple of the map's `step`; its frame is the
+/// latest one stored at or before that position (the video repeats it across
+/// deduplicated positions).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SpriteTile {
+    pub frame_id: i64,
+    pub offset_index: i64,
+    pub x: u32,
+    pub y: u32,
+}
+
+/// Layout of a chunk's sprite sheet
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SpriteMap {
+    pub chunk_id: i64,
+    /// Frames in the chunk when the sprite was generated
+    pub frame_count: i64,
+    /// Every `step`-th video position has a tile
+    pub step: i64,
+    pub columns: i64,
+    pub rows: i64,
+    pub tile_width: u32,
+    pub tile_height: u32,
+    pub image_url: String,
+    pub tiles: Vec<SpriteTile>,
+}
+
+/// GET /api/chunks/:id/sprite - frame to tile map, generating the sprite if needed
+pub async fn get_chunk_sprite(
+    State(state): State<AppState>,
+    Path(chunk_id): Path<i64>,
+) -> Result<Json<SpriteMap>, ApiError> {
+    let (map, _) = ensure_sprite(&state, chunk_id).await?;
+    Ok(Json(map))
+}
+
+/// GET /api/chunks/:id/sprite.jpg - the sprite sheet image
+pub async fn get_chunk_sprite_image(
+    State(state): State<AppState>,
+    Path(chunk_id): Path<i64>,
+) -> Result<Response, ApiError> {
+    let (_, image_path) = ensure_sprite(&state, chunk_id).await?;
+    let image = tokio::fs::read(&image_path).await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "image/jpeg"),
+            (header::CACHE_CONTROL, "public, max-age=3600"),
+        ],
+        image,
+    )
+        .into_response())
+}
+
+/// Map tiles to frames: tile `k` shows video position `k * step`
+///
+/// `frames` must be ordered by `offset_index`. Offsets are padded video
+/// positions, so a tile's position usually has no frame of its own and
+/// shows the latest frame before it.
+fn layout_tiles(frames: &[memoire_db::Frame], step: i64, tile_count: i64, tile_height: u32) -> Vec<SpriteTile> {
+    let mut tiles = Vec::with_capacity(tile_count.max(0) as usize);
+    let mut current: Option<&memoire_db::Frame> = None;
+    let mut remaining = frames.iter().peekable();
+
+    for tile in 0..tile_count {
+        let position = tile * step;
+        while let Some(frame) = remaining.next_if(|f| f.offset_index <= position) {
+            current = Some(frame);
+        }
+        if let Some(frame) = current {
+            tiles.push(SpriteTile {
+                frame_id: frame.id,
+                offset_index: frame.offset_index,
+                x: (tile % SPRITE_COLUMNS) as u32 * TILE_WIDTH,
+                y: (tile / SPRITE_COLUMNS) as u32 * tile_height,
+            });
+        }
+    }
+
+    tiles
+}
+
+/// Return the cached sprite for a chunk, generating it when missing or stale
+async fn ensure_sprite(state: &AppState, chunk_id: i64) -> Result<(SpriteMap, PathBuf), ApiError> {
+    let (chunk, frames) = {
+        let db = state.db.lock()
+            .map_err(|_| ApiError::Internal(anyhow::anyhow!("database lock poisoned")))?;
+        let chunk = memoire_db::get_video_chunk(&db, chunk_id)?
+            .ok_or_else(|| ApiError::NotFound(format!("chunk {} not found", chunk_id)))?;
+        let frames = memoire_db::get_frames_by_chunk(&db, chunk_id)
+            .map_err(|e| ApiError::Database(e.to_string()))?;
+        (chunk, frames)
+    };
+
+    let frame_count = frames.last().map_or(0, |f| f.offset_index + 1);
+    if frame_count == 0 {
+        return Err(ApiError::NotFound(format!("chunk {} has no frames", chunk_id)));
+    }
+
+    let sprites_dir = state.data_dir.join("sprites");
+    let map_path = sprites_dir.join(format!("chunk_{}.json", chunk_id));
+    let image_path = sprites_dir.join(format!("chunk_{}.jpg", chunk_id));
+
+    // Reuse the cached sprite unless frames were added since it was generated
+    if let Ok(cached) = tokio::fs::read(&map_path).await {
+        if let Ok(map) = serde_json::from_slice::<SpriteMap>(&cached) {
+            if map.frame_count == frame_count && image_path.exists() {
+                return Ok((map, image_path));
+            }
+        }
+    }
+
+    let video_path = state.data_dir.join(&chunk.file_path);
+    if !video_path.starts_with(&state.data_dir) {
+        return Err(ApiError::Forbidden("path traversal detected".to_string()));
+    }
+    if !video_path.exists() {
+        return Err(ApiError::NotFound(format!("video file not found: {}", chunk.file_path)));
+    }
+
+    // Sample evenly so the whole chunk fits in the grid
+    let max_tiles = SPRITE_COLUMNS * SPRITE_MAX_ROWS;
+    let step = (frame_count + max_tiles - 1) / max_tiles;
+    let tile_count = (frame_count + step - 1) / step;
+    let rows = (tile_count + SPRITE_COLUMNS - 1) / SPRITE_COLUMNS;
+    let tile_height = match (chunk.width, chunk.height) {
+        (Some(w), Some(h)) if w > 0 && h > 0 => ((TILE_WIDTH * h / w) & !1).max(2),
+        _ => TILE_WIDTH * 9 / 16,
+    };
+
+    let tiles = layout_tiles(&frames, step, tile_count, tile_height);
+
+    let map = SpriteMap {
+        chunk_id,
+        frame_count,
+        step,
+        columns: SPRITE_COLUMNS,
+        rows,
+        tile_width: TILE_WIDTH,
+        tile_height,
+        image_url: format!("/api/chunks/{}/sprite.jpg", chunk_id),
+        tiles,
+    };
+
+    tokio::fs::create_dir_all(&sprites_dir).await?;
+    let filter = format!(
+        "select='not(mod(n\\,{}))',scale={}:{},tile={}x{}",
+        step, TILE_WIDTH, tile_height, SPRITE_COLUMNS, rows
+    );
+    let tmp_image = sprites_dir.join(format!("chunk_{}.tmp.jpg", chunk_id));
+
+    info!("generating sprite for chunk {} ({} tiles)", chunk_id, tile_count);
+    let output_path = tmp_image.clone();
+    let output = tokio::task::spawn_blocking(move || {
+        ffmpeg_command()
+            .arg("-y")
+            .arg("-i")
+            .arg(&video_path)
+            .arg("-vf")
+            .arg(&filter)
+            .arg("-frames:v")
+            .arg("1")
+            .arg("-q:v")
+            .arg("5")
+            .arg(&output_path)
+            .stdout(Stdio::null())
+            .output()
+    })
+    .await
+    .map_err(|e| ApiError::Internal(anyhow::anyhow!("task join error: {}", e)))??;
+
+    if !output.status.success() {
+        let _ = tokio::fs::remove_file(&tmp_image).await;
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        debug!("ffmpeg sprite output: {}", stderr);
+        return Err(ApiError::Internal(anyhow::anyhow!(
+            "ffmpeg failed to generate sprite for chunk {}",
+            chunk_id
+        )));
+    }
+
+    tokio::fs::rename(&tmp_image, &image_path).await?;
+    let json = serde_json::to_vec(&map)
+        .map_err(|e| ApiError::Internal(anyhow::anyhow!("failed to serialize sprite map: {}", e)))?;
+    tokio::fs::write(&map_path, json).await?;
+
+    Ok((map, image_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn frame(id: i64, offset_index: i64) -> memoire_db::Frame {
+        memoire_db::Frame {
+            id,
+            video_chunk_id: 1,
+            offset_index,
+            timestamp: Utc::now(),
+            app_name: None,
+            window_name: None,
+            browser_url: None,
+            focused: true,
+            frame_hash: None,
+        }
+    }
+
+    #[test]
+    fn test_tiles_cover_padded_offsets() {
+        // Deduplication left the stored frames at non-contiguous positions
+        let frames = [frame(1, 0), frame(2, 3), frame(3, 4), frame(4, 11), frame(5, 13)];
+        let tiles = layout_tiles(&frames, 2, 7, 90);
+
+        let frame_ids: Vec<i64> = tiles.iter().map(|t| t.frame_id).collect();
+        assert_eq!(frame_ids, vec![1, 1, 3, 3, 3, 3, 4]);
+
+        assert_eq!((tiles[2].x, tiles[2].y), (2 * TILE_WIDTH, 0));
+        assert_eq!(tiles[2].offset_index, 4);
+    }
+
+    #[test]
+    fn test_tiles_wrap_into_rows() {
+        let frames: Vec<_> = (0..25).map(|i| frame(i + 1, i)).collect();
+        let tiles = layout_tiles(&frames, 2, 13, 90);
+
+        assert_eq!(tiles.len(), 13);
+        assert_eq!(tiles[10].frame_id, 21);
+        assert_eq!((tiles[10].x, tiles[10].y), (0, 90));
+        assert_eq!((tiles[12].x, tiles[12].y), (2 * TILE_WIDTH, 90));
+    }
+}
@@ -0,0 +1,71 @@
+//! WebVTT subtitles for video chunks from overlapping audio transcriptions
+
+use crate::{ApiError, AppState};
+use axum::{
+    extract::{Path, State},
+    http::header,
+    response::{IntoResponse, Response},
+};
+use memoire_db::SubtitleCue;
+use std::fmt::Write;
+
+/// GET /api/chunks/:id/subtitles.vtt - transcriptions as a side-car WebVTT track
+pub async fn get_chunk_subtitles(
+    State(state): State<AppState>,
+    Path(chunk_id): Path<i64>,
+) -> Result<Response, ApiError> {
+    let cues = {
+        let db = state.db.lock()
+            .map_err(|_| ApiError::Internal(anyhow::anyhow!("database lock poisoned")))?;
+        memoire_db::get_chunk_subtitle_cues(&db, chunk_id)
+            .map_err(|e| ApiError::Database(e.to_string()))?
+            .ok_or_else(|| ApiError::NotFound(format!("chunk {} not found", chunk_id)))?
+    };
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/vtt; charset=utf-8"),
+            (header::CACHE_CONTROL, "no-cache"),
+        ],
+        to_webvtt(&cues),
+    )
+        .into_response())
+}
+
+/// Render cues as a WebVTT document
+fn to_webvtt(cues: &[SubtitleCue]) -> String {
+    let mut vtt = String::from("WEBVTT\n");
+    for (i, cue) in cues.iter().enumerate() {
+        let _ = write!(
+            vtt,
+            "\n{}\n{} --> {}\n{}\n",
+            i + 1,
+            format_timestamp(cue.start_secs),
+            format_timestamp(cue.end_secs),
+            escape_cue_text(&cue.text)
+        );
+    }
+    vtt
+}
+
+/// Format seconds as a WebVTT timestamp (`HH:MM:SS.mmm`)
+fn format_timestamp(secs: f64) -> String {
+    let total_ms = (secs.max(0.0) * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        total_ms / 3_600_000,
+        total_ms / 60_000 % 60,
+        total_ms / 1000 % 60,
+        total_ms % 1000
+    )
+}
+
+/// Escape markup characters and drop blank lines, which would end the cue
+fn escape_cue_text(text: &str) -> String {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| line.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -2,23 +2,35 @@
 
 use crate::routes;
 use crate::state::AppState;
+use crate::trace::{LogFailure, LogResponse, RequestSpan};
 use axum::{
-    routing::get,
+    routing::{get, post},
     Router,
 };
-use std::net::SocketAddr;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::path::PathBuf;
 use tower_http::cors::{CorsLayer, Any};
+use tower_http::limit::RequestBodyLimitLayer;
+use tower_http::timeout::TimeoutLayer;
 use tower_http::trace::TraceLayer;
-use tracing::info;
+use tracing::{info, warn};
+
+/// Default bind address: only reachable from this machine
+pub const DEFAULT_HOST: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
 
 /// Start the web server
 pub async fn serve(
     db: rusqlite::Connection,
     data_dir: PathBuf,
+    host: IpAddr,
     port: u16,
 ) -> anyhow::Result<()> {
-    let state = AppState::new(db, data_dir);
+    serve_with_state(AppState::new(db, data_dir), host, port).await
+}
+
+/// Start the web server with pre-built state
+pub async fn serve_with_state(state: AppState, host: IpAddr, port: u16) -> anyhow::Result<()> {
+    let limits = state.limits;
 
     // Build router
     let app = Router::new()
@@ -26,13 +38,26 @@ pub async fn serve(
         .route("/api/chunks", get(routes::get_chunks))
         .route("/api/chunks/:id", get(routes::get_chunk))
         .route("/api/chunks/:id/frames", get(routes::get_chunk_frames))
+        .route("/api/chunks/:id/reindex", post(routes::reindex_chunk))
+        .route("/api/chunks/:id/sprite", get(routes::get_chunk_sprite))
+        .route("/api/chunks/:id/sprite.jpg", get(routes::get_chunk_sprite_image))
+        .route("/api/chunks/:id/subtitles.vtt", get(routes::get_chunk_subtitles))
         .route("/api/frames", get(routes::get_frames))
         .route("/api/frames/:id", get(routes::get_frame))
+        .route("/api/frames/:id/image", get(routes::get_frame_image))
+        .route("/api/frames/:id/similar", get(routes::get_similar_frames))
         .route("/api/stats", get(routes::get_stats))
         .route("/api/stats/ocr", get(routes::get_ocr_stats))
         .route("/api/stats/audio", get(routes::get_audio_stats))
+        .route("/api/stats/dedup", get(routes::get_dedup_stats))
+        .route("/api/dashboard", get(routes::get_dashboard))
         .route("/api/monitors", get(routes::get_monitors))
+        .route("/api/latest", get(routes::get_latest_frames))
+        .route("/api/activity", get(routes::get_activity))
+        .route("/api/context", get(routes::get_context))
         .route("/api/search", get(routes::search_ocr))
+        .route("/api/search/region", get(routes::search_ocr_region))
+        .route("/api/events", get(routes::stream_events))
         // Audio API routes
         .route("/api/audio-chunks", get(routes::get_audio_chunks))
         .route("/api/audio-chunks/:id", get(routes::get_audio_chunk))
@@ -41,6 +66,8 @@ pub async fn serve(
         .route("/video/:id", get(routes::stream_video))
         // Audio streaming
         .route("/audio/:id", get(routes::stream_audio))
+        // Prometheus metrics
+        .route("/metrics", get(routes::get_metrics))
         // Static files (embedded at compile time)
         .route("/", get(routes::serve_index))
         .route("/style.css", get(routes::serve_style))
@@ -54,10 +81,27 @@ pub async fn serve(
                 .allow_methods(Any)
                 .allow_headers(Any),
         )
-        .layer(TraceLayer::new_for_http());
+        // Slow requests get 408; the body limit answers oversized uploads with 413
+        .layer(TimeoutLayer::new(limits.timeout))
+        .layer(RequestBodyLimitLayer::new(limits.max_body_bytes))
+        // Outermost, so the elapsed time covers the other middleware too
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(RequestSpan)
+                .on_request(())
+                .on_response(LogResponse)
+                .on_failure(LogFailure),
+        );
 
     // Bind to address
-    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    let addr = SocketAddr::new(host, port);
+    if !host.is_loopback() {
+        // The API has no authentication and serves screen recordings and transcripts
+        warn!(
+            "viewer is bound to {}, which is reachable from other machines; anyone who can connect can read all recorded screens, OCR text and audio",
+            host
+        );
+    }
     info!("Starting Memoire viewer on http://{}", addr);
     println!("\n🎥 Memoire Validation Viewer");
     println!("   → http://{}\n", addr);
@@ -1,8 +1,48 @@
 //! Shared application state
 
 use std::path::PathBuf;
+use std::sync::atomic::AtomicU64;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use rusqlite::Connection;
+use tokio::sync::mpsc;
+
+use crate::events::EventHub;
+use crate::frame_cache::FrameCache;
+
+/// Page size used when a request does not set `limit`
+const DEFAULT_PAGE_SIZE: i64 = 50;
+
+/// Bounds applied to every request
+#[derive(Debug, Clone, Copy)]
+pub struct RequestLimits {
+    /// Time allowed to produce a response (streamed bodies are not cut off)
+    pub timeout: Duration,
+    /// Largest accepted request body
+    pub max_body_bytes: usize,
+    /// Largest `limit` a listing or search returns
+    pub max_page_size: i64,
+}
+
+impl Default for RequestLimits {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(30),
+            max_body_bytes: 1024 * 1024,
+            max_page_size: 100,
+        }
+    }
+}
+
+impl RequestLimits {
+    /// Page size for a requested `limit`, clamped to `1..=max_page_size`
+    pub fn page_size(&self, requested: Option<i64>) -> i64 {
+        requested
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .min(self.max_page_size)
+            .max(1)
+    }
+}
 
 /// Shared state across all handlers
 #[derive(Clone)]
@@ -12,6 +52,24 @@ pub struct AppState {
 
     /// Data directory (for resolving video file paths)
     pub data_dir: PathBuf,
+
+    /// Duplicate frames skipped by the recorder (shared when running in-process)
+    pub skipped_frames: Arc<AtomicU64>,
+
+    /// Newly recorded chunks, streamed to clients on /api/events
+    pub events: EventHub,
+
+    /// Recently extracted frame images
+    pub frame_cache: FrameCache,
+
+    /// Chunks to OCR again, sent to the in-process indexer (None when it runs elsewhere)
+    pub reindex_tx: Option<mpsc::UnboundedSender<i64>>,
+
+    /// Timeout, body size and page size bounds
+    pub limits: RequestLimits,
+
+    /// The database was opened read-only, so modifying endpoints are refused
+    pub read_only: bool,
 }
 
 impl AppState {
@@ -20,6 +78,48 @@ impl AppState {
         Self {
             db: Arc::new(Mutex::new(db)),
             data_dir,
+            skipped_frames: Arc::new(AtomicU64::new(0)),
+            events: EventHub::new(),
+            frame_cache: FrameCache::default(),
+            reindex_tx: None,
+            limits: RequestLimits::default(),
+            read_only: false,
         }
     }
+
+    /// Share the recorder's skipped frame counter for metrics
+    pub fn with_skipped_frames(mut self, counter: Arc<AtomicU64>) -> Self {
+        self.skipped_frames = counter;
+        self
+    }
+
+    /// Share an event hub fed by the recorder
+    pub fn with_events(mut self, events: EventHub) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Notify an in-process OCR indexer when a chunk is queued for re-indexing
+    pub fn with_reindex_sender(mut self, tx: mpsc::UnboundedSender<i64>) -> Self {
+        self.reindex_tx = Some(tx);
+        self
+    }
+
+    /// Set the memory budget for cached frame images (0 disables caching)
+    pub fn with_frame_cache_budget(mut self, budget_bytes: usize) -> Self {
+        self.frame_cache = FrameCache::new(budget_bytes);
+        self
+    }
+
+    /// Override the default request limits
+    pub fn with_limits(mut self, limits: RequestLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Refuse requests that would modify the database
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
 }
@@ -0,0 +1,61 @@
+//! Per-request tracing
+//!
+//! Every request runs in a `request` span carrying its method and path, so
+//! events logged by handlers can be traced to the request that caused them.
+//! The status and elapsed time are logged when the response is ready. All of
+//! it uses the `web` target at debug level: normal runs stay quiet, and
+//! `RUST_LOG=web=debug` (or `--verbose`, for every target) shows each request.
+
+use axum::http::{Request, Response};
+use std::time::Duration;
+use tower_http::classify::ServerErrorsFailureClass;
+use tower_http::trace::{MakeSpan, OnFailure, OnResponse};
+use tracing::{debug, warn, Span};
+
+/// Opens the `request` span with the method and path
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RequestSpan;
+
+impl<B> MakeSpan<B> for RequestSpan {
+    fn make_span(&mut self, request: &Request<B>) -> Span {
+        tracing::debug_span!(
+            target: "web",
+            "request",
+            method = %request.method(),
+            path = %request.uri().path(),
+        )
+    }
+}
+
+/// Logs the status and elapsed time of each response
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct LogResponse;
+
+impl<B> OnResponse<B> for LogResponse {
+    fn on_response(self, response: &Response<B>, latency: Duration, _span: &Span) {
+        debug!(
+            target: "web",
+            status = response.status().as_u16(),
+            elapsed_ms = latency.as_secs_f64() * 1000.0,
+            "request finished"
+        );
+    }
+}
+
+/// Logs server errors (5xx) and failed responses
+///
+/// Warns rather than staying at debug level, so failures are visible in
+/// normal runs.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct LogFailure;
+
+impl OnFailure<ServerErrorsFailureClass> for LogFailure {
+    fn on_failure(&mut self, failure: ServerErrorsFailureClass, latency: Duration, _span: &Span) {
+        warn!(
+            target: "web",
+            elapsed_ms = latency.as_secs_f64() * 1000.0,
+            "request failed: {}",
+            failure
+        );
+    }
+}